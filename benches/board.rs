@@ -0,0 +1,93 @@
+//! Times the operations that large boards stress: building a board (which
+//! counts every tile's neighbouring mines), flood-filling an opening
+//! across it, and the per-move paths that must not cost time or memory in
+//! proportion to the board: single clicks, hover previews and immutable
+//! snapshot steps.
+//!
+//! Run with `cargo bench`. Uses plain wall-clock timing so it needs no
+//! extra dependencies; compare runs on the same machine.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use minesweeper::{GameSnapshot, Minesweeper, Move};
+
+const SIZE: usize = 1000;
+const RUNS: u32 = 5;
+/// Moves timed together in the per-move benchmarks.
+const MOVES: usize = 1000;
+
+/// Times `run` on fresh input from `setup`. Neither the setup nor dropping
+/// what `run` returns is timed, since freeing a large board can take
+/// longer than the operation being measured.
+fn time<T, U>(name: &str, mut setup: impl FnMut() -> T, mut run: impl FnMut(T) -> U) {
+    let mut best = Duration::MAX;
+    for _ in 0..RUNS {
+        let input = setup();
+        let start = Instant::now();
+        let output = black_box(run(input));
+        best = best.min(start.elapsed());
+        drop(output);
+    }
+    println!("{:<32} {:>10.2?} (best of {})", name, best, RUNS);
+}
+
+fn main() {
+    // A mine every 97 tiles: numbers everywhere, but zeros connect the
+    // whole board.
+    let mines: Vec<(usize, usize)> = (0..SIZE * SIZE)
+        .step_by(97)
+        .map(|i| (i / SIZE, i % SIZE))
+        .collect();
+
+    time(
+        "adjacency counting 1000x1000",
+        || mines.clone(),
+        |mines| Minesweeper::new(SIZE, mines),
+    );
+
+    time(
+        "flood fill 1000x1000",
+        || Minesweeper::new(SIZE, vec![(SIZE - 1, SIZE - 1)]),
+        |mut game| {
+            game.click_tile(0, 0).unwrap();
+            game
+        },
+    );
+
+    // A mine on every other column: every safe tile is a number, so each
+    // click reveals one tile.
+    let stripes = || {
+        let mines = (0..SIZE)
+            .step_by(2)
+            .flat_map(|x| (0..SIZE).map(move |y| (x, y)))
+            .collect();
+        Minesweeper::new(SIZE, mines)
+    };
+    let clicks: Vec<(usize, usize)> = (0..MOVES).map(|i| (1, i % SIZE)).collect();
+
+    time("1000 single clicks 1000x1000", stripes, |mut game| {
+        for &(x, y) in &clicks {
+            game.click_tile(x, y).unwrap();
+        }
+        game
+    });
+
+    time("1000 hover previews 1000x1000", stripes, |game| {
+        for &(x, y) in &clicks {
+            black_box(game.opening_layers_at(x, y));
+        }
+        game
+    });
+
+    time(
+        "1000 snapshot steps 1000x1000",
+        || GameSnapshot::from(stripes()),
+        |mut snapshot| {
+            for &(x, y) in &clicks {
+                snapshot = snapshot.step(Move::Reveal(x, y)).unwrap().0;
+            }
+            snapshot
+        },
+    );
+}
@@ -0,0 +1,293 @@
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use crate::{GameState, Minesweeper};
+
+/// The facts about one finished game that achievements are judged on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameSummary {
+    pub outcome: GameState,
+    pub width: usize,
+    pub height: usize,
+    pub bomb_count: usize,
+    pub time: Duration,
+    pub flags_placed: usize,
+    /// The board's 3BV, the fewest clicks that clear it.
+    pub three_bv: usize,
+    /// Whether the flag check assist was on at the end.
+    pub flag_check: bool,
+    /// Moves made without a safe deduction available, if a solver was
+    /// watching the game.
+    pub guesses: Option<usize>,
+}
+
+impl GameSummary {
+    /// Summarises `game`, which took `time` to play. Guesses are counted
+    /// when the game kept a guess journal.
+    pub fn from_game(game: &Minesweeper, time: Duration) -> Self {
+        GameSummary {
+            outcome: game.get_game_state().clone(),
+            width: game.get_width(),
+            height: game.get_height(),
+            bomb_count: game.get_bomb_count(),
+            time,
+            flags_placed: game.flags_placed(),
+            three_bv: game.three_bv(),
+            flag_check: game.flag_check_enabled(),
+            guesses: game
+                .journal
+                .as_ref()
+                .map(|journal| journal.iter().filter(|guess| guess.forced).count()),
+        }
+    }
+
+    fn won(&self) -> bool {
+        self.outcome == GameState::Won
+    }
+}
+
+/// The condition that unlocks an achievement.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Trigger {
+    /// Win any game.
+    FirstWin,
+    /// Win this many games in total.
+    Wins(usize),
+    /// Win a square game of the given size and mine count within `time`.
+    WinUnder {
+        size: usize,
+        bomb_count: usize,
+        time: Duration,
+    },
+    /// Win without placing a single flag.
+    WinWithoutFlags,
+    /// Win a game in which no move was a guess.
+    WinWithoutGuessing,
+}
+
+impl Trigger {
+    fn is_met(&self, summary: &GameSummary, profile: &AchievementProfile) -> bool {
+        if !summary.won() {
+            return false;
+        }
+        match *self {
+            Trigger::FirstWin => true,
+            Trigger::Wins(count) => profile.wins >= count,
+            Trigger::WinUnder {
+                size,
+                bomb_count,
+                time,
+            } => {
+                summary.width == size
+                    && summary.height == size
+                    && summary.bomb_count == bomb_count
+                    && summary.time < time
+            }
+            Trigger::WinWithoutFlags => summary.flags_placed == 0,
+            Trigger::WinWithoutGuessing => summary.guesses == Some(0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Achievement {
+    /// Stable identifier, used when persisting a profile.
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub trigger: Trigger,
+}
+
+impl Achievement {
+    pub fn new(id: &str, name: &str, description: &str, trigger: Trigger) -> Self {
+        Achievement {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            trigger,
+        }
+    }
+}
+
+/// The achievements that ship with the crate.
+pub fn builtin_achievements() -> Vec<Achievement> {
+    vec![
+        Achievement::new("first_win", "First Sweep", "Win a game.", Trigger::FirstWin),
+        Achievement::new("ten_wins", "Veteran", "Win ten games.", Trigger::Wins(10)),
+        Achievement::new(
+            "beginner_10s",
+            "Quick Start",
+            "Win a 9x9 board with 10 mines in under 10 seconds.",
+            Trigger::WinUnder {
+                size: 9,
+                bomb_count: 10,
+                time: Duration::from_secs(10),
+            },
+        ),
+        Achievement::new(
+            "intermediate_100s",
+            "Century",
+            "Win a 16x16 board with 40 mines in under 100 seconds.",
+            Trigger::WinUnder {
+                size: 16,
+                bomb_count: 40,
+                time: Duration::from_secs(100),
+            },
+        ),
+        Achievement::new(
+            "no_flags",
+            "Flagless",
+            "Win without placing a flag.",
+            Trigger::WinWithoutFlags,
+        ),
+        Achievement::new(
+            "no_guesses",
+            "Pure Logic",
+            "Win a game without making a single guess.",
+            Trigger::WinWithoutGuessing,
+        ),
+    ]
+}
+
+/// One player's unlocked achievements and the history they depend on.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AchievementProfile {
+    wins: usize,
+    unlocked: BTreeSet<String>,
+}
+
+impl AchievementProfile {
+    /// Records a finished game and returns the ids of any achievements from
+    /// `achievements` it unlocked.
+    pub fn record_game(
+        &mut self,
+        summary: &GameSummary,
+        achievements: &[Achievement],
+    ) -> Vec<String> {
+        if summary.won() {
+            self.wins += 1;
+        }
+
+        let mut newly_unlocked = Vec::new();
+        for achievement in achievements {
+            if !self.unlocked.contains(&achievement.id) && achievement.trigger.is_met(summary, self)
+            {
+                self.unlocked.insert(achievement.id.clone());
+                newly_unlocked.push(achievement.id.clone());
+            }
+        }
+        newly_unlocked
+    }
+
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.unlocked.contains(id)
+    }
+
+    /// Ids of every unlocked achievement, in sorted order.
+    pub fn unlocked(&self) -> impl Iterator<Item = &str> {
+        self.unlocked.iter().map(String::as_str)
+    }
+
+    pub fn wins(&self) -> usize {
+        self.wins
+    }
+
+    /// The profile as text: a `wins=N` line followed by one unlocked
+    /// achievement id per line.
+    pub fn to_profile_string(&self) -> String {
+        let mut text = format!("wins={}\n", self.wins);
+        for id in &self.unlocked {
+            text.push_str(id);
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Parses text produced by [`AchievementProfile::to_profile_string`].
+    pub fn from_profile_string(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines();
+        let wins = lines
+            .next()
+            .and_then(|line| line.strip_prefix("wins="))
+            .and_then(|wins| wins.parse().ok())
+            .ok_or_else(|| "Expected wins=N on the first line".to_string())?;
+
+        let unlocked = lines
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        Ok(AchievementProfile { wins, unlocked })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn win(size: usize, bomb_count: usize, secs: u64) -> GameSummary {
+        GameSummary {
+            outcome: GameState::Won,
+            width: size,
+            height: size,
+            bomb_count,
+            time: Duration::from_secs(secs),
+            flags_placed: 3,
+            three_bv: 30,
+            flag_check: false,
+            guesses: None,
+        }
+    }
+
+    #[test]
+    fn test_unlocking() {
+        let achievements = builtin_achievements();
+        let mut profile = AchievementProfile::default();
+
+        let mut loss = win(9, 10, 5);
+        loss.outcome = GameState::Lost;
+        assert!(profile.record_game(&loss, &achievements).is_empty());
+
+        let unlocked = profile.record_game(&win(9, 10, 5), &achievements);
+        assert_eq!(unlocked, vec!["first_win", "beginner_10s"]);
+
+        // Already unlocked achievements are not reported again.
+        assert!(profile
+            .record_game(&win(9, 10, 5), &achievements)
+            .is_empty());
+
+        let mut clean = win(16, 40, 120);
+        clean.flags_placed = 0;
+        clean.guesses = Some(0);
+        let unlocked = profile.record_game(&clean, &achievements);
+        assert_eq!(unlocked, vec!["no_flags", "no_guesses"]);
+    }
+
+    #[test]
+    fn test_summary_from_game() {
+        let mut game = Minesweeper::new(2, vec![(0, 0)]);
+        game.toggle_flag(0, 0).unwrap();
+        for (x, y) in [(0, 1), (1, 0), (1, 1)] {
+            game.click_tile(x, y).unwrap();
+        }
+
+        let summary = GameSummary::from_game(&game, Duration::from_secs(4));
+        assert_eq!(summary.outcome, GameState::Won);
+        assert_eq!(summary.flags_placed, 1);
+    }
+
+    #[test]
+    fn test_profile_round_trip() {
+        let mut profile = AchievementProfile::default();
+        profile.record_game(&win(9, 10, 50), &builtin_achievements());
+
+        let text = profile.to_profile_string();
+        assert_eq!(text, "wins=1\nfirst_win\n");
+        assert_eq!(
+            AchievementProfile::from_profile_string(&text).unwrap(),
+            profile
+        );
+        assert!(AchievementProfile::from_profile_string("first_win\n").is_err());
+    }
+}
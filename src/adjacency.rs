@@ -0,0 +1,143 @@
+/// Which tiles count as neighbours: the tiles a number counts mines in, and
+/// the tiles a zero opens up.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Adjacency {
+    /// The eight surrounding tiles (a 3x3 area).
+    #[default]
+    Standard,
+    /// Every tile within two steps (a 5x5 area), so numbers run up to 24.
+    Extended,
+    /// The eight tiles a chess knight's move away, so a number says nothing
+    /// about the tiles right next to it.
+    Knight,
+    /// The six tiles around a hexagon, with (x, y) read as axial
+    /// coordinates: rows are shifted half a tile right of the row above,
+    /// so (x + 1, y - 1) and (x - 1, y + 1) touch (x, y) while
+    /// (x + 1, y + 1) and (x - 1, y - 1) don't.
+    Hex,
+}
+
+/// The shape of the board's surface: whether neighbours stop at the edges
+/// or wrap around them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Topology {
+    /// A flat board: edge tiles have fewer neighbours.
+    #[default]
+    Plane,
+    /// The left edge touches the right and the top touches the bottom, so
+    /// every tile has a full set of neighbours. Boards must be at least
+    /// `2 * radius + 1` tiles across each way so no tile is counted twice.
+    Torus,
+}
+
+impl Adjacency {
+    /// How far neighbours reach in each direction.
+    pub fn radius(self) -> usize {
+        match self {
+            Adjacency::Standard | Adjacency::Hex => 1,
+            Adjacency::Extended | Adjacency::Knight => 2,
+        }
+    }
+
+    /// The in-bounds neighbours of (x, y) on a flat `width` x `height`
+    /// board, not including (x, y) itself.
+    pub fn neighbors(
+        self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> impl Iterator<Item = (usize, usize)> {
+        self.neighbors_in(Topology::Plane, x, y, width, height)
+    }
+
+    /// The neighbours of (x, y) on a `width` x `height` board with the
+    /// given topology, not including (x, y) itself.
+    pub fn neighbors_in(
+        self,
+        topology: Topology,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> impl Iterator<Item = (usize, usize)> {
+        let r = self.radius() as i64;
+        (-r..=r)
+            .flat_map(move |dx| (-r..=r).map(move |dy| (dx, dy)))
+            .filter(move |&(dx, dy)| match self {
+                Adjacency::Knight => dx.abs() * dy.abs() == 2,
+                Adjacency::Hex => dx != dy,
+                _ => dx != 0 || dy != 0,
+            })
+            .filter_map(move |(dx, dy)| {
+                let nx = x as i64 + dx;
+                let ny = y as i64 + dy;
+                match topology {
+                    Topology::Torus => Some((
+                        nx.rem_euclid(width as i64) as usize,
+                        ny.rem_euclid(height as i64) as usize,
+                    )),
+                    Topology::Plane => {
+                        (nx >= 0 && ny >= 0 && (nx as usize) < width && (ny as usize) < height)
+                            .then_some((nx as usize, ny as usize))
+                    }
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neighbor_counts() {
+        assert_eq!(Adjacency::Standard.neighbors(2, 2, 5, 5).count(), 8);
+        assert_eq!(Adjacency::Standard.neighbors(0, 0, 5, 5).count(), 3);
+        assert_eq!(Adjacency::Extended.neighbors(2, 2, 5, 5).count(), 24);
+        assert_eq!(Adjacency::Extended.neighbors(0, 0, 5, 5).count(), 8);
+        // On a 5x2 board nothing lies below the bottom row.
+        assert_eq!(Adjacency::Standard.neighbors(2, 1, 5, 2).count(), 5);
+        assert_eq!(Adjacency::Extended.neighbors(2, 0, 5, 2).count(), 9);
+        assert_eq!(Adjacency::Knight.neighbors(2, 2, 5, 5).count(), 8);
+        assert_eq!(
+            Adjacency::Knight.neighbors(0, 0, 5, 5).collect::<Vec<_>>(),
+            vec![(1, 2), (2, 1)]
+        );
+        assert_eq!(
+            Adjacency::Hex.neighbors(2, 2, 5, 5).collect::<Vec<_>>(),
+            vec![(1, 2), (1, 3), (2, 1), (2, 3), (3, 1), (3, 2)]
+        );
+        assert_eq!(Adjacency::Hex.neighbors(0, 0, 5, 5).count(), 2);
+        assert_eq!(Adjacency::Hex.neighbors(4, 0, 5, 5).count(), 3);
+    }
+
+    #[test]
+    fn test_torus_wraps() {
+        let mut corner: Vec<_> = Adjacency::Standard
+            .neighbors_in(Topology::Torus, 0, 0, 4, 3)
+            .collect();
+        corner.sort();
+        assert_eq!(
+            corner,
+            vec![
+                (0, 1),
+                (0, 2),
+                (1, 0),
+                (1, 1),
+                (1, 2),
+                (3, 0),
+                (3, 1),
+                (3, 2)
+            ]
+        );
+        assert_eq!(
+            Adjacency::Extended
+                .neighbors_in(Topology::Torus, 4, 4, 5, 5)
+                .count(),
+            24
+        );
+    }
+}
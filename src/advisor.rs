@@ -0,0 +1,290 @@
+use rand::Rng;
+
+use crate::generator::{board_rng, generate_mines};
+use crate::{GameConfig, GameState, Minesweeper};
+
+/// How one candidate first click fared over the simulated boards.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpeningStats {
+    pub click: (usize, usize),
+    /// Fraction of boards on which the click landed on a zero.
+    pub zero_chance: f64,
+    /// Mean number of safe tiles the click revealed.
+    pub expected_tiles: f64,
+}
+
+/// A recommended first click, from [`suggest_opening`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpeningAdvice {
+    /// The candidate with the largest expected opening.
+    pub best: OpeningStats,
+    /// Every candidate considered: the top-left corner, the middles of the
+    /// top and left edges, and the centre.
+    pub candidates: Vec<OpeningStats>,
+}
+
+/// Recommends a first click for boards made from `config` by generating
+/// `samples` boards per candidate and measuring the opening each click
+/// produces.
+///
+/// Boards are drawn from [`board_rng`] seeded with `seed`, so the advice
+/// for a config is reproducible. When the first click only protects
+/// itself ([`Opening::ClickOnly`](crate::Opening::ClickOnly)), corners
+/// usually win: with fewer neighbours they are likelier to be zeros.
+pub fn suggest_opening(config: &GameConfig, samples: usize, seed: u64) -> OpeningAdvice {
+    let (width, height) = (config.width, config.height);
+    let mut clicks: Vec<(usize, usize)> = Vec::new();
+    for click in [
+        (0, 0),
+        (width / 2, 0),
+        (0, height / 2),
+        (width / 2, height / 2),
+    ] {
+        // Thin boards make some candidates coincide.
+        if !clicks.contains(&click) {
+            clicks.push(click);
+        }
+    }
+
+    let mut rng = board_rng(seed);
+    let samples = samples.max(1);
+    let candidates: Vec<OpeningStats> = clicks
+        .into_iter()
+        .map(|click| {
+            let mut zeros = 0;
+            let mut tiles = 0;
+            for _ in 0..samples {
+                let (_, opened, revealed) = open_sample(config, click, &mut rng);
+                zeros += usize::from(opened);
+                tiles += revealed;
+            }
+            OpeningStats {
+                click,
+                zero_chance: zeros as f64 / samples as f64,
+                expected_tiles: tiles as f64 / samples as f64,
+            }
+        })
+        .collect();
+
+    let best = candidates
+        .iter()
+        .fold(None::<&OpeningStats>, |best, stats| match best {
+            Some(best) if best.expected_tiles >= stats.expected_tiles => Some(best),
+            _ => Some(stats),
+        })
+        .unwrap()
+        .clone();
+    OpeningAdvice { best, candidates }
+}
+
+/// How one first click fared over the boards sampled by
+/// [`first_click_stats`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FirstClickStats {
+    pub click: (usize, usize),
+    /// Mean number of safe tiles the click revealed.
+    pub mean_opening: f64,
+    /// Fraction of boards on which the click landed on a zero.
+    pub zero_chance: f64,
+    /// The chance of dying on the first guess the opening leaves, averaged
+    /// over the boards. The first click itself is always safe, so this is
+    /// what makes a click deadly: after revealing every tile that is
+    /// certainly safe, the player has to click the covered tile least
+    /// likely to be a mine. Boards cleared without a guess count as 0.
+    pub death_chance: f64,
+}
+
+/// Every tile's [`FirstClickStats`] for one config.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FirstClickGrid {
+    pub width: usize,
+    pub height: usize,
+    /// Boards generated per tile.
+    pub samples: usize,
+    /// In row-major order: `cells[y * width + x]` is the click at (x, y).
+    pub cells: Vec<FirstClickStats>,
+}
+
+impl FirstClickGrid {
+    pub fn get(&self, x: usize, y: usize) -> Option<&FirstClickStats> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    /// The grid as three tab-separated tables, one per statistic, each
+    /// headed by a `# name` line and separated by a blank line, for
+    /// spreadsheets and heatmap tools.
+    pub fn to_tsv(&self) -> String {
+        let mut text = String::new();
+        for (i, name) in ["mean_opening", "zero_chance", "death_chance"]
+            .iter()
+            .enumerate()
+        {
+            if i > 0 {
+                text.push('\n');
+            }
+            text.push_str(&format!("# {}\n", name));
+            for row in self.cells.chunks(self.width.max(1)) {
+                let row: Vec<String> = row
+                    .iter()
+                    .map(|stats| match i {
+                        0 => stats.mean_opening,
+                        1 => stats.zero_chance,
+                        _ => stats.death_chance,
+                    })
+                    .map(|value| format!("{:.3}", value))
+                    .collect();
+                text.push_str(&row.join("\t"));
+                text.push('\n');
+            }
+        }
+        text
+    }
+}
+
+/// Measures every possible first click on boards made from `config`, by
+/// generating `samples` boards around each tile and playing out the
+/// opening. Answers "where should I click first" for the config
+/// empirically; [`suggest_opening`] is the quick version that only tries
+/// a few candidates.
+///
+/// Reproducible for a given `seed`. Costs `width * height * samples`
+/// boards, each solved up to its first guess, so keep boards and sample
+/// counts modest.
+pub fn first_click_stats(config: &GameConfig, samples: usize, seed: u64) -> FirstClickGrid {
+    let (width, height) = (config.width, config.height);
+    let mut rng = board_rng(seed);
+    let samples = samples.max(1);
+    let mut cells = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for x in 0..width {
+            let (mut zeros, mut tiles, mut death) = (0, 0, 0.0);
+            for _ in 0..samples {
+                let (mut game, opened, revealed) = open_sample(config, (x, y), &mut rng);
+                zeros += usize::from(opened);
+                tiles += revealed;
+                death += first_guess_risk(&mut game);
+            }
+            cells.push(FirstClickStats {
+                click: (x, y),
+                mean_opening: tiles as f64 / samples as f64,
+                zero_chance: zeros as f64 / samples as f64,
+                death_chance: death / samples as f64,
+            });
+        }
+    }
+    FirstClickGrid {
+        width,
+        height,
+        samples,
+        cells,
+    }
+}
+
+/// Generates a board from `config` around `click` and clicks it. Returns
+/// the game, whether the click was a zero and how many tiles it revealed.
+fn open_sample<R: Rng + ?Sized>(
+    config: &GameConfig,
+    click: (usize, usize),
+    rng: &mut R,
+) -> (Minesweeper, bool, usize) {
+    let (width, height) = (config.width, config.height);
+    let mines = generate_mines(
+        width,
+        height,
+        config.bomb_count,
+        click,
+        &config.generator,
+        rng,
+    );
+    let mut game = Minesweeper::new_rect_with_topology(
+        width,
+        height,
+        mines,
+        config.get_adjacency(),
+        config.generator.topology,
+    );
+    let zero = game.get_tile(click.0, click.1).and_then(|t| t.get_number()) == Some(0);
+    let covered = game.safe_tiles_remaining();
+    let mut revealed = 0;
+    if game.click_tile(click.0, click.1).is_ok() {
+        revealed = covered - game.safe_tiles_remaining();
+    }
+    (game, zero, revealed)
+}
+
+/// Reveals every tile that is certainly safe until none are left, then
+/// returns the mine probability of the safest covered tile, or 0 if the
+/// board was cleared.
+fn first_guess_risk(game: &mut Minesweeper) -> f64 {
+    while *game.get_game_state() == GameState::InProgress {
+        let probabilities = game.mine_probabilities();
+        let mut safest = f64::INFINITY;
+        let mut safe = Vec::new();
+        for (x, column) in probabilities.iter().enumerate() {
+            for (y, &p) in column.iter().enumerate() {
+                if game.board[(x, y)].exposed {
+                    continue;
+                }
+                if p <= f64::EPSILON {
+                    safe.push((x, y));
+                }
+                safest = safest.min(p);
+            }
+        }
+        if safe.is_empty() {
+            return safest;
+        }
+        for (x, y) in safe {
+            let _ = game.click_tile(x, y);
+        }
+    }
+    0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::Opening;
+
+    #[test]
+    fn test_corner_is_best_when_clicks_are_unprotected() {
+        let mut config = GameConfig::new(9, 25);
+        config.generator.opening = Opening::ClickOnly;
+
+        let advice = suggest_opening(&config, 200, 7);
+        assert_eq!(advice.candidates.len(), 4);
+        assert_eq!(advice.best.click, (0, 0));
+        let center = advice.candidates.last().unwrap();
+        assert!(advice.best.zero_chance > center.zero_chance);
+        assert_eq!(suggest_opening(&config, 200, 7), advice);
+    }
+
+    #[test]
+    fn test_first_click_grid() {
+        let mut config = GameConfig::new(5, 4);
+        config.generator.opening = Opening::ClickOnly;
+        let grid = first_click_stats(&config, 20, 3);
+        assert_eq!(grid.cells.len(), 25);
+        assert_eq!(grid.get(4, 0).unwrap().click, (4, 0));
+        assert!(grid.get(5, 0).is_none());
+        for stats in &grid.cells {
+            assert!(stats.mean_opening >= 1.0);
+            assert!((0.0..=1.0).contains(&stats.death_chance));
+        }
+        let corner = grid.get(0, 0).unwrap();
+        let centre = grid.get(2, 2).unwrap();
+        assert!(corner.zero_chance > centre.zero_chance);
+        assert_eq!(first_click_stats(&config, 20, 3), grid);
+
+        let tsv = grid.to_tsv();
+        assert!(tsv.starts_with("# mean_opening\n"));
+        assert!(tsv.contains("\n\n# death_chance\n"));
+        assert_eq!(tsv.lines().count(), 3 * 6 + 2);
+    }
+
+    #[test]
+    fn test_protected_clicks_always_open_a_zero() {
+        let advice = suggest_opening(&GameConfig::new(9, 10), 50, 1);
+        assert!(advice.candidates.iter().all(|c| c.zero_chance == 1.0));
+    }
+}
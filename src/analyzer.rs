@@ -0,0 +1,188 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::probability::ProbabilityEngine;
+use crate::{Analysis, Minesweeper};
+
+/// The analysis of one position submitted to an [`Analyzer`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalysisResult {
+    /// Which submission this answers, as returned by [`Analyzer::submit`].
+    pub generation: u64,
+    /// Tiles proven safe or mined, from [`Minesweeper::analyze`].
+    pub analysis: Analysis,
+    /// The chance each tile is a mine, indexed by `x * height + y`.
+    pub probabilities: Vec<f64>,
+}
+
+struct Queue {
+    /// The newest position not yet picked up by a worker.
+    pending: Option<(u64, Minesweeper)>,
+    shutdown: bool,
+}
+
+struct Shared {
+    queue: Mutex<Queue>,
+    wake: Condvar,
+    /// The generation of the newest submission; anything older is stale.
+    latest: AtomicU64,
+}
+
+/// Runs the solver and probability engine on background threads, so an
+/// interactive frontend can keep handling input while an overlay is
+/// computed.
+///
+/// Submit each new position with [`Analyzer::submit`] and collect answers
+/// with [`Analyzer::poll`] or [`Analyzer::wait`]. A submission replaces any
+/// position still queued, and work on a position that has been superseded
+/// is abandoned between stages, so results never arrive for a board the
+/// player has already moved on from.
+pub struct Analyzer {
+    shared: Arc<Shared>,
+    results: Receiver<AnalysisResult>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl Analyzer {
+    /// Starts `threads` worker threads (at least one).
+    pub fn new(threads: usize) -> Self {
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(Queue {
+                pending: None,
+                shutdown: false,
+            }),
+            wake: Condvar::new(),
+            latest: AtomicU64::new(0),
+        });
+        let (sender, results) = mpsc::channel();
+
+        let workers = (0..threads.max(1))
+            .map(|_| {
+                let shared = shared.clone();
+                let sender = sender.clone();
+                thread::spawn(move || work(&shared, &sender))
+            })
+            .collect();
+
+        Analyzer {
+            shared,
+            results,
+            workers,
+        }
+    }
+
+    /// Queues `game`'s current position for analysis and returns its
+    /// generation. Earlier positions are cancelled.
+    pub fn submit(&self, game: &Minesweeper) -> u64 {
+        let position = game.position();
+        let mut queue = self.shared.queue.lock().unwrap();
+        let generation = self.shared.latest.fetch_add(1, Ordering::SeqCst) + 1;
+        queue.pending = Some((generation, position));
+        self.shared.wake.notify_one();
+        generation
+    }
+
+    /// The result for the latest submission, if it is ready. Results for
+    /// superseded positions are discarded.
+    pub fn poll(&self) -> Option<AnalysisResult> {
+        let latest = self.shared.latest.load(Ordering::SeqCst);
+        self.results
+            .try_iter()
+            .filter(|result| result.generation == latest)
+            .last()
+    }
+
+    /// Blocks until the result for the latest submission arrives, or
+    /// `timeout` passes.
+    pub fn wait(&self, timeout: Duration) -> Option<AnalysisResult> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.results.recv_timeout(remaining) {
+                Ok(result) if result.generation == self.shared.latest.load(Ordering::SeqCst) => {
+                    return Some(result)
+                }
+                Ok(_) => {}
+                Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => return None,
+            }
+        }
+    }
+}
+
+impl Drop for Analyzer {
+    fn drop(&mut self) {
+        self.shared.queue.lock().unwrap().shutdown = true;
+        self.shared.wake.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn work(shared: &Shared, results: &Sender<AnalysisResult>) {
+    // Each worker keeps its own engine, and with it its own cache.
+    let mut engine = ProbabilityEngine::default();
+    let stale = |generation| shared.latest.load(Ordering::SeqCst) != generation;
+
+    loop {
+        let (generation, game) = {
+            let mut queue = shared.queue.lock().unwrap();
+            loop {
+                if queue.shutdown {
+                    return;
+                }
+                if let Some(pending) = queue.pending.take() {
+                    break pending;
+                }
+                queue = shared.wake.wait(queue).unwrap();
+            }
+        };
+
+        let analysis = game.analyze();
+        if stale(generation) {
+            continue;
+        }
+        let probabilities = engine.compute(&game);
+        if stale(generation) {
+            continue;
+        }
+
+        let result = AnalysisResult {
+            generation,
+            analysis,
+            probabilities,
+        };
+        if results.send(result).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_results_arrive_in_the_background() {
+        let mut game = Minesweeper::new_rect(4, 2, vec![(0, 0), (3, 0)]);
+        game.click_tile(0, 1).unwrap();
+        let analyzer = Analyzer::new(2);
+
+        let first = analyzer.submit(&game);
+        for x in 1..4 {
+            game.click_tile(x, 1).unwrap();
+        }
+        let second = analyzer.submit(&game);
+        assert_eq!(second, first + 1);
+
+        let result = analyzer.wait(Duration::from_secs(10)).unwrap();
+        assert_eq!(result.generation, second);
+        assert_eq!(result.analysis.mines, BTreeSet::from([(0, 0), (3, 0)]));
+        assert_eq!(result.probabilities[2], 0.0);
+        assert_eq!(analyzer.poll(), None);
+    }
+}
@@ -0,0 +1,196 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::{GameEvent, GameState, Minesweeper, MinesweeperError, Move};
+
+/// Which tiles [`Minesweeper::reveal_random_safe`] may pick from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AssistFairness {
+    /// Only tiles the player could prove safe from what is showing, so the
+    /// assist saves time but gives nothing away.
+    #[default]
+    Provable,
+    /// Any safe tile, using knowledge of the mines the player doesn't have.
+    AnySafe,
+}
+
+impl Minesweeper {
+    /// Reveals a covered, unflagged safe tile chosen uniformly at random
+    /// from those `fairness` allows, and returns the move it was played as.
+    ///
+    /// The reveal is recorded as [`Move::Assist`], so replays and histories
+    /// show which tiles the player didn't find themselves. Fails if no
+    /// tile qualifies.
+    pub fn reveal_random_safe<R: Rng + ?Sized>(
+        &mut self,
+        fairness: AssistFairness,
+        rng: &mut R,
+    ) -> Result<Move, String> {
+        if self.game_state != GameState::InProgress {
+            return Err(self.finished_error().into());
+        }
+
+        let candidates: Vec<(usize, usize)> = match fairness {
+            AssistFairness::Provable => self
+                .analyze()
+                .safe
+                .into_iter()
+                .filter(|&(x, y)| !self.board[(x, y)].flagged)
+                .collect(),
+            AssistFairness::AnySafe => (0..self.width)
+                .flat_map(|x| (0..self.height).map(move |y| (x, y)))
+                .filter(|&(x, y)| {
+                    let tile = &self.board[(x, y)];
+                    !tile.exposed && !tile.flagged && !tile.is_bomb()
+                })
+                .collect(),
+        };
+
+        let &(x, y) = candidates
+            .choose(rng)
+            .ok_or_else(|| "No safe tile to reveal".to_string())?;
+        self.reveal_assisted(x, y)?;
+        Ok(Move::Assist(x, y))
+    }
+
+    /// Plays [`Move::Assist`]: reveals (x, y), which must be safe.
+    pub(crate) fn reveal_assisted(&mut self, x: usize, y: usize) -> Result<(), MinesweeperError> {
+        if self.get_tile(x, y).is_some_and(|tile| tile.is_bomb()) {
+            return Err(MinesweeperError::NotSafe { pos: (x, y) });
+        }
+        self.reveal(x, y, Move::Assist(x, y))
+    }
+
+    pub fn flag_check_enabled(&self) -> bool {
+        self.flag_check
+    }
+
+    /// Turns the flag check assist on or off. While it is on, placing a
+    /// flag that leaves a number with more flags around it than it shows
+    /// sends a [`GameEvent::FlagConflict`], and renderers highlight the
+    /// number. Off by default for purist play; whether it was used is
+    /// kept in the game's [`GameSummary`](crate::GameSummary).
+    pub fn set_flag_check(&mut self, enabled: bool) {
+        self.flag_check = enabled;
+    }
+
+    /// Whether the number at (x, y) has more flags around it than it shows.
+    /// Always false for covered tiles.
+    pub fn is_over_flagged(&self, x: usize, y: usize) -> bool {
+        let Some(number) = self
+            .get_tile(x, y)
+            .filter(|tile| tile.exposed)
+            .and_then(|tile| tile.get_number())
+        else {
+            return false;
+        };
+        let flags = self
+            .neighbor_tiles(x, y)
+            .filter(|(_, _, tile)| tile.flagged)
+            .count();
+        flags > number as usize
+    }
+
+    /// Every exposed number with more flags around it than it shows.
+    pub fn over_flagged_numbers(&self) -> Vec<(usize, usize)> {
+        self.exposed_tiles()
+            .map(|(x, y, _)| (x, y))
+            .filter(|&(x, y)| self.is_over_flagged(x, y))
+            .collect()
+    }
+
+    /// Reports the numbers a new flag at (x, y) over-flagged, when the
+    /// flag check is on.
+    pub(crate) fn check_flag_conflicts(&mut self, x: usize, y: usize) {
+        if !self.flag_check {
+            return;
+        }
+        let conflicts: Vec<(usize, usize)> = self
+            .neighbors(x, y)
+            .filter(|&(nx, ny)| self.is_over_flagged(nx, ny))
+            .collect();
+        for (nx, ny) in conflicts {
+            self.emit(GameEvent::FlagConflict { x: nx, y: ny });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board_rng;
+
+    #[test]
+    fn test_flag_check() {
+        // (1, 0) is a 1 with two covered neighbours.
+        let mut game = Minesweeper::new_rect(3, 2, vec![(0, 1)]);
+        game.click_tile(2, 0).unwrap();
+        game.set_event_queue(true);
+
+        game.toggle_flag(0, 0).unwrap();
+        game.toggle_flag(0, 1).unwrap();
+        assert_eq!(game.over_flagged_numbers(), vec![(1, 0), (1, 1)]);
+        // Off by default, so nothing was said.
+        assert!(!game
+            .drain_events()
+            .iter()
+            .any(|event| matches!(event, GameEvent::FlagConflict { .. })));
+
+        game.set_flag_check(true);
+        game.toggle_flag(0, 1).unwrap();
+        assert!(game.over_flagged_numbers().is_empty());
+        game.toggle_flag(0, 1).unwrap();
+        let conflicts: Vec<_> = game
+            .drain_events()
+            .into_iter()
+            .filter(|event| matches!(event, GameEvent::FlagConflict { .. }))
+            .collect();
+        assert_eq!(
+            conflicts,
+            vec![
+                GameEvent::FlagConflict { x: 1, y: 0 },
+                GameEvent::FlagConflict { x: 1, y: 1 },
+            ]
+        );
+        assert!(!game.is_over_flagged(0, 0));
+    }
+
+    #[test]
+    fn test_provable_assist() {
+        let mut game = Minesweeper::new_rect(4, 2, vec![(0, 0), (3, 0)]);
+        for x in 0..4 {
+            game.click_tile(x, 1).unwrap();
+        }
+        game.set_history(true);
+
+        let mut rng = board_rng(3);
+        let first = game
+            .reveal_random_safe(AssistFairness::Provable, &mut rng)
+            .unwrap();
+        let second = game
+            .reveal_random_safe(AssistFairness::Provable, &mut rng)
+            .unwrap();
+        let mut picked = [first, second];
+        picked.sort_by_key(|mv| mv.to_string());
+        assert_eq!(picked, [Move::Assist(1, 0), Move::Assist(2, 0)]);
+        assert_eq!(game.history(), vec![first, second]);
+        assert_eq!(*game.get_game_state(), GameState::Won);
+    }
+
+    #[test]
+    fn test_assists_need_a_safe_tile() {
+        let mut game = Minesweeper::new(2, vec![(0, 0)]);
+        game.click_tile(1, 1).unwrap();
+        let mut rng = board_rng(1);
+
+        assert_eq!(
+            game.reveal_random_safe(AssistFairness::Provable, &mut rng),
+            Err("No safe tile to reveal".to_string())
+        );
+        let mv = game
+            .reveal_random_safe(AssistFairness::AnySafe, &mut rng)
+            .unwrap();
+        assert_ne!(mv, Move::Assist(0, 0));
+        assert!(game.apply_move(Move::Assist(0, 0)).is_err());
+    }
+}
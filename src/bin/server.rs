@@ -0,0 +1,43 @@
+//! Hosts a shared Minesweeper board over WebSockets.
+//!
+//! Usage: `minesweeper-server [ADDRESS]`, listening on 127.0.0.1:9001 by
+//! default. Open web/index.html in a browser to play; every browser
+//! connected plays on the same board.
+
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use minesweeper::{GameServer, RateLimit};
+use tokio::net::TcpListener;
+
+/// Moves per second each player may keep up, in bursts of up to
+/// `MOVE_BURST`: quicker than anyone clicks, slower than a script.
+const MOVES_PER_SECOND: f64 = 10.0;
+const MOVE_BURST: u32 = 20;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:9001".to_string());
+    let listener = match TcpListener::bind(&address).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Cannot listen on {}: {}", address, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("Serving games on ws://{}", address);
+
+    let server = Arc::new(GameServer::with_policy(RateLimit::new(
+        MOVES_PER_SECOND,
+        MOVE_BURST,
+    )));
+    match server.serve(listener).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Server stopped: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
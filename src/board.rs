@@ -0,0 +1,118 @@
+use std::ops::Index;
+use std::sync::Arc;
+
+use crate::Tile;
+
+/// Tiles per storage chunk. Large enough that walking a board touches
+/// contiguous memory, small enough that a move copies little when a
+/// snapshot still shares the chunk it writes to.
+pub(crate) const CHUNK: usize = 1024;
+
+/// The tiles of a board in one flat sequence, indexed `x * height + y`.
+///
+/// The sequence is split into fixed-size chunks that are reference counted
+/// and copied on write, so snapshots and undo checkpoints share storage
+/// with the live board until it changes. Reads are plain index arithmetic:
+/// chunks don't follow the board's shape, so a board of any size needs only
+/// `width * height / CHUNK` allocations.
+#[derive(Debug, Clone)]
+pub(crate) struct Board {
+    chunks: Vec<Arc<Vec<Tile>>>,
+    width: usize,
+    height: usize,
+}
+
+impl Board {
+    /// A board holding `tiles`, indexed `x * height + y`.
+    pub(crate) fn from_tiles(width: usize, height: usize, tiles: Vec<Tile>) -> Self {
+        debug_assert_eq!(tiles.len(), width * height);
+        let chunks = tiles
+            .chunks(CHUNK)
+            .map(|chunk| Arc::new(chunk.to_vec()))
+            .collect();
+        Board {
+            chunks,
+            width,
+            height,
+        }
+    }
+
+    pub(crate) fn width(&self) -> usize {
+        self.width
+    }
+
+    pub(crate) fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The tile at (x, y) for writing, copying its chunk first if anything
+    /// else still shares it.
+    pub(crate) fn tile_mut(&mut self, x: usize, y: usize) -> &mut Tile {
+        let index = self.index(x, y);
+        &mut Arc::make_mut(&mut self.chunks[index / CHUNK])[index % CHUNK]
+    }
+
+    /// Every tile with its position, in index order.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = ((usize, usize), &Tile)> + '_ {
+        let height = self.height;
+        self.tiles()
+            .enumerate()
+            .map(move |(index, tile)| ((index / height, index % height), tile))
+    }
+
+    /// Every tile, in index order.
+    pub(crate) fn tiles(&self) -> impl Iterator<Item = &Tile> + '_ {
+        self.chunks.iter().flat_map(|chunk| chunk.iter())
+    }
+
+    pub(crate) fn chunks(&self) -> &[Arc<Vec<Tile>>] {
+        &self.chunks
+    }
+
+    /// Applies `f` to every tile matching `filter`, leaving chunks with no
+    /// match shared.
+    pub(crate) fn update_where(&mut self, filter: impl Fn(&Tile) -> bool, f: impl Fn(&mut Tile)) {
+        for chunk in &mut self.chunks {
+            if !chunk.iter().any(&filter) {
+                continue;
+            }
+            for tile in Arc::make_mut(chunk).iter_mut().filter(|tile| filter(tile)) {
+                f(tile);
+            }
+        }
+    }
+
+    fn index(&self, x: usize, y: usize) -> usize {
+        debug_assert!(x < self.width && y < self.height);
+        x * self.height + y
+    }
+}
+
+impl Index<(usize, usize)> for Board {
+    type Output = Tile;
+
+    fn index(&self, (x, y): (usize, usize)) -> &Tile {
+        let index = Board::index(self, x, y);
+        &self.chunks[index / CHUNK][index % CHUNK]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_are_copied_on_write() {
+        let mut board = Board::from_tiles(40, 40, vec![Tile::new(); 40 * 40]);
+        assert_eq!(board.chunks().len(), 2);
+        assert_eq!(board.chunks()[1].len(), 40 * 40 - CHUNK);
+
+        let copy = board.clone();
+        board.tile_mut(39, 39).flagged = true;
+        assert!(board[(39, 39)].flagged);
+        assert!(!copy[(39, 39)].flagged);
+        assert!(Arc::ptr_eq(&board.chunks()[0], &copy.chunks()[0]));
+        assert!(!Arc::ptr_eq(&board.chunks()[1], &copy.chunks()[1]));
+        assert_eq!(board.iter().last().unwrap().0, (39, 39));
+    }
+}
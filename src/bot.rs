@@ -0,0 +1,137 @@
+use crate::{GameConfig, GameState, Hint, Minesweeper, Move};
+
+/// One move the [`Bot`] made.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BotMove {
+    pub mv: Move,
+    /// For a guess, the chance the tile was a mine; `None` for moves the
+    /// bot could prove safe.
+    pub guess: Option<f64>,
+}
+
+/// How a game played by the [`Bot`] went.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BotReport {
+    /// Every move, in order.
+    pub moves: Vec<BotMove>,
+    /// The state the game was left in.
+    pub state: GameState,
+}
+
+impl BotReport {
+    pub fn won(&self) -> bool {
+        self.state == GameState::Won
+    }
+
+    /// How many moves were guesses.
+    pub fn guesses(&self) -> usize {
+        self.moves.iter().filter(|mv| mv.guess.is_some()).count()
+    }
+}
+
+/// Plays Minesweeper by itself: reveals what the solver proves safe, flags
+/// what it proves are mines, and otherwise guesses the tile least likely
+/// to be a mine. Like [`Minesweeper::hint`], which it follows, it only
+/// uses what a player could see.
+pub struct Bot;
+
+impl Bot {
+    /// The move the bot would make next, or `None` once the game is over.
+    /// Lets a bot take its turns one at a time, e.g. as an opponent in a
+    /// [`GameSession`](crate::GameSession).
+    pub fn next_move(game: &Minesweeper) -> Option<BotMove> {
+        let (mv, guess) = match game.hint().ok()? {
+            Hint::Safe(x, y) => (Move::Reveal(x, y), None),
+            Hint::Flag(x, y) => (Move::ToggleFlag(x, y), None),
+            Hint::Guess { x, y, probability } => (Move::Reveal(x, y), Some(probability)),
+        };
+        Some(BotMove { mv, guess })
+    }
+
+    /// Plays `game` until it is won or lost.
+    pub fn play(game: &mut Minesweeper) -> BotReport {
+        let mut moves = Vec::new();
+        while let Some(mv) = Self::next_move(game) {
+            if game.apply_move(mv.mv).is_err() {
+                break;
+            }
+            moves.push(mv);
+        }
+        BotReport {
+            moves,
+            state: game.get_game_state().clone(),
+        }
+    }
+
+    /// The share of `games` boards made from `config` the bot wins, opening
+    /// in the middle. Board `i` is made with seed `seed + i`, so the same
+    /// arguments always give the same rate; useful for comparing how
+    /// hard generator settings make boards.
+    pub fn win_rate(config: &GameConfig, games: usize, seed: u64) -> Result<f64, String> {
+        let first_click = (config.width / 2, config.height / 2);
+        let mut won = 0;
+        for i in 0..games {
+            let mut game =
+                Minesweeper::new_with_config_and_seed(config, first_click, seed + i as u64)
+                    .map_err(|e| e.to_string())?;
+            won += usize::from(Self::play(&mut game).won());
+        }
+        Ok(won as f64 / games.max(1) as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FlagRule;
+
+    #[test]
+    fn test_bot_solves_a_board_without_guessing() {
+        // Mines along the bottom row; every other tile is provable.
+        let mut game = Minesweeper::new_rect(4, 4, vec![(0, 3), (2, 3)]);
+        game.click_tile(3, 0).unwrap();
+        let report = Bot::play(&mut game);
+        assert!(report.won());
+        assert_eq!(report.guesses(), 0);
+        assert!(report
+            .moves
+            .iter()
+            .all(|mv| !matches!(mv.mv, Move::Reveal(0, 3) | Move::Reveal(2, 3))));
+        assert_eq!(Bot::next_move(&game), None);
+    }
+
+    #[test]
+    fn test_bot_plays_every_game_to_the_end() {
+        let config = GameConfig::new(9, 9);
+        for seed in 0..10 {
+            let mut game = Minesweeper::new_with_config_and_seed(&config, (4, 4), seed).unwrap();
+            let report = Bot::play(&mut game);
+            assert_ne!(report.state, GameState::InProgress);
+            // Only a guess can lose.
+            if !report.won() {
+                assert!(report.moves.last().unwrap().guess.is_some());
+            }
+        }
+
+        let rate = Bot::win_rate(&config, 20, 1).unwrap();
+        assert!(rate > 0.0 && rate <= 1.0);
+        assert_eq!(Bot::win_rate(&config, 20, 1).unwrap(), rate);
+    }
+
+    #[test]
+    fn test_bot_plays_without_flags() {
+        let config = GameConfig {
+            flag_rule: FlagRule::Forbidden,
+            ..GameConfig::new(9, 9)
+        };
+        for seed in 0..50 {
+            let mut game = Minesweeper::new_with_config_and_seed(&config, (4, 4), seed).unwrap();
+            let report = Bot::play(&mut game);
+            assert!(report.state.is_over(), "seed {} got stuck", seed);
+            assert!(report
+                .moves
+                .iter()
+                .all(|mv| !matches!(mv.mv, Move::ToggleFlag(..))));
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use std::time::Duration;
+
+/// How much thinking time a player gets, chess-clock style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeControl {
+    /// The player's bank of time for the whole game.
+    pub initial: Duration,
+    /// Added to the bank after every move.
+    pub increment: Duration,
+    /// The most a single move may take, if limited.
+    pub per_move: Option<Duration>,
+}
+
+impl TimeControl {
+    /// A plain game clock with no increment or per-move limit.
+    pub fn sudden_death(initial: Duration) -> Self {
+        TimeControl {
+            initial,
+            increment: Duration::ZERO,
+            per_move: None,
+        }
+    }
+}
+
+/// What a clock shows, for frontends rendering countdowns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockState {
+    pub remaining: Duration,
+    /// Time left for the current move under a per-move limit.
+    pub move_remaining: Option<Duration>,
+    pub timed_out: bool,
+}
+
+/// One player's clock, run down by [`Clock::tick`] and topped up by
+/// [`Clock::on_move`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clock {
+    control: TimeControl,
+    remaining: Duration,
+    this_move: Duration,
+    timed_out: bool,
+}
+
+impl Clock {
+    pub fn new(control: TimeControl) -> Self {
+        Clock {
+            control,
+            remaining: control.initial,
+            this_move: Duration::ZERO,
+            timed_out: false,
+        }
+    }
+
+    pub fn control(&self) -> TimeControl {
+        self.control
+    }
+
+    /// Runs the clock for `delta`, returning true if that made it run out,
+    /// either of the bank or of the current move's allowance.
+    pub fn tick(&mut self, delta: Duration) -> bool {
+        if self.timed_out {
+            return false;
+        }
+        self.this_move += delta;
+        self.remaining = self.remaining.saturating_sub(delta);
+
+        let move_expired = self
+            .control
+            .per_move
+            .is_some_and(|limit| self.this_move >= limit);
+        self.timed_out = self.remaining.is_zero() || move_expired;
+        self.timed_out
+    }
+
+    /// Records a completed move: adds the increment and starts the next
+    /// move's allowance.
+    pub fn on_move(&mut self) {
+        if self.timed_out {
+            return;
+        }
+        self.remaining += self.control.increment;
+        self.this_move = Duration::ZERO;
+    }
+
+    pub fn is_timed_out(&self) -> bool {
+        self.timed_out
+    }
+
+    pub fn state(&self) -> ClockState {
+        ClockState {
+            remaining: self.remaining,
+            move_remaining: self
+                .control
+                .per_move
+                .map(|limit| limit.saturating_sub(self.this_move)),
+            timed_out: self.timed_out,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(n: u64) -> Duration {
+        Duration::from_secs(n)
+    }
+
+    #[test]
+    fn test_increment_and_bank() {
+        let mut clock = Clock::new(TimeControl {
+            initial: secs(10),
+            increment: secs(2),
+            per_move: None,
+        });
+
+        assert!(!clock.tick(secs(4)));
+        clock.on_move();
+        assert_eq!(clock.state().remaining, secs(8));
+        assert_eq!(clock.state().move_remaining, None);
+
+        assert!(clock.tick(secs(8)));
+        assert!(clock.is_timed_out());
+        // Already out: further ticks and moves change nothing.
+        assert!(!clock.tick(secs(1)));
+        clock.on_move();
+        assert_eq!(clock.state().remaining, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_per_move_limit() {
+        let mut clock = Clock::new(TimeControl {
+            initial: secs(60),
+            increment: Duration::ZERO,
+            per_move: Some(secs(5)),
+        });
+
+        assert!(!clock.tick(secs(4)));
+        assert_eq!(clock.state().move_remaining, Some(secs(1)));
+        clock.on_move();
+        assert_eq!(clock.state().move_remaining, Some(secs(5)));
+
+        assert!(!clock.tick(secs(3)));
+        assert!(clock.tick(secs(2)));
+        assert_eq!(clock.state().remaining, secs(51));
+    }
+}
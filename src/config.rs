@@ -0,0 +1,665 @@
+use std::time::Duration;
+
+use crate::generator::{Area, Corridor, Density, GeneratorOptions, Opening, Symmetry};
+use crate::{
+    Adjacency, FlagRule, MineCount, TimeControl, Topology, WinCondition, ZeroRule, BOARD_TOO_LARGE,
+    NO_FLAGS_TO_WIN,
+};
+
+/// Every rule and parameter a game is created with.
+///
+/// A config is enough to set up an equivalent game with
+/// [`Minesweeper::new_with_config`](crate::Minesweeper::new_with_config),
+/// and games report theirs through
+/// [`Minesweeper::config`](crate::Minesweeper::config), so a game's rules
+/// can travel with its saves and replays via
+/// [`GameConfig::to_config_string`].
+///
+/// Fields may be added in minor releases, so outside the crate a config is
+/// built with [`GameConfig::new_rect`] and the `with_*` methods rather than
+/// a struct literal:
+///
+/// ```
+/// use minesweeper::{FlagRule, GameConfig};
+///
+/// let config = GameConfig::new_rect(16, 16, 40)
+///     .with_flag_rule(FlagRule::Forbidden)
+///     .with_lives(1);
+/// assert_eq!(config.lives, 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct GameConfig {
+    pub width: usize,
+    pub height: usize,
+    pub bomb_count: usize,
+    pub flag_rule: FlagRule,
+    pub zero_rule: ZeroRule,
+    pub win_condition: WinCondition,
+    /// What the player is told of the mine count. With a range, games
+    /// draw their mine count from it instead of using `bomb_count`.
+    pub mine_count: MineCount,
+    /// Whether marking cycles through a question mark after the flag.
+    pub question_marks: bool,
+    /// Whether the remaining mines are flagged when the game is won.
+    pub auto_flag: bool,
+    /// How many mines the player can hit and carry on, each costing a
+    /// life; 0 for the classic rule.
+    pub lives: usize,
+    /// The clock each player is put on, if the game is timed. The game
+    /// doesn't keep time itself; a [`GameManager`](crate::GameManager) or
+    /// [`GameSession`](crate::GameSession) running it does.
+    pub time_control: Option<TimeControl>,
+    /// How mines are laid out, including the [`Adjacency`] the game is
+    /// played under.
+    pub generator: GeneratorOptions,
+}
+
+impl GameConfig {
+    /// A square board.
+    pub fn new(size: usize, bomb_count: usize) -> Self {
+        Self::new_rect(size, size, bomb_count)
+    }
+
+    pub fn new_rect(width: usize, height: usize, bomb_count: usize) -> Self {
+        GameConfig {
+            width,
+            height,
+            bomb_count,
+            ..Default::default()
+        }
+    }
+
+    /// A board with `density` of its tiles mined, rounded to the nearest
+    /// whole mine, with at least one mine and room for a first click.
+    pub fn from_density(width: usize, height: usize, density: f64) -> Self {
+        let tiles = width * height;
+        let mines = (density * tiles as f64).round() as usize;
+        Self::new_rect(
+            width,
+            height,
+            mines.clamp(1, tiles.saturating_sub(1).max(1)),
+        )
+    }
+
+    pub fn get_adjacency(&self) -> Adjacency {
+        self.generator.adjacency
+    }
+
+    pub fn with_flag_rule(mut self, flag_rule: FlagRule) -> Self {
+        self.flag_rule = flag_rule;
+        self
+    }
+
+    pub fn with_zero_rule(mut self, zero_rule: ZeroRule) -> Self {
+        self.zero_rule = zero_rule;
+        self
+    }
+
+    pub fn with_win_condition(mut self, win_condition: WinCondition) -> Self {
+        self.win_condition = win_condition;
+        self
+    }
+
+    pub fn with_mine_count(mut self, mine_count: MineCount) -> Self {
+        self.mine_count = mine_count;
+        self
+    }
+
+    pub fn with_question_marks(mut self, question_marks: bool) -> Self {
+        self.question_marks = question_marks;
+        self
+    }
+
+    pub fn with_auto_flag(mut self, auto_flag: bool) -> Self {
+        self.auto_flag = auto_flag;
+        self
+    }
+
+    pub fn with_lives(mut self, lives: usize) -> Self {
+        self.lives = lives;
+        self
+    }
+
+    pub fn with_time_control(mut self, time_control: TimeControl) -> Self {
+        self.time_control = Some(time_control);
+        self
+    }
+
+    pub fn with_generator(mut self, generator: GeneratorOptions) -> Self {
+        self.generator = generator;
+        self
+    }
+
+    /// Shorthand for setting the generator's adjacency.
+    pub fn with_adjacency(mut self, adjacency: Adjacency) -> Self {
+        self.generator.adjacency = adjacency;
+        self
+    }
+
+    /// Shorthand for setting the generator's topology.
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.generator.topology = topology;
+        self
+    }
+
+    /// Checks that a game can be created from this config.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.width == 0 || self.height == 0 {
+            return Err("Board size must be at least 1".to_string());
+        }
+        let tiles = self
+            .width
+            .checked_mul(self.height)
+            .ok_or_else(|| BOARD_TOO_LARGE.to_string())?;
+        if self.bomb_count >= tiles {
+            return Err("Too many mines for the board".to_string());
+        }
+        if let MineCount::Range { min, max } = self.mine_count {
+            if min > max {
+                return Err("Mine count range is empty".to_string());
+            }
+            if max >= tiles {
+                return Err("Too many mines for the board".to_string());
+            }
+        }
+        if self.generator.topology == Topology::Torus {
+            let across = 2 * self.generator.adjacency.radius() + 1;
+            if self.width < across || self.height < across {
+                return Err(format!(
+                    "Wrap-around boards must be at least {} tiles each way",
+                    across
+                ));
+            }
+        }
+        if let Some(corridor) = &self.generator.corridor {
+            let in_bounds = |(x, y): (usize, usize)| x < self.width && y < self.height;
+            if !in_bounds(corridor.from) || !in_bounds(corridor.to) {
+                return Err("Corridor is outside the board".to_string());
+            }
+        }
+        if self
+            .generator
+            .forbidden
+            .iter()
+            .any(|area| area.x >= self.width || area.y >= self.height)
+        {
+            return Err("Forbidden area is outside the board".to_string());
+        }
+        if self.win_condition == WinCondition::FlagAllMines && self.flag_rule == FlagRule::Forbidden
+        {
+            return Err(NO_FLAGS_TO_WIN.to_string());
+        }
+        if !self.generator.clustering.is_finite() {
+            return Err("Clustering must be a finite number".to_string());
+        }
+        Ok(())
+    }
+
+    /// One `key=value` per line. Custom densities are code, not data, and
+    /// can't be written out.
+    pub fn to_config_string(&self) -> Result<String, String> {
+        let options = &self.generator;
+        let density = match &options.density {
+            Density::Uniform => "uniform".to_string(),
+            Density::AlongX { start, end } => format!("along-x:{}:{}", start, end),
+            Density::AlongY { start, end } => format!("along-y:{}:{}", start, end),
+            Density::Radial { center, edge } => format!("radial:{}:{}", center, edge),
+            Density::Custom(_) => return Err("Custom densities cannot be saved".to_string()),
+        };
+        let corridor = match &options.corridor {
+            None => "none".to_string(),
+            Some(corridor) => format!(
+                "{},{}:{},{}:{}",
+                corridor.from.0, corridor.from.1, corridor.to.0, corridor.to.1, corridor.width
+            ),
+        };
+
+        let forbidden = if options.forbidden.is_empty() {
+            "none".to_string()
+        } else {
+            options
+                .forbidden
+                .iter()
+                .map(|area| format!("{},{}:{}x{}", area.x, area.y, area.width, area.height))
+                .collect::<Vec<_>>()
+                .join(";")
+        };
+
+        let mut text = String::new();
+        text.push_str(&format!("width={}\n", self.width));
+        text.push_str(&format!("height={}\n", self.height));
+        text.push_str(&format!("mines={}\n", self.bomb_count));
+        text.push_str(&format!("flags={}\n", flag_rule_name(self.flag_rule)));
+        text.push_str(&format!("zeros={}\n", zero_rule_name(self.zero_rule)));
+        let win = match self.win_condition {
+            WinCondition::Classic => "classic".to_string(),
+            WinCondition::FlagAllMines => "flag-all-mines".to_string(),
+            WinCondition::Score(target) => format!("score:{}", target),
+        };
+        text.push_str(&format!("win={}\n", win));
+        let mine_count = match self.mine_count {
+            MineCount::Exact => "exact".to_string(),
+            MineCount::Range { min, max } => format!("{}-{}", min, max),
+            MineCount::Hidden => "hidden".to_string(),
+        };
+        text.push_str(&format!("mine-count={}\n", mine_count));
+        text.push_str(&format!(
+            "question-marks={}\n",
+            if self.question_marks { "on" } else { "off" }
+        ));
+        text.push_str(&format!(
+            "auto-flag={}\n",
+            if self.auto_flag { "on" } else { "off" }
+        ));
+        text.push_str(&format!("lives={}\n", self.lives));
+        let timer = match self.time_control {
+            None => "none".to_string(),
+            Some(control) => timer_name(control),
+        };
+        text.push_str(&format!("timer={}\n", timer));
+        text.push_str(&format!(
+            "adjacency={}\n",
+            adjacency_name(options.adjacency)
+        ));
+        text.push_str(&format!(
+            "topology={}\n",
+            match options.topology {
+                Topology::Plane => "plane",
+                Topology::Torus => "torus",
+            }
+        ));
+        text.push_str(&format!("symmetry={}\n", symmetry_name(options.symmetry)));
+        text.push_str(&format!("density={}\n", density));
+        text.push_str(&format!("corridor={}\n", corridor));
+        text.push_str(&format!("forbidden={}\n", forbidden));
+        text.push_str(&format!("clustering={}\n", options.clustering));
+        text.push_str(&format!("opening={}\n", opening_name(options.opening)));
+        Ok(text)
+    }
+
+    /// Parses text produced by [`GameConfig::to_config_string`]. The
+    /// dimensions (`width` and `height`, or `size` for a square board) and
+    /// `mines` are required; anything else left out keeps its default.
+    pub fn from_config_string(text: &str) -> Result<Self, String> {
+        let mut config = GameConfig::default();
+        let (mut has_width, mut has_height, mut has_mines) = (false, false, false);
+
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("Expected key=value: {}", line))?;
+            let invalid = || format!("Invalid {}: {}", key, value);
+            let options = &mut config.generator;
+
+            match key {
+                "width" => {
+                    config.width = value.parse().map_err(|_| invalid())?;
+                    has_width = true;
+                }
+                "height" => {
+                    config.height = value.parse().map_err(|_| invalid())?;
+                    has_height = true;
+                }
+                "size" => {
+                    config.width = value.parse().map_err(|_| invalid())?;
+                    config.height = config.width;
+                    (has_width, has_height) = (true, true);
+                }
+                "mines" => {
+                    config.bomb_count = value.parse().map_err(|_| invalid())?;
+                    has_mines = true;
+                }
+                "flags" => {
+                    config.flag_rule = match value {
+                        "allowed" => FlagRule::Allowed,
+                        "forbidden" => FlagRule::Forbidden,
+                        _ => return Err(invalid()),
+                    }
+                }
+                "zeros" => {
+                    config.zero_rule = match value {
+                        "cascade" => ZeroRule::Cascade,
+                        "single" => ZeroRule::Single,
+                        _ => return Err(invalid()),
+                    }
+                }
+                "win" => {
+                    config.win_condition = match value {
+                        "classic" => WinCondition::Classic,
+                        "flag-all-mines" => WinCondition::FlagAllMines,
+                        _ => {
+                            let target = value.strip_prefix("score:").ok_or_else(invalid)?;
+                            WinCondition::Score(target.parse().map_err(|_| invalid())?)
+                        }
+                    }
+                }
+                "mine-count" => {
+                    config.mine_count = match value {
+                        "exact" => MineCount::Exact,
+                        "hidden" => MineCount::Hidden,
+                        _ => {
+                            let (min, max) = value.split_once('-').ok_or_else(invalid)?;
+                            MineCount::Range {
+                                min: min.parse().map_err(|_| invalid())?,
+                                max: max.parse().map_err(|_| invalid())?,
+                            }
+                        }
+                    }
+                }
+                "question-marks" => {
+                    config.question_marks = match value {
+                        "on" => true,
+                        "off" => false,
+                        _ => return Err(invalid()),
+                    }
+                }
+                "auto-flag" => {
+                    config.auto_flag = match value {
+                        "on" => true,
+                        "off" => false,
+                        _ => return Err(invalid()),
+                    }
+                }
+                "lives" => config.lives = value.parse().map_err(|_| invalid())?,
+                "timer" => {
+                    config.time_control = match value {
+                        "none" => None,
+                        _ => Some(parse_timer(value).ok_or_else(invalid)?),
+                    }
+                }
+                "adjacency" => {
+                    options.adjacency = match value {
+                        "standard" => Adjacency::Standard,
+                        "extended" => Adjacency::Extended,
+                        "knight" => Adjacency::Knight,
+                        "hex" => Adjacency::Hex,
+                        _ => return Err(invalid()),
+                    }
+                }
+                "topology" => {
+                    options.topology = match value {
+                        "plane" => Topology::Plane,
+                        "torus" => Topology::Torus,
+                        _ => return Err(invalid()),
+                    }
+                }
+                "symmetry" => {
+                    options.symmetry = SYMMETRIES
+                        .iter()
+                        .copied()
+                        .find(|&symmetry| symmetry_name(symmetry) == value)
+                        .ok_or_else(invalid)?
+                }
+                "density" => options.density = parse_density(value).ok_or_else(invalid)?,
+                "corridor" => {
+                    options.corridor = match value {
+                        "none" => None,
+                        _ => Some(parse_corridor(value).ok_or_else(invalid)?),
+                    }
+                }
+                "forbidden" => {
+                    options.forbidden = match value {
+                        "none" => Vec::new(),
+                        _ => value
+                            .split(';')
+                            .map(parse_area)
+                            .collect::<Option<_>>()
+                            .ok_or_else(invalid)?,
+                    }
+                }
+                "clustering" => options.clustering = value.parse().map_err(|_| invalid())?,
+                "opening" => {
+                    options.opening = match value {
+                        "zero" => Opening::Zero,
+                        "click-only" => Opening::ClickOnly,
+                        _ => return Err(invalid()),
+                    }
+                }
+                _ => return Err(format!("Unknown setting: {}", key)),
+            }
+        }
+
+        if !has_width || !has_height || !has_mines {
+            return Err("Config needs a width, a height and mines".to_string());
+        }
+        Ok(config)
+    }
+}
+
+const SYMMETRIES: [Symmetry; 6] = [
+    Symmetry::None,
+    Symmetry::MirrorX,
+    Symmetry::MirrorY,
+    Symmetry::MirrorBoth,
+    Symmetry::Rotate180,
+    Symmetry::Rotate90,
+];
+
+fn flag_rule_name(rule: FlagRule) -> &'static str {
+    match rule {
+        FlagRule::Allowed => "allowed",
+        FlagRule::Forbidden => "forbidden",
+    }
+}
+
+fn zero_rule_name(rule: ZeroRule) -> &'static str {
+    match rule {
+        ZeroRule::Cascade => "cascade",
+        ZeroRule::Single => "single",
+    }
+}
+
+fn adjacency_name(adjacency: Adjacency) -> &'static str {
+    match adjacency {
+        Adjacency::Standard => "standard",
+        Adjacency::Extended => "extended",
+        Adjacency::Knight => "knight",
+        Adjacency::Hex => "hex",
+    }
+}
+
+fn symmetry_name(symmetry: Symmetry) -> &'static str {
+    match symmetry {
+        Symmetry::None => "none",
+        Symmetry::MirrorX => "mirror-x",
+        Symmetry::MirrorY => "mirror-y",
+        Symmetry::MirrorBoth => "mirror-both",
+        Symmetry::Rotate180 => "rotate-180",
+        Symmetry::Rotate90 => "rotate-90",
+    }
+}
+
+fn opening_name(opening: Opening) -> &'static str {
+    match opening {
+        Opening::Zero => "zero",
+        Opening::ClickOnly => "click-only",
+    }
+}
+
+/// Writes `SECONDS[+INCREMENT][/PER-MOVE]`, all in seconds.
+fn timer_name(control: TimeControl) -> String {
+    let mut text = control.initial.as_secs_f64().to_string();
+    if !control.increment.is_zero() {
+        text.push_str(&format!("+{}", control.increment.as_secs_f64()));
+    }
+    if let Some(per_move) = control.per_move {
+        text.push_str(&format!("/{}", per_move.as_secs_f64()));
+    }
+    text
+}
+
+/// Parses what [`timer_name`] writes.
+fn parse_timer(value: &str) -> Option<TimeControl> {
+    let seconds = |text: &str| Duration::try_from_secs_f64(text.parse().ok()?).ok();
+    let (rest, per_move) = match value.split_once('/') {
+        Some((rest, per_move)) => (rest, Some(seconds(per_move)?)),
+        None => (value, None),
+    };
+    let (initial, increment) = match rest.split_once('+') {
+        Some((initial, increment)) => (initial, seconds(increment)?),
+        None => (rest, Duration::ZERO),
+    };
+    Some(TimeControl {
+        initial: seconds(initial)?,
+        increment,
+        per_move,
+    })
+}
+
+fn parse_density(value: &str) -> Option<Density> {
+    if value == "uniform" {
+        return Some(Density::Uniform);
+    }
+    let mut parts = value.split(':');
+    let kind = parts.next()?;
+    let a: f64 = parts.next()?.parse().ok()?;
+    let b: f64 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    match kind {
+        "along-x" => Some(Density::AlongX { start: a, end: b }),
+        "along-y" => Some(Density::AlongY { start: a, end: b }),
+        "radial" => Some(Density::Radial { center: a, edge: b }),
+        _ => None,
+    }
+}
+
+fn parse_corridor(value: &str) -> Option<Corridor> {
+    let point = |text: &str| {
+        let (x, y) = text.split_once(',')?;
+        Some((x.parse().ok()?, y.parse().ok()?))
+    };
+    let mut parts = value.split(':');
+    let corridor = Corridor {
+        from: point(parts.next()?)?,
+        to: point(parts.next()?)?,
+        width: parts.next()?.parse().ok()?,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(corridor)
+}
+
+/// Parses `X,Y:WIDTHxHEIGHT`.
+fn parse_area(value: &str) -> Option<Area> {
+    let (corner, size) = value.split_once(':')?;
+    let (x, y) = corner.split_once(',')?;
+    let (width, height) = size.split_once('x')?;
+    Some(Area {
+        x: x.parse().ok()?,
+        y: y.parse().ok()?,
+        width: width.parse().ok()?,
+        height: height.parse().ok()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Minesweeper;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_config_string_round_trip() {
+        let mut config = GameConfig::new(16, 40);
+        config.flag_rule = FlagRule::Forbidden;
+        config.question_marks = true;
+        config.auto_flag = true;
+        config.zero_rule = ZeroRule::Single;
+        config.mine_count = MineCount::Range { min: 30, max: 50 };
+        config.time_control = Some(TimeControl {
+            initial: Duration::from_secs(300),
+            increment: Duration::from_millis(2500),
+            per_move: Some(Duration::from_secs(30)),
+        });
+        config.generator = GeneratorOptions {
+            symmetry: Symmetry::Rotate180,
+            density: Density::Radial {
+                center: 0.5,
+                edge: 2.0,
+            },
+            corridor: Some(Corridor {
+                from: (0, 0),
+                to: (15, 15),
+                width: 2,
+            }),
+            adjacency: Adjacency::Extended,
+            forbidden: vec![
+                Area {
+                    x: 4,
+                    y: 4,
+                    width: 3,
+                    height: 2,
+                },
+                Area {
+                    x: 10,
+                    y: 0,
+                    width: 1,
+                    height: 16,
+                },
+            ],
+            clustering: 0.75,
+            topology: Topology::Torus,
+            opening: Opening::ClickOnly,
+        };
+
+        let text = config.to_config_string().unwrap();
+        let parsed = GameConfig::from_config_string(&text).unwrap();
+        assert_eq!(parsed.to_config_string().unwrap(), text);
+        assert_eq!(parsed.generator.corridor, config.generator.corridor);
+        assert_eq!(parsed.generator.forbidden, config.generator.forbidden);
+        assert_eq!(parsed.generator.clustering, 0.75);
+        assert_eq!(parsed.get_adjacency(), Adjacency::Extended);
+        assert_eq!(parsed.generator.topology, Topology::Torus);
+        assert!(parsed.question_marks);
+        assert!(parsed.auto_flag);
+        assert_eq!(parsed.zero_rule, ZeroRule::Single);
+        assert_eq!(parsed.mine_count, MineCount::Range { min: 30, max: 50 });
+        assert_eq!(parsed.time_control, config.time_control);
+        assert!(text.contains("timer=300+2.5/30\n"));
+    }
+
+    #[test]
+    fn test_config_string_errors() {
+        let defaults = GameConfig::from_config_string("size=9\nmines=10\n").unwrap();
+        assert_eq!((defaults.width, defaults.height), (9, 9));
+        assert_eq!(defaults.flag_rule, FlagRule::Allowed);
+        assert_eq!(defaults.generator.opening, Opening::Zero);
+
+        assert!(GameConfig::from_config_string("size=9").is_err());
+        assert!(GameConfig::from_config_string("width=9\nmines=10").is_err());
+        assert!(GameConfig::from_config_string("size=9\nmines=10\nwrap=yes").is_err());
+        assert!(GameConfig::from_config_string("size=9\nmines=10\ndensity=radial:1").is_err());
+        assert!(GameConfig::from_config_string("size=9\nmines=10\nforbidden=1,1:2").is_err());
+        assert!(GameConfig::from_config_string("size=9\nmines=10\ntimer=-5").is_err());
+        let timed = GameConfig::from_config_string("size=9\nmines=10\ntimer=60").unwrap();
+        assert_eq!(
+            timed.time_control,
+            Some(TimeControl::sudden_death(Duration::from_secs(60)))
+        );
+
+        let mut custom = GameConfig::new(9, 10);
+        custom.generator.density = Density::Custom(Arc::new(|_, _| 1.0));
+        assert!(custom.to_config_string().is_err());
+    }
+
+    #[test]
+    fn test_validate_and_build() {
+        assert!(GameConfig::new(0, 0).validate().is_err());
+        assert!(GameConfig::new(3, 9).validate().is_err());
+
+        let mut config = GameConfig::new(9, 10);
+        config.flag_rule = FlagRule::Forbidden;
+        assert!(config.validate().is_ok());
+
+        let game = Minesweeper::new_with_config(&config, (4, 4)).unwrap();
+        assert_eq!(game.get_bomb_count(), 10);
+        assert_eq!(game.get_flag_rule(), FlagRule::Forbidden);
+
+        let reported = game.config();
+        assert_eq!(reported.to_config_string(), config.to_config_string());
+    }
+}
@@ -0,0 +1,192 @@
+use crate::Move;
+
+/// How tile coordinates are written for players: in move scripts, spoken
+/// descriptions and error messages alike, so "C7" means the same tile
+/// everywhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CoordinateStyle {
+    /// A column letter for x (`A`..`Z`, then `AA`, `AB`, ...) followed by
+    /// the 1-based y, as on a chess board: `C7` is (2, 6).
+    #[default]
+    Chess,
+    /// The raw zero-based `x,y` used by the API: `2,6`.
+    Numeric,
+}
+
+impl CoordinateStyle {
+    /// The label of the tile at (x, y).
+    pub fn label(self, x: usize, y: usize) -> String {
+        match self {
+            CoordinateStyle::Chess => format!("{}{}", self.column_label(x), self.row_label(y)),
+            CoordinateStyle::Numeric => format!("{},{}", x, y),
+        }
+    }
+
+    /// The label of column `x` on its own, for the axis above a board.
+    pub fn column_label(self, x: usize) -> String {
+        match self {
+            CoordinateStyle::Chess => {
+                let mut letters = Vec::new();
+                let mut column = x + 1;
+                while column > 0 {
+                    column -= 1;
+                    letters.push(b'A' + (column % 26) as u8);
+                    column /= 26;
+                }
+                letters.reverse();
+                String::from_utf8(letters).unwrap()
+            }
+            CoordinateStyle::Numeric => x.to_string(),
+        }
+    }
+
+    /// The label of row `y` on its own, for the axis beside a board.
+    pub fn row_label(self, y: usize) -> String {
+        match self {
+            CoordinateStyle::Chess => (y + 1).to_string(),
+            CoordinateStyle::Numeric => y.to_string(),
+        }
+    }
+
+    /// Parses a label written by [`CoordinateStyle::label`]. Chess labels
+    /// are case-insensitive.
+    pub fn parse(self, text: &str) -> Result<(usize, usize), String> {
+        let invalid = || format!("Invalid tile label: {}", text);
+        match self {
+            CoordinateStyle::Chess => {
+                let split = text
+                    .find(|c: char| !c.is_ascii_alphabetic())
+                    .ok_or_else(invalid)?;
+                let (letters, digits) = text.split_at(split);
+                if letters.is_empty() {
+                    return Err(invalid());
+                }
+                let mut column = 0usize;
+                for c in letters.bytes() {
+                    let digit = (c.to_ascii_uppercase() - b'A') as usize + 1;
+                    column = column
+                        .checked_mul(26)
+                        .and_then(|column| column.checked_add(digit))
+                        .ok_or_else(invalid)?;
+                }
+                let row: usize = digits.parse().map_err(|_| invalid())?;
+                if row == 0 {
+                    return Err(invalid());
+                }
+                Ok((column - 1, row - 1))
+            }
+            CoordinateStyle::Numeric => {
+                let (x, y) = text.split_once(',').ok_or_else(invalid)?;
+                let x = x.trim().parse().map_err(|_| invalid())?;
+                let y = y.trim().parse().map_err(|_| invalid())?;
+                Ok((x, y))
+            }
+        }
+    }
+}
+
+impl Move {
+    /// Writes the move like its `Display` form, but with the tile as a
+    /// single label in `style`, e.g. `reveal C7`.
+    pub fn label_with(&self, style: CoordinateStyle) -> String {
+        let (verb, x, y) = match *self {
+            Move::Reveal(x, y) => ("reveal", x, y),
+            Move::ToggleFlag(x, y) => ("flag", x, y),
+            Move::SetMarker(x, y, _) => ("mark", x, y),
+            Move::Chord(x, y) => ("chord", x, y),
+            Move::Assist(x, y) => ("assist", x, y),
+            Move::AutoFinish => return self.to_string(),
+        };
+        let mut text = format!("{} {}", verb, style.label(x, y));
+        if let Move::SetMarker(..) = self {
+            // The marker is the last word of the plain form.
+            let plain = self.to_string();
+            text.push(' ');
+            text.push_str(plain.rsplit(' ').next().unwrap());
+        }
+        text
+    }
+
+    /// Parses a move written by [`Move::label_with`]. The plain `X Y` form
+    /// accepted by `FromStr` is understood too.
+    pub fn parse_with(text: &str, style: CoordinateStyle) -> Result<Move, String> {
+        let parts: Vec<&str> = text.split_whitespace().collect();
+        let takes_tile = matches!(
+            parts.first(),
+            Some(&"reveal") | Some(&"flag") | Some(&"mark") | Some(&"chord") | Some(&"assist")
+        );
+        if !takes_tile || parts.get(1).is_some_and(|p| p.parse::<usize>().is_ok()) {
+            return text.parse();
+        }
+
+        let label = parts
+            .get(1)
+            .ok_or_else(|| format!("Unknown move: {}", text))?;
+        let (x, y) = style.parse(label)?;
+        let mut plain = format!("{} {} {}", parts[0], x, y);
+        for rest in &parts[2..] {
+            plain.push(' ');
+            plain.push_str(rest);
+        }
+        plain.parse()
+    }
+}
+
+/// Puts the tile a rejected move was aimed at in front of the error, e.g.
+/// "C7: Tile already exposed".
+pub fn explain_error(mv: Move, error: &str, style: CoordinateStyle) -> String {
+    match mv {
+        Move::Reveal(x, y)
+        | Move::ToggleFlag(x, y)
+        | Move::SetMarker(x, y, _)
+        | Move::Chord(x, y)
+        | Move::Assist(x, y) => format!("{}: {}", style.label(x, y), error),
+        Move::AutoFinish => error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Marker;
+
+    #[test]
+    fn test_labels_round_trip() {
+        for style in [CoordinateStyle::Chess, CoordinateStyle::Numeric] {
+            for (x, y) in [(0, 0), (2, 6), (25, 9), (26, 0), (701, 3), (702, 12)] {
+                assert_eq!(style.parse(&style.label(x, y)), Ok((x, y)));
+            }
+        }
+        assert_eq!(CoordinateStyle::Chess.label(2, 6), "C7");
+        assert_eq!(CoordinateStyle::Numeric.label(2, 6), "2,6");
+        assert_eq!(CoordinateStyle::Chess.column_label(27), "AB");
+        assert_eq!(CoordinateStyle::Numeric.row_label(6), "6");
+        assert_eq!(CoordinateStyle::Chess.parse("aa1"), Ok((26, 0)));
+        assert!(CoordinateStyle::Chess.parse("C0").is_err());
+        assert!(CoordinateStyle::Chess.parse("7").is_err());
+        assert!(CoordinateStyle::Numeric.parse("C7").is_err());
+    }
+
+    #[test]
+    fn test_moves_with_labels() {
+        let style = CoordinateStyle::Chess;
+        let mark = Move::SetMarker(2, 6, Some(Marker::Symbol('?')));
+        assert_eq!(mark.label_with(style), "mark C7 symbol:?");
+        for mv in [
+            Move::Reveal(2, 6),
+            Move::Chord(0, 1),
+            mark,
+            Move::AutoFinish,
+        ] {
+            assert_eq!(Move::parse_with(&mv.label_with(style), style), Ok(mv));
+        }
+        assert_eq!(
+            Move::parse_with("flag 2 6", style),
+            Ok(Move::ToggleFlag(2, 6))
+        );
+        assert_eq!(
+            explain_error(Move::Reveal(2, 6), "Tile already exposed", style),
+            "C7: Tile already exposed"
+        );
+    }
+}
@@ -0,0 +1,299 @@
+use std::time::Duration;
+
+use crate::hash::Fnv64;
+#[cfg(feature = "daily")]
+use crate::{Difficulty, GameConfig, MinesweeperError};
+use crate::{GameState, Minesweeper, Move};
+
+/// Version tag at the start of every record, bumped if the format changes.
+const RECORD_VERSION: &str = "daily-v1";
+
+/// Hashed into every daily seed; changing it changes every daily board.
+#[cfg(feature = "daily")]
+const SEED_VERSION: &str = "daily-board-v1";
+
+#[cfg(feature = "daily")]
+impl Minesweeper {
+    /// The daily challenge board for `date` at `difficulty`. The seed comes
+    /// from the date and difficulty alone, so everyone asking for the same
+    /// day gets the same board, opened around its centre tile, wherever
+    /// they are and whichever release they run. Pair it with
+    /// [`DailyResult`] for leaderboards.
+    pub fn daily(
+        date: chrono::NaiveDate,
+        difficulty: Difficulty,
+    ) -> Result<Self, MinesweeperError> {
+        let config =
+            GameConfig::new_rect(difficulty.width, difficulty.height, difficulty.bomb_count);
+        let first_click = (difficulty.width / 2, difficulty.height / 2);
+        let seed = daily_seed(&date.to_string(), difficulty);
+        Self::new_with_config_and_seed(&config, first_click, seed)
+    }
+}
+
+/// The seed for the board on `date`, written `YYYY-MM-DD`.
+#[cfg(feature = "daily")]
+fn daily_seed(date: &str, difficulty: Difficulty) -> u64 {
+    let mut hash = Fnv64::new();
+    hash.write(SEED_VERSION.as_bytes());
+    hash.write(date.as_bytes());
+    hash.write_usize(difficulty.width);
+    hash.write_usize(difficulty.height);
+    hash.write_usize(difficulty.bomb_count);
+    hash.finish()
+}
+
+/// The canonical result of a daily challenge, for submission to a
+/// leaderboard server.
+///
+/// The board is identified by `config_hash` (its [`Minesweeper::board_id`])
+/// and the play by `replay_hash` (every move, in order), so a server that
+/// knows the day's board can check a submission with
+/// [`DailyResult::verify`] by replaying the submitted moves. The time is as
+/// reported by the client and can only be sanity-checked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyResult {
+    /// The challenge date, as `YYYY-MM-DD`.
+    pub date: String,
+    pub config_hash: u64,
+    pub outcome: GameState,
+    pub time: Duration,
+    pub replay_hash: u64,
+}
+
+impl DailyResult {
+    /// Builds the record for `game`, finished by playing `moves` in `time`.
+    pub fn new(
+        date: &str,
+        game: &Minesweeper,
+        moves: &[Move],
+        time: Duration,
+    ) -> Result<Self, String> {
+        if !is_valid_date(date) {
+            return Err(format!("Invalid date: {}", date));
+        }
+
+        Ok(DailyResult {
+            date: date.to_string(),
+            config_hash: game.board_id().0,
+            outcome: game.get_game_state().clone(),
+            time,
+            replay_hash: replay_hash(moves),
+        })
+    }
+
+    /// Checks the record against the day's board, before any move was made,
+    /// and the moves the player submitted with it.
+    pub fn verify(&self, mut board: Minesweeper, moves: &[Move]) -> bool {
+        if board.board_id().0 != self.config_hash || replay_hash(moves) != self.replay_hash {
+            return false;
+        }
+        board.apply_moves(moves.iter().copied());
+        *board.get_game_state() == self.outcome
+    }
+
+    /// The record as a single line, e.g.
+    /// `daily-v1;2026-10-17;cfg=...;outcome=won;time_ms=81234;replay=...`.
+    pub fn to_record_string(&self) -> String {
+        format!(
+            "{};{};cfg={:016x};outcome={};time_ms={};replay={:016x}",
+            RECORD_VERSION,
+            self.date,
+            self.config_hash,
+            outcome_name(&self.outcome),
+            self.time.as_millis(),
+            self.replay_hash
+        )
+    }
+
+    /// Parses a line produced by [`DailyResult::to_record_string`].
+    pub fn parse(record: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = record.trim().split(';').collect();
+        let [version, date, config, outcome, time, replay] = fields[..] else {
+            return Err("Expected six fields".to_string());
+        };
+
+        if version != RECORD_VERSION {
+            return Err(format!("Unsupported record version: {}", version));
+        }
+        if !is_valid_date(date) {
+            return Err(format!("Invalid date: {}", date));
+        }
+
+        let field = |text: &'_ str, key: &str| -> Result<String, String> {
+            text.strip_prefix(key)
+                .and_then(|rest| rest.strip_prefix('='))
+                .map(str::to_string)
+                .ok_or_else(|| format!("Expected {} field", key))
+        };
+        let hex = |text: String| u64::from_str_radix(&text, 16).map_err(|e| e.to_string());
+
+        let outcome = match field(outcome, "outcome")?.as_str() {
+            "won" => GameState::Won,
+            "lost" => GameState::Lost,
+            "in_progress" => GameState::InProgress,
+            "paused" => GameState::Paused,
+            other => return Err(format!("Unknown outcome: {}", other)),
+        };
+        let time_ms: u64 = field(time, "time_ms")?
+            .parse()
+            .map_err(|_| "Invalid time".to_string())?;
+
+        Ok(DailyResult {
+            date: date.to_string(),
+            config_hash: hex(field(config, "cfg")?)?,
+            outcome,
+            time: Duration::from_millis(time_ms),
+            replay_hash: hex(field(replay, "replay")?)?,
+        })
+    }
+}
+
+fn outcome_name(state: &GameState) -> &'static str {
+    match state {
+        GameState::InProgress => "in_progress",
+        GameState::Paused => "paused",
+        GameState::Won => "won",
+        GameState::Lost => "lost",
+    }
+}
+
+fn is_valid_date(date: &str) -> bool {
+    let bytes = date.as_bytes();
+    bytes.len() == 10
+        && bytes[4] == b'-'
+        && bytes[7] == b'-'
+        && bytes
+            .iter()
+            .enumerate()
+            .all(|(i, b)| i == 4 || i == 7 || b.is_ascii_digit())
+}
+
+fn replay_hash(moves: &[Move]) -> u64 {
+    let mut hash = Fnv64::new();
+    for mv in moves {
+        match *mv {
+            Move::Reveal(x, y) => {
+                hash.write(&[0]);
+                hash.write_usize(x);
+                hash.write_usize(y);
+            }
+            Move::ToggleFlag(x, y) => {
+                hash.write(&[1]);
+                hash.write_usize(x);
+                hash.write_usize(y);
+            }
+            Move::AutoFinish => hash.write(&[2]),
+            Move::Chord(x, y) => {
+                hash.write(&[3]);
+                hash.write_usize(x);
+                hash.write_usize(y);
+            }
+            Move::Assist(x, y) => {
+                hash.write(&[4]);
+                hash.write_usize(x);
+                hash.write_usize(y);
+            }
+            // Markers are annotations and do not affect the result.
+            Move::SetMarker(..) => {}
+        }
+    }
+    hash.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board() -> Minesweeper {
+        Minesweeper::new(3, vec![(0, 0), (2, 2)])
+    }
+
+    fn winning_moves() -> Vec<Move> {
+        vec![
+            Move::Reveal(0, 1),
+            Move::Reveal(0, 2),
+            Move::Reveal(1, 0),
+            Move::Reveal(1, 1),
+            Move::Reveal(1, 2),
+            Move::Reveal(2, 0),
+            Move::Reveal(2, 1),
+        ]
+    }
+
+    #[test]
+    fn test_record_round_trip() {
+        let mut game = board();
+        let moves = winning_moves();
+        game.apply_moves(moves.iter().copied());
+
+        let result =
+            DailyResult::new("2026-10-17", &game, &moves, Duration::from_millis(8123)).unwrap();
+        assert_eq!(result.outcome, GameState::Won);
+
+        let record = result.to_record_string();
+        assert!(record.starts_with("daily-v1;2026-10-17;cfg="));
+        assert_eq!(DailyResult::parse(&record).unwrap(), result);
+    }
+
+    #[test]
+    fn test_verify() {
+        let mut game = board();
+        let moves = winning_moves();
+        game.apply_moves(moves.iter().copied());
+        let result = DailyResult::new("2026-10-17", &game, &moves, Duration::from_secs(8)).unwrap();
+
+        assert!(result.verify(board(), &moves));
+        // Different moves, or a different board, do not verify.
+        assert!(!result.verify(board(), &moves[1..]));
+        assert!(!result.verify(Minesweeper::new(3, vec![(0, 0), (2, 1)]), &moves));
+
+        let mut forged = result.clone();
+        forged.outcome = GameState::Lost;
+        assert!(!forged.verify(board(), &moves));
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_records() {
+        assert!(DailyResult::parse("daily-v1;2026-10-17").is_err());
+        assert!(
+            DailyResult::parse("daily-v2;2026-10-17;cfg=0;outcome=won;time_ms=1;replay=0").is_err()
+        );
+        assert!(
+            DailyResult::parse("daily-v1;17/10/2026;cfg=0;outcome=won;time_ms=1;replay=0").is_err()
+        );
+        assert!(
+            DailyResult::parse("daily-v1;2026-10-17;cfg=0;outcome=draw;time_ms=1;replay=0")
+                .is_err()
+        );
+    }
+
+    #[cfg(feature = "daily")]
+    #[test]
+    fn test_daily_boards() {
+        let day = |d| chrono::NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+        let today = Minesweeper::daily(day(18), Difficulty::BEGINNER).unwrap();
+        assert_eq!(
+            today.board_id(),
+            Minesweeper::daily(day(18), Difficulty::BEGINNER)
+                .unwrap()
+                .board_id()
+        );
+        assert_ne!(
+            today.board_id(),
+            Minesweeper::daily(day(19), Difficulty::BEGINNER)
+                .unwrap()
+                .board_id()
+        );
+        let expert = Minesweeper::daily(day(18), Difficulty::EXPERT).unwrap();
+        assert_eq!((expert.get_width(), expert.get_bomb_count()), (30, 99));
+        // Pinned, as every player must derive the same seed.
+        assert_eq!(day(18).to_string(), "2026-10-18");
+        assert_eq!(
+            daily_seed("2026-10-18", Difficulty::BEGINNER),
+            0x705d5b495ab40107
+        );
+        // The layout too, so a change to the generator shows up here.
+        assert_eq!(today.board_id().to_string(), "57ff182d9c9beb5e");
+    }
+}
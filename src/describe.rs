@@ -0,0 +1,221 @@
+use crate::coords::explain_error;
+use crate::{diff, BoardSnapshot, CoordinateStyle, GameState, Marker, Minesweeper, Move};
+
+/// The [`CoordinateStyle::Chess`] label of the tile at (x, y), e.g. `B3`
+/// for (1, 2).
+pub fn tile_label(x: usize, y: usize) -> String {
+    CoordinateStyle::Chess.label(x, y)
+}
+
+/// Describes in plain English what `mv` did to the board, given snapshots
+/// from just before and just after it, e.g. "opened a 12-tile region from
+/// B3 to E6 bordered by 1s and 2s".
+///
+/// Meant for screen readers and chat frontends, which need to narrate the
+/// game rather than redraw it.
+pub fn describe_move(before: &BoardSnapshot, after: &BoardSnapshot, mv: Move) -> String {
+    describe_move_with(before, after, mv, CoordinateStyle::Chess)
+}
+
+/// [`describe_move`] with tiles labelled in `style`.
+pub fn describe_move_with(
+    before: &BoardSnapshot,
+    after: &BoardSnapshot,
+    mv: Move,
+    style: CoordinateStyle,
+) -> String {
+    let tile_label = |x, y| style.label(x, y);
+    let mut description = match mv {
+        Move::Reveal(x, y) => describe_reveal(before, after, x, y, style),
+        Move::Assist(x, y) => format!(
+            "used an assist and {}",
+            describe_reveal(before, after, x, y, style)
+        ),
+        Move::ToggleFlag(x, y) => match after.get_tile(x, y) {
+            Some(tile) if tile.flagged => format!("flagged {}", tile_label(x, y)),
+            _ => format!("removed the flag from {}", tile_label(x, y)),
+        },
+        Move::SetMarker(x, y, None) => format!("cleared the marker on {}", tile_label(x, y)),
+        Move::SetMarker(x, y, Some(marker)) => {
+            let name = match marker {
+                Marker::Red => "red".to_string(),
+                Marker::Yellow => "yellow".to_string(),
+                Marker::Green => "green".to_string(),
+                Marker::Blue => "blue".to_string(),
+                Marker::Symbol(c) => format!("with '{}'", c),
+            };
+            format!("marked {} {}", tile_label(x, y), name)
+        }
+        Move::Chord(x, y) => {
+            let revealed = diff(before, after)
+                .iter()
+                .filter(|change| change.tile.exposed && !change.tile.is_bomb())
+                .count();
+            format!(
+                "chorded on {}, revealing {} tiles",
+                tile_label(x, y),
+                revealed
+            )
+        }
+        Move::AutoFinish => {
+            let revealed = diff(before, after)
+                .iter()
+                .filter(|change| change.tile.exposed && !change.tile.is_bomb())
+                .count();
+            format!("finished the board, revealing {} safe tiles", revealed)
+        }
+    };
+
+    if *before.get_game_state() == GameState::InProgress {
+        match after.get_game_state() {
+            GameState::Won => description.push_str(", winning the game"),
+            GameState::Lost => description.push_str(", losing the game"),
+            GameState::InProgress | GameState::Paused => {}
+        }
+    }
+    description
+}
+
+/// Applies `mv` to `game` and describes what it did. Errors name the tile
+/// the move was aimed at.
+pub fn apply_move_described(game: &mut Minesweeper, mv: Move) -> Result<String, String> {
+    apply_move_described_with(game, mv, CoordinateStyle::Chess)
+}
+
+/// [`apply_move_described`] with tiles labelled in `style`.
+pub fn apply_move_described_with(
+    game: &mut Minesweeper,
+    mv: Move,
+    style: CoordinateStyle,
+) -> Result<String, String> {
+    let before = game.snapshot();
+    game.apply_move(mv)
+        .map_err(|error| explain_error(mv, &error.to_string(), style))?;
+    Ok(describe_move_with(&before, &game.snapshot(), mv, style))
+}
+
+fn describe_reveal(
+    before: &BoardSnapshot,
+    after: &BoardSnapshot,
+    x: usize,
+    y: usize,
+    style: CoordinateStyle,
+) -> String {
+    let tile_label = |x, y| style.label(x, y);
+    let label = tile_label(x, y);
+    if after.get_tile(x, y).is_some_and(|tile| tile.is_bomb()) {
+        return format!("stepped on a mine at {}", label);
+    }
+
+    let (width, height) = (after.get_width(), after.get_height());
+    let mut revealed = 0;
+    let mut min = (usize::MAX, usize::MAX);
+    let mut max = (0, 0);
+    let mut border = [false; 256];
+    for tx in 0..width {
+        for ty in 0..height {
+            let (Some(old), Some(new)) = (before.get_tile(tx, ty), after.get_tile(tx, ty)) else {
+                continue;
+            };
+            if old.exposed || !new.exposed {
+                continue;
+            }
+            revealed += 1;
+            min = (min.0.min(tx), min.1.min(ty));
+            max = (max.0.max(tx), max.1.max(ty));
+            if let Some(n) = new.get_number().filter(|&n| n > 0) {
+                border[n as usize] = true;
+            }
+        }
+    }
+
+    match revealed {
+        0 => format!("nothing new at {}", label),
+        1 => match after.get_tile(x, y).and_then(|tile| tile.get_number()) {
+            Some(n) => format!("revealed a {} at {}", n, label),
+            None => format!("revealed {}", label),
+        },
+        _ => {
+            let mut description = format!(
+                "opened a {}-tile region from {} to {}",
+                revealed,
+                tile_label(min.0, min.1),
+                tile_label(max.0, max.1)
+            );
+            let numbers: Vec<String> = (1..border.len())
+                .filter(|&n| border[n])
+                .map(|n| format!("{}s", n))
+                .collect();
+            match numbers.split_last() {
+                None => {}
+                Some((last, [])) => description.push_str(&format!(" bordered by {}", last)),
+                Some((last, rest)) => {
+                    description.push_str(&format!(" bordered by {} and {}", rest.join(", "), last))
+                }
+            }
+            description
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tile_labels() {
+        assert_eq!(tile_label(0, 0), "A1");
+        assert_eq!(tile_label(1, 2), "B3");
+        assert_eq!(tile_label(25, 9), "Z10");
+        assert_eq!(tile_label(26, 0), "AA1");
+        assert_eq!(tile_label(27, 0), "AB1");
+    }
+
+    #[test]
+    fn test_describe_moves() {
+        let mut game = Minesweeper::new(4, vec![(3, 3), (3, 2)]);
+
+        assert_eq!(
+            apply_move_described(&mut game, Move::ToggleFlag(3, 3)),
+            Ok("flagged D4".to_string())
+        );
+        assert_eq!(
+            apply_move_described(&mut game, Move::SetMarker(3, 2, Some(Marker::Red))),
+            Ok("marked D3 red".to_string())
+        );
+        assert_eq!(
+            apply_move_described(&mut game, Move::Reveal(0, 0)),
+            Ok(
+                "opened a 14-tile region from A1 to D4 bordered by 1s and 2s, winning the game"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_describe_single_tiles() {
+        let mut game = Minesweeper::new(3, vec![(1, 1)]);
+        assert_eq!(
+            apply_move_described(&mut game, Move::Reveal(0, 0)),
+            Ok("revealed a 1 at A1".to_string())
+        );
+        assert_eq!(
+            apply_move_described(&mut game, Move::Reveal(1, 1)),
+            Ok("stepped on a mine at B2, losing the game".to_string())
+        );
+    }
+
+    #[test]
+    fn test_numeric_labels_and_errors() {
+        let mut game = Minesweeper::new(3, vec![(1, 1)]);
+        let style = CoordinateStyle::Numeric;
+        assert_eq!(
+            apply_move_described_with(&mut game, Move::Reveal(2, 0), style),
+            Ok("revealed a 1 at 2,0".to_string())
+        );
+        assert_eq!(
+            apply_move_described_with(&mut game, Move::Reveal(2, 0), style),
+            Err("2,0: Tile already exposed".to_string())
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::{GameConfig, GameState, GameSummary};
+
+/// How far one game moves the density, per unit of error.
+const STEP: f64 = 0.05;
+/// How much pace counts next to the win rate.
+const PACE_WEIGHT: f64 = 0.25;
+
+/// Casual-play assist that picks each board's mine density from how the
+/// player has been doing, to keep their win rate near a target.
+///
+/// Feed it every finished game with [`DynamicDifficulty::record`] and
+/// build the next board from [`DynamicDifficulty::config`]. Winning more
+/// often than the target, or winning faster than the target pace, makes
+/// boards denser; losing makes them sparser.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DynamicDifficulty {
+    /// The share of games the player should win, between 0 and 1.
+    pub target_win_rate: f64,
+    /// How many recent games are taken into account.
+    pub window: usize,
+    /// The lowest and highest density boards are made with, in mines per
+    /// tile.
+    pub min_density: f64,
+    pub max_density: f64,
+    /// The time per safe tile a comfortable win should take, if speed
+    /// should count too.
+    pub target_pace: Option<Duration>,
+    density: f64,
+    /// Whether each recent game was won, and its time per safe tile.
+    recent: VecDeque<(bool, Duration)>,
+}
+
+impl Default for DynamicDifficulty {
+    /// Aims for winning 60% of games, starting at beginner density.
+    fn default() -> Self {
+        DynamicDifficulty {
+            target_win_rate: 0.6,
+            window: 10,
+            min_density: 0.08,
+            max_density: 0.25,
+            target_pace: Some(Duration::from_millis(500)),
+            density: 10.0 / 81.0,
+            recent: VecDeque::new(),
+        }
+    }
+}
+
+impl DynamicDifficulty {
+    /// Mines per tile for the next board.
+    pub fn density(&self) -> f64 {
+        self.density
+    }
+
+    /// The share of recent games won, or `None` before the first one.
+    pub fn win_rate(&self) -> Option<f64> {
+        if self.recent.is_empty() {
+            return None;
+        }
+        let wins = self.recent.iter().filter(|&&(won, _)| won).count();
+        Some(wins as f64 / self.recent.len() as f64)
+    }
+
+    /// The average time per safe tile of the recent wins.
+    pub fn average_pace(&self) -> Option<Duration> {
+        let wins: Vec<Duration> = self
+            .recent
+            .iter()
+            .filter(|&&(won, _)| won)
+            .map(|&(_, pace)| pace)
+            .collect();
+        if wins.is_empty() {
+            return None;
+        }
+        Some(wins.iter().sum::<Duration>() / wins.len() as u32)
+    }
+
+    /// Takes a finished game into account and adjusts the density for the
+    /// next one. Games still in progress are ignored.
+    pub fn record(&mut self, summary: &GameSummary) {
+        if summary.outcome == GameState::InProgress {
+            return;
+        }
+        let safe = (summary.width * summary.height)
+            .saturating_sub(summary.bomb_count)
+            .max(1);
+        let pace = summary.time / safe as u32;
+        self.recent
+            .push_back((summary.outcome == GameState::Won, pace));
+        while self.recent.len() > self.window.max(1) {
+            self.recent.pop_front();
+        }
+
+        // Positive when the boards are too easy.
+        let mut error = self.win_rate().unwrap_or(self.target_win_rate) - self.target_win_rate;
+        if let (Some(target), Some(pace)) = (self.target_pace, self.average_pace()) {
+            let target = target.as_secs_f64();
+            if target > 0.0 {
+                let faster = (target - pace.as_secs_f64()) / target;
+                error += faster.clamp(-1.0, 1.0) * PACE_WEIGHT;
+            }
+        }
+        self.density = (self.density + STEP * error).clamp(self.min_density, self.max_density);
+    }
+
+    /// A config for the next `width` x `height` board at the current
+    /// density, with at least one mine and room for a first click.
+    pub fn config(&self, width: usize, height: usize) -> GameConfig {
+        GameConfig::from_density(width, height, self.density)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(outcome: GameState, secs: u64) -> GameSummary {
+        GameSummary {
+            outcome,
+            width: 9,
+            height: 9,
+            bomb_count: 10,
+            time: Duration::from_secs(secs),
+            flags_placed: 0,
+            three_bv: 20,
+            flag_check: false,
+            guesses: None,
+        }
+    }
+
+    #[test]
+    fn test_density_follows_results() {
+        let mut difficulty = DynamicDifficulty::default();
+        let start = difficulty.density();
+        assert_eq!(difficulty.config(9, 9).bomb_count, 10);
+
+        for _ in 0..5 {
+            difficulty.record(&summary(GameState::Lost, 10));
+        }
+        assert_eq!(difficulty.win_rate(), Some(0.0));
+        assert!(difficulty.density() < start);
+
+        let mut easy = DynamicDifficulty::default();
+        for _ in 0..30 {
+            easy.record(&summary(GameState::Won, 20));
+        }
+        assert_eq!(easy.density(), easy.max_density);
+        assert_eq!(easy.config(9, 9).bomb_count, 20);
+
+        // The window forgets old games.
+        for _ in 0..10 {
+            easy.record(&summary(GameState::Lost, 10));
+        }
+        assert_eq!(easy.win_rate(), Some(0.0));
+        assert_eq!(easy.average_pace(), None);
+    }
+
+    #[test]
+    fn test_slow_wins_ease_off() {
+        let mut fast = DynamicDifficulty::default();
+        let mut slow = DynamicDifficulty::default();
+        for _ in 0..3 {
+            fast.record(&summary(GameState::Won, 10));
+            slow.record(&summary(GameState::Won, 300));
+        }
+        assert!(fast.density() > slow.density());
+
+        let mut ignored = DynamicDifficulty::default();
+        ignored.record(&summary(GameState::InProgress, 10));
+        assert_eq!(ignored.win_rate(), None);
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{Adjacency, Minesweeper};
+
+/// Builds a board by hand, for authoring puzzles and hand-made layouts.
+///
+/// Mines are placed and removed freely and tiles can be marked to start
+/// revealed; nothing is numbered until [`Editor::finalize`] turns the
+/// layout into a playable [`Minesweeper`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Editor {
+    width: usize,
+    height: usize,
+    adjacency: Adjacency,
+    mines: Vec<bool>,
+    revealed: Vec<bool>,
+}
+
+impl Editor {
+    /// An empty square board with standard adjacency.
+    pub fn new(size: usize) -> Self {
+        Self::new_rect(size, size)
+    }
+
+    /// An empty `width` x `height` board with standard adjacency.
+    pub fn new_rect(width: usize, height: usize) -> Self {
+        Editor {
+            width,
+            height,
+            adjacency: Adjacency::Standard,
+            mines: vec![false; width * height],
+            revealed: vec![false; width * height],
+        }
+    }
+
+    /// Starts from an existing game's layout and exposed tiles.
+    pub fn from_game(game: &Minesweeper) -> Self {
+        let (width, height) = (game.get_width(), game.get_height());
+        let mut editor = Editor::new_rect(width, height);
+        editor.adjacency = game.get_adjacency();
+        for x in 0..width {
+            for y in 0..height {
+                let tile = game.get_tile(x, y).unwrap();
+                editor.mines[x * height + y] = tile.is_bomb();
+                editor.revealed[x * height + y] = tile.exposed && !tile.is_bomb();
+            }
+        }
+        editor
+    }
+
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get_adjacency(&self) -> Adjacency {
+        self.adjacency
+    }
+
+    pub fn set_adjacency(&mut self, adjacency: Adjacency) {
+        self.adjacency = adjacency;
+    }
+
+    pub fn is_mine(&self, x: usize, y: usize) -> bool {
+        self.index(x, y).is_ok_and(|i| self.mines[i])
+    }
+
+    pub fn is_revealed(&self, x: usize, y: usize) -> bool {
+        self.index(x, y).is_ok_and(|i| self.revealed[i])
+    }
+
+    pub fn mine_count(&self) -> usize {
+        self.mines.iter().filter(|&&mine| mine).count()
+    }
+
+    /// Places or removes a mine. Revealed tiles can't hold mines.
+    pub fn set_mine(&mut self, x: usize, y: usize, mine: bool) -> Result<(), String> {
+        let i = self.index(x, y)?;
+        if mine && self.revealed[i] {
+            return Err("Cannot place a mine on a revealed tile".to_string());
+        }
+        self.mines[i] = mine;
+        Ok(())
+    }
+
+    pub fn toggle_mine(&mut self, x: usize, y: usize) -> Result<(), String> {
+        let mine = !self.is_mine(x, y);
+        self.set_mine(x, y, mine)
+    }
+
+    /// Marks a tile to start revealed. Mines can't be revealed.
+    pub fn set_revealed(&mut self, x: usize, y: usize, revealed: bool) -> Result<(), String> {
+        let i = self.index(x, y)?;
+        if revealed && self.mines[i] {
+            return Err("Cannot reveal a mine".to_string());
+        }
+        self.revealed[i] = revealed;
+        Ok(())
+    }
+
+    /// Checks that the layout makes a playable game: at least one mine and
+    /// at least one safe tile left to uncover.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.width == 0 || self.height == 0 {
+            return Err("Board is empty".to_string());
+        }
+        if self.mine_count() == 0 {
+            return Err("Board has no mines".to_string());
+        }
+        let covered_safe = self
+            .mines
+            .iter()
+            .zip(&self.revealed)
+            .filter(|&(&mine, &revealed)| !mine && !revealed)
+            .count();
+        if covered_safe == 0 {
+            return Err("Board has no safe tiles left to reveal".to_string());
+        }
+        Ok(())
+    }
+
+    /// Numbers the board and returns it as a game in progress, with the
+    /// chosen tiles already revealed. Revealing a tile here never cascades:
+    /// exactly the marked tiles start open.
+    pub fn finalize(&self) -> Result<Minesweeper, String> {
+        self.validate()?;
+
+        let mines = self.positions(&self.mines).collect::<Vec<(usize, usize)>>();
+        let mut game =
+            Minesweeper::new_rect_with_adjacency(self.width, self.height, mines, self.adjacency);
+        for (x, y) in self.positions(&self.revealed) {
+            game.expose(x, y);
+        }
+        Ok(game)
+    }
+
+    fn index(&self, x: usize, y: usize) -> Result<usize, String> {
+        if x < self.width && y < self.height {
+            Ok(x * self.height + y)
+        } else {
+            Err("Invalid coordinates".to_string())
+        }
+    }
+
+    fn positions<'a>(&self, cells: &'a [bool]) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let height = self.height;
+        cells
+            .iter()
+            .enumerate()
+            .filter(|(_, &set)| set)
+            .map(move |(i, _)| (i / height, i % height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameState;
+
+    #[test]
+    fn test_edit_and_finalize() {
+        let mut editor = Editor::new(3);
+        editor.toggle_mine(1, 1).unwrap();
+        editor.set_mine(2, 2, true).unwrap();
+        editor.toggle_mine(2, 2).unwrap();
+        editor.set_revealed(0, 0, true).unwrap();
+
+        assert_eq!(editor.mine_count(), 1);
+        assert_eq!(
+            editor.set_mine(0, 0, true),
+            Err("Cannot place a mine on a revealed tile".to_string())
+        );
+        assert_eq!(
+            editor.set_revealed(1, 1, true),
+            Err("Cannot reveal a mine".to_string())
+        );
+        assert!(editor.set_mine(3, 0, true).is_err());
+
+        let mut game = editor.finalize().unwrap();
+        assert_eq!(game.get_bomb_count(), 1);
+        assert_eq!(game.get_tile(0, 0).unwrap().get_number(), Some(1));
+        assert!(game.get_tile(0, 0).unwrap().exposed);
+        assert_eq!(game.count_exposed_tiles(), 1);
+        assert_eq!(game.safe_tiles_remaining(), 7);
+
+        for (x, y) in [(0, 1), (0, 2), (1, 0), (1, 2), (2, 0), (2, 1), (2, 2)] {
+            game.click_tile(x, y).unwrap();
+        }
+        assert_eq!(*game.get_game_state(), GameState::Won);
+    }
+
+    #[test]
+    fn test_validation() {
+        let mut editor = Editor::new(2);
+        assert_eq!(
+            editor.finalize().err(),
+            Some("Board has no mines".to_string())
+        );
+
+        editor.set_mine(0, 0, true).unwrap();
+        for (x, y) in [(0, 1), (1, 0), (1, 1)] {
+            editor.set_revealed(x, y, true).unwrap();
+        }
+        assert_eq!(
+            editor.validate(),
+            Err("Board has no safe tiles left to reveal".to_string())
+        );
+
+        editor.set_revealed(1, 1, false).unwrap();
+        assert!(editor.validate().is_ok());
+    }
+
+    #[test]
+    fn test_round_trip_through_game() {
+        let mut game = Minesweeper::new(4, vec![(0, 3), (3, 0)]);
+        game.click_tile(2, 1).unwrap();
+
+        let editor = Editor::from_game(&game);
+        assert!(editor.is_mine(0, 3) && editor.is_mine(3, 0));
+        assert!(editor.is_revealed(2, 1));
+
+        let copy = editor.finalize().unwrap();
+        assert_eq!(copy.board_id(), game.board_id());
+        assert_eq!(copy.count_exposed_tiles(), game.count_exposed_tiles());
+    }
+}
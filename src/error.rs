@@ -0,0 +1,113 @@
+use std::fmt;
+
+use crate::{GameState, Pos};
+
+/// Why a move such as [`Minesweeper::click_tile`](crate::Minesweeper::click_tile)
+/// was rejected, or why a game couldn't be created around a first click.
+///
+/// Each variant carries what a frontend needs to explain it, so messages
+/// can be built without parsing text. Converts into the `String` errors
+/// used elsewhere, with the same text as its `Display`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MinesweeperError {
+    /// `pos` is off the `width` x `height` board.
+    OutOfBounds {
+        pos: Pos,
+        width: usize,
+        height: usize,
+    },
+    /// The game has already been won or lost, as `state` says.
+    GameFinished { state: GameState },
+    /// The game is paused; see
+    /// [`Minesweeper::resume`](crate::Minesweeper::resume).
+    Paused,
+    /// Only a paused game can be resumed.
+    NotPaused,
+    /// The tile at `pos` is already revealed.
+    AlreadyExposed { pos: Pos },
+    /// The tile at `pos` is flagged, so it can't be revealed until the
+    /// flag is removed.
+    Flagged { pos: Pos },
+    /// The game's [`FlagRule`](crate::FlagRule) forbids flags.
+    FlagsForbidden,
+    /// A chord was tried on `pos`, which isn't an exposed number above 0.
+    NotANumber { pos: Pos },
+    /// A chord on the `number` at `pos` found `flags` flags around it.
+    FlagCountMismatch { pos: Pos, number: u8, flags: usize },
+    /// History is off or holds no move to undo.
+    NothingToUndo,
+    /// No move has been undone since the last one made.
+    NothingToRedo,
+    /// Every tile is exposed or flagged, so there is nothing to hint at.
+    NoHint,
+    /// An assist was asked to reveal `pos`, which holds a mine.
+    NotSafe { pos: Pos },
+    /// [`Minesweeper::auto_finish`](crate::Minesweeper::auto_finish) was
+    /// asked for while some covered tile could still go either way.
+    NeedsGuess,
+    /// A note was put on `pos`, which is already revealed.
+    NoteOnExposed { pos: Pos },
+    /// A note for `pos` was longer than `limit` characters.
+    NoteTooLong { pos: Pos, limit: usize },
+    /// A new game was asked for with `bomb_count` mines, but the board only
+    /// has room for `capacity` besides the first click.
+    TooManyMines { bomb_count: usize, capacity: usize },
+    /// A new game was asked for with a config that
+    /// [`GameConfig::validate`](crate::GameConfig::validate) rejects, for
+    /// the `reason` it gives.
+    InvalidConfig { reason: String },
+}
+
+impl fmt::Display for MinesweeperError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MinesweeperError::OutOfBounds {
+                pos: (x, y),
+                width,
+                height,
+            } => write!(
+                f,
+                "Invalid coordinates: ({}, {}) is outside the {}x{} board",
+                x, y, width, height
+            ),
+            MinesweeperError::GameFinished { .. } => f.write_str("Game is already finished"),
+            MinesweeperError::Paused => f.write_str("Game is paused"),
+            MinesweeperError::NotPaused => f.write_str("Game is not paused"),
+            MinesweeperError::AlreadyExposed { .. } => f.write_str("Tile already exposed"),
+            MinesweeperError::Flagged { .. } => f.write_str("Tile is flagged"),
+            MinesweeperError::FlagsForbidden => f.write_str("Flags are not allowed in this game"),
+            MinesweeperError::NotANumber { .. } => f.write_str("Can only chord an exposed number"),
+            MinesweeperError::FlagCountMismatch { .. } => {
+                f.write_str("Flag count does not match the number")
+            }
+            MinesweeperError::NothingToUndo => f.write_str("Nothing to undo"),
+            MinesweeperError::NothingToRedo => f.write_str("Nothing to redo"),
+            MinesweeperError::NoHint => f.write_str("No covered tile to suggest"),
+            MinesweeperError::NotSafe { .. } => f.write_str("Assists can only reveal safe tiles"),
+            MinesweeperError::NeedsGuess => {
+                f.write_str("Board cannot be finished without guessing")
+            }
+            MinesweeperError::NoteOnExposed { .. } => f.write_str("Cannot annotate exposed tile"),
+            MinesweeperError::NoteTooLong { limit, .. } => {
+                write!(f, "Note is longer than {} characters", limit)
+            }
+            MinesweeperError::TooManyMines {
+                bomb_count,
+                capacity,
+            } => write!(
+                f,
+                "Too many mines: {} requested, but the board has room for {}",
+                bomb_count, capacity
+            ),
+            MinesweeperError::InvalidConfig { reason } => f.write_str(reason),
+        }
+    }
+}
+
+impl std::error::Error for MinesweeperError {}
+
+impl From<MinesweeperError> for String {
+    fn from(error: MinesweeperError) -> Self {
+        error.to_string()
+    }
+}
@@ -0,0 +1,189 @@
+use std::fmt;
+use std::sync::{Mutex, PoisonError};
+
+use crate::Minesweeper;
+
+/// Something that happened during a move, for frontends that animate
+/// changes instead of redrawing the whole board.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent {
+    /// A safe tile was uncovered, showing `number`.
+    TileRevealed {
+        x: usize,
+        y: usize,
+        number: u8,
+    },
+    /// A flag was placed on (x, y), or removed when `flagged` is false.
+    TileFlagged {
+        x: usize,
+        y: usize,
+        flagged: bool,
+    },
+    /// The player uncovered the mine at (x, y).
+    BombExploded {
+        x: usize,
+        y: usize,
+    },
+    /// The mine at (x, y) was hit but cost a life instead of the game;
+    /// see [`Minesweeper::set_lives`].
+    LifeLost {
+        x: usize,
+        y: usize,
+        lives_left: usize,
+    },
+    /// A zero at (x, y) opened up, uncovering `tiles` tiles in all. Comes
+    /// after the [`GameEvent::TileRevealed`] events for those tiles.
+    FloodFillCompleted {
+        x: usize,
+        y: usize,
+        tiles: usize,
+    },
+    /// The flags around the number at (x, y) outnumber it, so one of them
+    /// is wrong. Only sent while the flag check assist is on; see
+    /// [`Minesweeper::set_flag_check`].
+    FlagConflict {
+        x: usize,
+        y: usize,
+    },
+    GameWon,
+    /// The game was lost, by hitting a mine or by forfeiting.
+    GameLost,
+}
+
+/// Called with every [`GameEvent`] as it happens.
+pub type EventListener = Box<dyn FnMut(&GameEvent) + Send>;
+
+/// Where a game's events go. Both are off by default, so games that
+/// nobody watches pay nothing.
+#[derive(Default)]
+pub(crate) struct Events {
+    queue: Option<Vec<GameEvent>>,
+    /// Only ever called through `&mut`, so the lock is never contended; it
+    /// is there to keep games `Sync` for read-only work across threads.
+    listener: Option<Mutex<EventListener>>,
+}
+
+impl fmt::Debug for Events {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Events")
+            .field("queue", &self.queue)
+            .field("listener", &self.listener.is_some())
+            .finish()
+    }
+}
+
+impl Minesweeper {
+    /// Turns the event queue on or off. While it is on, every
+    /// [`GameEvent`] is kept until [`Minesweeper::drain_events`] collects
+    /// it. Turning it off drops anything not yet collected.
+    pub fn set_event_queue(&mut self, enabled: bool) {
+        if !enabled {
+            self.events.queue = None;
+        } else if self.events.queue.is_none() {
+            self.events.queue = Some(Vec::new());
+        }
+    }
+
+    /// The events queued since the last call, oldest first.
+    pub fn drain_events(&mut self) -> Vec<GameEvent> {
+        match &mut self.events.queue {
+            Some(queue) => std::mem::take(queue),
+            None => Vec::new(),
+        }
+    }
+
+    /// Registers a callback to run on every [`GameEvent`] as it happens,
+    /// replacing any earlier one, or removes it with `None`. Independent of
+    /// the event queue.
+    ///
+    /// Undo restores earlier state without replaying events; redraw after
+    /// [`Minesweeper::undo`].
+    pub fn set_event_listener(&mut self, listener: Option<EventListener>) {
+        self.events.listener = listener.map(Mutex::new);
+    }
+
+    pub(crate) fn emit(&mut self, event: GameEvent) {
+        let events = &mut self.events;
+        if let Some(listener) = &mut events.listener {
+            let listener = listener.get_mut().unwrap_or_else(PoisonError::into_inner);
+            listener(&event);
+        }
+        if let Some(queue) = &mut events.queue {
+            queue.push(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_event_queue() {
+        let mut game = Minesweeper::new_rect(4, 1, vec![(0, 0)]);
+        game.toggle_flag(0, 0).unwrap();
+        assert!(game.drain_events().is_empty());
+
+        game.set_event_queue(true);
+        game.toggle_flag(0, 0).unwrap();
+        game.click_tile(3, 0).unwrap();
+        assert_eq!(
+            game.drain_events(),
+            vec![
+                GameEvent::TileFlagged {
+                    x: 0,
+                    y: 0,
+                    flagged: false
+                },
+                GameEvent::TileRevealed {
+                    x: 3,
+                    y: 0,
+                    number: 0
+                },
+                GameEvent::TileRevealed {
+                    x: 2,
+                    y: 0,
+                    number: 0
+                },
+                GameEvent::TileRevealed {
+                    x: 1,
+                    y: 0,
+                    number: 1
+                },
+                GameEvent::FloodFillCompleted {
+                    x: 3,
+                    y: 0,
+                    tiles: 3
+                },
+                GameEvent::GameWon,
+            ]
+        );
+        assert!(game.drain_events().is_empty());
+    }
+
+    #[test]
+    fn test_event_listener() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let mut game = Minesweeper::new_rect(4, 1, vec![(0, 0)]);
+        game.set_event_listener(Some(Box::new(move |event| {
+            sink.lock().unwrap().push(event.clone())
+        })));
+
+        game.click_tile(1, 0).unwrap();
+        game.click_tile(0, 0).unwrap();
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                GameEvent::TileRevealed {
+                    x: 1,
+                    y: 0,
+                    number: 1
+                },
+                GameEvent::BombExploded { x: 0, y: 0 },
+                GameEvent::GameLost,
+            ]
+        );
+    }
+}
@@ -0,0 +1,333 @@
+use std::io::{self, Write};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::{diff, GameState, Mark, Marker, Minesweeper, Move, Tile, TileValue};
+
+/// One line of an event log written by [`export_events`].
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Event {
+    /// The board, before any move.
+    Start {
+        width: usize,
+        height: usize,
+        mines: usize,
+        board_id: String,
+        three_bv: usize,
+    },
+    /// A move, whether or not it was legal.
+    Move {
+        seq: usize,
+        at_ms: u64,
+        #[serde(rename = "move")]
+        mv: String,
+        error: Option<String>,
+        three_bv_remaining: usize,
+    },
+    /// A tile uncovered by the move with the same `seq`.
+    Reveal {
+        seq: usize,
+        at_ms: u64,
+        x: usize,
+        y: usize,
+        /// The number shown, or `None` for a mine.
+        number: Option<u8>,
+    },
+    /// The game was won or lost by the move with the same `seq`.
+    State {
+        seq: usize,
+        at_ms: u64,
+        state: &'static str,
+    },
+}
+
+/// Replays `moves`, each with the time it was made, on `board` (a fresh
+/// copy of the board they were played on) and writes everything that
+/// happened as newline-delimited JSON: a `start` line describing the board,
+/// then for each move a `move` line, a `reveal` line per tile it uncovered
+/// and a `state` line if it ended the game.
+///
+/// Every line is a flat object with an `event` field, so the log loads
+/// straight into dataframe tools (e.g. `pandas.read_json(path,
+/// lines=True)`). Times are milliseconds from the start of the game. Pass
+/// the output of [`Minesweeper::timed_history`] to export a game that had
+/// history on.
+pub fn export_events(
+    mut board: Minesweeper,
+    moves: &[(Duration, Move)],
+    out: &mut impl Write,
+) -> io::Result<()> {
+    write_event(
+        out,
+        &Event::Start {
+            width: board.get_width(),
+            height: board.get_height(),
+            mines: board.get_bomb_count(),
+            board_id: board.board_id().to_string(),
+            three_bv: board.three_bv(),
+        },
+    )?;
+
+    for (seq, &(at, mv)) in moves.iter().enumerate() {
+        let seq = seq + 1;
+        let at_ms = at.as_millis() as u64;
+        let before = board.snapshot();
+        let error = board.apply_move(mv).err().map(|e| e.to_string());
+        write_event(
+            out,
+            &Event::Move {
+                seq,
+                at_ms,
+                mv: mv.to_string(),
+                error,
+                three_bv_remaining: board.three_bv_remaining(),
+            },
+        )?;
+
+        for change in diff(&before, &board.snapshot()) {
+            if !change.tile.exposed {
+                continue;
+            }
+            let number = match change.tile.value {
+                TileValue::Number(n) => Some(n),
+                TileValue::Bomb => None,
+            };
+            let (x, y) = (change.x, change.y);
+            write_event(
+                out,
+                &Event::Reveal {
+                    seq,
+                    at_ms,
+                    x,
+                    y,
+                    number,
+                },
+            )?;
+        }
+
+        if board.get_game_state() != before.get_game_state() {
+            let state = match board.get_game_state() {
+                GameState::Won => "won",
+                GameState::Lost => "lost",
+                GameState::InProgress => "in_progress",
+                GameState::Paused => "paused",
+            };
+            write_event(out, &Event::State { seq, at_ms, state })?;
+        }
+    }
+    Ok(())
+}
+
+/// A frontend-neutral animation of a game, from [`export_animation`].
+#[derive(Serialize)]
+struct Animation {
+    width: usize,
+    height: usize,
+    mines: usize,
+    board_id: String,
+    /// When the last keyframe happens.
+    duration_ms: u64,
+    keyframes: Vec<Keyframe>,
+}
+
+/// The tiles that changed look at one moment.
+#[derive(Serialize)]
+struct Keyframe {
+    at_ms: u64,
+    /// The move that caused the change, counted from 1, or 0 for the
+    /// board as it was before the first move.
+    seq: usize,
+    changes: Vec<Change>,
+    /// Set on the keyframe that ended the game, to `won` or `lost`.
+    state: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+struct Change {
+    x: usize,
+    y: usize,
+    /// `covered`, `flag`, `question`, `marker:<name>` (as in a
+    /// [`Move::SetMarker`]), `mine`, or the number shown, `0` to `8` (up
+    /// to `24` under extended adjacency).
+    look: String,
+}
+
+/// Replays `moves` like [`export_events`] and writes the game as one JSON
+/// animation script: the board's dimensions and a list of timestamped
+/// keyframes, each giving the new look of every tile that changed.
+///
+/// The first keyframe, at 0 ms, holds every tile that wasn't covered before
+/// the first move. Moves that changed nothing get no keyframe. Video
+/// overlays and web visualizers can play the script back by drawing a
+/// covered board and applying the keyframes in order, without the engine.
+pub fn export_animation(
+    mut board: Minesweeper,
+    moves: &[(Duration, Move)],
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let mut shown = Vec::new();
+    for y in 0..board.get_height() {
+        for x in 0..board.get_width() {
+            let tile = &board.board[(x, y)];
+            if tile.exposed || tile.marker.is_some() {
+                shown.push(Change {
+                    x,
+                    y,
+                    look: tile_look(tile),
+                });
+            }
+        }
+    }
+    let mut keyframes = vec![Keyframe {
+        at_ms: 0,
+        seq: 0,
+        changes: shown,
+        state: None,
+    }];
+
+    for (seq, &(at, mv)) in moves.iter().enumerate() {
+        let before = board.snapshot();
+        if board.apply_move(mv).is_err() {
+            continue;
+        }
+        let changes: Vec<Change> = diff(&before, &board.snapshot())
+            .into_iter()
+            .map(|change| Change {
+                x: change.x,
+                y: change.y,
+                look: tile_look(&change.tile),
+            })
+            .collect();
+        if changes.is_empty() {
+            continue;
+        }
+        let state = match board.get_game_state() {
+            GameState::Won => Some("won"),
+            GameState::Lost => Some("lost"),
+            GameState::InProgress | GameState::Paused => None,
+        };
+        keyframes.push(Keyframe {
+            at_ms: at.as_millis() as u64,
+            seq: seq + 1,
+            changes,
+            state,
+        });
+    }
+
+    let animation = Animation {
+        width: board.get_width(),
+        height: board.get_height(),
+        mines: board.get_bomb_count(),
+        board_id: board.board_id().to_string(),
+        duration_ms: keyframes.last().map_or(0, |frame| frame.at_ms),
+        keyframes,
+    };
+    serde_json::to_writer(&mut *out, &animation)?;
+    out.write_all(b"\n")
+}
+
+/// How `tile` looks to the player, in the words of [`Change::look`].
+fn tile_look(tile: &Tile) -> String {
+    if tile.exposed {
+        return match tile.value {
+            TileValue::Bomb => "mine".to_string(),
+            TileValue::Number(n) => n.to_string(),
+        };
+    }
+    match (tile.mark(), tile.marker) {
+        (Mark::Flag, _) => "flag".to_string(),
+        (Mark::Question, _) => "question".to_string(),
+        (Mark::None, Some(marker)) => format!("marker:{}", marker_name(marker)),
+        (Mark::None, None) => "covered".to_string(),
+    }
+}
+
+fn marker_name(marker: Marker) -> String {
+    match marker {
+        Marker::Red => "red".to_string(),
+        Marker::Yellow => "yellow".to_string(),
+        Marker::Green => "green".to_string(),
+        Marker::Blue => "blue".to_string(),
+        Marker::Symbol(c) => format!("symbol:{}", c),
+    }
+}
+
+fn write_event(out: &mut impl Write, event: &Event) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, event)?;
+    out.write_all(b"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_log() {
+        let board = || Minesweeper::new_rect(3, 1, vec![(0, 0)]);
+        let moves = [
+            (Duration::from_millis(500), Move::Reveal(2, 0)),
+            (Duration::from_millis(900), Move::Reveal(2, 0)),
+        ];
+        let mut out = Vec::new();
+        export_events(board(), &moves, &mut out).unwrap();
+
+        let lines: Vec<serde_json::Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let events: Vec<&str> = lines
+            .iter()
+            .map(|line| line["event"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            events,
+            ["start", "move", "reveal", "reveal", "state", "move"]
+        );
+
+        assert_eq!(lines[0]["board_id"], board().board_id().to_string());
+        assert_eq!(lines[1]["move"], "reveal 2 0");
+        assert_eq!(lines[1]["at_ms"], 500);
+        assert_eq!(lines[2]["number"], 1);
+        assert_eq!(lines[4]["state"], "won");
+        assert_eq!(lines[5]["error"], "Game is already finished");
+    }
+
+    #[test]
+    fn test_animation_script() {
+        let board = Minesweeper::new_rect(3, 2, vec![(0, 0)]);
+        let moves = [
+            (Duration::from_millis(200), Move::ToggleFlag(0, 0)),
+            (Duration::from_millis(300), Move::Reveal(9, 9)),
+            (Duration::from_millis(700), Move::Reveal(2, 0)),
+            (Duration::from_millis(900), Move::Reveal(0, 1)),
+        ];
+        let mut out = Vec::new();
+        export_animation(board, &moves, &mut out).unwrap();
+
+        let script: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(script["width"], 3);
+        assert_eq!(script["duration_ms"], 900);
+        let keyframes = script["keyframes"].as_array().unwrap();
+        // The initial board, the flag, the opening and the last tile; the
+        // illegal move left no trace.
+        assert_eq!(keyframes.len(), 4);
+        assert_eq!(keyframes[0]["changes"].as_array().unwrap().len(), 0);
+        assert_eq!(
+            keyframes[1]["changes"],
+            serde_json::json!([{"x": 0, "y": 0, "look": "flag"}])
+        );
+        assert_eq!(keyframes[2]["seq"], 3);
+        assert_eq!(keyframes[2]["state"], serde_json::Value::Null);
+        let looks: Vec<&str> = keyframes[2]["changes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|change| change["look"].as_str().unwrap())
+            .collect();
+        assert_eq!(looks, ["1", "1", "0", "0"]);
+        assert_eq!(keyframes[3]["state"], "won");
+    }
+}
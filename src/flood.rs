@@ -0,0 +1,127 @@
+use std::collections::VecDeque;
+
+use crate::{Adjacency, Tile, Topology};
+
+/// Buffers for walking openings, reused so that a walk never allocates.
+///
+/// Every flood-fill style question — what a click reveals, which tiles
+/// make up an opening for 3BV, what a hover preview shows — goes through
+/// [`FloodScratch::fill`].
+#[derive(Debug, Default)]
+pub(crate) struct FloodScratch {
+    /// Zeros still to expand, with their distance from the start.
+    queue: VecDeque<(usize, usize, usize)>,
+    /// Per-tile stamp; a tile is visited when its stamp equals `generation`.
+    visited: Vec<u32>,
+    generation: u32,
+    /// The tiles reached by the last fill, in the order they were reached.
+    region: Vec<(usize, usize)>,
+    /// Where in `region` each distance from the start begins.
+    layer_starts: Vec<usize>,
+}
+
+impl FloodScratch {
+    /// Only zeros are ever queued, so `zero_count` bounds the queue length.
+    pub(crate) fn new(tile_count: usize, zero_count: usize) -> Self {
+        FloodScratch {
+            queue: VecDeque::with_capacity(zero_count),
+            visited: vec![0; tile_count],
+            generation: 0,
+            region: Vec::with_capacity(tile_count),
+            layer_starts: Vec::with_capacity(tile_count),
+        }
+    }
+
+    /// Walks the region a click on (start_x, start_y) uncovers: the tile
+    /// itself and, if it is a zero, every connected zero and the numbers
+    /// around them. Tiles failing `passable` are neither entered nor
+    /// recorded. The result is left in [`FloodScratch::region`].
+    ///
+    /// The walk is breadth-first, so tiles are reached in order of their
+    /// distance from the start; see [`FloodScratch::layers`].
+    pub(crate) fn fill<'a>(
+        &mut self,
+        (start_x, start_y): (usize, usize),
+        (width, height): (usize, usize),
+        adjacency: Adjacency,
+        topology: Topology,
+        tile: impl Fn(usize, usize) -> &'a Tile,
+        passable: impl Fn(&Tile) -> bool,
+    ) {
+        self.begin(width * height);
+        if !passable(tile(start_x, start_y)) {
+            return;
+        }
+
+        self.visit(start_x * height + start_y);
+        self.reach((start_x, start_y), 0);
+        if tile(start_x, start_y).get_number() == Some(0) {
+            self.queue.push_back((start_x, start_y, 0));
+        }
+
+        while let Some((x, y, depth)) = self.queue.pop_front() {
+            for (nx, ny) in adjacency.neighbors_in(topology, x, y, width, height) {
+                let neighbor = tile(nx, ny);
+                if passable(neighbor) && self.visit(nx * height + ny) {
+                    self.reach((nx, ny), depth + 1);
+                    if neighbor.get_number() == Some(0) {
+                        self.queue.push_back((nx, ny, depth + 1));
+                    }
+                }
+            }
+        }
+    }
+
+    pub(crate) fn region(&self) -> &[(usize, usize)] {
+        &self.region
+    }
+
+    /// The region split by distance from the start, nearest first: the
+    /// start alone, then the tiles next to it, and so on outward.
+    pub(crate) fn layers(&self) -> impl Iterator<Item = &[(usize, usize)]> + '_ {
+        let ends = self
+            .layer_starts
+            .iter()
+            .skip(1)
+            .copied()
+            .chain([self.region.len()]);
+        self.layer_starts
+            .iter()
+            .zip(ends)
+            .map(|(&start, end)| &self.region[start..end])
+    }
+
+    /// Records a tile reached `depth` steps from the start. Breadth-first
+    /// order means depths never decrease.
+    fn reach(&mut self, pos: (usize, usize), depth: usize) {
+        if depth == self.layer_starts.len() {
+            self.layer_starts.push(self.region.len());
+        }
+        self.region.push(pos);
+    }
+
+    /// Starts a new fill, forgetting every tile visited by the previous one.
+    /// A default scratch buffer is sized here, on its first fill.
+    fn begin(&mut self, tile_count: usize) {
+        if self.visited.len() < tile_count {
+            self.visited.resize(tile_count, 0);
+        }
+        self.queue.clear();
+        self.region.clear();
+        self.layer_starts.clear();
+        self.generation = self.generation.wrapping_add(1);
+        if self.generation == 0 {
+            self.visited.fill(0);
+            self.generation = 1;
+        }
+    }
+
+    /// Marks a tile as visited, returning false if it already was.
+    fn visit(&mut self, index: usize) -> bool {
+        if self.visited[index] == self.generation {
+            return false;
+        }
+        self.visited[index] = self.generation;
+        true
+    }
+}
@@ -0,0 +1,822 @@
+use std::fmt;
+use std::sync::Arc;
+
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{Adjacency, Topology};
+
+/// Symmetry imposed on the mine layout. Every mine's images under the
+/// chosen transformations are mines too.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Symmetry {
+    #[default]
+    None,
+    /// Mirrored across the vertical centre line: `x -> width - 1 - x`.
+    MirrorX,
+    /// Mirrored across the horizontal centre line: `y -> height - 1 - y`.
+    MirrorY,
+    /// Mirrored across both centre lines.
+    MirrorBoth,
+    /// Unchanged by a half turn about the centre.
+    Rotate180,
+    /// Unchanged by a quarter turn about the centre. A quarter turn only
+    /// maps a square board onto itself, so rectangular boards get
+    /// `Rotate180` instead.
+    Rotate90,
+}
+
+impl Symmetry {
+    /// The images of (x, y) under this symmetry, including (x, y) itself,
+    /// without duplicates.
+    fn orbit(self, x: usize, y: usize, width: usize, height: usize) -> Vec<(usize, usize)> {
+        let (mx, my) = (width - 1, height - 1);
+        let mut images = match self {
+            Symmetry::None => vec![(x, y)],
+            Symmetry::MirrorX => vec![(x, y), (mx - x, y)],
+            Symmetry::MirrorY => vec![(x, y), (x, my - y)],
+            Symmetry::MirrorBoth => vec![(x, y), (mx - x, y), (x, my - y), (mx - x, my - y)],
+            Symmetry::Rotate90 if width == height => {
+                vec![(x, y), (mx - y, x), (mx - x, my - y), (y, my - x)]
+            }
+            Symmetry::Rotate180 | Symmetry::Rotate90 => vec![(x, y), (mx - x, my - y)],
+        };
+        images.sort_unstable();
+        images.dedup();
+        images
+    }
+}
+
+/// A custom density function, called with a tile's (x, y).
+pub type DensityFn = Arc<dyn Fn(usize, usize) -> f64 + Send + Sync>;
+
+/// How likely each tile is to receive a mine, relative to the others.
+///
+/// Weights are relative: only their ratios matter, and a weight of zero (or
+/// less) keeps a tile clear unless the mine count cannot be met otherwise.
+#[derive(Clone, Default)]
+pub enum Density {
+    /// Every tile equally likely.
+    #[default]
+    Uniform,
+    /// Weight changes linearly from `start` at x = 0 to `end` at the last x.
+    AlongX {
+        start: f64,
+        end: f64,
+    },
+    /// Weight changes linearly from `start` at y = 0 to `end` at the last y.
+    AlongY {
+        start: f64,
+        end: f64,
+    },
+    /// Weight changes linearly with distance from the first click, from
+    /// `center` next to it to `edge` at the farthest tile.
+    Radial {
+        center: f64,
+        edge: f64,
+    },
+    Custom(DensityFn),
+}
+
+impl fmt::Debug for Density {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Density::Uniform => write!(f, "Uniform"),
+            Density::AlongX { start, end } => {
+                write!(f, "AlongX {{ start: {:?}, end: {:?} }}", start, end)
+            }
+            Density::AlongY { start, end } => {
+                write!(f, "AlongY {{ start: {:?}, end: {:?} }}", start, end)
+            }
+            Density::Radial { center, edge } => {
+                write!(f, "Radial {{ center: {:?}, edge: {:?} }}", center, edge)
+            }
+            Density::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+impl Density {
+    fn weight(
+        &self,
+        x: usize,
+        y: usize,
+        (width, height): (usize, usize),
+        first_click: (usize, usize),
+    ) -> f64 {
+        let lerp = |from: f64, to: f64, t: f64| from + (to - from) * t;
+        let span_x = width.saturating_sub(1).max(1) as f64;
+        let span_y = height.saturating_sub(1).max(1) as f64;
+
+        match self {
+            Density::Uniform => 1.0,
+            Density::AlongX { start, end } => lerp(*start, *end, x as f64 / span_x),
+            Density::AlongY { start, end } => lerp(*start, *end, y as f64 / span_y),
+            Density::Radial { center, edge } => {
+                let (cx, cy) = (first_click.0 as f64, first_click.1 as f64);
+                let distance = |x: f64, y: f64| ((x - cx).powi(2) + (y - cy).powi(2)).sqrt();
+                let farthest = [(0.0, 0.0), (span_x, 0.0), (0.0, span_y), (span_x, span_y)]
+                    .iter()
+                    .map(|&(x, y)| distance(x, y))
+                    .fold(f64::MIN_POSITIVE, f64::max);
+                lerp(*center, *edge, distance(x as f64, y as f64) / farthest)
+            }
+            Density::Custom(f) => f(x, y),
+        }
+    }
+}
+
+/// A mine-free path between two tiles, e.g. from the opening to a goal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Corridor {
+    pub from: (usize, usize),
+    pub to: (usize, usize),
+    /// Width of the path in tiles; a width of 1 is a single king-move line.
+    pub width: usize,
+}
+
+impl Corridor {
+    /// The tiles the corridor keeps clear on a `width` x `height` board.
+    pub fn tiles(&self, width: usize, height: usize) -> Vec<(usize, usize)> {
+        let mut covered = vec![false; width * height];
+        let brush = self.width.max(1) as i64;
+        // Brush offsets, centred on the path with any extra tile after it.
+        let (low, high) = (-(brush - 1) / 2, brush / 2);
+
+        let (x0, y0) = (self.from.0 as i64, self.from.1 as i64);
+        let (x1, y1) = (self.to.0 as i64, self.to.1 as i64);
+        let steps = (x1 - x0).abs().max((y1 - y0).abs());
+
+        for step in 0..=steps {
+            let (px, py) = if steps == 0 {
+                (x0, y0)
+            } else {
+                (
+                    x0 + ((x1 - x0) * step * 2 + steps).div_euclid(steps * 2),
+                    y0 + ((y1 - y0) * step * 2 + steps).div_euclid(steps * 2),
+                )
+            };
+
+            for dx in low..=high {
+                for dy in low..=high {
+                    let (nx, ny) = (px + dx, py + dy);
+                    if nx >= 0 && ny >= 0 && (nx as usize) < width && (ny as usize) < height {
+                        covered[nx as usize * height + ny as usize] = true;
+                    }
+                }
+            }
+        }
+
+        (0..width * height)
+            .filter(|&index| covered[index])
+            .map(|index| (index / height, index % height))
+            .collect()
+    }
+}
+
+/// A rectangle of tiles, e.g. one a level designer keeps free of mines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Area {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Area {
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        (self.x..self.x + self.width).contains(&x) && (self.y..self.y + self.height).contains(&y)
+    }
+}
+
+/// How much of the area around the first click is kept free of mines.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Opening {
+    /// The click and its neighbours, so the game opens on a zero when the
+    /// board has room.
+    #[default]
+    Zero,
+    /// Only the clicked tile, which may turn out to be a lone number.
+    ClickOnly,
+}
+
+/// The random number generator boards are generated with: ChaCha with 8
+/// rounds, as implemented by `rand_chacha` 0.3.
+///
+/// Unlike `rand`'s thread RNG, whose algorithm may change between releases,
+/// ChaCha8's output is fully specified and identical on every platform,
+/// including WASM, so a seed always produces the same board (together with
+/// the `rand` 0.8 sampling routines [`generate_mines`] uses, which are
+/// value-stable within that release series).
+pub type BoardRng = ChaCha8Rng;
+
+/// A [`BoardRng`] seeded from a single `u64`, which is expanded to a full
+/// ChaCha key with PCG32 as `rand_core`'s `seed_from_u64` specifies.
+pub fn board_rng(seed: u64) -> BoardRng {
+    ChaCha8Rng::seed_from_u64(seed)
+}
+
+/// Options controlling how [`generate_mines`] lays out a board.
+#[derive(Debug, Clone, Default)]
+pub struct GeneratorOptions {
+    pub symmetry: Symmetry,
+    pub density: Density,
+    /// A path that is guaranteed to stay free of mines.
+    pub corridor: Option<Corridor>,
+    /// Areas that are guaranteed to stay free of mines, like the corridor.
+    pub forbidden: Vec<Area>,
+    /// How strongly mines attract each other. Every mine already placed
+    /// multiplies the weight of the tiles around it by `e^clustering`, so
+    /// positive values clump mines together and negative values spread them
+    /// out. Zero places mines independently.
+    pub clustering: f64,
+    /// The counting rule the board is played under. The area kept clear
+    /// around the first click grows to match, so it still opens on a zero.
+    pub adjacency: Adjacency,
+    /// Whether the board's edges wrap around, for adjacency and openings.
+    pub topology: Topology,
+    pub opening: Opening,
+}
+
+/// Picks `bomb_count` mine positions for a `width` x `height` board.
+///
+/// The first click and, when there is room and the [`Opening`] asks for it,
+/// its neighbours are kept free so the game opens on a zero. With a
+/// [`Symmetry`] other than `None`, mines are placed a whole orbit at a time;
+/// if no combination of remaining orbits adds up to exactly `bomb_count`,
+/// the last few mines are placed on single tiles so the count is still
+/// respected at the cost of perfect symmetry.
+/// A non-uniform [`Density`] biases which orbits are picked, weighting each
+/// orbit by the mean density of its tiles.
+///
+/// A nonzero clustering draws mines one orbit at a time, adjusting the
+/// weights around each as it goes.
+///
+/// A [`Corridor`] and forbidden [`Area`]s are always kept clear, even if
+/// that means placing fewer than `bomb_count` mines because the rest of the
+/// board is too small.
+pub fn generate_mines<R: Rng + ?Sized>(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    first_click: (usize, usize),
+    options: &GeneratorOptions,
+    rng: &mut R,
+) -> Vec<(usize, usize)> {
+    let (first_x, first_y) = first_click;
+
+    let mut forbidden = vec![false; width * height];
+    forbidden[first_x * height + first_y] = true;
+    if let Some(corridor) = &options.corridor {
+        for (x, y) in corridor.tiles(width, height) {
+            forbidden[x * height + y] = true;
+        }
+    }
+    for x in 0..width {
+        for y in 0..height {
+            if options.forbidden.iter().any(|area| area.contains(x, y)) {
+                forbidden[x * height + y] = true;
+            }
+        }
+    }
+
+    // Keep the first click's neighbours clear too so it opens on a zero,
+    // unless that leaves too little room for the mines.
+    let mut with_opening = forbidden.clone();
+    if options.opening == Opening::Zero {
+        for (x, y) in
+            options
+                .adjacency
+                .neighbors_in(options.topology, first_x, first_y, width, height)
+        {
+            with_opening[x * height + y] = true;
+        }
+    }
+    let mut orbits = allowed_orbits((width, height), options.symmetry, &with_opening);
+    if orbits.iter().map(Vec::len).sum::<usize>() < bomb_count {
+        orbits = allowed_orbits((width, height), options.symmetry, &forbidden);
+    }
+
+    // Shuffling first breaks ties between equally weighted orbits at random.
+    orbits.shuffle(rng);
+    let mean_weight = |orbit: &[(usize, usize)]| {
+        orbit
+            .iter()
+            .map(|&(x, y)| options.density.weight(x, y, (width, height), first_click))
+            .sum::<f64>()
+            / orbit.len() as f64
+    };
+    let ordered = if options.clustering == 0.0 {
+        weighted_order(orbits, mean_weight, rng)
+    } else {
+        clustered_order(orbits, mean_weight, (width, height), options, rng)
+    };
+
+    // Aim for the largest count whole orbits can reach, and only take an
+    // orbit if the orbits after it can still make up the rest exactly.
+    let mut unvisited = OrbitCounts::default();
+    for orbit in &ordered {
+        unvisited.add(orbit.len());
+    }
+    let mut target = bomb_count;
+    while !unvisited.can_make(target) {
+        target -= 1;
+    }
+
+    let mut mines = Vec::with_capacity(bomb_count);
+    let mut leftovers = Vec::new();
+    for orbit in ordered {
+        unvisited.remove(orbit.len());
+        let remaining = target - mines.len();
+        if orbit.len() <= remaining && unvisited.can_make(remaining - orbit.len()) {
+            mines.extend(orbit);
+        } else {
+            leftovers.extend(orbit);
+        }
+    }
+
+    // No whole orbit fits the remainder; top up from single tiles, which
+    // are still in weighted order.
+    let missing = bomb_count.saturating_sub(mines.len());
+    mines.extend(leftovers.into_iter().take(missing));
+
+    mines
+}
+
+/// The orbits in weighted random order (Efraimidis-Spirakis): sorting by
+/// ln(u) / w draws orbits without replacement in proportion to their weight.
+fn weighted_order<R: Rng + ?Sized>(
+    orbits: Vec<Vec<(usize, usize)>>,
+    weight: impl Fn(&[(usize, usize)]) -> f64,
+    rng: &mut R,
+) -> Vec<Vec<(usize, usize)>> {
+    let mut keyed: Vec<(f64, Vec<(usize, usize)>)> = orbits
+        .into_iter()
+        .map(|orbit| {
+            let weight = weight(&orbit);
+            let key = if weight > 0.0 {
+                rng.gen::<f64>().ln() / weight
+            } else {
+                f64::NEG_INFINITY
+            };
+            (key, orbit)
+        })
+        .collect();
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+    keyed.into_iter().map(|(_, orbit)| orbit).collect()
+}
+
+/// The orbits drawn one at a time in proportion to their weight, where each
+/// orbit drawn before changes the weight of its neighbours' orbits by the
+/// clustering factor. Every drawn orbit is assumed to become mines, which
+/// holds for all but the last few when the count can't be met exactly.
+/// Orbits whose weight is zero come last, in the shuffled order.
+fn clustered_order<R: Rng + ?Sized>(
+    orbits: Vec<Vec<(usize, usize)>>,
+    weight: impl Fn(&[(usize, usize)]) -> f64,
+    (width, height): (usize, usize),
+    options: &GeneratorOptions,
+    rng: &mut R,
+) -> Vec<Vec<(usize, usize)>> {
+    let mut orbit_of = vec![usize::MAX; width * height];
+    for (index, orbit) in orbits.iter().enumerate() {
+        for &(x, y) in orbit {
+            orbit_of[x * height + y] = index;
+        }
+    }
+    let base: Vec<f64> = orbits.iter().map(|orbit| weight(orbit).max(0.0)).collect();
+    // Mined neighbours of each orbit's tiles, summed over the orbit.
+    let mut mined = vec![0usize; orbits.len()];
+    let clustered = |index: usize, mined: usize, len: usize| {
+        // Capped so that weights, and their sum, stay finite.
+        let exponent = (options.clustering * mined as f64 / len as f64).clamp(-200.0, 200.0);
+        base[index] * exponent.exp()
+    };
+
+    let mut tree = WeightTree::new(&base);
+    let mut drawn = vec![false; orbits.len()];
+    let mut order = Vec::with_capacity(orbits.len());
+    let mut next_unweighted = 0;
+    while order.len() < orbits.len() {
+        let index = match tree.sample(rng, &drawn) {
+            Some(index) => index,
+            None => {
+                // Only zero weights are left.
+                while drawn[next_unweighted] {
+                    next_unweighted += 1;
+                }
+                next_unweighted
+            }
+        };
+        drawn[index] = true;
+        tree.set(index, 0.0);
+        order.push(index);
+
+        for &(x, y) in &orbits[index] {
+            for (nx, ny) in options
+                .adjacency
+                .neighbors_in(options.topology, x, y, width, height)
+            {
+                let neighbor = orbit_of[nx * height + ny];
+                if neighbor == usize::MAX || drawn[neighbor] {
+                    continue;
+                }
+                mined[neighbor] += 1;
+                let len = orbits[neighbor].len();
+                tree.set(neighbor, clustered(neighbor, mined[neighbor], len));
+            }
+        }
+    }
+
+    let mut orbits: Vec<Option<Vec<(usize, usize)>>> = orbits.into_iter().map(Some).collect();
+    order
+        .into_iter()
+        .map(|index| orbits[index].take().expect("each orbit is drawn once"))
+        .collect()
+}
+
+/// Weights in a Fenwick tree, for drawing by weight while they change.
+struct WeightTree {
+    weights: Vec<f64>,
+    sums: Vec<f64>,
+}
+
+impl WeightTree {
+    fn new(weights: &[f64]) -> Self {
+        let mut tree = WeightTree {
+            weights: weights.to_vec(),
+            sums: Vec::new(),
+        };
+        tree.rebuild();
+        tree
+    }
+
+    fn rebuild(&mut self) {
+        let len = self.weights.len();
+        self.sums = vec![0.0; len + 1];
+        for i in 1..=len {
+            self.sums[i] += self.weights[i - 1];
+            let parent = i + (i & i.wrapping_neg());
+            if parent <= len {
+                self.sums[parent] += self.sums[i];
+            }
+        }
+    }
+
+    fn set(&mut self, index: usize, weight: f64) {
+        let delta = weight - self.weights[index];
+        self.weights[index] = weight;
+        let mut i = index + 1;
+        while i < self.sums.len() {
+            self.sums[i] += delta;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    fn total(&self) -> f64 {
+        let mut i = self.weights.len();
+        let mut total = 0.0;
+        while i > 0 {
+            total += self.sums[i];
+            i -= i & i.wrapping_neg();
+        }
+        total
+    }
+
+    /// An index drawn in proportion to its weight, or `None` if every
+    /// weight is zero.
+    fn sample<R: Rng + ?Sized>(&mut self, rng: &mut R, drawn: &[bool]) -> Option<usize> {
+        for attempt in 0..2 {
+            let total = self.total();
+            if total > 0.0 {
+                let index = self.find(rng.gen::<f64>() * total);
+                if !drawn[index] && self.weights[index] > 0.0 {
+                    return Some(index);
+                }
+            }
+            if attempt == 0 {
+                // Rounding in the running sums can leave them pointing at
+                // an orbit that is already drawn; start them afresh.
+                self.rebuild();
+            }
+        }
+        None
+    }
+
+    /// The first index whose running sum exceeds `target`.
+    fn find(&self, mut target: f64) -> usize {
+        let len = self.weights.len();
+        let mut position = 0;
+        let mut step = len.next_power_of_two();
+        while step > 0 {
+            let next = position + step;
+            if next <= len && self.sums[next] <= target {
+                position = next;
+                target -= self.sums[next];
+            }
+            step /= 2;
+        }
+        position.min(len - 1)
+    }
+}
+
+/// How many orbits of each size (1, 2 or 4 tiles) are available.
+#[derive(Debug, Default)]
+struct OrbitCounts([usize; 3]);
+
+impl OrbitCounts {
+    fn slot(len: usize) -> usize {
+        match len {
+            1 => 0,
+            2 => 1,
+            _ => 2,
+        }
+    }
+
+    fn add(&mut self, len: usize) {
+        self.0[Self::slot(len)] += 1;
+    }
+
+    fn remove(&mut self, len: usize) {
+        self.0[Self::slot(len)] -= 1;
+    }
+
+    /// Whether some of these orbits hold exactly `target` tiles. Each size
+    /// divides the next, so taking the largest orbits first never misses a
+    /// solution.
+    fn can_make(&self, target: usize) -> bool {
+        let [ones, twos, fours] = self.0;
+        let rest = target - 4 * fours.min(target / 4);
+        let rest = rest - 2 * twos.min(rest / 2);
+        rest <= ones
+    }
+}
+
+/// Orbits of tiles that may hold mines: those with no tile marked in
+/// `forbidden`, so that the forbidden area stays clear under the symmetry.
+fn allowed_orbits(
+    (width, height): (usize, usize),
+    symmetry: Symmetry,
+    forbidden: &[bool],
+) -> Vec<Vec<(usize, usize)>> {
+    let mut orbits = Vec::new();
+
+    for x in 0..width {
+        for y in 0..height {
+            let orbit = symmetry.orbit(x, y, width, height);
+            // Each orbit is visited from all of its members; keep it once.
+            if orbit[0] != (x, y) {
+                continue;
+            }
+            if orbit.iter().any(|&(x, y)| forbidden[x * height + y]) {
+                continue;
+            }
+            orbits.push(orbit);
+        }
+    }
+
+    orbits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generate(size: usize, bomb_count: usize, symmetry: Symmetry) -> Vec<(usize, usize)> {
+        let options = GeneratorOptions {
+            symmetry,
+            ..Default::default()
+        };
+        generate_mines(
+            size,
+            size,
+            bomb_count,
+            (0, 0),
+            &options,
+            &mut rand::thread_rng(),
+        )
+    }
+
+    #[test]
+    fn test_symmetric_layouts_are_symmetric() {
+        for symmetry in [
+            Symmetry::MirrorX,
+            Symmetry::MirrorY,
+            Symmetry::MirrorBoth,
+            Symmetry::Rotate180,
+            Symmetry::Rotate90,
+        ] {
+            // 9x9 has a centre tile, so every count can be met exactly.
+            let mines = generate(9, 12, symmetry);
+            assert_eq!(mines.len(), 12);
+
+            for &(x, y) in &mines {
+                for image in symmetry.orbit(x, y, 9, 9) {
+                    assert!(mines.contains(&image), "{:?} breaks {:?}", image, symmetry);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_rectangular_layouts() {
+        let options = GeneratorOptions {
+            symmetry: Symmetry::Rotate90,
+            ..Default::default()
+        };
+        // A quarter turn doesn't fit a 30x16 board, so it falls back to a
+        // half turn.
+        let mines = generate_mines(30, 16, 98, (0, 0), &options, &mut rand::thread_rng());
+        assert_eq!(mines.len(), 98);
+        for &(x, y) in &mines {
+            assert!(x < 30 && y < 16);
+            assert!(mines.contains(&(29 - x, 15 - y)));
+        }
+    }
+
+    #[test]
+    fn test_symmetry_keeps_opening_and_count() {
+        // An even board under Rotate90 only has orbits of four, so an odd
+        // count forces a few asymmetric mines.
+        for _ in 0..10 {
+            let mines = generate(8, 13, Symmetry::Rotate90);
+            assert_eq!(mines.len(), 13);
+            for forbidden in Adjacency::Standard.neighbors(0, 0, 8, 8) {
+                assert!(!mines.contains(&forbidden));
+            }
+        }
+    }
+
+    #[test]
+    fn test_density_gradient_biases_placement() {
+        let options = GeneratorOptions {
+            density: Density::AlongX {
+                start: 0.0,
+                end: 1.0,
+            },
+            ..Default::default()
+        };
+        let mines = generate_mines(30, 30, 90, (0, 0), &options, &mut rand::thread_rng());
+
+        assert_eq!(mines.len(), 90);
+        // Zero weight keeps the first row clear.
+        assert!(mines.iter().all(|&(x, _)| x > 0));
+        // About three quarters of the weight lies in the far half.
+        let far_half = mines.iter().filter(|&&(x, _)| x >= 15).count();
+        assert!(far_half > 50, "only {} mines in the dense half", far_half);
+    }
+
+    #[test]
+    fn test_custom_density_still_meets_count() {
+        // Only one column is weighted, but it cannot hold every mine.
+        let options = GeneratorOptions {
+            density: Density::Custom(Arc::new(|_, y| if y == 5 { 1.0 } else { 0.0 })),
+            ..Default::default()
+        };
+        let mines = generate_mines(10, 10, 15, (0, 0), &options, &mut rand::thread_rng());
+
+        assert_eq!(mines.len(), 15);
+        assert_eq!(mines.iter().filter(|&&(_, y)| y == 5).count(), 10);
+    }
+
+    #[test]
+    fn test_corridor_tiles() {
+        let diagonal = Corridor {
+            from: (0, 0),
+            to: (3, 3),
+            width: 1,
+        };
+        assert_eq!(diagonal.tiles(4, 4), vec![(0, 0), (1, 1), (2, 2), (3, 3)]);
+
+        let wide = Corridor {
+            from: (0, 1),
+            to: (3, 1),
+            width: 3,
+        };
+        assert_eq!(wide.tiles(4, 4).len(), 12);
+    }
+
+    #[test]
+    fn test_corridor_stays_clear() {
+        let corridor = Corridor {
+            from: (0, 0),
+            to: (9, 6),
+            width: 2,
+        };
+        let options = GeneratorOptions {
+            corridor: Some(corridor),
+            symmetry: Symmetry::Rotate180,
+            ..Default::default()
+        };
+
+        for _ in 0..10 {
+            let mines = generate_mines(10, 10, 30, (0, 0), &options, &mut rand::thread_rng());
+            assert_eq!(mines.len(), 30);
+            for tile in corridor.tiles(10, 10) {
+                assert!(!mines.contains(&tile));
+            }
+        }
+
+        // The corridor wins over the mine count on a crowded board.
+        let options = GeneratorOptions {
+            corridor: Some(corridor),
+            ..Default::default()
+        };
+        let mines = generate_mines(10, 10, 99, (0, 0), &options, &mut rand::thread_rng());
+        assert_eq!(mines.len(), 100 - corridor.tiles(10, 10).len());
+    }
+
+    /// Pairs of mines next to each other, averaged over a few boards.
+    fn mean_touching(clustering: f64) -> f64 {
+        let options = GeneratorOptions {
+            clustering,
+            ..Default::default()
+        };
+        let mut total = 0;
+        for seed in 0..20 {
+            let mines = generate_mines(20, 20, 60, (0, 0), &options, &mut board_rng(seed));
+            assert_eq!(mines.len(), 60);
+            for &(x, y) in &mines {
+                total += mines
+                    .iter()
+                    .filter(|&&(mx, my)| {
+                        (mx, my) != (x, y) && mx.abs_diff(x) <= 1 && my.abs_diff(y) <= 1
+                    })
+                    .count();
+            }
+        }
+        total as f64 / 40.0
+    }
+
+    #[test]
+    fn test_clustering_clumps_or_spreads_mines() {
+        let uniform = mean_touching(0.0);
+        assert!(mean_touching(2.0) > uniform * 1.5);
+        assert!(mean_touching(-2.0) < uniform * 0.5);
+    }
+
+    #[test]
+    fn test_clustered_symmetric_layouts_meet_the_count() {
+        let options = GeneratorOptions {
+            clustering: 1.5,
+            symmetry: Symmetry::MirrorBoth,
+            ..Default::default()
+        };
+        for seed in 0..10 {
+            let mines = generate_mines(9, 9, 15, (4, 4), &options, &mut board_rng(seed));
+            assert_eq!(mines.len(), 15);
+            let again = generate_mines(9, 9, 15, (4, 4), &options, &mut board_rng(seed));
+            assert_eq!(again, mines);
+        }
+    }
+
+    #[test]
+    fn test_forbidden_areas_stay_clear() {
+        let area = Area {
+            x: 2,
+            y: 3,
+            width: 4,
+            height: 5,
+        };
+        let options = GeneratorOptions {
+            forbidden: vec![area],
+            clustering: 1.0,
+            ..Default::default()
+        };
+        for seed in 0..10 {
+            let mines = generate_mines(10, 10, 40, (9, 9), &options, &mut board_rng(seed));
+            assert_eq!(mines.len(), 40);
+            assert!(mines.iter().all(|&(x, y)| !area.contains(x, y)));
+        }
+
+        // Like a corridor, the area wins over the mine count.
+        let mines = generate_mines(10, 10, 99, (9, 9), &options, &mut board_rng(0));
+        assert_eq!(mines.len(), 100 - 20 - 1);
+    }
+
+    #[test]
+    fn test_seeded_generation_is_stable() {
+        // Pinned output: if this changes, every shared seed and daily board
+        // changes with it.
+        let mines = generate_mines(
+            8,
+            8,
+            6,
+            (0, 0),
+            &GeneratorOptions::default(),
+            &mut board_rng(42),
+        );
+        assert_eq!(mines, vec![(6, 0), (3, 4), (1, 2), (0, 7), (3, 6), (7, 7)]);
+
+        let again = generate_mines(
+            8,
+            8,
+            6,
+            (0, 0),
+            &GeneratorOptions::default(),
+            &mut board_rng(42),
+        );
+        assert_eq!(again, mines);
+    }
+}
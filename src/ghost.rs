@@ -0,0 +1,182 @@
+use std::time::Duration;
+
+use crate::{BoardId, GameState, InputKind, InputRecording, Minesweeper, Move, SplitDelta};
+
+/// A previous run raced alongside the live game, like a ghost car.
+///
+/// The run is replayed once up front into a curve of 3BV cleared over time,
+/// which the live game can then be compared against at any moment. Ghosts
+/// from the same board compare 3BV directly; a ghost from another board of
+/// the same difficulty still works, compared by the fraction cleared.
+#[derive(Debug, Clone)]
+pub struct Ghost {
+    board_id: BoardId,
+    total_bv: usize,
+    /// (time, 3BV cleared) after each move that changed it.
+    points: Vec<(Duration, usize)>,
+    finish: Option<Duration>,
+}
+
+impl Ghost {
+    /// Replays `moves`, each with the time it was made, on `board`: a
+    /// fresh copy of the board the run was played on.
+    pub fn from_replay(mut board: Minesweeper, moves: &[(Duration, Move)]) -> Self {
+        let mut points = Vec::new();
+        let mut finish = None;
+        for &(at, mv) in moves {
+            let before = board.three_bv_remaining();
+            if board.apply_move(mv).is_err() {
+                continue;
+            }
+            if board.three_bv_remaining() != before {
+                points.push((at, board.three_bv() - board.three_bv_remaining()));
+            }
+            if finish.is_none() && *board.get_game_state() == GameState::Won {
+                finish = Some(at);
+            }
+        }
+
+        Ghost {
+            board_id: board.board_id(),
+            total_bv: board.three_bv(),
+            points,
+            finish,
+        }
+    }
+
+    /// A ghost from the moves in a keystroke recording.
+    pub fn from_recording(board: Minesweeper, recording: &InputRecording) -> Self {
+        let moves: Vec<(Duration, Move)> = recording
+            .events()
+            .iter()
+            .filter_map(|event| match event.kind {
+                InputKind::Move(mv) => Some((event.at, mv)),
+                _ => None,
+            })
+            .collect();
+        Self::from_replay(board, &moves)
+    }
+
+    /// The board the ghost's run was played on.
+    pub fn board_id(&self) -> BoardId {
+        self.board_id
+    }
+
+    /// When the ghost won, if it did.
+    pub fn finish_time(&self) -> Option<Duration> {
+        self.finish
+    }
+
+    /// 3BV the ghost had cleared at `elapsed`.
+    pub fn bv_at(&self, elapsed: Duration) -> usize {
+        let reached = self.points.partition_point(|&(at, _)| at <= elapsed);
+        match reached {
+            0 => 0,
+            n => self.points[n - 1].1,
+        }
+    }
+
+    /// Fraction of its board the ghost had cleared at `elapsed`.
+    pub fn progress_at(&self, elapsed: Duration) -> f64 {
+        if self.total_bv == 0 {
+            return 0.0;
+        }
+        self.bv_at(elapsed) as f64 / self.total_bv as f64
+    }
+
+    /// How far ahead of or behind the ghost the live game is, in time: the
+    /// gap between `elapsed` and when the ghost got as far as the game has
+    /// now. `None` before the game has made progress, or if the ghost never
+    /// got that far.
+    pub fn delta(&self, game: &Minesweeper, elapsed: Duration) -> Option<SplitDelta> {
+        let solved = game.three_bv() - game.three_bv_remaining();
+        if solved == 0 {
+            return None;
+        }
+
+        let reached = |ghost_solved: usize| {
+            if game.board_id() == self.board_id {
+                ghost_solved >= solved
+            } else {
+                ghost_solved as f64 / self.total_bv as f64 >= game.progress()
+            }
+        };
+        let &(ghost_time, _) = self.points.iter().find(|&&(_, bv)| reached(bv))?;
+
+        Some(if elapsed <= ghost_time {
+            SplitDelta::Ahead(ghost_time - elapsed)
+        } else {
+            SplitDelta::Behind(elapsed - ghost_time)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(n: u64) -> Duration {
+        Duration::from_secs(n)
+    }
+
+    /// An opening on the far side plus two isolated numbers between the
+    /// mines: a 3BV of 3.
+    fn board() -> Minesweeper {
+        Minesweeper::new(4, vec![(0, 0), (0, 2)])
+    }
+
+    fn ghost() -> Ghost {
+        Ghost::from_replay(
+            board(),
+            &[
+                (secs(2), Move::Reveal(3, 3)),
+                (secs(3), Move::ToggleFlag(0, 0)),
+                (secs(5), Move::Reveal(0, 1)),
+                (secs(9), Move::Reveal(0, 3)),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_ghost_progress_curve() {
+        let ghost = ghost();
+        let total = board().three_bv();
+        assert_eq!(ghost.bv_at(secs(1)), 0);
+        assert_eq!(ghost.bv_at(secs(2)), 1);
+        assert_eq!(ghost.bv_at(secs(6)), 2);
+        assert_eq!(ghost.bv_at(secs(60)), total);
+        assert_eq!(ghost.progress_at(secs(60)), 1.0);
+        assert_eq!(ghost.finish_time(), Some(secs(9)));
+    }
+
+    #[test]
+    fn test_live_delta_on_same_board() {
+        let ghost = ghost();
+        let mut game = board();
+        assert_eq!(ghost.delta(&game, secs(1)), None);
+
+        game.click_tile(3, 3).unwrap();
+        assert_eq!(
+            ghost.delta(&game, secs(1)),
+            Some(SplitDelta::Ahead(secs(1)))
+        );
+
+        game.click_tile(0, 1).unwrap();
+        assert_eq!(
+            ghost.delta(&game, secs(7)),
+            Some(SplitDelta::Behind(secs(2)))
+        );
+    }
+
+    #[test]
+    fn test_ghost_from_recording() {
+        let mut recording = InputRecording::new();
+        recording.record(secs(1), InputKind::Key("Enter".to_string()));
+        recording.record(secs(2), InputKind::Move(Move::Reveal(3, 3)));
+
+        let ghost = Ghost::from_recording(board(), &recording);
+        assert_eq!(ghost.bv_at(secs(2)), 1);
+        assert_eq!(ghost.board_id(), board().board_id());
+        assert_eq!(ghost.finish_time(), None);
+    }
+}
@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use crate::{GameConfig, GeneratorOptions, Minesweeper, MinesweeperError, Opening};
+
+/// Disadvantages given to a stronger player so that competitive games
+/// between mismatched players stay close.
+///
+/// A handicap is applied when a player's board is set up, via
+/// [`Handicap::new_game`], and when their time is compared against others,
+/// via [`Handicap::adjusted_time`]. The default handicap changes nothing.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Handicap {
+    /// Mines added on top of the mode's mine count.
+    pub extra_mines: usize,
+    /// Added to the player's time before it is compared.
+    pub time_penalty: Duration,
+    /// Overrides the generator's opening, e.g. [`Opening::ClickOnly`] for a
+    /// start that may not open up.
+    pub opening: Option<Opening>,
+}
+
+impl Handicap {
+    /// The generator settings and mine count a handicapped player's board is
+    /// built with, starting from the mode's own.
+    pub fn apply(
+        &self,
+        bomb_count: usize,
+        options: &GeneratorOptions,
+    ) -> (usize, GeneratorOptions) {
+        let mut options = options.clone();
+        if let Some(opening) = self.opening {
+            options.opening = opening;
+        }
+        (bomb_count + self.extra_mines, options)
+    }
+
+    /// `config` with the handicap applied. Extra mines stop at what the
+    /// board can hold besides a first click.
+    pub fn config(&self, config: &GameConfig) -> GameConfig {
+        let mut config = config.clone();
+        let (bomb_count, generator) = self.apply(config.bomb_count, &config.generator);
+        let capacity = (config.width * config.height).saturating_sub(1);
+        config.bomb_count = bomb_count.min(capacity.max(config.bomb_count));
+        config.generator = generator;
+        config
+    }
+
+    /// Creates a handicapped player's game, as
+    /// [`Minesweeper::new_with_options`] does for an unhandicapped one.
+    pub fn new_game(
+        &self,
+        size: usize,
+        bomb_count: usize,
+        first_click: (usize, usize),
+        options: &GeneratorOptions,
+    ) -> Result<Minesweeper, MinesweeperError> {
+        let (bomb_count, options) = self.apply(bomb_count, options);
+        // The generator cannot place more mines than there are free tiles.
+        let bomb_count = bomb_count.min((size * size).saturating_sub(1));
+        Minesweeper::new_with_options(size, bomb_count, first_click, &options)
+    }
+
+    /// The time a player is ranked by, given how long they actually took.
+    pub fn adjusted_time(&self, elapsed: Duration) -> Duration {
+        elapsed + self.time_penalty
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_handicap_changes_nothing() {
+        let handicap = Handicap::default();
+        let (bomb_count, options) = handicap.apply(10, &GeneratorOptions::default());
+
+        assert_eq!(bomb_count, 10);
+        assert_eq!(options.opening, Opening::Zero);
+        assert_eq!(
+            handicap.adjusted_time(Duration::from_secs(30)),
+            Duration::from_secs(30)
+        );
+    }
+
+    #[test]
+    fn test_handicapped_game() {
+        let handicap = Handicap {
+            extra_mines: 5,
+            time_penalty: Duration::from_secs(10),
+            opening: Some(Opening::ClickOnly),
+        };
+
+        let game = handicap
+            .new_game(9, 10, (4, 4), &GeneratorOptions::default())
+            .unwrap();
+        assert_eq!(game.get_bomb_count(), 15);
+        assert!(game.get_tile(4, 4).unwrap().exposed);
+        assert_eq!(
+            handicap.adjusted_time(Duration::from_secs(30)),
+            Duration::from_secs(40)
+        );
+
+        let config = handicap.config(&GameConfig::new_rect(4, 3, 8));
+        assert_eq!(config.bomb_count, 11);
+        assert_eq!(config.generator.opening, Opening::ClickOnly);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::probability::{ProbabilityEngine, CERTAIN};
+use crate::solver::Meter;
+use crate::{BudgetedAnalysis, FlagRule, GameState, Minesweeper, MinesweeperError, SolverBudget};
+
+/// The move [`Minesweeper::hint`] recommends.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Hint {
+    /// A covered tile that is certainly safe to reveal.
+    Safe(usize, usize),
+    /// A covered, unflagged tile that is certainly a mine.
+    Flag(usize, usize),
+    /// Nothing is certain; this tile is the least likely to be a mine.
+    Guess {
+        x: usize,
+        y: usize,
+        /// The chance the tile is a mine.
+        probability: f64,
+    },
+}
+
+/// What [`Minesweeper::hint_within`] found before it finished or ran out
+/// of budget.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BudgetedHint {
+    /// The suggestion, or `None` if the budget ran out before anything was
+    /// found. A safe tile or mine suggested when cut short is still
+    /// certain, but may not be the one [`Minesweeper::hint`] picks.
+    pub hint: Option<Hint>,
+    /// Whether the search ran to completion, so `hint` matches
+    /// [`Minesweeper::hint`].
+    pub complete: bool,
+}
+
+impl Minesweeper {
+    /// Suggests the best next move from what the player can see: a safe
+    /// reveal if there is one, otherwise a mine to flag, otherwise the
+    /// guess least likely to hit a mine. Meant for teaching modes, so a
+    /// hint never relies on where the mines actually are.
+    ///
+    /// Tiles the player has flagged are never suggested for revealing.
+    /// Under [`FlagRule::Forbidden`] no flag is suggested either, and a
+    /// guess follows straight after the safe tiles run out.
+    ///
+    /// Weighing guesses enumerates the frontier, which can take a long
+    /// time on a big one; [`Minesweeper::hint_within`] caps the work.
+    pub fn hint(&self) -> Result<Hint, MinesweeperError> {
+        self.hint_within(SolverBudget::default())?
+            .hint
+            .ok_or(MinesweeperError::NoHint)
+    }
+
+    /// [`Minesweeper::hint`] with a cap on the work done, shared between
+    /// the solver and the probability engine. When the budget runs out
+    /// before the guesses are weighed, only a tile already proven safe or a
+    /// mine is suggested.
+    pub fn hint_within(&self, budget: SolverBudget) -> Result<BudgetedHint, MinesweeperError> {
+        if self.game_state != GameState::InProgress {
+            return Err(self.finished_error());
+        }
+
+        let mut meter = Meter::new(budget);
+        let covered = |&(x, y): &(usize, usize)| {
+            let tile = &self.board[(x, y)];
+            !tile.exposed && !tile.flagged
+        };
+        let BudgetedAnalysis { analysis, complete } = self.analyze_metered(&mut meter);
+        let found = |hint| {
+            Ok(BudgetedHint {
+                hint: Some(hint),
+                complete,
+            })
+        };
+        if let Some(&(x, y)) = analysis.safe.iter().find(|pos| covered(pos)) {
+            return found(Hint::Safe(x, y));
+        }
+
+        let flags = self.flag_rule == FlagRule::Allowed;
+        let proven = analysis.mines.iter().copied().find(|pos| covered(pos));
+
+        // The probability engine sees whole-frontier deductions that
+        // propagation misses.
+        let Some(probabilities) = ProbabilityEngine::default().compute_metered(self, &mut meter)
+        else {
+            return Ok(BudgetedHint {
+                hint: proven.filter(|_| flags).map(|(x, y)| Hint::Flag(x, y)),
+                complete: false,
+            });
+        };
+        let probability = |(x, y): (usize, usize)| probabilities[x * self.height + y];
+        let candidates = (0..self.width)
+            .flat_map(|x| (0..self.height).map(move |y| (x, y)))
+            .filter(covered);
+
+        let mut best: Option<((usize, usize), f64)> = None;
+        for pos in candidates.clone() {
+            let p = probability(pos);
+            if best.is_none_or(|(_, lowest)| p < lowest) {
+                best = Some((pos, p));
+            }
+        }
+        if let Some(((x, y), _)) = best.filter(|&(_, p)| p < CERTAIN) {
+            return found(Hint::Safe(x, y));
+        }
+
+        let mine = proven.or_else(|| {
+            candidates
+                .clone()
+                .find(|&pos| probability(pos) > 1.0 - CERTAIN)
+        });
+        if let Some((x, y)) = mine.filter(|_| flags) {
+            return found(Hint::Flag(x, y));
+        }
+
+        Ok(BudgetedHint {
+            hint: best.map(|((x, y), probability)| Hint::Guess { x, y, probability }),
+            complete,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hints_prefer_safe_then_flag_then_guess() {
+        // The 1-2-1 down column 1 puts the mines at either end of column 0.
+        let mut game = Minesweeper::new(3, vec![(0, 0), (0, 2)]);
+        game.click_tile(2, 1).unwrap();
+        assert_eq!(game.hint(), Ok(Hint::Safe(0, 1)));
+
+        // A wrong flag on the safe (1, 0) leaves only the mine to suggest.
+        let mut game = Minesweeper::new_rect(5, 1, vec![(0, 0)]);
+        game.toggle_flag(1, 0).unwrap();
+        game.click_tile(4, 0).unwrap();
+        assert_eq!(game.hint(), Ok(Hint::Flag(0, 0)));
+        game.toggle_flag(0, 0).unwrap();
+        assert!(game.hint().is_err());
+    }
+
+    #[test]
+    fn test_guess_picks_the_lowest_probability() {
+        // A 1 on a 2x2 board with one mine: each covered tile is a third.
+        let mut game = Minesweeper::new(2, vec![(0, 0)]);
+        game.click_tile(1, 1).unwrap();
+        match game.hint() {
+            Ok(Hint::Guess { probability, .. }) => assert!((probability - 1.0 / 3.0).abs() < 1e-9),
+            other => panic!("expected a guess, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_budget_limits_the_hint() {
+        // A 1 on a 2x2 board: nothing is proven, so a guess must be weighed.
+        let mut game = Minesweeper::new(2, vec![(0, 0)]);
+        game.click_tile(1, 1).unwrap();
+        let full = game.hint_within(SolverBudget::default()).unwrap();
+        assert!(full.complete);
+        assert_eq!(full.hint, game.hint().ok());
+
+        let cut = game
+            .hint_within(SolverBudget {
+                max_steps: Some(2),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(
+            cut,
+            BudgetedHint {
+                hint: None,
+                complete: false
+            }
+        );
+        assert_eq!(
+            game.mine_probabilities_within(SolverBudget {
+                max_steps: Some(2),
+                ..Default::default()
+            }),
+            None
+        );
+        assert_eq!(
+            game.mine_probabilities_within(SolverBudget::default()),
+            Some(game.mine_probabilities())
+        );
+    }
+}
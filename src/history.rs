@@ -0,0 +1,307 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::board::Board;
+use crate::metrics::BvTracker;
+use crate::{GameState, Minesweeper, MinesweeperError, Move};
+
+/// The parts of a game a move can change, captured just before it.
+/// Tiles are shared with the live board until it next writes to them.
+#[derive(Debug)]
+pub(crate) struct Checkpoint {
+    board: Board,
+    game_state: GameState,
+    covered_safe: usize,
+    flags_placed: usize,
+    bv: BvTracker,
+    lives: usize,
+}
+
+/// Reports how long the game has been going, for timestamping moves.
+pub type MoveClock = Arc<dyn Fn() -> Duration + Send + Sync>;
+
+/// Moves that can be undone, each with when it was made and the state from
+/// before it, and the moves that were undone and can be redone, most
+/// recent last.
+pub(crate) struct History {
+    done: Vec<(Move, Duration, Checkpoint)>,
+    undone: Vec<Move>,
+    clock: MoveClock,
+}
+
+impl fmt::Debug for History {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("History")
+            .field("done", &self.done)
+            .field("undone", &self.undone)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for History {
+    /// Times moves from the moment history is turned on.
+    fn default() -> Self {
+        let start = Instant::now();
+        History {
+            done: Vec::new(),
+            undone: Vec::new(),
+            clock: Arc::new(move || start.elapsed()),
+        }
+    }
+}
+
+impl Minesweeper {
+    /// Turns undo/redo history on or off. Turning it off forgets every
+    /// recorded move.
+    ///
+    /// While it is on, every successful move is recorded along with the
+    /// state it replaced. Tiles are shared copy-on-write, so a checkpoint
+    /// costs little more than the chunks the move changed, but recording
+    /// does allocate, so it is off by default to keep bulk simulation
+    /// allocation-free.
+    pub fn set_history(&mut self, enabled: bool) {
+        if !enabled {
+            self.history = None;
+        } else if self.history.is_none() {
+            self.history = Some(History::default());
+        }
+    }
+
+    /// Drops every move that could be undone or redone, keeping history
+    /// on, for changes to the board that aren't moves.
+    pub(crate) fn forget_history(&mut self) {
+        if let Some(history) = &mut self.history {
+            history.done.clear();
+            history.undone.clear();
+        }
+    }
+
+    /// Replaces the clock moves are timestamped with, turning history on
+    /// if it is off. By default moves are timed from when history was
+    /// turned on; inject a clock to share a game timer or to test.
+    pub fn set_move_clock(&mut self, clock: MoveClock) {
+        self.history.get_or_insert_with(History::default).clock = clock;
+    }
+
+    /// The state before the oldest move that can be undone: the board, the
+    /// flags placed and the lives left. `None` without such a move.
+    #[cfg(feature = "serde")]
+    pub(crate) fn history_start(&self) -> Option<(&Board, usize, usize)> {
+        let (_, _, checkpoint) = self.history.as_ref()?.done.first()?;
+        Some((&checkpoint.board, checkpoint.flags_placed, checkpoint.lives))
+    }
+
+    /// The moves that can be redone, most recently undone last.
+    #[cfg(feature = "serde")]
+    pub(crate) fn redoable(&self) -> &[Move] {
+        self.history
+            .as_ref()
+            .map_or(&[], |history| history.undone.as_slice())
+    }
+
+    /// Replays `moves` with history on, stamping each with its saved time,
+    /// and leaves `undone` to redo. Moves made afterwards are timed on from
+    /// the last one.
+    #[cfg(feature = "serde")]
+    pub(crate) fn replay_history(
+        &mut self,
+        moves: &[(Duration, Move)],
+        undone: Vec<Move>,
+    ) -> Result<(), String> {
+        self.set_history(true);
+        for &(_, mv) in moves {
+            self.apply_move(mv)?;
+        }
+        let last = moves.last().map_or(Duration::ZERO, |&(at, _)| at);
+        let start = Instant::now();
+        let history = self.history.as_mut().expect("history was turned on");
+        for (done, &(at, _)) in history.done.iter_mut().zip(moves) {
+            done.1 = at;
+        }
+        history.undone = undone;
+        history.clock = Arc::new(move || last + start.elapsed());
+        Ok(())
+    }
+
+    /// The moves that can currently be undone, oldest first.
+    pub fn history(&self) -> Vec<Move> {
+        self.timed_history().into_iter().map(|(_, mv)| mv).collect()
+    }
+
+    /// [`Minesweeper::history`] with the time each move was made, as
+    /// [`Ghost::from_replay`](crate::Ghost::from_replay) takes it.
+    pub fn timed_history(&self) -> Vec<(Duration, Move)> {
+        match &self.history {
+            Some(history) => history.done.iter().map(|&(mv, at, _)| (at, mv)).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Moves per minute over the recorded history, from the first move to
+    /// the last. `None` until two moves have been made at different times.
+    pub fn moves_per_minute(&self) -> Option<f64> {
+        let history = self.timed_history();
+        let (first, last) = (history.first()?.0, history.last()?.0);
+        let span = last.checked_sub(first).filter(|span| !span.is_zero())?;
+        Some((history.len() - 1) as f64 * 60.0 / span.as_secs_f64())
+    }
+
+    /// Reverts the most recent move and returns it. Undoing a losing click
+    /// puts the game back in progress. Refused while paused.
+    pub fn undo(&mut self) -> Result<Move, MinesweeperError> {
+        if self.game_state == GameState::Paused {
+            return Err(self.finished_error());
+        }
+        let (mv, _, checkpoint) = self
+            .history
+            .as_mut()
+            .and_then(|history| history.done.pop())
+            .ok_or(MinesweeperError::NothingToUndo)?;
+
+        self.board = checkpoint.board;
+        self.game_state = checkpoint.game_state;
+        self.covered_safe = checkpoint.covered_safe;
+        self.flags_placed = checkpoint.flags_placed;
+        self.bv = checkpoint.bv;
+        if self.bv.zero_rule() != self.zero_rule {
+            self.recount_bv();
+        }
+        self.lives = checkpoint.lives;
+        self.exploded = None;
+        let board = &self.board;
+        self.contained.retain(|&pos| board[pos].exposed);
+        self.forget_covered_provenance();
+        if let Some(history) = &mut self.history {
+            history.undone.push(mv);
+        }
+        self.forget_guess(mv);
+        self.refresh_overlay();
+        Ok(mv)
+    }
+
+    /// Replays the most recently undone move and returns it. Any other
+    /// move made after an undo discards the moves that could be redone.
+    /// A redo that fails, e.g. while paused, leaves the move to redo later.
+    pub fn redo(&mut self) -> Result<Move, MinesweeperError> {
+        let mut undone = match &mut self.history {
+            Some(history) => std::mem::take(&mut history.undone),
+            None => Vec::new(),
+        };
+        let mv = undone.pop().ok_or(MinesweeperError::NothingToRedo)?;
+
+        let outcome = self.apply_move(mv);
+        if outcome.is_err() {
+            undone.push(mv);
+        }
+        if let Some(history) = &mut self.history {
+            history.undone = undone;
+        }
+        outcome.map(|_| mv)
+    }
+
+    /// The state to restore if the move about to be made is undone, or
+    /// `None` while history is off.
+    pub(crate) fn checkpoint(&self) -> Option<Checkpoint> {
+        self.history.as_ref()?;
+        Some(Checkpoint {
+            board: self.board.clone(),
+            game_state: self.game_state.clone(),
+            covered_safe: self.covered_safe,
+            flags_placed: self.flags_placed,
+            bv: self.bv.clone(),
+            lives: self.lives,
+        })
+    }
+
+    /// Records a move that succeeded, given the checkpoint taken before it.
+    pub(crate) fn record(&mut self, mv: Move, checkpoint: Option<Checkpoint>) {
+        if let (Some(history), Some(checkpoint)) = (&mut self.history, checkpoint) {
+            let at = (history.clock)();
+            history.done.push((mv, at, checkpoint));
+            history.undone.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undo_losing_click() {
+        let mut game = Minesweeper::new(3, vec![(0, 0), (2, 2)]);
+        game.set_history(true);
+        game.click_tile(0, 2).unwrap();
+        game.toggle_flag(2, 2).unwrap();
+        game.click_tile(0, 0).unwrap();
+        assert_eq!(*game.get_game_state(), GameState::Lost);
+        assert_eq!(game.losing_tile(), Some((0, 0)));
+
+        assert_eq!(game.undo(), Ok(Move::Reveal(0, 0)));
+        assert_eq!(*game.get_game_state(), GameState::InProgress);
+        assert_eq!(game.losing_tile(), None);
+        assert!(!game.get_tile(0, 0).unwrap().exposed);
+        assert_eq!(game.undo(), Ok(Move::ToggleFlag(2, 2)));
+        assert!(!game.get_tile(2, 2).unwrap().flagged);
+        assert_eq!(game.history(), vec![Move::Reveal(0, 2)]);
+        assert_eq!(game.three_bv_remaining(), game.three_bv() - 1);
+    }
+
+    #[test]
+    fn test_redo_and_branching() {
+        let mut game = Minesweeper::new(3, vec![(0, 0), (2, 2)]);
+        game.set_history(true);
+        game.click_tile(0, 2).unwrap();
+        game.click_tile(2, 0).unwrap();
+        let remaining = game.safe_tiles_remaining();
+
+        game.undo().unwrap();
+        game.undo().unwrap();
+        assert_eq!(game.redo(), Ok(Move::Reveal(0, 2)));
+        // A refused redo keeps the move for later.
+        game.pause().unwrap();
+        assert_eq!(game.redo(), Err(MinesweeperError::Paused));
+        game.resume().unwrap();
+        assert_eq!(game.redo(), Ok(Move::Reveal(2, 0)));
+        assert_eq!(game.safe_tiles_remaining(), remaining);
+        assert_eq!(game.redo(), Err(MinesweeperError::NothingToRedo));
+
+        game.undo().unwrap();
+        game.toggle_flag(0, 0).unwrap();
+        assert_eq!(game.redo(), Err(MinesweeperError::NothingToRedo));
+    }
+
+    #[test]
+    fn test_moves_are_timed() {
+        let mut game = Minesweeper::new(3, vec![(0, 0), (2, 2)]);
+        let now = Arc::new(std::sync::Mutex::new(Duration::ZERO));
+        let clock = now.clone();
+        game.set_move_clock(Arc::new(move || *clock.lock().unwrap()));
+
+        *now.lock().unwrap() = Duration::from_secs(1);
+        game.click_tile(0, 2).unwrap();
+        *now.lock().unwrap() = Duration::from_secs(4);
+        game.toggle_flag(0, 0).unwrap();
+        game.click_tile(2, 0).unwrap();
+
+        assert_eq!(
+            game.timed_history(),
+            vec![
+                (Duration::from_secs(1), Move::Reveal(0, 2)),
+                (Duration::from_secs(4), Move::ToggleFlag(0, 0)),
+                (Duration::from_secs(4), Move::Reveal(2, 0)),
+            ]
+        );
+        // Two moves after the first, three seconds apart.
+        assert_eq!(game.moves_per_minute(), Some(40.0));
+    }
+
+    #[test]
+    fn test_history_is_off_by_default() {
+        let mut game = Minesweeper::new(3, vec![(0, 0)]);
+        game.click_tile(2, 2).unwrap();
+        assert_eq!(game.undo(), Err(MinesweeperError::NothingToUndo));
+        assert!(game.history().is_empty());
+    }
+}
@@ -0,0 +1,183 @@
+use std::time::Duration;
+
+use crate::Move;
+
+/// One raw input from the player.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputKind {
+    /// The cursor moved to this tile.
+    Cursor { x: usize, y: usize },
+    /// A key was pressed, named by the frontend (e.g. `"Left"`, `"f"`).
+    Key(String),
+    /// The input resolved into an engine move.
+    Move(Move),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputEvent {
+    /// Time since the start of the game.
+    pub at: Duration,
+    pub kind: InputKind,
+}
+
+/// Keystroke-level record of a game: every cursor movement and key press,
+/// interleaved with the engine moves they produced.
+///
+/// Replaying just the moves reproduces the game; the rest shows how the
+/// player got around the board, for coaching and input-efficiency analysis.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputRecording {
+    events: Vec<InputEvent>,
+}
+
+impl InputRecording {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, at: Duration, kind: InputKind) {
+        self.events.push(InputEvent { at, kind });
+    }
+
+    pub fn events(&self) -> &[InputEvent] {
+        &self.events
+    }
+
+    /// The engine moves in order, ready for [`crate::Minesweeper::apply_moves`].
+    pub fn moves(&self) -> impl Iterator<Item = Move> + '_ {
+        self.events.iter().filter_map(|event| match event.kind {
+            InputKind::Move(mv) => Some(mv),
+            _ => None,
+        })
+    }
+
+    /// Key presses per engine move; lower means more efficient input.
+    pub fn keys_per_move(&self) -> Option<f64> {
+        let keys = self
+            .events
+            .iter()
+            .filter(|event| matches!(event.kind, InputKind::Key(_)))
+            .count();
+        match self.moves().count() {
+            0 => None,
+            moves => Some(keys as f64 / moves as f64),
+        }
+    }
+
+    /// Total distance the cursor travelled, in king moves.
+    pub fn cursor_distance(&self) -> usize {
+        let mut last: Option<(usize, usize)> = None;
+        let mut distance = 0;
+        for event in &self.events {
+            if let InputKind::Cursor { x, y } = event.kind {
+                if let Some((lx, ly)) = last {
+                    distance += lx.abs_diff(x).max(ly.abs_diff(y));
+                }
+                last = Some((x, y));
+            }
+        }
+        distance
+    }
+
+    /// One event per line: `MILLIS cursor X Y`, `MILLIS key NAME` or
+    /// `MILLIS move MOVE`, with moves written as by [`Move`]'s `Display`.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for event in &self.events {
+            let millis = event.at.as_millis();
+            let line = match &event.kind {
+                InputKind::Cursor { x, y } => format!("{} cursor {} {}", millis, x, y),
+                InputKind::Key(name) => format!("{} key {}", millis, name),
+                InputKind::Move(mv) => format!("{} move {}", millis, mv),
+            };
+            text.push_str(&line);
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Parses text produced by [`InputRecording::to_text`].
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let mut recording = InputRecording::new();
+
+        for (number, line) in text
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+        {
+            let error = |message: &str| format!("Line {}: {}", number + 1, message);
+            let mut parts = line.splitn(3, ' ');
+            let (Some(millis), Some(kind), Some(rest)) = (parts.next(), parts.next(), parts.next())
+            else {
+                return Err(error("expected time, kind and value"));
+            };
+            let millis: u64 = millis.parse().map_err(|_| error("invalid time"))?;
+
+            let kind = match kind {
+                "cursor" => {
+                    let coordinates: Vec<usize> = rest
+                        .split(' ')
+                        .map(|part| part.parse().map_err(|_| error("invalid cursor position")))
+                        .collect::<Result<_, _>>()?;
+                    match coordinates[..] {
+                        [x, y] => InputKind::Cursor { x, y },
+                        _ => return Err(error("invalid cursor position")),
+                    }
+                }
+                "key" => InputKind::Key(rest.to_string()),
+                "move" => InputKind::Move(rest.parse().map_err(|e: String| error(&e))?),
+                _ => return Err(error("unknown event kind")),
+            };
+            recording.record(Duration::from_millis(millis), kind);
+        }
+
+        Ok(recording)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameState, Minesweeper};
+
+    fn recording() -> InputRecording {
+        let mut recording = InputRecording::new();
+        let ms = Duration::from_millis;
+        recording.record(ms(0), InputKind::Cursor { x: 0, y: 0 });
+        recording.record(ms(150), InputKind::Key("Right".to_string()));
+        recording.record(ms(150), InputKind::Cursor { x: 0, y: 1 });
+        recording.record(ms(300), InputKind::Key("Enter".to_string()));
+        recording.record(ms(300), InputKind::Move(Move::Reveal(0, 1)));
+        recording.record(ms(700), InputKind::Key("Down".to_string()));
+        recording.record(ms(700), InputKind::Cursor { x: 1, y: 1 });
+        recording.record(ms(800), InputKind::Key("Enter".to_string()));
+        recording.record(ms(800), InputKind::Move(Move::Reveal(1, 1)));
+        recording
+    }
+
+    #[test]
+    fn test_moves_replay_the_game() {
+        let mut game = Minesweeper::new(2, vec![(0, 0), (1, 0)]);
+        game.apply_moves(recording().moves());
+        assert_eq!(*game.get_game_state(), GameState::Won);
+    }
+
+    #[test]
+    fn test_efficiency_stats() {
+        let recording = recording();
+        assert_eq!(recording.keys_per_move(), Some(2.0));
+        assert_eq!(recording.cursor_distance(), 2);
+        assert_eq!(InputRecording::new().keys_per_move(), None);
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let recording = recording();
+        let text = recording.to_text();
+        assert!(text.starts_with("0 cursor 0 0\n150 key Right\n"));
+        assert_eq!(InputRecording::from_text(&text), Ok(recording));
+
+        assert!(InputRecording::from_text("10 cursor 1").is_err());
+        assert!(InputRecording::from_text("10 jump 1 1").is_err());
+    }
+}
@@ -0,0 +1,9 @@
+//! Building blocks the crate's own binaries, benches and tools share, such
+//! as the raw mine generator and the terminal drawing helpers.
+//!
+//! Hidden from the docs and outside semver: anything here may change or
+//! go in any release. Frontends should stick to the
+//! [`prelude`](crate::prelude) and the documented items at the crate root.
+
+pub use crate::generator::generate_mines;
+pub use crate::render::{column_header, row_label_width};
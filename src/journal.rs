@@ -0,0 +1,153 @@
+use crate::{Minesweeper, Move};
+
+/// A reveal on a tile that wasn't known to be safe, from the guess journal
+/// (see [`Minesweeper::set_guess_journal`]).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Guess {
+    pub x: usize,
+    pub y: usize,
+    /// The chance the tile was a mine, given what the player could see.
+    pub probability: f64,
+    /// Whether the player had to guess: no covered tile was known to be
+    /// safe. An unforced guess was a risk that could have been avoided.
+    pub forced: bool,
+}
+
+impl Minesweeper {
+    /// Turns the guess journal on or off. While it is on, every reveal of a
+    /// tile that might have been a mine is recorded with its odds, so a
+    /// lost game can be put down to bad luck or to bad play. Judging each
+    /// reveal costs a probability calculation. Turning it off clears it.
+    pub fn set_guess_journal(&mut self, enabled: bool) {
+        if !enabled {
+            self.journal = None;
+        } else if self.journal.is_none() {
+            self.journal = Some(Vec::new());
+        }
+    }
+
+    /// The guesses made since the journal was turned on, oldest first.
+    pub fn guesses(&self) -> &[Guess] {
+        self.journal.as_deref().unwrap_or_default()
+    }
+
+    /// A line for the end of the game, e.g. "You made 3 guesses: 12%,
+    /// 33%, 50%.", noting any that could have been avoided. `None` while
+    /// the journal is off.
+    pub fn guess_summary(&self) -> Option<String> {
+        let guesses = self.journal.as_ref()?;
+        if guesses.is_empty() {
+            return Some("You made no guesses.".to_string());
+        }
+        let odds: Vec<String> = guesses
+            .iter()
+            .map(|guess| {
+                let risk = format!("{:.0}%", guess.probability * 100.0);
+                if guess.forced {
+                    risk
+                } else {
+                    format!("{} (avoidable)", risk)
+                }
+            })
+            .collect();
+        let survival: f64 = guesses.iter().map(|g| 1.0 - g.probability).product();
+        Some(format!(
+            "You made {} guess{}: {}. The odds of surviving them all were {:.0}%.",
+            guesses.len(),
+            if guesses.len() == 1 { "" } else { "es" },
+            odds.join(", "),
+            survival * 100.0
+        ))
+    }
+
+    /// Records the reveal about to be made at (x, y) if it is a guess.
+    pub(crate) fn note_guess(&mut self, x: usize, y: usize) {
+        // The opening click is made blind, so there is nothing to judge.
+        if self.journal.is_none() || self.exposed_tiles().next().is_none() {
+            return;
+        }
+        let probabilities = self.mine_probabilities();
+        let probability = probabilities[x][y];
+        if probability <= f64::EPSILON {
+            return;
+        }
+        let forced = !self
+            .covered_tiles()
+            .any(|(cx, cy, _)| probabilities[cx][cy] <= f64::EPSILON);
+        if let Some(journal) = &mut self.journal {
+            journal.push(Guess {
+                x,
+                y,
+                probability,
+                forced,
+            });
+        }
+    }
+
+    /// Drops the guess recorded for `mv`, which is being undone.
+    pub(crate) fn forget_guess(&mut self, mv: Move) {
+        let Move::Reveal(x, y) = mv else {
+            return;
+        };
+        // A tile is only revealed once between undos, so the last guess
+        // on it belongs to this move.
+        if let Some(journal) = &mut self.journal {
+            if journal
+                .last()
+                .is_some_and(|guess| (guess.x, guess.y) == (x, y))
+            {
+                journal.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_journal_records_risky_reveals() {
+        // Opening at (2, 0) leaves a 50-50 between (0, 0) and (0, 1).
+        let mut game = Minesweeper::new_rect(3, 2, vec![(0, 1)]);
+        assert_eq!(game.guess_summary(), None);
+        game.set_guess_journal(true);
+        game.set_history(true);
+
+        game.click_tile(2, 0).unwrap();
+        assert!(game.guesses().is_empty());
+        assert_eq!(game.guess_summary().unwrap(), "You made no guesses.");
+
+        game.click_tile(0, 0).unwrap();
+        let guess = &game.guesses()[0];
+        assert_eq!((guess.x, guess.y), (0, 0));
+        assert!((guess.probability - 0.5).abs() < 1e-9);
+        assert!(guess.forced);
+        assert_eq!(
+            game.guess_summary().unwrap(),
+            "You made 1 guess: 50%. The odds of surviving them all were 50%."
+        );
+
+        game.undo().unwrap();
+        assert!(game.guesses().is_empty());
+        game.redo().unwrap();
+        assert_eq!(game.guesses().len(), 1);
+
+        game.set_guess_journal(false);
+        assert!(game.guesses().is_empty());
+    }
+
+    #[test]
+    fn test_avoidable_guesses_are_marked() {
+        // The 1 at (2, 0) proves (0, 0) safe but not (1, 0).
+        let mut game = Minesweeper::new_rect(4, 1, vec![(3, 0)]);
+        game.set_guess_journal(true);
+        game.click_tile(2, 0).unwrap();
+        game.click_tile(1, 0).unwrap();
+
+        assert_eq!(game.guesses().len(), 1);
+        assert!(!game.guesses()[0].forced);
+        assert!(game.guess_summary().unwrap().contains("50% (avoidable)"));
+    }
+}
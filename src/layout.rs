@@ -0,0 +1,108 @@
+use crate::{Minesweeper, TileValue};
+
+impl Minesweeper {
+    /// Parses a board from a text grid, one line per row: `*` for a mine
+    /// and `.` or the tile's number for a safe tile. Numbers are optional
+    /// but must be right when given. Blank lines and surrounding
+    /// whitespace are ignored, so fixtures can be indented.
+    ///
+    /// ```text
+    /// *1.
+    /// 11.
+    /// ...
+    /// ```
+    pub fn from_board_str(text: &str) -> Result<Self, String> {
+        let rows: Vec<&str> = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        if width == 0 {
+            return Err("Board is empty".to_string());
+        }
+
+        let mut mines = Vec::new();
+        let mut numbers = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            if row.chars().count() != width {
+                return Err(format!("Row {} is not {} tiles wide", y + 1, width));
+            }
+            for (x, c) in row.chars().enumerate() {
+                match c {
+                    '*' => mines.push((x, y)),
+                    '.' => {}
+                    '0'..='9' => numbers.push((x, y, c as u8 - b'0')),
+                    _ => return Err(format!("Unknown tile '{}' at ({}, {})", c, x, y)),
+                }
+            }
+        }
+
+        let game = Minesweeper::new_rect(width, rows.len(), mines);
+        for (x, y, number) in numbers {
+            if game.board[(x, y)].get_number() != Some(number) {
+                return Err(format!("Wrong number {} at ({}, {})", number, x, y));
+            }
+        }
+        Ok(game)
+    }
+
+    /// The layout in the format [`Minesweeper::from_board_str`] reads:
+    /// `*` for mines, the number for safe tiles and `.` for zeros. Numbers
+    /// too big for one digit, under extended adjacency, are written as `.`
+    /// too. The state of play is not included.
+    pub fn to_board_str(&self) -> String {
+        let mut text = String::with_capacity((self.width + 1) * self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                text.push(match self.board[(x, y)].value {
+                    TileValue::Bomb => '*',
+                    TileValue::Number(n @ 1..=9) => (b'0' + n) as char,
+                    TileValue::Number(_) => '.',
+                });
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_board_str_round_trip() {
+        let game = Minesweeper::from_board_str(
+            "
+            *1..
+            11..
+            ..1*
+            ",
+        )
+        .unwrap();
+        assert_eq!((game.get_width(), game.get_height()), (4, 3));
+        assert_eq!(game.mine_positions(), vec![(0, 0), (3, 2)]);
+        assert_eq!(game.to_board_str(), "*1..\n1111\n..1*\n");
+        assert_eq!(
+            Minesweeper::from_board_str(&game.to_board_str())
+                .unwrap()
+                .board_id(),
+            game.board_id()
+        );
+    }
+
+    #[test]
+    fn test_board_str_errors() {
+        assert!(Minesweeper::from_board_str("").is_err());
+        assert_eq!(
+            Minesweeper::from_board_str("*.\n...").unwrap_err(),
+            "Row 2 is not 2 tiles wide"
+        );
+        assert_eq!(
+            Minesweeper::from_board_str("*2").unwrap_err(),
+            "Wrong number 2 at (1, 0)"
+        );
+        assert!(Minesweeper::from_board_str("*x").is_err());
+    }
+}
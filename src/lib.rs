@@ -1,31 +1,489 @@
-use std::collections::VecDeque;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use rand::Rng;
+
+mod achievements;
+mod adjacency;
+mod advisor;
+mod analyzer;
+mod assist;
+mod board;
+mod bot;
+mod clock;
+mod config;
+mod coords;
+mod daily;
+mod describe;
+mod difficulty;
+mod editor;
+mod error;
+mod events;
+#[cfg(feature = "serde")]
+mod export;
+mod flood;
+mod generator;
+mod ghost;
+mod handicap;
+mod hash;
+mod hint;
+mod history;
+mod input;
+#[doc(hidden)]
+pub mod internal;
+mod journal;
+mod layout;
+mod manager;
+mod metrics;
+mod morph;
+mod multiplayer;
+mod policy;
+pub mod prelude;
+mod probability;
+#[cfg(feature = "serde")]
+mod protocol;
+mod provenance;
+mod render;
+mod report;
+#[cfg(feature = "serde")]
+mod save;
+mod scoring;
+#[cfg(feature = "server")]
+mod server;
+#[cfg(feature = "serde")]
+mod simulation;
+mod snapshot;
+mod solver;
+mod sound;
+mod splits;
+mod stats;
+mod svg;
+mod theme;
+mod timed;
+mod transform;
+mod value;
+mod variants;
+
+pub use achievements::{
+    builtin_achievements, Achievement, AchievementProfile, GameSummary, Trigger,
+};
+pub use adjacency::{Adjacency, Topology};
+pub use advisor::{
+    first_click_stats, suggest_opening, FirstClickGrid, FirstClickStats, OpeningAdvice,
+    OpeningStats,
+};
+pub use analyzer::{AnalysisResult, Analyzer};
+pub use assist::AssistFairness;
+use board::Board;
+pub use bot::{Bot, BotMove, BotReport};
+pub use clock::{Clock, ClockState, TimeControl};
+pub use config::GameConfig;
+pub use coords::{explain_error, CoordinateStyle};
+pub use daily::DailyResult;
+pub use describe::{
+    apply_move_described, apply_move_described_with, describe_move, describe_move_with, tile_label,
+};
+pub use difficulty::DynamicDifficulty;
+pub use editor::Editor;
+pub use error::MinesweeperError;
+use events::Events;
+pub use events::{EventListener, GameEvent};
+#[cfg(feature = "serde")]
+pub use export::{export_animation, export_events};
+use flood::FloodScratch;
+pub use generator::{
+    board_rng, Area, BoardRng, Corridor, Density, DensityFn, GeneratorOptions, Opening, Symmetry,
+};
+pub use ghost::Ghost;
+pub use handicap::Handicap;
+use hash::Fnv64;
+pub use hint::{BudgetedHint, Hint};
+use history::History;
+pub use history::MoveClock;
+pub use input::{InputEvent, InputKind, InputRecording};
+pub use journal::Guess;
+pub use manager::{GameId, GameManager, GameUpdate, Region};
+use metrics::BvTracker;
+pub use multiplayer::{GameSession, SessionEvent, SessionMode};
+pub use policy::{MovePolicy, RateLimit};
+use probability::{ProbabilityEngine, ProbabilityOverlay, CERTAIN};
+#[cfg(feature = "serde")]
+pub use protocol::{
+    Broker, ClientMessage, Connection, Request, Response, ServerMessage, VisibleChange,
+};
+pub use provenance::{Provenance, RevealSource};
+pub use render::{AnsiRenderer, BlockSummary, BoardRenderer, TextRenderer, View, Viewport};
+pub use report::{BoardMetrics, BoardReport};
+pub use scoring::{ClassicScoring, Score, ScoreEvent, Scoring};
+#[cfg(feature = "server")]
+pub use server::GameServer;
+#[cfg(feature = "serde")]
+pub use simulation::{ClientScript, Simulation};
+pub use snapshot::{diff, BoardDelta, BoardSnapshot, DeltaStream, TileChange};
+use solver::Meter;
+pub use solver::{Analysis, BudgetedAnalysis, SolverBudget};
+#[cfg(feature = "sound")]
+pub use sound::RodioSink;
+pub use sound::{apply_move_with_sound, NoSound, SoundEvent, SoundSink, BIG_OPENING_TILES};
+pub use splits::{Milestone, SplitDelta, SplitTracker};
+pub use stats::{Difficulty, Statistics};
+pub use svg::export_svg;
+pub use theme::{display_width, Glyph, Theme, ThemeColor};
+pub use timed::TimedMinesweeper;
+pub use transform::Transform;
+pub use value::GameSnapshot;
+pub use variants::{RulesFn, Variant, VariantRegistry};
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tile {
     pub value: TileValue,
     pub exposed: bool,
     pub flagged: bool,
+    pub marker: Option<Marker>,
 }
 
+/// A player's annotation on a covered tile, such as "probably a mine".
+///
+/// Markers are purely cosmetic: unlike flags they never block a click and
+/// the engine ignores them, but they are kept with the tile so frontends,
+/// saves and replays can show them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Marker {
+    Red,
+    Yellow,
+    Green,
+    Blue,
+    /// A custom single-character marker.
+    Symbol(char),
+}
+
+/// The classic mark on a covered tile, as cycled by
+/// [`Minesweeper::cycle_mark`]: blank, then a flag, then a question mark.
+///
+/// A question mark is the `'?'` [`Marker::Symbol`], so like any marker it
+/// never blocks a click.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mark {
+    None,
+    Flag,
+    Question,
+}
+
+/// A tile as the player sees it, from [`Minesweeper::player_view`]. Unlike
+/// [`Tile`] it carries nothing the player can't see, so it is safe to send
+/// to clients: a covered tile never gives away whether it holds a mine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VisibleTile {
+    Covered,
+    Flagged,
+    /// An exposed safe tile showing how many mines are around it.
+    Number(u8),
+    /// An exposed mine.
+    Mine,
+    /// A mine that was hit: the one that lost the game, or one a life was
+    /// spent on.
+    Exploded,
+    /// A flag on a safe tile, shown once the game is lost.
+    WrongFlag,
+}
+
+impl From<&Tile> for VisibleTile {
+    fn from(tile: &Tile) -> Self {
+        match (tile.exposed, &tile.value) {
+            (true, TileValue::Bomb) => VisibleTile::Mine,
+            (true, TileValue::Number(n)) => VisibleTile::Number(*n),
+            (false, _) if tile.flagged => VisibleTile::Flagged,
+            (false, _) => VisibleTile::Covered,
+        }
+    }
+}
+
+/// The marker [`Mark::Question`] is stored as.
+const QUESTION_MARK: Marker = Marker::Symbol('?');
+
+/// Why [`WinCondition::FlagAllMines`] and [`FlagRule::Forbidden`] can't go
+/// together.
+pub(crate) const NO_FLAGS_TO_WIN: &str = "Flagging every mine can't win a game without flags";
+/// Why a config is refused when its tile count does not fit in a `usize`.
+pub(crate) const BOARD_TOO_LARGE: &str = "Board is too large";
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TileValue {
     Bomb,
     Number(u8),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// A tile position, (x, y).
+pub type Pos = (usize, usize);
+
+/// Where a game stands. New states may be added in minor releases.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum GameState {
     InProgress,
+    /// Put on hold with [`Minesweeper::pause`]: moves are refused and
+    /// timers stand still until [`Minesweeper::resume`].
+    Paused,
     Won,
     Lost,
 }
 
+impl GameState {
+    /// Whether the game has been won or lost. A paused game isn't over.
+    pub fn is_over(&self) -> bool {
+        matches!(self, GameState::Won | GameState::Lost)
+    }
+}
+
+/// Whether the player may place flags.
+///
+/// Flag use is always tracked (see [`Minesweeper::is_no_flag`]), so a game
+/// played without flags under `Allowed` still counts as no-flag for stats
+/// and achievements; `Forbidden` enforces it for no-flag (NF) play.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FlagRule {
+    #[default]
+    Allowed,
+    /// Every attempt to flag is rejected.
+    Forbidden,
+}
+
+/// What revealing a zero does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ZeroRule {
+    /// The classic rule: the whole opening around the zero is revealed.
+    #[default]
+    Cascade,
+    /// Only the zero itself is revealed, and the player clears the opening
+    /// by hand.
+    Single,
+}
+
+/// What the player has to do to win.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WinCondition {
+    /// The classic rule: every safe tile revealed.
+    #[default]
+    Classic,
+    /// Every mine flagged and no safe tile flagged. Revealing every safe
+    /// tile is not enough on its own.
+    FlagAllMines,
+    /// This much of the board's 3BV cleared, for short rounds in teaching
+    /// modes. Clearing the whole board wins too.
+    Score(usize),
+}
+
+/// What the player is told about how many mines the board holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MineCount {
+    /// The exact count, as usual.
+    #[default]
+    Exact,
+    /// Only that the count is between `min` and `max`, inclusive. Games
+    /// created from a config with a range draw their mine count from it.
+    Range { min: usize, max: usize },
+    /// Nothing at all.
+    Hidden,
+}
+
+/// A single player action, as consumed by [`Minesweeper::apply_move`].
+/// New kinds of move may be added in minor releases.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Move {
+    Reveal(usize, usize),
+    ToggleFlag(usize, usize),
+    SetMarker(usize, usize, Option<Marker>),
+    /// Reveals the unflagged neighbours of a satisfied number; see
+    /// [`Minesweeper::chord`].
+    Chord(usize, usize),
+    /// Reveals a safe tile chosen by an assist; see
+    /// [`Minesweeper::reveal_random_safe`]. Kept apart from `Reveal` so
+    /// assisted games can be told apart in replays.
+    Assist(usize, usize),
+    /// Reveals every remaining safe tile at once; see
+    /// [`Minesweeper::auto_finish`].
+    AutoFinish,
+}
+
+/// Moves are written as `reveal X Y`, `flag X Y`, `mark X Y MARKER`,
+/// `chord X Y`, `assist X Y` or `finish`, where `MARKER` is `none`, a
+/// colour (`red`, `yellow`, `green`, `blue`) or `symbol:C`.
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Move::Reveal(x, y) => write!(f, "reveal {} {}", x, y),
+            Move::ToggleFlag(x, y) => write!(f, "flag {} {}", x, y),
+            Move::SetMarker(x, y, marker) => {
+                write!(f, "mark {} {} ", x, y)?;
+                match marker {
+                    None => write!(f, "none"),
+                    Some(Marker::Red) => write!(f, "red"),
+                    Some(Marker::Yellow) => write!(f, "yellow"),
+                    Some(Marker::Green) => write!(f, "green"),
+                    Some(Marker::Blue) => write!(f, "blue"),
+                    Some(Marker::Symbol(c)) => write!(f, "symbol:{}", c),
+                }
+            }
+            Move::Chord(x, y) => write!(f, "chord {} {}", x, y),
+            Move::Assist(x, y) => write!(f, "assist {} {}", x, y),
+            Move::AutoFinish => write!(f, "finish"),
+        }
+    }
+}
+
+impl FromStr for Move {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split_whitespace().collect();
+        let coordinate = |text: &str| {
+            text.parse::<usize>()
+                .map_err(|_| format!("Invalid coordinate: {}", text))
+        };
+
+        match parts[..] {
+            ["reveal", x, y] => Ok(Move::Reveal(coordinate(x)?, coordinate(y)?)),
+            ["flag", x, y] => Ok(Move::ToggleFlag(coordinate(x)?, coordinate(y)?)),
+            ["mark", x, y, marker] => {
+                let marker = match marker {
+                    "none" => None,
+                    "red" => Some(Marker::Red),
+                    "yellow" => Some(Marker::Yellow),
+                    "green" => Some(Marker::Green),
+                    "blue" => Some(Marker::Blue),
+                    other => {
+                        let mut symbol = other
+                            .strip_prefix("symbol:")
+                            .ok_or_else(|| format!("Unknown marker: {}", other))?
+                            .chars();
+                        match (symbol.next(), symbol.next()) {
+                            (Some(c), None) => Some(Marker::Symbol(c)),
+                            _ => return Err(format!("Unknown marker: {}", other)),
+                        }
+                    }
+                };
+                Ok(Move::SetMarker(coordinate(x)?, coordinate(y)?, marker))
+            }
+            ["chord", x, y] => Ok(Move::Chord(coordinate(x)?, coordinate(y)?)),
+            ["assist", x, y] => Ok(Move::Assist(coordinate(x)?, coordinate(y)?)),
+            ["finish"] => Ok(Move::AutoFinish),
+            _ => Err(format!("Unknown move: {}", s)),
+        }
+    }
+}
+
+/// The result of applying one [`Move`].
+pub type Outcome = Result<(), MinesweeperError>;
+
+/// What a click did, from [`Minesweeper::click_tile_detailed`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClickOutcome {
+    /// The safe tiles the click uncovered, sorted; empty if it hit a mine.
+    pub revealed: Vec<(usize, usize)>,
+    /// The same tiles in the order the opening spread outward from the
+    /// click, as [`Minesweeper::opening_layers_at`] reports them, so a
+    /// frontend can reveal them a layer at a time.
+    pub layers: Vec<Vec<(usize, usize)>>,
+    pub hit_bomb: bool,
+    /// The game's state after the click.
+    pub state: GameState,
+}
+
+/// A stable fingerprint of a board layout, from [`Minesweeper::board_id`].
+///
+/// Written as 16 hex digits, so players can refer to "that board" and
+/// histories and replays can link back to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BoardId(pub u64);
+
+impl fmt::Display for BoardId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl FromStr for BoardId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 16 {
+            return Err(format!("Invalid board id: {}", s));
+        }
+        u64::from_str_radix(s, 16)
+            .map(BoardId)
+            .map_err(|_| format!("Invalid board id: {}", s))
+    }
+}
+
+/// The longest note, in characters, that can be attached to a tile.
+pub const MAX_NOTE_LENGTH: usize = 200;
+
 #[derive(Debug)]
 pub struct Minesweeper {
-    board: Vec<Vec<Tile>>,
+    /// Flat, chunked storage, copied on write so snapshots stay cheap.
+    board: Board,
     game_state: GameState,
-    size: usize,
+    width: usize,
+    height: usize,
     bomb_count: usize,
+    adjacency: Adjacency,
+    topology: Topology,
+    bv: BvTracker,
+    scratch: FloodScratch,
+    /// Safe tiles still covered; the game is won when this reaches zero.
+    covered_safe: usize,
+    /// Free-text notes on covered tiles. Kept beside the board rather than
+    /// in `Tile` since few tiles ever carry one.
+    notes: HashMap<(usize, usize), String>,
+    /// How each tile was uncovered, when reveal tracking is enabled.
+    provenance: Option<HashMap<(usize, usize), Provenance>>,
+    /// Revealing moves played so far, numbering [`Provenance`] entries.
+    reveal_moves: usize,
+    /// Mine probabilities refreshed after every reveal, when enabled.
+    overlay: Option<ProbabilityOverlay>,
+    /// How many times a flag has been placed, including ones later removed.
+    flags_placed: usize,
+    flag_rule: FlagRule,
+    zero_rule: ZeroRule,
+    win_condition: WinCondition,
+    mine_count: MineCount,
+    /// Whether [`Minesweeper::cycle_mark`] passes through a question mark.
+    question_marks: bool,
+    /// Whether the remaining mines are flagged when the game is won.
+    auto_flag: bool,
+    /// The mine that lost the game, when one did.
+    exploded: Option<(usize, usize)>,
+    /// Mine hits the player can still survive.
+    lives: usize,
+    /// The clock the game is meant to be played on, for whatever runs it.
+    time_control: Option<TimeControl>,
+    /// Mines hit and survived by spending a life, in the order they were
+    /// hit. They stay exposed.
+    contained: Vec<(usize, usize)>,
+    /// Whether the over-flagged number assist is on.
+    flag_check: bool,
+    /// The options the mines were generated with, reported back through
+    /// [`Minesweeper::config`]. Defaults for hand-placed layouts.
+    generator: GeneratorOptions,
+    /// Undo/redo history, when enabled.
+    history: Option<History>,
+    /// Risky reveals, when the guess journal is enabled.
+    journal: Option<Vec<Guess>>,
+    /// Where [`GameEvent`]s are delivered, when anywhere.
+    events: Events,
 }
 
 impl Default for Tile {
@@ -40,6 +498,7 @@ impl Tile {
             value: TileValue::Number(0),
             exposed: false,
             flagged: false,
+            marker: None,
         }
     }
 
@@ -61,346 +520,1552 @@ impl Tile {
             TileValue::Bomb => None,
         }
     }
+
+    /// The tile's classic mark. A flag wins over any marker.
+    pub fn mark(&self) -> Mark {
+        if self.flagged {
+            Mark::Flag
+        } else if self.marker == Some(QUESTION_MARK) {
+            Mark::Question
+        } else {
+            Mark::None
+        }
+    }
 }
 
 impl Minesweeper {
     pub fn new(size: usize, mine_locations: Vec<(usize, usize)>) -> Self {
-        let mut board = Self::create_empty_board(size);
+        Self::new_with_adjacency(size, mine_locations, Adjacency::Standard)
+    }
+
+    /// Creates a `width` x `height` game, e.g. 30 x 16 for classic Expert.
+    /// x runs across the width and y down the height.
+    pub fn new_rect(width: usize, height: usize, mine_locations: Vec<(usize, usize)>) -> Self {
+        Self::new_rect_with_adjacency(width, height, mine_locations, Adjacency::Standard)
+    }
+
+    /// Creates a game whose numbers count mines under the given adjacency
+    /// rule, e.g. [`Adjacency::Extended`] for 5x5 counting.
+    pub fn new_with_adjacency(
+        size: usize,
+        mine_locations: Vec<(usize, usize)>,
+        adjacency: Adjacency,
+    ) -> Self {
+        Self::new_rect_with_adjacency(size, size, mine_locations, adjacency)
+    }
+
+    /// A rectangular game, as [`Minesweeper::new_rect`], under the given
+    /// adjacency rule.
+    pub fn new_rect_with_adjacency(
+        width: usize,
+        height: usize,
+        mine_locations: Vec<(usize, usize)>,
+        adjacency: Adjacency,
+    ) -> Self {
+        Self::new_rect_with_topology(width, height, mine_locations, adjacency, Topology::Plane)
+    }
+
+    /// A rectangular game under the given adjacency rule, on a flat board
+    /// or one whose edges wrap around.
+    pub fn new_rect_with_topology(
+        width: usize,
+        height: usize,
+        mine_locations: Vec<(usize, usize)>,
+        adjacency: Adjacency,
+        topology: Topology,
+    ) -> Self {
+        // Laid out flat, `x * height + y`, as the board stores them.
+        let mut tiles = vec![Tile::new(); width * height];
         let bomb_count = mine_locations.len();
 
         for (x, y) in &mine_locations {
-            if *x < size && *y < size {
-                board[*x][*y].set_bomb();
+            if *x < width && *y < height {
+                tiles[x * height + y].set_bomb();
             }
         }
 
-        for x in 0..size {
-            for y in 0..size {
-                if !board[x][y].is_bomb() {
-                    let adjacent_bombs = Self::count_adjacent_bombs(&board, x, y, size);
-                    board[x][y].set_number(adjacent_bombs);
+        for x in 0..width {
+            for y in 0..height {
+                if !tiles[x * height + y].is_bomb() {
+                    let adjacent_bombs = Self::count_adjacent_bombs(
+                        &tiles,
+                        x,
+                        y,
+                        (width, height),
+                        (adjacency, topology),
+                    );
+                    tiles[x * height + y].set_number(adjacent_bombs);
                 }
             }
         }
+        let board = Board::from_tiles(width, height, tiles);
+
+        let bv = BvTracker::new(&board, adjacency, topology, ZeroRule::Cascade);
+        let zero_count = board
+            .tiles()
+            .filter(|tile| tile.get_number() == Some(0))
+            .count();
+        let covered_safe = board.tiles().filter(|tile| !tile.is_bomb()).count();
 
         Minesweeper {
             board,
             game_state: GameState::InProgress,
-            size,
+            width,
+            height,
             bomb_count,
+            adjacency,
+            topology,
+            bv,
+            scratch: FloodScratch::new(width * height, zero_count),
+            covered_safe,
+            notes: HashMap::new(),
+            provenance: None,
+            reveal_moves: 0,
+            overlay: None,
+            flags_placed: 0,
+            flag_rule: FlagRule::Allowed,
+            zero_rule: ZeroRule::Cascade,
+            win_condition: WinCondition::Classic,
+            mine_count: MineCount::Exact,
+            question_marks: false,
+            auto_flag: false,
+            exploded: None,
+            lives: 0,
+            time_control: None,
+            contained: Vec::new(),
+            flag_check: false,
+            generator: GeneratorOptions {
+                adjacency,
+                topology,
+                ..Default::default()
+            },
+            history: None,
+            journal: None,
+            events: Events::default(),
         }
     }
 
     /// Creates a new minesweeper game that generates the board after the first click
     /// to guarantee a good starting area (no bomb, ideally a zero for expansion)
-    pub fn new_with_first_click(size: usize, bomb_count: usize, first_click: (usize, usize)) -> Self {
-        use rand::Rng;
-        
+    ///
+    /// Fails if the first click is off the board or the mines don't fit
+    /// around it.
+    pub fn new_with_first_click(
+        size: usize,
+        bomb_count: usize,
+        first_click: (usize, usize),
+    ) -> Result<Self, MinesweeperError> {
+        Self::new_with_options(size, bomb_count, first_click, &GeneratorOptions::default())
+    }
+
+    /// Like [`Minesweeper::new_with_first_click`], with control over how the
+    /// mines are laid out.
+    pub fn new_with_options(
+        size: usize,
+        bomb_count: usize,
+        first_click: (usize, usize),
+        options: &GeneratorOptions,
+    ) -> Result<Self, MinesweeperError> {
+        let config = GameConfig {
+            width: size,
+            height: size,
+            bomb_count,
+            generator: options.clone(),
+            ..Default::default()
+        };
+        Self::new_with_config(&config, first_click)
+    }
+
+    /// Creates a game following every rule in `config`, generating the
+    /// board around the first click as [`Minesweeper::new_with_first_click`]
+    /// does.
+    pub fn new_with_config(
+        config: &GameConfig,
+        first_click: (usize, usize),
+    ) -> Result<Self, MinesweeperError> {
+        Self::new_with_config_and_seed(config, first_click, rand::random())
+    }
+
+    /// Like [`Minesweeper::new_with_first_click`], but generated from
+    /// `seed` so the same arguments always give the same board, on any
+    /// platform (see [`BoardRng`]). For tests, replays and daily boards.
+    pub fn new_with_seed(
+        size: usize,
+        bomb_count: usize,
+        first_click: (usize, usize),
+        seed: u64,
+    ) -> Result<Self, MinesweeperError> {
+        Self::new_with_config_and_seed(&GameConfig::new(size, bomb_count), first_click, seed)
+    }
+
+    /// [`Minesweeper::new_with_config`] with the board generated from
+    /// `seed`.
+    pub fn new_with_config_and_seed(
+        config: &GameConfig,
+        first_click: (usize, usize),
+        seed: u64,
+    ) -> Result<Self, MinesweeperError> {
         let (first_x, first_y) = first_click;
-        
-        // Validate first click coordinates
-        if first_x >= size || first_y >= size {
-            panic!("First click coordinates out of bounds");
-        }
-        
-        // Create list of all positions
-        let mut all_positions = Vec::new();
-        for x in 0..size {
-            for y in 0..size {
-                all_positions.push((x, y));
-            }
+        if first_x >= config.width || first_y >= config.height {
+            return Err(MinesweeperError::OutOfBounds {
+                pos: first_click,
+                width: config.width,
+                height: config.height,
+            });
         }
-        
-        // Remove the first click position and its neighbors from possible bomb locations
-        // This ensures the first click will be a zero (or at least not a bomb with low numbers around)
-        let forbidden_positions = Self::get_area_around(first_x, first_y, size);
-        all_positions.retain(|pos| !forbidden_positions.contains(pos));
-        
-        // If we don't have enough positions left, just exclude the first click position
-        if all_positions.len() < bomb_count {
-            all_positions.clear();
-            for x in 0..size {
-                for y in 0..size {
-                    if x != first_x || y != first_y {
-                        all_positions.push((x, y));
-                    }
-                }
+        // Every tile but the first click can hold a mine.
+        let capacity = config.width.checked_mul(config.height).ok_or_else(|| {
+            MinesweeperError::InvalidConfig {
+                reason: BOARD_TOO_LARGE.to_string(),
             }
+        })? - 1;
+        let most = match config.mine_count {
+            MineCount::Range { max, .. } => max,
+            MineCount::Exact | MineCount::Hidden => config.bomb_count,
+        };
+        if most > capacity {
+            return Err(MinesweeperError::TooManyMines {
+                bomb_count: most,
+                capacity,
+            });
         }
-        
-        // Randomly select bomb positions
-        let mut rng = rand::thread_rng();
-        let mut mine_locations = Vec::new();
-        
-        for _ in 0..bomb_count.min(all_positions.len()) {
-            let index = rng.gen_range(0..all_positions.len());
-            mine_locations.push(all_positions.remove(index));
-        }
-        
+        config
+            .validate()
+            .map_err(|reason| MinesweeperError::InvalidConfig { reason })?;
+
+        let mut rng = generator::board_rng(seed);
+        let bomb_count = match config.mine_count {
+            // Drawn as u64 so the count is the same on every platform.
+            MineCount::Range { min, max } => rng.gen_range(min as u64..=max as u64) as usize,
+            MineCount::Exact | MineCount::Hidden => config.bomb_count,
+        };
+        let mine_locations = generator::generate_mines(
+            config.width,
+            config.height,
+            bomb_count,
+            first_click,
+            &config.generator,
+            &mut rng,
+        );
+
         // Create the game with the selected mine locations
-        let mut game = Self::new(size, mine_locations);
-        
+        let mut game = Self::new_rect_with_topology(
+            config.width,
+            config.height,
+            mine_locations,
+            config.generator.adjacency,
+            config.generator.topology,
+        );
+        game.generator = config.generator.clone();
+        game.flag_rule = config.flag_rule;
+        game.set_zero_rule(config.zero_rule);
+        game.win_condition = config.win_condition;
+        game.mine_count = config.mine_count;
+        game.question_marks = config.question_marks;
+        game.auto_flag = config.auto_flag;
+        game.lives = config.lives;
+        game.time_control = config.time_control;
+
         // Automatically perform the first click
-        game.click_tile(first_x, first_y).expect("First click should always be safe");
-        
-        game
-    }
-    
-    /// Get all positions around a given coordinate (including the coordinate itself)
-    fn get_area_around(x: usize, y: usize, size: usize) -> Vec<(usize, usize)> {
-        let mut positions = Vec::new();
-        
-        for dx in -1..=1i32 {
-            for dy in -1..=1i32 {
-                let nx = x as i32 + dx;
-                let ny = y as i32 + dy;
-                
-                if nx >= 0 && ny >= 0 && (nx as usize) < size && (ny as usize) < size {
-                    positions.push((nx as usize, ny as usize));
-                }
-            }
+        game.click_tile(first_x, first_y)?;
+        Ok(game)
+    }
+
+    fn count_adjacent_bombs(
+        tiles: &[Tile],
+        x: usize,
+        y: usize,
+        (width, height): (usize, usize),
+        (adjacency, topology): (Adjacency, Topology),
+    ) -> u8 {
+        adjacency
+            .neighbors_in(topology, x, y, width, height)
+            .filter(|&(nx, ny)| tiles[nx * height + ny].is_bomb())
+            .count() as u8
+    }
+
+    pub fn get_tile(&self, x: usize, y: usize) -> Option<&Tile> {
+        if x < self.width && y < self.height {
+            Some(&self.board[(x, y)])
+        } else {
+            None
         }
-        
-        positions
     }
 
-    fn create_empty_board(size: usize) -> Vec<Vec<Tile>> {
-        vec![vec![Tile::new(); size]; size]
+    /// Every tile with its coordinates, ordered by x, then y.
+    pub fn tiles(&self) -> impl Iterator<Item = (usize, usize, &Tile)> + '_ {
+        self.board.iter().map(|((x, y), tile)| (x, y, tile))
     }
 
-    fn count_adjacent_bombs(board: &[Vec<Tile>], x: usize, y: usize, size: usize) -> u8 {
-        let mut count = 0;
+    /// The exposed tiles, in the order of [`Minesweeper::tiles`].
+    pub fn exposed_tiles(&self) -> impl Iterator<Item = (usize, usize, &Tile)> + '_ {
+        self.tiles().filter(|(_, _, tile)| tile.exposed)
+    }
 
-        for dx in -1..=1i32 {
-            for dy in -1..=1i32 {
-                if dx == 0 && dy == 0 {
-                    continue;
-                }
+    /// The flagged tiles, in the order of [`Minesweeper::tiles`].
+    pub fn flagged_tiles(&self) -> impl Iterator<Item = (usize, usize, &Tile)> + '_ {
+        self.tiles().filter(|(_, _, tile)| tile.flagged)
+    }
 
-                let nx = x as i32 + dx;
-                let ny = y as i32 + dy;
+    /// The covered tiles, flagged or not, in the order of
+    /// [`Minesweeper::tiles`].
+    pub fn covered_tiles(&self) -> impl Iterator<Item = (usize, usize, &Tile)> + '_ {
+        self.tiles().filter(|(_, _, tile)| !tile.exposed)
+    }
 
-                if nx >= 0 && ny >= 0 && (nx as usize) < size && (ny as usize) < size {
-                    if board[nx as usize][ny as usize].is_bomb() {
-                        count += 1;
-                    }
-                }
-            }
+    /// The coordinates of the tiles adjacent to (x, y) under this game's
+    /// adjacency and topology, not including (x, y) itself. Nothing for a
+    /// position off the board.
+    pub fn neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        let on_board = x < self.width && y < self.height;
+        self.adjacency
+            .neighbors_in(self.topology, x, y, self.width, self.height)
+            .filter(move |_| on_board)
+    }
+
+    /// The tiles adjacent to (x, y) with their coordinates, as in
+    /// [`Minesweeper::neighbors`].
+    pub fn neighbor_tiles(
+        &self,
+        x: usize,
+        y: usize,
+    ) -> impl Iterator<Item = (usize, usize, &Tile)> + '_ {
+        self.neighbors(x, y)
+            .map(move |(nx, ny)| (nx, ny, &self.board[(nx, ny)]))
+    }
+
+    /// The mine whose reveal lost the game. `None` unless the game is
+    /// lost, and for a forfeit.
+    pub fn losing_tile(&self) -> Option<(usize, usize)> {
+        if self.game_state != GameState::Lost {
+            return None;
         }
+        self.exploded
+    }
 
-        count
+    /// The board as the player sees it, indexed `[x][y]`, for servers to
+    /// send to clients without leaking where the mines are. Once the game
+    /// is lost it shows the mine that was hit and any misplaced flags.
+    pub fn player_view(&self) -> Vec<Vec<VisibleTile>> {
+        (0..self.width)
+            .map(|x| (0..self.height).map(|y| self.visible_tile(x, y)).collect())
+            .collect()
     }
 
-    pub fn get_tile(&self, x: usize, y: usize) -> Option<&Tile> {
-        if x < self.size && y < self.size {
-            Some(&self.board[x][y])
+    /// One tile of [`Minesweeper::player_view`].
+    pub(crate) fn visible_tile(&self, x: usize, y: usize) -> VisibleTile {
+        let tile = &self.board[(x, y)];
+        let lost = self.game_state == GameState::Lost;
+        if (lost && self.exploded == Some((x, y))) || self.contained.contains(&(x, y)) {
+            VisibleTile::Exploded
+        } else if lost && tile.flagged && !tile.is_bomb() {
+            VisibleTile::WrongFlag
         } else {
-            None
+            VisibleTile::from(tile)
         }
     }
 
     fn get_tile_mut(&mut self, x: usize, y: usize) -> Option<&mut Tile> {
-        if x < self.size && y < self.size {
-            Some(&mut self.board[x][y])
+        if x < self.width && y < self.height {
+            Some(self.board.tile_mut(x, y))
         } else {
             None
         }
     }
 
-    pub fn click_tile(&mut self, x: usize, y: usize) -> Result<(), String> {
+    /// Like [`Minesweeper::click_tile`], but reports which tiles the click
+    /// uncovered so a frontend can update just those. Unlike `click_tile`,
+    /// this allocates.
+    pub fn click_tile_detailed(
+        &mut self,
+        x: usize,
+        y: usize,
+    ) -> Result<ClickOutcome, MinesweeperError> {
+        let layers = self.opening_layers_at(x, y);
+        self.click_tile(x, y)?;
+        let hit_bomb = self.board[(x, y)].is_bomb();
+        let mut revealed: Vec<(usize, usize)> = layers.iter().flatten().copied().collect();
+        revealed.sort_unstable();
+        Ok(ClickOutcome {
+            revealed,
+            layers,
+            hit_bomb,
+            state: self.game_state.clone(),
+        })
+    }
+
+    pub fn click_tile(&mut self, x: usize, y: usize) -> Result<(), MinesweeperError> {
+        self.reveal(x, y, Move::Reveal(x, y))
+    }
+
+    /// Reveals (x, y) and records it in the history as `mv`.
+    fn reveal(&mut self, x: usize, y: usize, mv: Move) -> Result<(), MinesweeperError> {
         if self.game_state != GameState::InProgress {
-            return Err("Game is already finished".to_string());
+            return Err(self.finished_error());
         }
+        let checkpoint = self.checkpoint();
 
-        let tile = match self.get_tile(x, y) {
-            Some(tile) => tile,
-            None => return Err("Invalid coordinates".to_string()),
-        };
-
-        if tile.exposed || tile.flagged {
-            return Err("Tile already exposed or flagged".to_string());
+        let tile = self
+            .get_tile(x, y)
+            .ok_or_else(|| self.out_of_bounds(x, y))?;
+        if tile.exposed {
+            return Err(MinesweeperError::AlreadyExposed { pos: (x, y) });
         }
+        if tile.flagged {
+            return Err(MinesweeperError::Flagged { pos: (x, y) });
+        }
+        let value = tile.value.clone();
+        self.note_guess(x, y);
+        self.begin_reveal_move();
+        let source = match mv {
+            Move::Assist(..) => RevealSource::Assist,
+            _ => RevealSource::Click,
+        };
 
-        match tile.value {
+        match value {
             TileValue::Bomb => {
-                self.game_state = GameState::Lost;
-                self.expose_all_bombs();
+                if !self.contain(x, y) {
+                    self.emit(GameEvent::BombExploded { x, y });
+                    self.lose(Some((x, y)));
+                }
             }
-            TileValue::Number(0) => {
-                self.flood_fill(x, y);
+            TileValue::Number(0) if self.zero_rule == ZeroRule::Cascade => {
+                self.flood_fill(x, y, source);
                 self.check_win_condition();
             }
             TileValue::Number(_) => {
-                if let Some(tile) = self.get_tile_mut(x, y) {
-                    tile.exposed = true;
-                }
+                self.expose_from(x, y, source);
                 self.check_win_condition();
             }
         }
 
+        self.record(mv, checkpoint);
+        self.refresh_overlay();
         Ok(())
     }
 
-    fn flood_fill(&mut self, start_x: usize, start_y: usize) {
-        let mut queue = VecDeque::new();
-        let mut visited = vec![vec![false; self.size]; self.size];
+    /// Opens the region around the zero at (start_x, start_y), which is
+    /// itself revealed by `source`.
+    fn flood_fill(&mut self, start_x: usize, start_y: usize, source: RevealSource) {
+        // Borrow the scratch buffers for the duration of the fill; taking
+        // them out leaves empty, unallocated placeholders behind.
+        let mut scratch = std::mem::take(&mut self.scratch);
+        let board = &self.board;
+        scratch.fill(
+            (start_x, start_y),
+            (self.width, self.height),
+            self.adjacency,
+            self.topology,
+            |x, y| &board[(x, y)],
+            |tile| !tile.is_bomb() && !tile.flagged,
+        );
+
+        let covered = self.covered_safe;
+        let origin = (start_x, start_y);
+        for &(x, y) in scratch.region() {
+            let source = if (x, y) == origin {
+                source
+            } else {
+                RevealSource::Flood { origin }
+            };
+            self.expose_from(x, y, source);
+        }
+        self.scratch = scratch;
+        self.emit(GameEvent::FloodFillCompleted {
+            x: start_x,
+            y: start_y,
+            tiles: covered - self.covered_safe,
+        });
+    }
+
+    /// The covered tiles a click on (x, y) would reveal right now, sorted,
+    /// without revealing them: the whole opening for a zero (unless the
+    /// [`ZeroRule`] is `Single`), just the tile for a number, and nothing
+    /// for a mine, a flagged or exposed tile, or a finished game.
+    ///
+    /// Meant for hover previews and solver heuristics.
+    pub fn opening_at(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
+        let mut region: Vec<(usize, usize)> =
+            self.opening_layers_at(x, y).into_iter().flatten().collect();
+        region.sort_unstable();
+        region
+    }
+
+    /// [`Minesweeper::opening_at`] in the order the opening spreads: the
+    /// clicked tile, then the covered tiles one step out, then two, and so
+    /// on, each layer sorted. For animating an opening as it expands.
+    pub fn opening_layers_at(&self, x: usize, y: usize) -> Vec<Vec<(usize, usize)>> {
+        match self.get_tile(x, y) {
+            Some(tile) if self.game_state == GameState::InProgress && !tile.exposed => {
+                if tile.is_bomb() || tile.flagged {
+                    return Vec::new();
+                }
+                // Only a zero spreads; anything else is answered without
+                // the board-sized scratch buffer a walk needs.
+                if tile.get_number() != Some(0) || self.zero_rule == ZeroRule::Single {
+                    return vec![vec![(x, y)]];
+                }
+            }
+            _ => return Vec::new(),
+        }
+
+        let mut scratch = FloodScratch::default();
+        scratch.fill(
+            (x, y),
+            (self.width, self.height),
+            self.adjacency,
+            self.topology,
+            |x, y| &self.board[(x, y)],
+            |tile| !tile.is_bomb() && !tile.flagged,
+        );
+
+        scratch
+            .layers()
+            .map(|layer| {
+                let mut layer: Vec<(usize, usize)> = layer
+                    .iter()
+                    .copied()
+                    .filter(|&(x, y)| !self.board[(x, y)].exposed)
+                    .collect();
+                layer.sort_unstable();
+                layer
+            })
+            // Exposed zeros can carry the walk without revealing anything.
+            .filter(|layer| !layer.is_empty())
+            .collect()
+    }
 
-        queue.push_back((start_x, start_y));
-        visited[start_x][start_y] = true;
+    /// Exposes a single tile, keeping the 3BV tracker in step.
+    fn expose(&mut self, x: usize, y: usize) {
+        if !self.board[(x, y)].exposed {
+            let tile = self.board.tile_mut(x, y);
+            tile.exposed = true;
+            if let Some(number) = tile.get_number() {
+                self.covered_safe -= 1;
+                self.emit(GameEvent::TileRevealed { x, y, number });
+            }
+            self.bv.on_reveal(x, y);
+        }
+    }
 
-        while let Some((x, y)) = queue.pop_front() {
-            self.board[x][y].exposed = true;
+    /// Reveals every remaining safe tile in one call, once the numbers on
+    /// show and the mine count prove where all the remaining mines are.
+    /// Saves the mop-up clicks at the end of a large board; the game is won
+    /// afterwards. Returns how many tiles were revealed.
+    ///
+    /// Fails, changing nothing, while any covered tile could still go
+    /// either way. A flag on a tile proven safe is removed.
+    pub fn auto_finish(&mut self) -> Result<usize, MinesweeperError> {
+        if self.game_state != GameState::InProgress {
+            return Err(self.finished_error());
+        }
 
-            if let TileValue::Number(n) = self.board[x][y].value {
-                if n > 0 {
+        let probabilities = match &self.overlay {
+            Some(overlay) => overlay.probabilities().clone(),
+            None => Arc::new(ProbabilityEngine::default().compute(self)),
+        };
+        let mut safe = Vec::new();
+        for x in 0..self.width {
+            for y in 0..self.height {
+                if self.board[(x, y)].exposed {
                     continue;
                 }
+                let p = probabilities[x * self.height + y];
+                if p < CERTAIN {
+                    safe.push((x, y));
+                } else if p < 1.0 - CERTAIN {
+                    return Err(MinesweeperError::NeedsGuess);
+                }
             }
+        }
 
-            for dx in -1..=1i32 {
-                for dy in -1..=1i32 {
-                    if dx == 0 && dy == 0 {
-                        continue;
-                    }
+        let checkpoint = self.checkpoint();
+        self.begin_reveal_move();
+        for &(x, y) in &safe {
+            if self.board[(x, y)].flagged {
+                self.board.tile_mut(x, y).flagged = false;
+                self.emit(GameEvent::TileFlagged {
+                    x,
+                    y,
+                    flagged: false,
+                });
+            }
+            self.expose_from(x, y, RevealSource::AutoFinish);
+        }
+        self.check_win_condition();
+        self.record(Move::AutoFinish, checkpoint);
+        self.refresh_overlay();
+        Ok(safe.len())
+    }
 
-                    let nx = x as i32 + dx;
-                    let ny = y as i32 + dy;
+    /// Puts a game in progress on hold: moves are refused until
+    /// [`Minesweeper::resume`], and [`TimedMinesweeper`] and
+    /// [`GameManager`] clocks stop.
+    pub fn pause(&mut self) -> Result<(), MinesweeperError> {
+        if self.game_state != GameState::InProgress {
+            return Err(self.finished_error());
+        }
+        self.game_state = GameState::Paused;
+        Ok(())
+    }
 
-                    if nx >= 0 && ny >= 0 && (nx as usize) < self.size && (ny as usize) < self.size
-                    {
-                        let nx = nx as usize;
-                        let ny = ny as usize;
+    /// Carries on with a paused game.
+    pub fn resume(&mut self) -> Result<(), MinesweeperError> {
+        if self.game_state != GameState::Paused {
+            return Err(MinesweeperError::NotPaused);
+        }
+        self.game_state = GameState::InProgress;
+        Ok(())
+    }
 
-                        if !visited[nx][ny]
-                            && !self.board[nx][ny].is_bomb()
-                            && !self.board[nx][ny].flagged
-                        {
-                            visited[nx][ny] = true;
-                            self.board[nx][ny].exposed = true;
+    pub fn is_paused(&self) -> bool {
+        self.game_state == GameState::Paused
+    }
 
-                            if let TileValue::Number(0) = self.board[nx][ny].value {
-                                queue.push_back((nx, ny));
-                            }
-                        }
-                    }
-                }
-            }
+    /// Ends the game as a loss without a mine being hit, e.g. when the
+    /// player resigns, also from a pause, or runs out of time.
+    pub fn forfeit(&mut self) -> Result<(), MinesweeperError> {
+        if self.game_state.is_over() {
+            return Err(self.finished_error());
         }
+        self.lose(None);
+        Ok(())
     }
 
-    fn expose_all_bombs(&mut self) {
-        for row in &mut self.board {
-            for tile in row {
-                if tile.is_bomb() {
-                    tile.exposed = true;
-                }
-            }
+    /// Spends a life on the mine just hit at (x, y), leaving it exposed.
+    /// Returns false, changing nothing, when no lives are left.
+    fn contain(&mut self, x: usize, y: usize) -> bool {
+        if self.lives == 0 {
+            return false;
         }
+        self.lives -= 1;
+        self.board.tile_mut(x, y).exposed = true;
+        self.contained.push((x, y));
+        self.emit(GameEvent::LifeLost {
+            x,
+            y,
+            lives_left: self.lives,
+        });
+        true
+    }
+
+    /// Ends the game, `exploded` being the mine that was hit, if any.
+    fn lose(&mut self, exploded: Option<(usize, usize)>) {
+        self.game_state = GameState::Lost;
+        self.exploded = exploded;
+        self.expose_all_bombs();
+        self.emit(GameEvent::GameLost);
+    }
+
+    fn expose_all_bombs(&mut self) {
+        self.board.update_where(
+            |tile| tile.is_bomb() && !tile.exposed,
+            |tile| tile.exposed = true,
+        );
     }
 
     fn check_win_condition(&mut self) {
-        let mut unexposed_non_bombs = 0;
+        let won = match self.win_condition {
+            WinCondition::Classic => self.covered_safe == 0,
+            WinCondition::FlagAllMines => self
+                .board
+                .iter()
+                .all(|(_, tile)| tile.flagged == (tile.is_bomb() && !tile.exposed)),
+            WinCondition::Score(target) => {
+                self.covered_safe == 0 || self.bv.total() - self.bv.remaining() >= target
+            }
+        };
+        if won && self.game_state != GameState::Won {
+            self.game_state = GameState::Won;
+            if self.auto_flag && self.flag_rule == FlagRule::Allowed {
+                self.flag_remaining_mines();
+            }
+            self.emit(GameEvent::GameWon);
+        }
+    }
 
-        for row in &self.board {
-            for tile in row {
-                if !tile.is_bomb() && !tile.exposed {
-                    unexposed_non_bombs += 1;
+    /// Flags every covered mine once the game is won, as classic
+    /// Minesweeper does. Not counted in [`Minesweeper::flags_placed`].
+    fn flag_remaining_mines(&mut self) {
+        for x in 0..self.width {
+            for y in 0..self.height {
+                let tile = &self.board[(x, y)];
+                if tile.is_bomb() && !tile.flagged {
+                    self.board.tile_mut(x, y).flagged = true;
+                    self.emit(GameEvent::TileFlagged {
+                        x,
+                        y,
+                        flagged: true,
+                    });
                 }
             }
         }
+    }
 
-        if unexposed_non_bombs == 0 {
-            self.game_state = GameState::Won;
+    /// Turns the probability overlay on or off.
+    ///
+    /// While it is on, the chance that each covered tile is a mine is kept
+    /// up to date after every reveal and can be read with
+    /// [`Minesweeper::mine_probability`] or from a [`BoardSnapshot`]. Only
+    /// the parts of the frontier a move touched are re-enumerated, but the
+    /// update does allocate, so leave it off for bulk simulation.
+    pub fn set_probability_overlay(&mut self, enabled: bool) {
+        if !enabled {
+            self.overlay = None;
+        } else if self.overlay.is_none() {
+            self.overlay = Some(ProbabilityOverlay::default());
+            self.refresh_overlay();
+        }
+    }
+
+    /// The chance that the tile at (x, y) is a mine, given what the player
+    /// can see. `None` unless the probability overlay is enabled.
+    pub fn mine_probability(&self, x: usize, y: usize) -> Option<f64> {
+        let overlay = self.overlay.as_ref()?;
+        if x < self.width && y < self.height {
+            Some(overlay.probabilities()[x * self.height + y])
+        } else {
+            None
+        }
+    }
+
+    /// The chance that each tile is a mine, indexed `[x][y]`, given what
+    /// the player can see and the mine count. Exposed numbers are 0.0.
+    ///
+    /// Frontier tiles are weighed by enumerating every mine arrangement
+    /// consistent with the numbers, one independent partition of the
+    /// frontier at a time; the remaining mines are spread evenly over the
+    /// other covered tiles. Reuses the overlay's results when it is on
+    /// (see [`Minesweeper::set_probability_overlay`]) and computes them
+    /// afresh otherwise.
+    pub fn mine_probabilities(&self) -> Vec<Vec<f64>> {
+        let flat = match &self.overlay {
+            Some(overlay) => overlay.probabilities().clone(),
+            None => Arc::new(ProbabilityEngine::default().compute(self)),
+        };
+        flat.chunks(self.height).map(<[f64]>::to_vec).collect()
+    }
+
+    /// [`Minesweeper::mine_probabilities`] with a cap on the work done.
+    /// `None` if the budget runs out first, since probabilities from only
+    /// some of the arrangements would be misleading. Free while the overlay
+    /// is on.
+    pub fn mine_probabilities_within(&self, budget: SolverBudget) -> Option<Vec<Vec<f64>>> {
+        let flat = match &self.overlay {
+            Some(overlay) => overlay.probabilities().clone(),
+            None => Arc::new(
+                ProbabilityEngine::default().compute_metered(self, &mut Meter::new(budget))?,
+            ),
+        };
+        Some(flat.chunks(self.height).map(<[f64]>::to_vec).collect())
+    }
+
+    fn refresh_overlay(&mut self) {
+        if let Some(mut overlay) = self.overlay.take() {
+            overlay.refresh(self);
+            self.overlay = Some(overlay);
+        }
+    }
+
+    /// A copy of the position for analysis elsewhere, e.g. on another
+    /// thread: board, state and counters, without the overlay or history.
+    /// Rows are shared until either copy changes them, and the copy's
+    /// flood-fill buffers are only allocated if it is played on.
+    pub(crate) fn position(&self) -> Minesweeper {
+        Minesweeper {
+            board: self.board.clone(),
+            game_state: self.game_state.clone(),
+            width: self.width,
+            height: self.height,
+            bomb_count: self.bomb_count,
+            adjacency: self.adjacency,
+            topology: self.topology,
+            bv: self.bv.clone(),
+            scratch: FloodScratch::default(),
+            covered_safe: self.covered_safe,
+            notes: HashMap::new(),
+            provenance: None,
+            reveal_moves: self.reveal_moves,
+            overlay: None,
+            flags_placed: self.flags_placed,
+            flag_rule: self.flag_rule,
+            zero_rule: self.zero_rule,
+            win_condition: self.win_condition,
+            mine_count: self.mine_count,
+            question_marks: self.question_marks,
+            auto_flag: self.auto_flag,
+            exploded: self.exploded,
+            lives: self.lives,
+            time_control: self.time_control,
+            contained: self.contained.clone(),
+            flag_check: self.flag_check,
+            generator: self.generator.clone(),
+            history: None,
+            journal: None,
+            events: Events::default(),
         }
     }
 
+    /// Takes an immutable snapshot of the current board.
+    ///
+    /// Snapshots share tile storage with the game and with each other; a
+    /// chunk of tiles is only copied when the game next changes it, so
+    /// holding many historical states costs memory proportional to what
+    /// changed.
+    pub fn snapshot(&self) -> BoardSnapshot {
+        BoardSnapshot::new(
+            self.board.clone(),
+            self.game_state.clone(),
+            self.overlay
+                .as_ref()
+                .map(|overlay| overlay.probabilities().clone()),
+        )
+    }
+
     pub fn get_game_state(&self) -> &GameState {
         &self.game_state
     }
 
+    /// The side length of a square board. For boards made with
+    /// [`Minesweeper::new_rect`] this is the width; see
+    /// [`Minesweeper::get_width`] and [`Minesweeper::get_height`].
     pub fn get_size(&self) -> usize {
-        self.size
+        self.width
+    }
+
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+
+    pub fn get_height(&self) -> usize {
+        self.height
     }
 
     pub fn get_bomb_count(&self) -> usize {
         self.bomb_count
     }
 
-    pub fn count_flagged_tiles(&self) -> usize {
-        self.board
-            .iter()
-            .flat_map(|row| row.iter())
-            .filter(|tile| tile.flagged)
-            .count()
+    pub fn get_adjacency(&self) -> Adjacency {
+        self.adjacency
     }
 
-    pub fn count_exposed_tiles(&self) -> usize {
-        self.board
-            .iter()
-            .flat_map(|row| row.iter())
-            .filter(|tile| tile.exposed)
-            .count()
+    pub fn get_topology(&self) -> Topology {
+        self.topology
     }
 
-    pub fn toggle_flag(&mut self, x: usize, y: usize) -> Result<(), String> {
-        if self.game_state != GameState::InProgress {
-            return Err("Game is already finished".to_string());
+    /// The rules and parameters this game was created with.
+    pub fn config(&self) -> GameConfig {
+        GameConfig {
+            width: self.width,
+            height: self.height,
+            bomb_count: self.bomb_count,
+            flag_rule: self.flag_rule,
+            zero_rule: self.zero_rule,
+            win_condition: self.win_condition,
+            mine_count: self.mine_count,
+            question_marks: self.question_marks,
+            auto_flag: self.auto_flag,
+            lives: self.lives + self.contained.len(),
+            time_control: self.time_control,
+            generator: GeneratorOptions {
+                adjacency: self.adjacency,
+                topology: self.topology,
+                ..self.generator.clone()
+            },
         }
+    }
 
-        let tile = match self.get_tile_mut(x, y) {
-            Some(tile) => tile,
-            None => return Err("Invalid coordinates".to_string()),
-        };
+    /// Fingerprint of the board layout: dimensions, adjacency and mine
+    /// positions.
+    ///
+    /// It depends only on where the mines are, not on how they were placed
+    /// or on the state of play, so the same layout always gets the same id.
+    pub fn board_id(&self) -> BoardId {
+        self.layout_id((self.width, self.height), &self.mine_positions())
+    }
 
-        if tile.exposed {
-            return Err("Cannot flag exposed tile".to_string());
+    /// The [`Minesweeper::board_id`] of a board with this game's rules, the
+    /// given dimensions and mines at `mines`, in row-major order.
+    fn layout_id(&self, (width, height): (usize, usize), mines: &[(usize, usize)]) -> BoardId {
+        let mut hash = Fnv64::new();
+        hash.write_usize(width);
+        hash.write_usize(height);
+        hash.write_usize(self.bomb_count);
+        hash.write(&[match self.adjacency {
+            Adjacency::Standard => 0,
+            Adjacency::Extended => 1,
+            Adjacency::Knight => 2,
+            Adjacency::Hex => 3,
+        }]);
+        // Written only for tori, so ids of flat boards are unchanged.
+        if self.topology == Topology::Torus {
+            hash.write(&[1]);
         }
+        for &(x, y) in mines {
+            hash.write_usize(x);
+            hash.write_usize(y);
+        }
+        BoardId(hash.finish())
+    }
 
-        tile.flagged = !tile.flagged;
-        Ok(())
+    /// The board's 3BV: the minimum number of clicks needed to clear it.
+    pub fn three_bv(&self) -> usize {
+        self.bv.total()
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// How much of the board's 3BV has not been cleared yet.
+    pub fn three_bv_remaining(&self) -> usize {
+        self.bv.remaining()
+    }
 
-    #[test]
-    fn test_game_creation() {
-        let mine_locations = vec![(0, 0), (1, 1)];
-        let game = Minesweeper::new(3, mine_locations);
+    /// The number of openings (connected regions of zeros) on the board.
+    pub fn openings(&self) -> usize {
+        self.bv.openings()
+    }
 
-        assert_eq!(game.get_size(), 3);
-        assert_eq!(game.get_bomb_count(), 2);
-        assert_eq!(*game.get_game_state(), GameState::InProgress);
+    /// The number of openings that have not been revealed yet.
+    pub fn openings_remaining(&self) -> usize {
+        self.bv.openings_remaining()
     }
 
-    #[test]
-    fn test_mine_placement() {
-        let mine_locations = vec![(0, 0), (2, 2)];
-        let game = Minesweeper::new(3, mine_locations);
+    /// Fraction of the board's 3BV cleared so far, from 0.0 to 1.0.
+    pub fn progress(&self) -> f64 {
+        let total = self.bv.total();
+        if total == 0 {
+            return 1.0;
+        }
+        (total - self.bv.remaining()) as f64 / total as f64
+    }
 
-        assert!(game.get_tile(0, 0).unwrap().is_bomb());
-        assert!(game.get_tile(2, 2).unwrap().is_bomb());
-        assert!(!game.get_tile(1, 1).unwrap().is_bomb());
+    /// Every mine on the board, in row-major order.
+    pub fn mine_positions(&self) -> Vec<(usize, usize)> {
+        let mut positions = Vec::with_capacity(self.bomb_count);
+        for (pos, tile) in self.board.iter() {
+            if tile.is_bomb() {
+                positions.push(pos);
+            }
+        }
+        positions
     }
 
-    #[test]
-    fn test_adjacent_bomb_counting() {
-        let mine_locations = vec![(0, 0)];
-        let game = Minesweeper::new(3, mine_locations);
+    /// The number of safe tiles that are still covered.
+    pub fn safe_tiles_remaining(&self) -> usize {
+        self.covered_safe
+    }
 
-        let tile = game.get_tile(1, 1).unwrap();
-        assert_eq!(tile.get_number(), Some(1));
+    /// How many times a flag has been placed this game, counting flags
+    /// that were later removed.
+    pub fn flags_placed(&self) -> usize {
+        self.flags_placed
+    }
 
-        let tile = game.get_tile(2, 2).unwrap();
-        assert_eq!(tile.get_number(), Some(0));
+    /// Whether the game has been played without ever placing a flag.
+    pub fn is_no_flag(&self) -> bool {
+        self.flags_placed == 0
     }
 
-    #[test]
-    fn test_clicking_bomb() {
-        let mine_locations = vec![(0, 0)];
-        let mut game = Minesweeper::new(2, mine_locations);
+    pub fn get_flag_rule(&self) -> FlagRule {
+        self.flag_rule
+    }
+
+    /// Changes whether flags may be placed. A game can only switch to
+    /// [`FlagRule::Forbidden`] before any flag has been placed.
+    pub fn set_flag_rule(&mut self, rule: FlagRule) -> Result<(), String> {
+        if rule == FlagRule::Forbidden && !self.is_no_flag() {
+            return Err("Flags have already been placed".to_string());
+        }
+        if rule == FlagRule::Forbidden && self.win_condition == WinCondition::FlagAllMines {
+            return Err(NO_FLAGS_TO_WIN.to_string());
+        }
+        self.flag_rule = rule;
+        Ok(())
+    }
+
+    pub fn get_zero_rule(&self) -> ZeroRule {
+        self.zero_rule
+    }
+
+    /// Changes what revealing a zero does from the next move on. 3BV is
+    /// recounted, as under `Single` every zero takes a click of its own.
+    pub fn set_zero_rule(&mut self, rule: ZeroRule) {
+        self.zero_rule = rule;
+        if self.bv.zero_rule() != rule {
+            self.recount_bv();
+        }
+    }
+
+    /// Rebuilds the 3BV tracker for the current layout and zero rule,
+    /// counting the tiles already exposed as solved.
+    pub(crate) fn recount_bv(&mut self) {
+        self.bv = BvTracker::new(&self.board, self.adjacency, self.topology, self.zero_rule);
+        for x in 0..self.width {
+            for y in 0..self.height {
+                if self.board[(x, y)].exposed && !self.board[(x, y)].is_bomb() {
+                    self.bv.on_reveal(x, y);
+                }
+            }
+        }
+    }
+
+    pub fn get_win_condition(&self) -> WinCondition {
+        self.win_condition
+    }
+
+    /// Changes what wins the game. A game in progress that already meets
+    /// the new condition is won straight away.
+    pub fn set_win_condition(&mut self, condition: WinCondition) -> Result<(), String> {
+        if condition == WinCondition::FlagAllMines && self.flag_rule == FlagRule::Forbidden {
+            return Err(NO_FLAGS_TO_WIN.to_string());
+        }
+        self.win_condition = condition;
+        if self.game_state == GameState::InProgress {
+            self.check_win_condition();
+        }
+        Ok(())
+    }
+
+    pub fn get_mine_count(&self) -> MineCount {
+        self.mine_count
+    }
+
+    /// Changes what the player is told about the mine count. A range must
+    /// contain the real count.
+    pub fn set_mine_count(&mut self, mine_count: MineCount) -> Result<(), String> {
+        if let MineCount::Range { min, max } = mine_count {
+            if !(min..=max).contains(&self.bomb_count) {
+                return Err("Mine count range must contain the mine count".to_string());
+            }
+        }
+        self.mine_count = mine_count;
+        Ok(())
+    }
+
+    /// The fewest and most mines the board could hold, as far as the
+    /// player knows. The solver and probabilities work from these rather
+    /// than the real count.
+    pub fn mine_count_bounds(&self) -> (usize, usize) {
+        match self.mine_count {
+            MineCount::Exact => (self.bomb_count, self.bomb_count),
+            MineCount::Range { min, max } => (min, max),
+            MineCount::Hidden => (0, self.width * self.height),
+        }
+    }
+
+    /// The remaining-mines counter: the known bounds on the mine count
+    /// less the flags placed, or `None` when the count is hidden. Both
+    /// ends are the same unless the count is a range.
+    pub fn mines_left(&self) -> Option<(i64, i64)> {
+        if self.mine_count == MineCount::Hidden {
+            return None;
+        }
+        let (min, max) = self.mine_count_bounds();
+        // Mines hit with a life to spare are found as surely as flagged ones.
+        let found = (self.count_flagged_tiles() + self.contained.len()) as i64;
+        Some((min as i64 - found, max as i64 - found))
+    }
+
+    /// How many more mines the player can hit without losing.
+    pub fn lives_remaining(&self) -> usize {
+        self.lives
+    }
+
+    /// Gives the player `lives` mine hits to survive from now on. With a
+    /// life to spare, a mine that is hit stays exposed as a contained mine
+    /// and the game carries on.
+    pub fn set_lives(&mut self, lives: usize) {
+        self.lives = lives;
+    }
+
+    /// The mines hit so far that cost a life rather than the game, in the
+    /// order they were hit.
+    pub fn contained_mines(&self) -> &[(usize, usize)] {
+        &self.contained
+    }
+
+    pub fn question_marks_enabled(&self) -> bool {
+        self.question_marks
+    }
+
+    /// Turns the question-mark step of [`Minesweeper::cycle_mark`] on or
+    /// off. Question marks already placed stay where they are.
+    pub fn set_question_marks(&mut self, enabled: bool) {
+        self.question_marks = enabled;
+    }
+
+    pub fn auto_flag_enabled(&self) -> bool {
+        self.auto_flag
+    }
+
+    /// Sets whether winning flags the mines still covered, so the final
+    /// board shows every mine. Off by default, and never done when flags
+    /// are forbidden.
+    pub fn set_auto_flag(&mut self, enabled: bool) {
+        self.auto_flag = enabled;
+    }
+
+    pub fn count_flagged_tiles(&self) -> usize {
+        self.board.tiles().filter(|tile| tile.flagged).count()
+    }
+
+    pub fn count_exposed_tiles(&self) -> usize {
+        self.board.tiles().filter(|tile| tile.exposed).count()
+    }
+
+    /// Chords on the exposed number at (x, y): if exactly that many of its
+    /// neighbours are flagged, every other covered neighbour is revealed at
+    /// once, opening up any zeros among them. A misplaced flag means one
+    /// of them is a mine, and the game is lost.
+    pub fn chord(&mut self, x: usize, y: usize) -> Result<(), MinesweeperError> {
+        if self.game_state != GameState::InProgress {
+            return Err(self.finished_error());
+        }
+
+        let number = match self.get_tile(x, y) {
+            None => return Err(self.out_of_bounds(x, y)),
+            Some(tile) if tile.exposed => tile.get_number().unwrap_or(0),
+            Some(_) => 0,
+        };
+        if number == 0 {
+            return Err(MinesweeperError::NotANumber { pos: (x, y) });
+        }
+
+        let flags = self
+            .neighbor_tiles(x, y)
+            .filter(|(_, _, tile)| tile.flagged)
+            .count();
+        if flags != number as usize {
+            return Err(MinesweeperError::FlagCountMismatch {
+                pos: (x, y),
+                number,
+                flags,
+            });
+        }
+
+        let checkpoint = self.checkpoint();
+        self.begin_reveal_move();
+        let source = RevealSource::Chord { origin: (x, y) };
+        let mut hit_mine = None;
+        let (width, height) = (self.width, self.height);
+        for (nx, ny) in self
+            .adjacency
+            .neighbors_in(self.topology, x, y, width, height)
+        {
+            let tile = &self.board[(nx, ny)];
+            if tile.exposed || tile.flagged {
+                continue;
+            }
+            match tile.value {
+                TileValue::Bomb => {
+                    if !self.contain(nx, ny) {
+                        self.emit(GameEvent::BombExploded { x: nx, y: ny });
+                        hit_mine = hit_mine.or(Some((nx, ny)));
+                    }
+                }
+                TileValue::Number(0) if self.zero_rule == ZeroRule::Cascade => {
+                    self.flood_fill(nx, ny, source)
+                }
+                TileValue::Number(_) => self.expose_from(nx, ny, source),
+            }
+        }
+
+        if hit_mine.is_some() {
+            self.lose(hit_mine);
+        } else {
+            self.check_win_condition();
+        }
+        self.record(Move::Chord(x, y), checkpoint);
+        self.refresh_overlay();
+        Ok(())
+    }
+
+    pub fn toggle_flag(&mut self, x: usize, y: usize) -> Result<(), MinesweeperError> {
+        if self.game_state != GameState::InProgress {
+            return Err(self.finished_error());
+        }
+
+        if self.flag_rule == FlagRule::Forbidden {
+            return Err(MinesweeperError::FlagsForbidden);
+        }
+
+        let checkpoint = self.checkpoint();
+        let out_of_bounds = self.out_of_bounds(x, y);
+        let tile = self.get_tile_mut(x, y).ok_or(out_of_bounds)?;
+        if tile.exposed {
+            return Err(MinesweeperError::AlreadyExposed { pos: (x, y) });
+        }
+
+        tile.flagged = !tile.flagged;
+        let flagged = tile.flagged;
+        if flagged {
+            self.flags_placed += 1;
+        }
+        self.emit(GameEvent::TileFlagged { x, y, flagged });
+        if flagged {
+            self.check_flag_conflicts(x, y);
+        }
+        if self.win_condition == WinCondition::FlagAllMines {
+            self.check_win_condition();
+        }
+        self.record(Move::ToggleFlag(x, y), checkpoint);
+        Ok(())
+    }
+
+    /// Why a move can't be made while the game isn't in progress.
+    fn finished_error(&self) -> MinesweeperError {
+        match self.game_state {
+            GameState::Paused => MinesweeperError::Paused,
+            _ => MinesweeperError::GameFinished {
+                state: self.game_state.clone(),
+            },
+        }
+    }
+
+    fn out_of_bounds(&self, x: usize, y: usize) -> MinesweeperError {
+        MinesweeperError::OutOfBounds {
+            pos: (x, y),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Places, replaces or (with `None`) removes the marker on a covered tile.
+    pub fn set_marker(
+        &mut self,
+        x: usize,
+        y: usize,
+        marker: Option<Marker>,
+    ) -> Result<(), MinesweeperError> {
+        if self.game_state != GameState::InProgress {
+            return Err(self.finished_error());
+        }
+
+        match self.get_tile(x, y) {
+            Some(tile) if tile.exposed => {
+                return Err(MinesweeperError::AlreadyExposed { pos: (x, y) })
+            }
+            Some(_) => {}
+            None => return Err(self.out_of_bounds(x, y)),
+        }
+
+        self.write_marker(x, y, marker);
+        Ok(())
+    }
+
+    /// Plays a [`Move::SetMarker`] already known to be legal.
+    fn write_marker(&mut self, x: usize, y: usize, marker: Option<Marker>) {
+        let checkpoint = self.checkpoint();
+        self.board.tile_mut(x, y).marker = marker;
+        self.record(Move::SetMarker(x, y, marker), checkpoint);
+    }
+
+    /// Advances a covered tile to its next [`Mark`] and returns it: blank,
+    /// flag, then question mark when enabled (see
+    /// [`Minesweeper::set_question_marks`]), then blank again. Under
+    /// [`FlagRule::Forbidden`] the flag step is skipped.
+    ///
+    /// The change is played as the [`Move::ToggleFlag`] and
+    /// [`Move::SetMarker`] moves it is made of, so replays and histories
+    /// need nothing new; moving from a flag to a question mark is two moves.
+    pub fn cycle_mark(&mut self, x: usize, y: usize) -> Result<Mark, MinesweeperError> {
+        if self.game_state != GameState::InProgress {
+            return Err(self.finished_error());
+        }
+        let tile = self
+            .get_tile(x, y)
+            .ok_or_else(|| self.out_of_bounds(x, y))?;
+        if tile.exposed {
+            return Err(MinesweeperError::AlreadyExposed { pos: (x, y) });
+        }
+
+        let current = tile.mark();
+        let flags = self.flag_rule == FlagRule::Allowed;
+        let next = match current {
+            Mark::None if flags => Mark::Flag,
+            Mark::None | Mark::Flag if self.question_marks => Mark::Question,
+            Mark::None => return Err(MinesweeperError::FlagsForbidden),
+            Mark::Flag | Mark::Question => Mark::None,
+        };
+
+        match current {
+            Mark::Flag => self.toggle_flag(x, y)?,
+            Mark::Question => self.write_marker(x, y, None),
+            Mark::None => {}
+        }
+        match next {
+            Mark::Flag => self.toggle_flag(x, y)?,
+            Mark::Question => self.write_marker(x, y, Some(QUESTION_MARK)),
+            Mark::None => {}
+        }
+        Ok(next)
+    }
+
+    /// Attaches a note to a covered tile, replacing any existing one. An
+    /// empty note removes it.
+    pub fn set_note(
+        &mut self,
+        x: usize,
+        y: usize,
+        note: impl Into<String>,
+    ) -> Result<(), MinesweeperError> {
+        if self.game_state != GameState::InProgress {
+            return Err(self.finished_error());
+        }
+
+        let tile = match self.get_tile(x, y) {
+            Some(tile) => tile,
+            None => return Err(self.out_of_bounds(x, y)),
+        };
+
+        if tile.exposed {
+            return Err(MinesweeperError::NoteOnExposed { pos: (x, y) });
+        }
+
+        let note = note.into();
+        if note.chars().count() > MAX_NOTE_LENGTH {
+            return Err(MinesweeperError::NoteTooLong {
+                pos: (x, y),
+                limit: MAX_NOTE_LENGTH,
+            });
+        }
+
+        if note.is_empty() {
+            self.notes.remove(&(x, y));
+        } else {
+            self.notes.insert((x, y), note);
+        }
+        Ok(())
+    }
+
+    pub fn get_note(&self, x: usize, y: usize) -> Option<&str> {
+        self.notes.get(&(x, y)).map(String::as_str)
+    }
+
+    /// Every note on the board with its tile, in no particular order.
+    pub fn notes(&self) -> impl Iterator<Item = ((usize, usize), &str)> {
+        self.notes.iter().map(|(&pos, note)| (pos, note.as_str()))
+    }
+
+    pub fn apply_move(&mut self, mv: Move) -> Outcome {
+        match mv {
+            Move::Reveal(x, y) => self.click_tile(x, y),
+            Move::ToggleFlag(x, y) => self.toggle_flag(x, y),
+            Move::SetMarker(x, y, marker) => self.set_marker(x, y, marker),
+            Move::Chord(x, y) => self.chord(x, y),
+            Move::Assist(x, y) => self.reveal_assisted(x, y),
+            Move::AutoFinish => self.auto_finish().map(|_| ()),
+        }
+    }
+
+    /// Applies a sequence of moves in order, returning one outcome per move.
+    ///
+    /// A rejected move does not stop the batch; later moves are still
+    /// attempted (and rejected once the game is over). Flood fills share the
+    /// game's scratch buffers and the win check is O(1), so replaying a long
+    /// history on a large board costs no more than the reveals themselves.
+    pub fn apply_moves(&mut self, moves: impl IntoIterator<Item = Move>) -> Vec<Outcome> {
+        moves.into_iter().map(|mv| self.apply_move(mv)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_creation() {
+        let mine_locations = vec![(0, 0), (1, 1)];
+        let game = Minesweeper::new(3, mine_locations);
+
+        assert_eq!(game.get_size(), 3);
+        assert_eq!(game.get_bomb_count(), 2);
+        assert_eq!(*game.get_game_state(), GameState::InProgress);
+    }
+
+    #[test]
+    fn test_mine_placement() {
+        let mine_locations = vec![(0, 0), (2, 2)];
+        let game = Minesweeper::new(3, mine_locations);
+
+        assert!(game.get_tile(0, 0).unwrap().is_bomb());
+        assert!(game.get_tile(2, 2).unwrap().is_bomb());
+        assert!(!game.get_tile(1, 1).unwrap().is_bomb());
+    }
+
+    #[test]
+    fn test_adjacent_bomb_counting() {
+        let mine_locations = vec![(0, 0)];
+        let game = Minesweeper::new(3, mine_locations);
+
+        let tile = game.get_tile(1, 1).unwrap();
+        assert_eq!(tile.get_number(), Some(1));
+
+        let tile = game.get_tile(2, 2).unwrap();
+        assert_eq!(tile.get_number(), Some(0));
+    }
+
+    #[test]
+    fn test_rectangular_board() {
+        // 5 wide, 3 tall, with a mine in the bottom-right corner.
+        let mut game = Minesweeper::new_rect(5, 3, vec![(4, 2)]);
+        assert_eq!((game.get_width(), game.get_height()), (5, 3));
+        assert_eq!(game.get_tile(3, 1).unwrap().get_number(), Some(1));
+        assert_eq!(game.get_tile(3, 0).unwrap().get_number(), Some(0));
+        assert!(game.get_tile(0, 3).is_none());
+        assert!(game.get_tile(4, 0).is_some());
+
+        assert_eq!(game.opening_at(0, 0).len(), 14);
+        game.click_tile(0, 0).unwrap();
+        assert_eq!(*game.get_game_state(), GameState::Won);
+        assert_eq!(game.three_bv(), 1);
+    }
+
+    #[test]
+    fn test_rectangular_config() {
+        let config = GameConfig::new_rect(30, 16, 99);
+        let game = Minesweeper::new_with_config(&config, (29, 15)).unwrap();
+        assert_eq!((game.get_width(), game.get_height()), (30, 16));
+        assert_eq!(game.get_bomb_count(), 99);
+        assert_eq!(game.mine_positions().len(), 99);
+        assert!(game.get_tile(29, 15).unwrap().exposed);
+    }
+
+    #[test]
+    fn test_extended_adjacency_counting() {
+        let game = Minesweeper::new_with_adjacency(5, vec![(0, 0), (4, 4)], Adjacency::Extended);
+
+        assert_eq!(game.get_tile(2, 2).unwrap().get_number(), Some(2));
+        assert_eq!(game.get_tile(1, 3).unwrap().get_number(), Some(0));
+        assert_eq!(game.get_tile(0, 3).unwrap().get_number(), Some(0));
+        assert_eq!(game.get_tile(3, 0).unwrap().get_number(), Some(0));
+        assert_eq!(game.get_tile(0, 2).unwrap().get_number(), Some(1));
+    }
+
+    #[test]
+    fn test_extended_adjacency_flood_fill() {
+        let mut game = Minesweeper::new_with_adjacency(7, vec![(0, 0)], Adjacency::Extended);
+        game.click_tile(6, 6).unwrap();
+
+        // Zeros open everything two steps away, which is the whole board.
+        assert_eq!(*game.get_game_state(), GameState::Won);
+    }
+
+    #[test]
+    fn test_extended_adjacency_first_click_opens() {
+        let options = GeneratorOptions {
+            adjacency: Adjacency::Extended,
+            ..Default::default()
+        };
+        let game = Minesweeper::new_with_options(9, 10, (4, 4), &options).unwrap();
+
+        assert_eq!(game.get_adjacency(), Adjacency::Extended);
+        assert_eq!(game.get_tile(4, 4).unwrap().get_number(), Some(0));
+    }
+
+    #[test]
+    fn test_torus_wraps_counts_and_openings() {
+        let mut game = Minesweeper::new_rect_with_topology(
+            4,
+            3,
+            vec![(0, 0)],
+            Adjacency::Standard,
+            Topology::Torus,
+        );
+        assert_eq!(game.get_tile(3, 2).unwrap().get_number(), Some(1));
+        assert_eq!(game.get_tile(2, 1).unwrap().get_number(), Some(0));
+
+        // The zeros in the third column open both columns beside them,
+        // including the first-column tiles' wrapped neighbours in the last.
+        game.click_tile(2, 1).unwrap();
+        assert_eq!(game.count_exposed_tiles(), 9);
+        assert!(!game.get_tile(0, 1).unwrap().exposed);
+
+        let mut config = game.config();
+        assert_eq!(config.generator.topology, Topology::Torus);
+        config.width = 2;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_tile_iterators() {
+        let mut game = Minesweeper::new_rect(3, 2, vec![(0, 0), (2, 1)]);
+        game.click_tile(1, 0).unwrap();
+        game.toggle_flag(0, 0).unwrap();
+
+        let all: Vec<(usize, usize)> = game.tiles().map(|(x, y, _)| (x, y)).collect();
+        assert_eq!(all, vec![(0, 0), (0, 1), (1, 0), (1, 1), (2, 0), (2, 1)]);
+        assert_eq!(game.tiles().filter(|(_, _, t)| t.is_bomb()).count(), 2);
+
+        let exposed: Vec<_> = game
+            .exposed_tiles()
+            .map(|(x, y, t)| (x, y, t.get_number()))
+            .collect();
+        assert_eq!(exposed, vec![(1, 0, Some(2))]);
+        let flagged: Vec<_> = game.flagged_tiles().map(|(x, y, _)| (x, y)).collect();
+        assert_eq!(flagged, vec![(0, 0)]);
+        assert_eq!(game.covered_tiles().count(), 5);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let mut game = Minesweeper::new_rect(3, 3, vec![(0, 0)]);
+        let corner: Vec<_> = game.neighbors(0, 0).collect();
+        assert_eq!(corner, vec![(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(game.neighbors(1, 1).count(), 8);
+        assert_eq!(game.neighbors(3, 0).count(), 0);
+
+        game.toggle_flag(0, 0).unwrap();
+        let flagged: Vec<_> = game
+            .neighbor_tiles(1, 0)
+            .filter(|(_, _, tile)| tile.flagged)
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        assert_eq!(flagged, vec![(0, 0)]);
+
+        let torus =
+            Minesweeper::new_rect_with_topology(3, 3, vec![], Adjacency::Standard, Topology::Torus);
+        assert!(torus.neighbors(0, 0).any(|pos| pos == (2, 2)));
+        assert_eq!(torus.neighbors(0, 0).count(), 8);
+    }
+
+    #[test]
+    fn test_player_view_hides_mines() {
+        let mut game = Minesweeper::new_rect(3, 2, vec![(0, 0), (2, 1)]);
+        game.click_tile(1, 0).unwrap();
+        game.toggle_flag(0, 0).unwrap();
+        let view = game.player_view();
+        assert_eq!(view.len(), 3);
+        assert_eq!(view[0], vec![VisibleTile::Flagged, VisibleTile::Covered]);
+        assert_eq!(view[1][0], VisibleTile::Number(2));
+        assert_eq!(view[2][1], VisibleTile::Covered);
+
+        game.click_tile(2, 1).unwrap();
+        assert_eq!(game.player_view()[2][1], VisibleTile::Exploded);
+        assert_eq!(game.player_view()[0][0], VisibleTile::Mine);
+    }
+
+    #[test]
+    fn test_loss_shows_exploded_mine_and_wrong_flags() {
+        let mut game = Minesweeper::new_rect(3, 2, vec![(0, 0), (2, 1)]);
+        game.click_tile(1, 0).unwrap();
+        game.toggle_flag(0, 1).unwrap();
+        assert_eq!(game.losing_tile(), None);
+        assert_eq!(game.player_view()[0][1], VisibleTile::Flagged);
+
+        game.click_tile(0, 0).unwrap();
+        assert_eq!(game.losing_tile(), Some((0, 0)));
+        let view = game.player_view();
+        assert_eq!(view[0], vec![VisibleTile::Exploded, VisibleTile::WrongFlag]);
+        assert_eq!(view[2][1], VisibleTile::Mine);
+
+        let mut resigned = Minesweeper::new_rect(3, 2, vec![(0, 0)]);
+        resigned.forfeit().unwrap();
+        assert_eq!(resigned.losing_tile(), None);
+    }
+
+    #[test]
+    fn test_clicking_bomb() {
+        let mine_locations = vec![(0, 0)];
+        let mut game = Minesweeper::new(2, mine_locations);
 
         let result = game.click_tile(0, 0);
         assert!(result.is_ok());
@@ -420,6 +2085,449 @@ mod tests {
         assert!(!game.get_tile(0, 0).unwrap().flagged);
     }
 
+    #[test]
+    fn test_click_tile_detailed() {
+        let mut game = Minesweeper::new(3, vec![(0, 0), (2, 2)]);
+        let outcome = game.click_tile_detailed(0, 2).unwrap();
+        assert_eq!(outcome.revealed, vec![(0, 1), (0, 2), (1, 1), (1, 2)]);
+        assert_eq!(
+            outcome.layers,
+            vec![vec![(0, 2)], vec![(0, 1), (1, 1), (1, 2)]]
+        );
+        assert!(!outcome.hit_bomb);
+        assert_eq!(outcome.state, GameState::InProgress);
+
+        assert_eq!(
+            game.click_tile_detailed(0, 2),
+            Err(MinesweeperError::AlreadyExposed { pos: (0, 2) })
+        );
+        let outcome = game.click_tile_detailed(2, 2).unwrap();
+        assert!(outcome.revealed.is_empty());
+        assert!(outcome.hit_bomb);
+        assert_eq!(outcome.state, GameState::Lost);
+    }
+
+    #[test]
+    fn test_opening_layers_spread_outward() {
+        let mut game = Minesweeper::new_rect(5, 1, vec![(4, 0)]);
+        assert_eq!(
+            game.opening_layers_at(0, 0),
+            vec![vec![(0, 0)], vec![(1, 0)], vec![(2, 0)], vec![(3, 0)]]
+        );
+
+        // A flag stops the first click at (1, 0). The second walk passes
+        // through the exposed tiles without listing them.
+        game.toggle_flag(2, 0).unwrap();
+        game.click_tile(0, 0).unwrap();
+        game.toggle_flag(2, 0).unwrap();
+        assert_eq!(
+            game.opening_layers_at(2, 0),
+            vec![vec![(2, 0)], vec![(3, 0)]]
+        );
+        assert!(game.opening_layers_at(0, 0).is_empty());
+    }
+
+    #[test]
+    fn test_mine_probabilities() {
+        let mut game = Minesweeper::new(2, vec![(0, 0)]);
+        game.click_tile(1, 1).unwrap();
+
+        let third = 1.0 / 3.0;
+        let probabilities = game.mine_probabilities();
+        assert_eq!(probabilities.len(), 2);
+        for (x, y) in [(0, 0), (0, 1), (1, 0)] {
+            assert!((probabilities[x][y] - third).abs() < 1e-9);
+        }
+        assert_eq!(probabilities[1][1], 0.0);
+
+        game.set_probability_overlay(true);
+        assert_eq!(game.mine_probabilities(), probabilities);
+    }
+
+    #[test]
+    fn test_mines_left_with_uncertain_count() {
+        let mut game = Minesweeper::new(3, vec![(0, 0), (2, 2)]);
+        game.toggle_flag(0, 0).unwrap();
+        assert_eq!(game.mines_left(), Some((1, 1)));
+
+        assert!(game
+            .set_mine_count(MineCount::Range { min: 3, max: 4 })
+            .is_err());
+        game.set_mine_count(MineCount::Range { min: 1, max: 4 })
+            .unwrap();
+        assert_eq!(game.mines_left(), Some((0, 3)));
+        game.set_mine_count(MineCount::Hidden).unwrap();
+        assert_eq!(game.mines_left(), None);
+        assert_eq!(game.config().mine_count, MineCount::Hidden);
+    }
+
+    #[test]
+    fn test_first_click_constructor_errors() {
+        assert_eq!(
+            Minesweeper::new_with_first_click(3, 2, (3, 0)).unwrap_err(),
+            MinesweeperError::OutOfBounds {
+                pos: (3, 0),
+                width: 3,
+                height: 3
+            }
+        );
+        assert_eq!(
+            Minesweeper::new_with_first_click(3, 9, (1, 1)).unwrap_err(),
+            MinesweeperError::TooManyMines {
+                bomb_count: 9,
+                capacity: 8
+            }
+        );
+        assert!(Minesweeper::new_with_first_click(0, 0, (0, 0)).is_err());
+        assert!(Minesweeper::new_with_first_click(3, 8, (1, 1)).is_ok());
+
+        let mut config = GameConfig::new(3, 2);
+        config.mine_count = MineCount::Range { min: 2, max: 9 };
+        assert!(Minesweeper::new_with_config(&config, (1, 1)).is_err());
+
+        // Configs `validate` refuses are refused here too, without panicking.
+        let invalid = |config: &str| {
+            let config = GameConfig::from_config_string(config).unwrap();
+            matches!(
+                Minesweeper::new_with_config_and_seed(&config, (0, 0), 1),
+                Err(MinesweeperError::InvalidConfig { .. })
+            )
+        };
+        assert!(invalid("width=9\nheight=9\nmines=4\nmine-count=5-3"));
+        assert!(invalid("width=18446744073709551615\nheight=2\nmines=1"));
+        assert!(invalid("width=2\nheight=2\nmines=1\ntopology=torus"));
+    }
+
+    #[test]
+    fn test_single_zero_rule() {
+        let mut game = Minesweeper::new_rect(4, 1, vec![(0, 0)]);
+        game.set_zero_rule(ZeroRule::Single);
+        assert_eq!(game.opening_at(3, 0), vec![(3, 0)]);
+
+        game.click_tile(3, 0).unwrap();
+        assert!(!game.get_tile(2, 0).unwrap().exposed);
+        game.click_tile(2, 0).unwrap();
+        assert_eq!(game.safe_tiles_remaining(), 1);
+
+        // Every safe tile takes its own click, so each one counts for 3BV.
+        assert_eq!((game.three_bv(), game.openings()), (3, 2));
+        assert_eq!(game.three_bv_remaining(), 1);
+        game.set_zero_rule(ZeroRule::Cascade);
+        assert_eq!((game.three_bv(), game.openings()), (1, 1));
+        assert_eq!(game.three_bv_remaining(), 0);
+        let config = GameConfig {
+            zero_rule: ZeroRule::Single,
+            ..GameConfig::new(9, 9)
+        };
+        let game = Minesweeper::new_with_config_and_seed(&config, (4, 4), 3).unwrap();
+        assert_eq!(game.three_bv(), 81 - game.get_bomb_count());
+
+        // Zeros uncovered by a chord don't cascade either.
+        let mut game = Minesweeper::new_rect(5, 1, vec![(0, 0)]);
+        game.set_zero_rule(ZeroRule::Single);
+        game.click_tile(1, 0).unwrap();
+        game.toggle_flag(0, 0).unwrap();
+        game.chord(1, 0).unwrap();
+        assert!(game.get_tile(2, 0).unwrap().exposed);
+        assert!(!game.get_tile(3, 0).unwrap().exposed);
+    }
+
+    #[test]
+    fn test_error_kinds() {
+        let mut game = Minesweeper::new(3, vec![(0, 0)]);
+        game.toggle_flag(1, 0).unwrap();
+        game.click_tile(2, 0).unwrap();
+
+        let out_of_bounds = game.click_tile(3, 0).unwrap_err();
+        assert_eq!(
+            out_of_bounds,
+            MinesweeperError::OutOfBounds {
+                pos: (3, 0),
+                width: 3,
+                height: 3
+            }
+        );
+        assert_eq!(
+            out_of_bounds.to_string(),
+            "Invalid coordinates: (3, 0) is outside the 3x3 board"
+        );
+        assert_eq!(
+            game.click_tile(1, 0),
+            Err(MinesweeperError::Flagged { pos: (1, 0) })
+        );
+        assert_eq!(
+            game.click_tile(2, 0),
+            Err(MinesweeperError::AlreadyExposed { pos: (2, 0) })
+        );
+        assert_eq!(
+            game.toggle_flag(2, 0),
+            Err(MinesweeperError::AlreadyExposed { pos: (2, 0) })
+        );
+        assert_eq!(
+            game.apply_move(Move::Reveal(2, 0)),
+            Err(MinesweeperError::AlreadyExposed { pos: (2, 0) })
+        );
+
+        game.click_tile(0, 0).unwrap();
+        assert_eq!(
+            game.toggle_flag(1, 1),
+            Err(MinesweeperError::GameFinished {
+                state: GameState::Lost
+            })
+        );
+    }
+
+    #[test]
+    fn test_auto_flag_on_win() {
+        let mut game = Minesweeper::new_rect(3, 2, vec![(0, 1)]);
+        game.set_auto_flag(true);
+        game.click_tile(2, 0).unwrap();
+        assert_eq!(game.count_flagged_tiles(), 0);
+        game.click_tile(0, 0).unwrap();
+        assert_eq!(game.get_game_state(), &GameState::Won);
+        assert!(game.get_tile(0, 1).unwrap().flagged);
+        assert_eq!(game.flags_placed(), 0);
+
+        let mut plain = Minesweeper::new_rect(3, 2, vec![(0, 1)]);
+        plain.click_tile(2, 0).unwrap();
+        plain.click_tile(0, 0).unwrap();
+        assert_eq!(plain.count_flagged_tiles(), 0);
+
+        let mut no_flags = Minesweeper::new_rect(3, 2, vec![(0, 1)]);
+        no_flags.set_auto_flag(true);
+        no_flags.set_flag_rule(FlagRule::Forbidden).unwrap();
+        no_flags.click_tile(2, 0).unwrap();
+        no_flags.click_tile(0, 0).unwrap();
+        assert_eq!(no_flags.count_flagged_tiles(), 0);
+    }
+
+    #[test]
+    fn test_cycle_mark() {
+        let mut game = Minesweeper::new(3, vec![(0, 0)]);
+        assert_eq!(game.cycle_mark(0, 0), Ok(Mark::Flag));
+        assert_eq!(game.cycle_mark(0, 0), Ok(Mark::None));
+
+        game.set_question_marks(true);
+        game.set_history(true);
+        assert_eq!(game.cycle_mark(0, 0), Ok(Mark::Flag));
+        assert_eq!(game.cycle_mark(0, 0), Ok(Mark::Question));
+        assert_eq!(game.get_tile(0, 0).unwrap().mark(), Mark::Question);
+        assert_eq!(game.count_flagged_tiles(), 0);
+        assert_eq!(game.cycle_mark(0, 0), Ok(Mark::None));
+        assert_eq!(game.get_tile(0, 0).unwrap().marker, None);
+        assert_eq!(game.history().len(), 4);
+
+        // Question marks don't block clicks, and exposed tiles can't be marked.
+        game.cycle_mark(1, 1).unwrap();
+        game.cycle_mark(1, 1).unwrap();
+        game.click_tile(1, 1).unwrap();
+        assert_eq!(
+            game.cycle_mark(1, 1),
+            Err(MinesweeperError::AlreadyExposed { pos: (1, 1) })
+        );
+    }
+
+    #[test]
+    fn test_cycle_mark_without_flags() {
+        let mut game = Minesweeper::new(3, vec![(0, 0)]);
+        game.set_flag_rule(FlagRule::Forbidden).unwrap();
+        assert_eq!(game.cycle_mark(0, 0), Err(MinesweeperError::FlagsForbidden));
+        game.set_question_marks(true);
+        assert_eq!(game.cycle_mark(0, 0), Ok(Mark::Question));
+        assert_eq!(game.cycle_mark(0, 0), Ok(Mark::None));
+        assert!(game.is_no_flag());
+    }
+
+    #[test]
+    fn test_flag_rules() {
+        let mut game = Minesweeper::new(3, vec![(0, 0)]);
+        assert!(game.is_no_flag());
+
+        game.set_flag_rule(FlagRule::Forbidden).unwrap();
+        assert!(game.toggle_flag(0, 0).is_err());
+        assert!(game.is_no_flag());
+
+        game.set_flag_rule(FlagRule::Allowed).unwrap();
+        game.toggle_flag(0, 0).unwrap();
+        game.toggle_flag(0, 0).unwrap();
+        // Removing the flag does not make it a no-flag game again.
+        assert!(!game.is_no_flag());
+        assert!(game.set_flag_rule(FlagRule::Forbidden).is_err());
+    }
+
+    #[test]
+    fn test_pause_and_resume() {
+        let mut game = Minesweeper::new_rect(4, 1, vec![(0, 0)]);
+        game.set_history(true);
+        assert!(game.resume().is_err());
+        game.pause().unwrap();
+        assert!(game.is_paused());
+        assert_eq!(game.click_tile(1, 0), Err(MinesweeperError::Paused));
+        assert_eq!(game.toggle_flag(0, 0), Err(MinesweeperError::Paused));
+        assert_eq!(game.chord(1, 0), Err(MinesweeperError::Paused));
+        assert!(!game.get_game_state().is_over());
+
+        game.resume().unwrap();
+        game.click_tile(1, 0).unwrap();
+        game.pause().unwrap();
+        assert!(game.undo().is_err());
+        game.resume().unwrap();
+        game.click_tile(3, 0).unwrap();
+        assert!(game.get_game_state().is_over());
+        assert!(game.pause().is_err());
+    }
+
+    #[test]
+    fn test_lives() {
+        let mut game = Minesweeper::new_rect(5, 1, vec![(0, 0), (2, 0)]);
+        game.set_history(true);
+        game.set_event_queue(true);
+        game.set_lives(1);
+        game.click_tile(0, 0).unwrap();
+        assert_eq!(game.get_game_state(), &GameState::InProgress);
+        assert_eq!(
+            game.drain_events(),
+            vec![GameEvent::LifeLost {
+                x: 0,
+                y: 0,
+                lives_left: 0
+            }]
+        );
+        assert_eq!(game.lives_remaining(), 0);
+        assert_eq!(game.contained_mines(), &[(0, 0)]);
+        assert_eq!(game.visible_tile(0, 0), VisibleTile::Exploded);
+        assert_eq!(game.mines_left(), Some((1, 1)));
+        assert_eq!(game.config().lives, 1);
+
+        // Undoing the hit gives the life back.
+        game.undo().unwrap();
+        assert_eq!(game.lives_remaining(), 1);
+        assert!(game.contained_mines().is_empty());
+        assert!(!game.get_tile(0, 0).unwrap().exposed);
+
+        game.click_tile(0, 0).unwrap();
+        game.click_tile(2, 0).unwrap();
+        assert_eq!(game.get_game_state(), &GameState::Lost);
+        assert_eq!(game.visible_tile(2, 0), VisibleTile::Exploded);
+
+        let mut config = GameConfig::new(5, 3);
+        config.lives = 2;
+        let text = config.to_config_string().unwrap();
+        assert_eq!(GameConfig::from_config_string(&text).unwrap().lives, 2);
+        let game = Minesweeper::new_with_config_and_seed(&config, (2, 2), 1).unwrap();
+        assert_eq!(game.lives_remaining(), 2);
+    }
+
+    #[test]
+    fn test_win_conditions() {
+        // Flagging: a wrong flag holds the win back, and revealing the
+        // safe tiles is not enough.
+        let mut game = Minesweeper::new_rect(4, 1, vec![(0, 0)]);
+        game.set_win_condition(WinCondition::FlagAllMines).unwrap();
+        assert!(game.set_flag_rule(FlagRule::Forbidden).is_err());
+        game.click_tile(3, 0).unwrap();
+        assert_eq!(game.get_game_state(), &GameState::InProgress);
+        game.toggle_flag(0, 0).unwrap();
+        assert_eq!(game.get_game_state(), &GameState::Won);
+
+        let mut game = Minesweeper::new_rect(3, 1, vec![(0, 0)]);
+        game.set_win_condition(WinCondition::FlagAllMines).unwrap();
+        game.toggle_flag(2, 0).unwrap();
+        game.toggle_flag(0, 0).unwrap();
+        assert_eq!(game.get_game_state(), &GameState::InProgress);
+        game.toggle_flag(2, 0).unwrap();
+        assert_eq!(game.get_game_state(), &GameState::Won);
+
+        // Two openings either side of the mine; clearing one scores 1.
+        let mut game = Minesweeper::new_rect(5, 1, vec![(2, 0)]);
+        game.set_win_condition(WinCondition::Score(1)).unwrap();
+        game.click_tile(0, 0).unwrap();
+        assert_eq!(game.get_game_state(), &GameState::Won);
+        assert_eq!(game.safe_tiles_remaining(), 2);
+
+        let mut config = GameConfig::new(5, 3);
+        config.win_condition = WinCondition::Score(4);
+        let text = config.to_config_string().unwrap();
+        assert!(text.contains("win=score:4\n"));
+        let parsed = GameConfig::from_config_string(&text).unwrap();
+        assert_eq!(parsed.win_condition, WinCondition::Score(4));
+        config.win_condition = WinCondition::FlagAllMines;
+        config.flag_rule = FlagRule::Forbidden;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_markers() {
+        let mut game = Minesweeper::new(3, vec![(0, 0), (2, 2)]);
+
+        game.set_marker(0, 0, Some(Marker::Red)).unwrap();
+        game.set_marker(1, 1, Some(Marker::Symbol('?'))).unwrap();
+        assert_eq!(game.get_tile(0, 0).unwrap().marker, Some(Marker::Red));
+
+        // Markers do not block clicks the way flags do.
+        assert!(game.click_tile(1, 1).is_ok());
+        assert!(game.set_marker(1, 1, Some(Marker::Blue)).is_err());
+
+        game.apply_move(Move::SetMarker(0, 0, None)).unwrap();
+        assert_eq!(game.get_tile(0, 0).unwrap().marker, None);
+    }
+
+    #[test]
+    fn test_notes() {
+        let mut game = Minesweeper::new(3, vec![(0, 0), (2, 2)]);
+
+        game.set_note(0, 0, "1 below says one of these two")
+            .unwrap();
+        assert_eq!(game.get_note(0, 0), Some("1 below says one of these two"));
+        assert_eq!(game.notes().count(), 1);
+
+        assert_eq!(
+            game.set_note(0, 1, "x".repeat(MAX_NOTE_LENGTH + 1)),
+            Err(MinesweeperError::NoteTooLong {
+                pos: (0, 1),
+                limit: MAX_NOTE_LENGTH
+            })
+        );
+        assert_eq!(
+            game.set_note(5, 5, "off the board"),
+            Err(MinesweeperError::OutOfBounds {
+                pos: (5, 5),
+                width: 3,
+                height: 3
+            })
+        );
+
+        game.click_tile(1, 1).unwrap();
+        assert_eq!(
+            game.set_note(1, 1, "too late"),
+            Err(MinesweeperError::NoteOnExposed { pos: (1, 1) })
+        );
+
+        game.set_note(0, 0, "").unwrap();
+        assert_eq!(game.get_note(0, 0), None);
+    }
+
+    #[test]
+    fn test_probability_overlay() {
+        let mut game = Minesweeper::new(3, vec![(1, 1)]);
+        assert_eq!(game.mine_probability(0, 1), None);
+
+        game.set_probability_overlay(true);
+        // Nothing revealed yet: one mine among nine tiles.
+        assert!((game.mine_probability(0, 1).unwrap() - 1.0 / 9.0).abs() < 1e-9);
+
+        game.click_tile(0, 0).unwrap();
+        assert_eq!(game.mine_probability(0, 0), Some(0.0));
+        assert!((game.mine_probability(1, 1).unwrap() - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(game.mine_probability(2, 2), Some(0.0));
+
+        let snapshot = game.snapshot();
+        assert_eq!(snapshot.mine_probability(2, 2), Some(0.0));
+
+        game.set_probability_overlay(false);
+        assert_eq!(game.mine_probability(1, 1), None);
+    }
+
     #[test]
     fn test_win_condition() {
         let mine_locations = vec![(0, 0)];
@@ -437,16 +2545,16 @@ mod tests {
     fn test_new_with_first_click() {
         // Test that first click never hits a bomb
         for _ in 0..10 {
-            let game = Minesweeper::new_with_first_click(10, 15, (5, 5));
-            
+            let game = Minesweeper::new_with_first_click(10, 15, (5, 5)).unwrap();
+
             // First click should be exposed and not be a bomb
             let first_tile = game.get_tile(5, 5).unwrap();
             assert!(first_tile.exposed);
             assert!(!first_tile.is_bomb());
-            
+
             // Game should still be in progress (not lost)
             assert_eq!(*game.get_game_state(), GameState::InProgress);
-            
+
             // Should have correct bomb count
             assert_eq!(game.get_bomb_count(), 15);
         }
@@ -457,15 +2565,235 @@ mod tests {
         // Test that first click often creates a nice opening (zero tile)
         let mut zero_count = 0;
         for _ in 0..20 {
-            let game = Minesweeper::new_with_first_click(10, 10, (5, 5));
+            let game = Minesweeper::new_with_first_click(10, 10, (5, 5)).unwrap();
             let first_tile = game.get_tile(5, 5).unwrap();
-            
+
             if let Some(0) = first_tile.get_number() {
                 zero_count += 1;
             }
         }
-        
+
         // Should get zeros fairly often (this is probabilistic, but should usually work)
         assert!(zero_count > 5, "Should get some zero tiles as first clicks");
     }
+
+    #[test]
+    fn test_three_bv() {
+        // A corner mine leaves one opening bordered by every number.
+        let game = Minesweeper::new(4, vec![(0, 0)]);
+        assert_eq!(game.openings(), 1);
+        assert_eq!(game.three_bv(), 1);
+
+        // (4, 0) only touches numbers, so it needs a click of its own.
+        let game = Minesweeper::new(5, vec![(0, 4), (3, 0)]);
+        assert_eq!(game.openings(), 1);
+        assert_eq!(game.three_bv(), 2);
+
+        // A lone mine in the middle of a 3x3 board leaves only isolated numbers.
+        let game = Minesweeper::new(3, vec![(1, 1)]);
+        assert_eq!(game.openings(), 0);
+        assert_eq!(game.three_bv(), 8);
+    }
+
+    #[test]
+    fn test_three_bv_remaining_tracks_reveals() {
+        let mut game = Minesweeper::new(3, vec![(1, 1)]);
+        assert_eq!(game.three_bv_remaining(), 8);
+        assert_eq!(game.progress(), 0.0);
+
+        game.click_tile(0, 0).unwrap();
+        game.click_tile(0, 1).unwrap();
+        assert_eq!(game.three_bv_remaining(), 6);
+        assert_eq!(game.progress(), 0.25);
+
+        let mut game = Minesweeper::new(5, vec![(0, 4), (3, 0)]);
+        game.click_tile(4, 4).unwrap();
+        assert_eq!(game.openings_remaining(), 0);
+        assert_eq!(game.three_bv_remaining(), 1);
+
+        game.click_tile(4, 0).unwrap();
+        assert_eq!(game.three_bv_remaining(), 0);
+        assert_eq!(game.progress(), 1.0);
+    }
+
+    #[test]
+    fn test_new_with_symmetric_options() {
+        let options = GeneratorOptions {
+            symmetry: Symmetry::MirrorX,
+            ..Default::default()
+        };
+        let game = Minesweeper::new_with_options(9, 10, (4, 4), &options).unwrap();
+
+        assert_eq!(game.get_bomb_count(), 10);
+        assert!(game.get_tile(4, 4).unwrap().exposed);
+        for x in 0..9 {
+            for y in 0..9 {
+                let tile = game.get_tile(x, y).unwrap();
+                let mirror = game.get_tile(8 - x, y).unwrap();
+                assert_eq!(tile.is_bomb(), mirror.is_bomb());
+            }
+        }
+    }
+
+    #[test]
+    fn test_opening_at() {
+        let mut game = Minesweeper::new(4, vec![(3, 3)]);
+
+        assert_eq!(game.opening_at(2, 2), vec![(2, 2)]);
+        assert!(game.opening_at(3, 3).is_empty());
+        assert!(game.opening_at(4, 0).is_empty());
+        assert_eq!(game.opening_at(0, 0).len(), 15);
+        assert_eq!(game.count_exposed_tiles(), 0);
+
+        // Flags block the preview just as they block the fill, and
+        // exposed tiles are left out.
+        game.toggle_flag(2, 2).unwrap();
+        game.click_tile(3, 2).unwrap();
+        let region = game.opening_at(0, 0);
+        assert_eq!(region.len(), 13);
+        assert!(!region.contains(&(2, 2)) && !region.contains(&(3, 2)));
+
+        game.click_tile(0, 0).unwrap();
+        assert_eq!(game.count_exposed_tiles(), 14);
+        assert!(game.opening_at(0, 0).is_empty());
+    }
+
+    #[test]
+    fn test_seeded_boards_are_reproducible() {
+        let a = Minesweeper::new_with_seed(16, 40, (8, 8), 7).unwrap();
+        let b = Minesweeper::new_with_seed(16, 40, (8, 8), 7).unwrap();
+        assert_eq!(a.board_id(), b.board_id());
+        assert_eq!(a.count_exposed_tiles(), b.count_exposed_tiles());
+
+        let other = Minesweeper::new_with_seed(16, 40, (8, 8), 8).unwrap();
+        assert_ne!(a.board_id(), other.board_id());
+
+        let mut config = GameConfig::new_rect(30, 16, 99);
+        config.generator.symmetry = Symmetry::MirrorX;
+        let c = Minesweeper::new_with_config_and_seed(&config, (0, 0), 7).unwrap();
+        let d = Minesweeper::new_with_config_and_seed(&config, (0, 0), 7).unwrap();
+        assert_eq!(c.mine_positions(), d.mine_positions());
+    }
+
+    #[test]
+    fn test_chord() {
+        let mut game = Minesweeper::new(4, vec![(0, 0), (3, 3)]);
+        game.click_tile(1, 1).unwrap();
+
+        assert_eq!(
+            game.chord(1, 1),
+            Err(MinesweeperError::FlagCountMismatch {
+                pos: (1, 1),
+                number: 1,
+                flags: 0
+            })
+        );
+        assert_eq!(
+            game.chord(0, 0),
+            Err(MinesweeperError::NotANumber { pos: (0, 0) })
+        );
+
+        game.toggle_flag(0, 0).unwrap();
+        game.apply_move(Move::Chord(1, 1)).unwrap();
+        // The zero at (2, 0) opened up the rest of the board.
+        assert!(game.get_tile(1, 0).unwrap().exposed);
+        assert!(game.get_tile(3, 0).unwrap().exposed);
+        assert!(!game.get_tile(0, 0).unwrap().exposed);
+        assert_eq!(*game.get_game_state(), GameState::Won);
+    }
+
+    #[test]
+    fn test_chord_with_wrong_flag_loses() {
+        let mut game = Minesweeper::new(3, vec![(0, 0)]);
+        game.click_tile(1, 1).unwrap();
+        game.toggle_flag(2, 2).unwrap();
+
+        game.chord(1, 1).unwrap();
+        assert_eq!(*game.get_game_state(), GameState::Lost);
+        assert!(game.get_tile(0, 0).unwrap().exposed);
+    }
+
+    #[test]
+    fn test_auto_finish() {
+        // Mines fill the bottom row. The centre's 3 alone leaves them
+        // anywhere among its neighbours.
+        let mut game = Minesweeper::new(3, vec![(0, 2), (1, 2), (2, 2)]);
+        game.click_tile(1, 1).unwrap();
+        assert_eq!(game.auto_finish(), Err(MinesweeperError::NeedsGuess));
+
+        // The zero at (1, 0) opens everything but the flagged (0, 0), which
+        // is then provably safe.
+        game.toggle_flag(0, 0).unwrap();
+        game.click_tile(1, 0).unwrap();
+        assert_eq!(game.safe_tiles_remaining(), 1);
+        assert_eq!(game.apply_move(Move::AutoFinish), Ok(()));
+        assert_eq!(*game.get_game_state(), GameState::Won);
+        // The wrong flag on (0, 0) was cleared and the tile revealed.
+        assert!(game.get_tile(0, 0).unwrap().exposed);
+        assert!(!game.get_tile(0, 0).unwrap().flagged);
+        assert!(game.auto_finish().is_err());
+    }
+
+    #[test]
+    fn test_board_id() {
+        let mut game = Minesweeper::new(4, vec![(0, 1), (3, 2)]);
+        let id = game.board_id();
+
+        assert_eq!(Minesweeper::new(4, vec![(3, 2), (0, 1)]).board_id(), id);
+        assert_ne!(Minesweeper::new(4, vec![(0, 1), (3, 3)]).board_id(), id);
+        assert_ne!(Minesweeper::new(5, vec![(0, 1), (3, 2)]).board_id(), id);
+
+        game.click_tile(0, 0).unwrap();
+        assert_eq!(game.board_id(), id);
+
+        assert_eq!(id.to_string().len(), 16);
+        assert_eq!(id.to_string().parse::<BoardId>(), Ok(id));
+        assert!("xyz".parse::<BoardId>().is_err());
+    }
+
+    #[test]
+    fn test_move_text_round_trip() {
+        for mv in [
+            Move::Reveal(3, 4),
+            Move::ToggleFlag(0, 12),
+            Move::SetMarker(1, 2, None),
+            Move::SetMarker(1, 2, Some(Marker::Green)),
+            Move::SetMarker(1, 2, Some(Marker::Symbol('?'))),
+            Move::Chord(2, 5),
+            Move::Assist(0, 4),
+            Move::AutoFinish,
+        ] {
+            assert_eq!(mv.to_string().parse::<Move>(), Ok(mv));
+        }
+
+        assert!("reveal 3".parse::<Move>().is_err());
+        assert!("reveal a b".parse::<Move>().is_err());
+        assert!("mark 1 2 purple".parse::<Move>().is_err());
+    }
+
+    #[test]
+    fn test_apply_moves() {
+        let mut game = Minesweeper::new(2, vec![(0, 0)]);
+
+        let outcomes = game.apply_moves(vec![
+            Move::ToggleFlag(0, 0),
+            Move::Reveal(0, 0),
+            Move::Reveal(0, 1),
+            Move::Reveal(1, 0),
+            Move::Reveal(1, 1),
+            Move::Reveal(1, 1),
+        ]);
+
+        assert_eq!(outcomes.len(), 6);
+        assert!(outcomes[0].is_ok());
+        assert!(outcomes[1].is_err());
+        assert!(outcomes[2..5].iter().all(|outcome| outcome.is_ok()));
+        assert_eq!(
+            outcomes[5],
+            Err(MinesweeperError::GameFinished {
+                state: GameState::Won
+            })
+        );
+        assert_eq!(*game.get_game_state(), GameState::Won);
+    }
 }
@@ -0,0 +1,1098 @@
+//! A playable terminal Minesweeper.
+//!
+//! Arrow keys (or hjkl) move the cursor, Enter or Space reveals (or chords
+//! on an exposed number), F flags, N starts a new game and Q quits.
+//!
+//! With `--players N`, players take turns at the same keyboard in a
+//! [`GameSession`], each with their own clock and score. On a shared board
+//! every move passes play on and the best score wins; with `--race` each
+//! player gets an identical board of their own, the board is hidden
+//! between turns, which end after a reveal or chord, and the fastest clear
+//! wins.
+//!
+//! After a loss in a one-player game the TUI switches to review mode: the
+//! whole board is uncovered, the fatal move is highlighted, and the left and
+//! right arrows step back and forth through the moves, each annotated with
+//! what the solver could have known when it was played. R leaves (and
+//! re-enters) the review.
+//!
+//! `--theme NAME` picks one of the built-in [`Theme`]s, e.g. `emoji`.
+//!
+//! Boards bigger than the terminal scroll to follow the cursor, and `-`
+//! and `+` zoom out and back in: zoomed out, each cell sums up a square
+//! block of tiles and the cursor moves a block at a time.
+
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::{cursor, execute, queue, terminal};
+
+use minesweeper::internal::{column_header, row_label_width};
+use minesweeper::{
+    suggest_opening, AnsiRenderer, BlockSummary, BoardRenderer, CoordinateStyle, GameConfig,
+    GameSession, GameState, MineCount, Minesweeper, Move, Theme, Tile, VariantRegistry, View,
+    Viewport,
+};
+
+/// Terminal lines taken up by everything but the board and its axis
+/// labels: the status line above it and the scores, notes and help below.
+const CHROME_LINES: usize = 12;
+
+/// Boards simulated per candidate when suggesting where to start.
+const OPENING_SAMPLES: usize = 50;
+
+const USAGE: &str = "usage: minesweeper [--width W] [--height H] [--mines M] [--variant ID] \
+                     [--seed N] [--players N [--race]] [--theme NAME] [--coords chess|numeric]";
+
+/// What the command line asked for.
+struct Options {
+    config: GameConfig,
+    seed: Option<u64>,
+    players: usize,
+    /// Each player gets their own copy of the same board instead of
+    /// sharing one.
+    race: bool,
+    theme: Theme,
+    /// How the axes and moves are labelled.
+    coords: CoordinateStyle,
+}
+
+/// One player's side of the game. Their score is kept by the session.
+#[derive(Default)]
+struct Player {
+    /// Time spent on this player's own turns.
+    elapsed: Duration,
+    /// Whether one of this player's moves hit a mine.
+    hit_mine: bool,
+    cursor: (usize, usize),
+}
+
+/// The session's id for the player in seat `seat`.
+fn player_id(seat: usize) -> u64 {
+    seat as u64 + 1
+}
+
+/// Stepping through a lost game, on a copy of the board replayed from the
+/// start so it can be moved back and forth with undo and redo. It sits at
+/// the position being reviewed.
+struct Review {
+    game: Minesweeper,
+    /// Every move of the game, the fatal one last.
+    moves: Vec<Move>,
+    /// How many moves have been played in the position on screen.
+    step: usize,
+    /// What the solver made of each move, worked out as it is first
+    /// reached.
+    notes: Vec<Option<String>>,
+}
+
+impl Review {
+    /// The move highlighted on screen: the next one to be played, or the
+    /// fatal one once the whole game has been stepped through.
+    fn current(&self) -> usize {
+        self.step.min(self.moves.len() - 1)
+    }
+}
+
+struct App {
+    config: GameConfig,
+    seed: Option<u64>,
+    race: bool,
+    /// The boards, scores and turn order. A shared board is `None` until
+    /// the first click, which it is generated around; a race starts at the
+    /// centre straight away.
+    session: Option<GameSession>,
+    /// Where the advisor suggests making the first click, marked on the
+    /// board until it is made.
+    opening: Option<(usize, usize)>,
+    /// Every move the session accepted, kept for the review after a loss.
+    moves: Vec<Move>,
+    players: Vec<Player>,
+    /// The seat at the keyboard.
+    turn: usize,
+    /// When the current turn's clock started; `None` while it is stopped.
+    turn_started: Option<Instant>,
+    /// The board is hidden until the next player is ready, so they can't
+    /// see how their rival is doing.
+    handoff: bool,
+    renderer: AnsiRenderer,
+    coords: CoordinateStyle,
+    /// The part of the board on screen.
+    viewport: Viewport,
+    review: Option<Review>,
+    message: String,
+}
+
+impl App {
+    fn new(options: &Options) -> Self {
+        let seed = options.seed;
+        let session = options.race.then(|| {
+            // Everyone starts from the same seeded board, opened at the
+            // centre, so nobody's first click decides the layout.
+            let seed = seed.unwrap_or_else(rand::random);
+            let centre = (options.config.width / 2, options.config.height / 2);
+            let players: Vec<u64> = (0..options.players).map(player_id).collect();
+            GameSession::race(&options.config, centre, seed, &players)
+                .expect("parse_args validated the config")
+        });
+
+        // The advice is over every board the config can make, so any seed
+        // will do when none was given.
+        let opening = session.is_none().then(|| {
+            suggest_opening(&options.config, OPENING_SAMPLES, seed.unwrap_or(0))
+                .best
+                .click
+        });
+
+        App {
+            config: options.config.clone(),
+            seed,
+            race: options.race,
+            session,
+            opening,
+            moves: Vec::new(),
+            players: (0..options.players).map(|_| Player::default()).collect(),
+            turn: 0,
+            turn_started: None,
+            handoff: options.race,
+            renderer: AnsiRenderer::new(options.theme.clone()),
+            coords: options.coords,
+            viewport: Viewport::new(options.config.width, options.config.height),
+            review: None,
+            message: String::new(),
+        }
+    }
+
+    fn restart(&mut self) {
+        let viewport = self.viewport;
+        *self = App::new(&Options {
+            config: self.config.clone(),
+            seed: self.seed,
+            players: self.players.len(),
+            race: self.race,
+            theme: self.renderer.theme().clone(),
+            coords: self.coords,
+        });
+        self.viewport = Viewport {
+            x: 0,
+            y: 0,
+            ..viewport
+        };
+    }
+
+    /// Fits the viewport to a terminal of `columns` x `rows` characters.
+    fn resize(&mut self, columns: u16, rows: u16) {
+        // Every cell is drawn after a space, right of the row labels and
+        // below the column labels, which are as deep as the longest one.
+        let (width, height) = (self.config.width, self.config.height);
+        let margin = row_label_width(self.coords, height);
+        let header = self.coords.column_label(width.saturating_sub(1)).len();
+        self.viewport.columns =
+            ((columns as usize).saturating_sub(margin) / (self.renderer.cell_width() + 1)).max(1);
+        self.viewport.rows = (rows as usize).saturating_sub(CHROME_LINES + header).max(1);
+        self.zoom(self.viewport.zoom);
+    }
+
+    /// Zooms to show `zoom` x `zoom` tiles per cell, within what makes
+    /// sense for the screen, and keeps the cursor in view.
+    fn zoom(&mut self, zoom: usize) {
+        let (width, height) = (self.config.width, self.config.height);
+        self.viewport.set_zoom(zoom, self.focus(), width, height);
+    }
+
+    /// The tile the view should keep on screen.
+    fn focus(&self) -> (usize, usize) {
+        match &self.review {
+            Some(review) => move_tile(review.moves[review.current()]),
+            None => None,
+        }
+        .unwrap_or(self.players[self.turn].cursor)
+    }
+
+    /// The board on screen: the one under review, or else the one the
+    /// current player is playing on.
+    fn board(&self) -> Option<&Minesweeper> {
+        if let Some(review) = &self.review {
+            return Some(&review.game);
+        }
+        self.session.as_ref()?.game(player_id(self.turn))
+    }
+
+    fn finished(&self, seat: usize) -> bool {
+        self.session
+            .as_ref()
+            .and_then(|session| session.game(player_id(seat)))
+            .is_some_and(|game| game.get_game_state().is_over())
+    }
+
+    fn over(&self) -> bool {
+        self.session.as_ref().is_some_and(GameSession::is_finished)
+    }
+
+    fn elapsed(&self, player: usize) -> Duration {
+        let running = match self.turn_started {
+            Some(started) if player == self.turn => started.elapsed(),
+            _ => Duration::ZERO,
+        };
+        self.players[player].elapsed + running
+    }
+
+    fn stop_clock(&mut self) {
+        if let Some(started) = self.turn_started.take() {
+            self.players[self.turn].elapsed += started.elapsed();
+        }
+    }
+
+    fn score(&self, seat: usize) -> i64 {
+        self.session
+            .as_ref()
+            .and_then(|session| session.score(player_id(seat)))
+            .unwrap_or(0)
+    }
+
+    /// The remaining-mines counter: a number, a range under an uncertain
+    /// mine count, or `?` when the count is hidden.
+    fn mines_left(&self) -> String {
+        let left = match self.board() {
+            Some(game) => game.mines_left(),
+            None => match self.config.mine_count {
+                MineCount::Exact => {
+                    Some((self.config.bomb_count as i64, self.config.bomb_count as i64))
+                }
+                MineCount::Range { min, max } => Some((min as i64, max as i64)),
+                MineCount::Hidden => None,
+            },
+        };
+        match left {
+            Some((min, max)) if min == max => min.to_string(),
+            Some((min, max)) => format!("{}-{}", min, max),
+            None => "?".to_string(),
+        }
+    }
+
+    /// Handles a key press, returning false when the player quits.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if let KeyCode::Char('q') | KeyCode::Esc = key.code {
+            return false;
+        }
+        if self.handoff {
+            if let KeyCode::Enter | KeyCode::Char(' ') = key.code {
+                self.handoff = false;
+                self.turn_started = Some(Instant::now());
+            }
+            return true;
+        }
+
+        match key.code {
+            KeyCode::Char('-') => self.zoom(self.viewport.zoom * 2),
+            KeyCode::Char('+' | '=') => self.zoom(self.viewport.zoom / 2),
+            _ => {}
+        }
+
+        if self.review.is_some() {
+            match key.code {
+                KeyCode::Left | KeyCode::Char('h') => self.step_review(false),
+                KeyCode::Right | KeyCode::Char('l') => self.step_review(true),
+                KeyCode::Char('r') => self.review = None,
+                KeyCode::Char('n') => self.restart(),
+                _ => {}
+            }
+            self.zoom(self.viewport.zoom);
+            return true;
+        }
+
+        let (x, y) = self.players[self.turn].cursor;
+        let cursor = &mut self.players[self.turn].cursor;
+        // Zoomed out, the cursor moves a cell at a time.
+        let step = self.viewport.zoom;
+        match key.code {
+            KeyCode::Left | KeyCode::Char('h') => cursor.0 = x.saturating_sub(step),
+            KeyCode::Right | KeyCode::Char('l') => cursor.0 = (x + step).min(self.config.width - 1),
+            KeyCode::Up | KeyCode::Char('k') => cursor.1 = y.saturating_sub(step),
+            KeyCode::Down | KeyCode::Char('j') => cursor.1 = (y + step).min(self.config.height - 1),
+            KeyCode::Enter | KeyCode::Char(' ') => self.reveal(),
+            KeyCode::Char('f') => self.play(Move::ToggleFlag(x, y)),
+            KeyCode::Char('n') => self.restart(),
+            KeyCode::Char('r') if self.players.len() == 1 && self.players[0].hit_mine => {
+                self.start_review()
+            }
+            _ => {}
+        }
+        self.zoom(self.viewport.zoom);
+        true
+    }
+
+    /// Enters review mode on a replay of the lost game, at the position the
+    /// fatal move was played from.
+    fn start_review(&mut self) {
+        let Some(session) = &self.session else {
+            return;
+        };
+        if self.moves.is_empty() {
+            return;
+        }
+        let Ok(mut game) = Minesweeper::new_with_config_and_seed(
+            &self.config,
+            session.first_click(),
+            session.seed(),
+        ) else {
+            return;
+        };
+        game.set_history(true);
+        game.apply_moves(self.moves.iter().copied());
+        // The overlay keeps probabilities current as the board steps back
+        // and forth, for the annotations.
+        game.set_probability_overlay(true);
+        self.review = Some(Review {
+            game,
+            step: self.moves.len(),
+            notes: vec![None; self.moves.len()],
+            moves: self.moves.clone(),
+        });
+        self.step_review(false);
+    }
+
+    /// Moves the review one move back or forward.
+    fn step_review(&mut self, forward: bool) {
+        let Some(review) = self.review.as_mut() else {
+            return;
+        };
+        let game = &mut review.game;
+        if forward && review.step < review.moves.len() {
+            if game.redo().is_ok() {
+                review.step += 1;
+            }
+        } else if !forward && review.step > 0 && game.undo().is_ok() {
+            review.step -= 1;
+        }
+        if review.step < review.moves.len() && review.notes[review.step].is_none() {
+            review.notes[review.step] = Some(annotate(game, review.moves[review.step]));
+        }
+    }
+
+    fn reveal(&mut self) {
+        let (x, y) = self.players[self.turn].cursor;
+        let exposed = self
+            .board()
+            .and_then(|game| game.get_tile(x, y))
+            .is_some_and(|tile| tile.exposed);
+        if exposed {
+            self.play(Move::Chord(x, y));
+        } else {
+            self.play(Move::Reveal(x, y));
+        }
+    }
+
+    fn play(&mut self, mv: Move) {
+        if self.over() {
+            return;
+        }
+        let player = player_id(self.turn);
+        let elapsed = self.elapsed(self.turn);
+
+        let Some(session) = &mut self.session else {
+            // Flags before the first click have nothing to go on.
+            let Move::Reveal(x, y) = mv else { return };
+            let seed = self.seed.unwrap_or_else(rand::random);
+            let players: Vec<u64> = (0..self.players.len()).map(player_id).collect();
+            match GameSession::turns(&self.config, (x, y), seed, &players) {
+                Ok(session) => self.session = Some(session),
+                Err(error) => {
+                    self.message = error;
+                    return;
+                }
+            }
+            // Opening the board is not a move; the first player makes the
+            // first one.
+            self.turn_started = Some(Instant::now());
+            self.message.clear();
+            return;
+        };
+
+        if let Err(error) = session.play(player, mv, elapsed) {
+            self.message = error;
+            return;
+        }
+        self.message.clear();
+        self.moves.push(mv);
+        let lost = session
+            .game(player)
+            .is_some_and(|game| *game.get_game_state() == GameState::Lost);
+        self.players[self.turn].hit_mine |= lost;
+
+        if self.over() {
+            self.stop_clock();
+            if self.players.len() == 1 && self.players[0].hit_mine {
+                self.start_review();
+            }
+        } else if self.players.len() > 1 {
+            // Taking turns, every move passes play on. Racing, players
+            // hand over the keyboard after each reveal or chord.
+            let hands_over = matches!(mv, Move::Reveal(..) | Move::Chord(..));
+            if !self.race || hands_over || self.finished(self.turn) {
+                self.next_turn();
+            }
+        }
+    }
+
+    /// Passes play to the next player: the session's choice when taking
+    /// turns, and the next one with a board left to clear in a race.
+    fn next_turn(&mut self) {
+        self.stop_clock();
+        let players = self.players.len();
+        let current = self.session.as_ref().and_then(GameSession::current_player);
+        match current {
+            Some(player) => self.turn = player as usize - 1,
+            None => {
+                for step in 1..=players {
+                    let next = (self.turn + step) % players;
+                    if !self.finished(next) {
+                        self.turn = next;
+                        break;
+                    }
+                }
+            }
+        }
+        if self.race {
+            self.handoff = true;
+        } else {
+            self.turn_started = Some(Instant::now());
+        }
+    }
+
+    /// One line per player once the game is over, the session's winner
+    /// first.
+    fn standings(&self) -> Vec<String> {
+        let winner = self.session.as_ref().and_then(GameSession::winner);
+        let mut order: Vec<usize> = (0..self.players.len()).collect();
+        // After the winner, hitting a mine puts a player last; then the
+        // best score and the fastest time.
+        order.sort_by_key(|&seat| {
+            let stats = &self.players[seat];
+            (
+                winner != Some(player_id(seat)),
+                stats.hit_mine,
+                std::cmp::Reverse(self.score(seat)),
+                stats.elapsed,
+            )
+        });
+        order
+            .into_iter()
+            .map(|seat| {
+                let stats = &self.players[seat];
+                format!(
+                    "Player {}: {} points, {}s{}",
+                    seat + 1,
+                    self.score(seat),
+                    stats.elapsed.as_secs(),
+                    if stats.hit_mine { " (boom)" } else { "" }
+                )
+            })
+            .collect()
+    }
+
+    /// The tile at (x, y) as it is drawn at full detail.
+    fn render_tile(&self, x: usize, y: usize) -> String {
+        let tile = match self.board().and_then(|game| game.get_tile(x, y)) {
+            // The review shows where every mine was.
+            Some(tile) if self.review.is_some() && tile.is_bomb() && !tile.flagged => {
+                View::Full.show(tile)
+            }
+            Some(tile) => View::Player.show(tile),
+            None => Cow::Owned(Tile::new()),
+        };
+        self.renderer.render_tile(&tile)
+    }
+
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        queue!(
+            out,
+            cursor::MoveTo(0, 0),
+            terminal::Clear(terminal::ClearType::All)
+        )?;
+
+        if self.handoff {
+            queue!(
+                out,
+                Print(format!(
+                    "Player {}'s turn.\r\n\r\nPress Enter when ready, q to quit.\r\n",
+                    self.turn + 1
+                ))
+            )?;
+            return out.flush();
+        }
+
+        let state = self.board().map(|game| game.get_game_state());
+        let status = match state {
+            _ if self.review.is_some() => "Review".to_string(),
+            _ if self.players.len() > 1 && !self.over() => {
+                format!("Player {} to move", self.turn + 1)
+            }
+            Some(GameState::Won) if self.players.len() == 1 => "You win!".to_string(),
+            Some(GameState::Lost) if self.players.len() == 1 => {
+                "Boom. Press R to review, N for a new game.".to_string()
+            }
+            Some(GameState::Won | GameState::Lost) => {
+                "Game over. Press N for a new game.".to_string()
+            }
+            _ => match self.opening.filter(|_| self.session.is_none()) {
+                Some((x, y)) => format!("Suggested start: {}", self.coords.label(x, y)),
+                None => String::new(),
+            },
+        };
+        queue!(
+            out,
+            Print(format!(
+                "Mines: {:<4} Time: {:<5} {}\r\n\r\n",
+                self.mines_left(),
+                self.elapsed(self.turn).as_secs(),
+                status
+            ))
+        )?;
+
+        let cursor = match &self.review {
+            Some(review) => move_tile(review.moves[review.current()]),
+            None => Some(self.players[self.turn].cursor),
+        };
+        let selected = cursor.and_then(|tile| self.viewport.cell(tile));
+        let suggested = self
+            .opening
+            .filter(|_| self.session.is_none())
+            .and_then(|tile| self.viewport.cell(tile));
+        let (width, height) = (self.config.width, self.config.height);
+        let zoom = self.viewport.zoom;
+        // A zoomed-out cell is labelled with its first tile's column and row.
+        let margin = row_label_width(self.coords, height);
+        let columns = (0..self.viewport.columns)
+            .map(|column| self.viewport.x + column * zoom)
+            .take_while(|&x| x < width);
+        let header = column_header(self.coords, columns, self.renderer.cell_width(), margin);
+        queue!(out, Print(header.replace('\n', "\r\n")))?;
+        for row in 0..self.viewport.rows {
+            let y = self.viewport.y + row * zoom;
+            if y >= height {
+                break;
+            }
+            queue!(out, Print(format!("{:>margin$}", self.coords.row_label(y))))?;
+            if zoom == 1 {
+                let indent = self.renderer.row_indent(self.config.get_adjacency(), y);
+                queue!(out, Print(indent))?;
+            }
+            for column in 0..self.viewport.columns {
+                let x = self.viewport.x + column * zoom;
+                if x >= width {
+                    break;
+                }
+                if selected == Some((column, row)) {
+                    queue!(out, SetAttribute(Attribute::Reverse))?;
+                }
+                if suggested == Some((column, row)) {
+                    queue!(out, SetAttribute(Attribute::Underlined))?;
+                }
+                let cell = if zoom == 1 {
+                    self.render_tile(x, y)
+                } else {
+                    let block = match self.board() {
+                        Some(game) => BlockSummary::of(game, View::Player, (x, y), zoom),
+                        None => {
+                            let tiles = (width - x).min(zoom) * (height - y).min(zoom);
+                            BlockSummary {
+                                tiles,
+                                covered: tiles,
+                                ..Default::default()
+                            }
+                        }
+                    };
+                    self.renderer.render_block(&block)
+                };
+                queue!(
+                    out,
+                    Print(format!(" {}", cell)),
+                    SetAttribute(Attribute::Reset)
+                )?;
+            }
+            queue!(out, Print("\r\n"))?;
+        }
+
+        if self.players.len() > 1 {
+            queue!(out, Print("\r\n"))?;
+            if self.over() {
+                for line in self.standings() {
+                    queue!(out, Print(format!("{}\r\n", line)))?;
+                }
+            } else {
+                for seat in 0..self.players.len() {
+                    queue!(
+                        out,
+                        Print(format!(
+                            "Player {}: {} points, {}s\r\n",
+                            seat + 1,
+                            self.score(seat),
+                            self.elapsed(seat).as_secs()
+                        ))
+                    )?;
+                }
+            }
+        }
+
+        if let Some(review) = &self.review {
+            let current = review.current();
+            let note = review.notes[current].as_deref().unwrap_or("");
+            queue!(
+                out,
+                Print(format!(
+                    "\r\nMove {}/{}: {}{}\r\n\r\nleft/right step  r leave review  n new  q quit\r\n",
+                    current + 1,
+                    review.moves.len(),
+                    review.moves[current].label_with(self.coords),
+                    note
+                ))
+            )?;
+            return out.flush();
+        }
+
+        queue!(
+            out,
+            Print(format!(
+                "\r\narrows/hjkl move  enter reveal  f flag  -/+ zoom  n new  q quit\r\n{}",
+                self.message
+            ))
+        )?;
+        out.flush()
+    }
+}
+
+/// The tile a move was played on, if it was played on one.
+fn move_tile(mv: Move) -> Option<(usize, usize)> {
+    match mv {
+        Move::Reveal(x, y)
+        | Move::ToggleFlag(x, y)
+        | Move::SetMarker(x, y, _)
+        | Move::Chord(x, y)
+        | Move::Assist(x, y) => Some((x, y)),
+        _ => None,
+    }
+}
+
+/// What the solver knew about the tile `mv` was played on, in the position
+/// it was played from, e.g. " - a guess, 33% mine (2 safe tiles known)".
+fn annotate(game: &Minesweeper, mv: Move) -> String {
+    let (Move::Reveal(x, y) | Move::Assist(x, y) | Move::ToggleFlag(x, y)) = mv else {
+        return String::new();
+    };
+    let analysis = game.analyze();
+    if analysis.safe.contains(&(x, y)) {
+        return " - provably safe".to_string();
+    }
+    if analysis.mines.contains(&(x, y)) {
+        return " - provably a mine".to_string();
+    }
+    let probability = game.mine_probabilities()[x][y];
+    let mut note = format!(" - a guess, {:.0}% mine", probability * 100.0);
+    if matches!(mv, Move::Reveal(..)) && !analysis.safe.is_empty() {
+        note.push_str(&format!(" ({} safe tiles known)", analysis.safe.len()));
+    }
+    note
+}
+
+/// Restores the terminal however the game ends.
+struct RawTerminal;
+
+impl RawTerminal {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(RawTerminal)
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let (mut width, mut height, mut mines) = (9, 9, 10);
+    let mut variant = "classic".to_string();
+    let mut seed = None;
+    let mut players = 1;
+    let mut race = false;
+    let mut theme = Theme::default();
+    let mut coords = CoordinateStyle::Chess;
+
+    while let Some(flag) = args.next() {
+        if flag == "--race" {
+            race = true;
+            continue;
+        }
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", flag));
+        let number = |text: String| {
+            text.parse::<usize>()
+                .map_err(|_| format!("Not a number: {}", text))
+        };
+        match flag.as_str() {
+            "--width" => width = number(value()?)?,
+            "--height" => height = number(value()?)?,
+            "--mines" => mines = number(value()?)?,
+            "--variant" => variant = value()?,
+            "--players" => players = number(value()?)?,
+            "--theme" => theme = Theme::builtin(&value()?)?,
+            "--coords" => {
+                coords = match value()?.as_str() {
+                    "chess" => CoordinateStyle::Chess,
+                    "numeric" => CoordinateStyle::Numeric,
+                    other => return Err(format!("Unknown coordinate style: {}", other)),
+                }
+            }
+            "--seed" => {
+                let text = value()?;
+                seed = Some(
+                    text.parse()
+                        .map_err(|_| format!("Not a number: {}", text))?,
+                );
+            }
+            _ => return Err(format!("Unknown option: {}\n{}", flag, USAGE)),
+        }
+    }
+
+    if players == 0 {
+        return Err("There must be at least one player".to_string());
+    }
+    if race && players < 2 {
+        return Err("A race needs at least two players".to_string());
+    }
+    let config = VariantRegistry::builtin()
+        .get(&variant)?
+        .config(width, height, mines);
+    config.validate()?;
+    Ok(Options {
+        config,
+        seed,
+        players,
+        race,
+        theme,
+        coords,
+    })
+}
+
+fn run(app: &mut App) -> io::Result<()> {
+    let _terminal = RawTerminal::enter()?;
+    let mut out = io::stdout();
+    loop {
+        let (columns, rows) = terminal::size()?;
+        app.resize(columns, rows);
+        app.draw(&mut out)?;
+        // Wake up regularly so the timer keeps ticking.
+        if !event::poll(Duration::from_millis(250))? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && !app.handle_key(key) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn main() {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(2);
+        }
+    };
+
+    let mut app = App::new(&options);
+    if let Err(error) = run(&mut app) {
+        eprintln!("terminal error: {}", error);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use minesweeper::Adjacency;
+
+    fn args(text: &str) -> impl Iterator<Item = String> + '_ {
+        text.split_whitespace().map(String::from)
+    }
+
+    fn options(players: usize, race: bool) -> Options {
+        Options {
+            config: GameConfig::new(9, 10),
+            seed: Some(1),
+            players,
+            race,
+            theme: Theme::default(),
+            coords: CoordinateStyle::Chess,
+        }
+    }
+
+    fn press(app: &mut App, code: KeyCode) -> bool {
+        app.handle_key(KeyEvent::from(code))
+    }
+
+    #[test]
+    fn test_zoomed_out_view_follows_the_cursor() {
+        let mut app = App::new(&parse_args(args("--width 40 --height 30 --mines 1")).unwrap());
+        // Two columns of row labels and two lines of column labels: up to AN
+        // and 30.
+        app.resize(23, CHROME_LINES as u16 + 2 + 5);
+        assert_eq!((app.viewport.columns, app.viewport.rows), (10, 5));
+
+        for _ in 0..12 {
+            press(&mut app, KeyCode::Right);
+        }
+        assert_eq!(app.viewport.x, 3);
+
+        press(&mut app, KeyCode::Char('-'));
+        assert_eq!((app.viewport.zoom, app.viewport.x), (2, 2));
+        press(&mut app, KeyCode::Char('-'));
+        press(&mut app, KeyCode::Char('-'));
+        assert_eq!(app.viewport.zoom, 6, "capped where the board fits");
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.players[0].cursor, (12, 6));
+
+        let mut out = Vec::new();
+        app.draw(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.matches('█').count(), 7 * 5);
+
+        press(&mut app, KeyCode::Char('+'));
+        assert_eq!(app.viewport.zoom, 3);
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let options = parse_args(args("--width 16 --mines 40 --variant knight --seed 3")).unwrap();
+        let config = &options.config;
+        assert_eq!(
+            (config.width, config.height, config.bomb_count),
+            (16, 9, 40)
+        );
+        assert_eq!(config.get_adjacency(), Adjacency::Knight);
+        assert_eq!(options.seed, Some(3));
+        assert_eq!((options.players, options.race), (1, false));
+
+        let options = parse_args(args("--players 3 --race")).unwrap();
+        assert_eq!((options.players, options.race), (3, true));
+
+        assert!(parse_args(args("--mines 81")).is_err());
+        assert!(parse_args(args("--variant nope")).is_err());
+        assert!(parse_args(args("--width")).is_err());
+        assert!(parse_args(args("--race")).is_err());
+        assert!(parse_args(args("--players 0")).is_err());
+
+        let options = parse_args(args("--theme emoji")).unwrap();
+        assert_eq!(options.theme.name, "emoji");
+        assert!(parse_args(args("--theme sepia")).is_err());
+
+        let options = parse_args(args("--coords numeric")).unwrap();
+        assert_eq!(options.coords, CoordinateStyle::Numeric);
+        assert!(parse_args(args("--coords polar")).is_err());
+    }
+
+    #[test]
+    fn test_draw_labels_the_axes() {
+        let mut out = Vec::new();
+        App::new(&options(1, false)).draw(&mut out).unwrap();
+        let screen = String::from_utf8(out).unwrap();
+        assert!(screen.contains(" A B C D E F G H I\r\n"));
+        assert!(screen.contains("\r\n9 #"));
+
+        let mut options = options(1, false);
+        options.coords = CoordinateStyle::Numeric;
+        let mut out = Vec::new();
+        App::new(&options).draw(&mut out).unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .contains(" 0 1 2 3 4 5 6 7 8\r\n"));
+    }
+
+    #[test]
+    fn test_draw_uses_the_theme() {
+        let mut options = options(1, false);
+        options.theme = Theme::builtin("emoji").unwrap();
+        let mut app = App::new(&options);
+        press(&mut app, KeyCode::Char('f'));
+
+        let mut out = Vec::new();
+        app.draw(&mut out).unwrap();
+        let screen = String::from_utf8(out).unwrap();
+        assert!(screen.contains(" \u{1F7E6}"));
+        assert!(!screen.contains(" #"));
+    }
+
+    #[test]
+    fn test_uncertain_mine_counter() {
+        let mut app = App::new(&parse_args(args("--variant uncertain")).unwrap());
+        assert_eq!(app.mines_left(), "7-13");
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Char('f'));
+        assert!(app.mines_left().contains('-'));
+
+        let app = App::new(&parse_args(args("--variant hidden-count")).unwrap());
+        assert_eq!(app.mines_left(), "?");
+    }
+
+    #[test]
+    fn test_loss_opens_a_review() {
+        let mut app = App::new(&options(1, false));
+        press(&mut app, KeyCode::Enter);
+        let game = app.board().unwrap();
+        let covered = |mine: bool| {
+            (0..9)
+                .flat_map(|x| (0..9).map(move |y| (x, y)))
+                .find(|&(x, y)| {
+                    let tile = game.get_tile(x, y).unwrap();
+                    !tile.exposed && tile.is_bomb() == mine
+                })
+                .unwrap()
+        };
+        let (safe, mine) = (covered(false), covered(true));
+        app.players[0].cursor = safe;
+        press(&mut app, KeyCode::Enter);
+        app.players[0].cursor = mine;
+        press(&mut app, KeyCode::Enter);
+
+        // The review starts just before the fatal move.
+        let review = app.review.as_ref().unwrap();
+        assert_eq!((review.step, review.moves.len()), (1, 2));
+        assert!(review.notes[1].as_ref().unwrap().contains("mine"));
+        assert_eq!(
+            *app.board().unwrap().get_game_state(),
+            GameState::InProgress
+        );
+
+        press(&mut app, KeyCode::Left);
+        press(&mut app, KeyCode::Left);
+        assert_eq!(app.review.as_ref().unwrap().step, 0);
+        assert!(
+            !app.board()
+                .unwrap()
+                .get_tile(safe.0, safe.1)
+                .unwrap()
+                .exposed
+        );
+
+        let mut out = Vec::new();
+        app.draw(&mut out).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains("Move 1/2: reveal"));
+
+        press(&mut app, KeyCode::Char('r'));
+        assert!(app.review.is_none());
+        assert_eq!(*app.board().unwrap().get_game_state(), GameState::Lost);
+    }
+
+    #[test]
+    fn test_suggested_start_until_the_first_click() {
+        let mut app = App::new(&options(1, false));
+        let (x, y) = app.opening.unwrap();
+        let mut out = Vec::new();
+        app.draw(&mut out).unwrap();
+        let label = CoordinateStyle::Chess.label(x, y);
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .contains(&format!("Suggested start: {}", label)));
+
+        press(&mut app, KeyCode::Enter);
+        let mut out = Vec::new();
+        app.draw(&mut out).unwrap();
+        assert!(!String::from_utf8(out).unwrap().contains("Suggested start"));
+        assert!(App::new(&options(2, true)).opening.is_none());
+    }
+
+    #[test]
+    fn test_first_click_starts_the_game() {
+        let mut app = App::new(&options(1, false));
+        press(&mut app, KeyCode::Char('f'));
+        assert!(app.board().is_none());
+
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Enter);
+        let game = app.board().unwrap();
+        assert!(game.get_tile(1, 0).unwrap().exposed);
+        assert!(app.turn_started.is_some());
+        // Opening the board scores nothing; the moves after it do.
+        assert_eq!(app.score(0), 0);
+        app.players[0].cursor = safe_tile(app.board().unwrap());
+        press(&mut app, KeyCode::Enter);
+        assert!(app.score(0) > 0);
+        assert_eq!(app.moves.len(), 1);
+        assert!(!press(&mut app, KeyCode::Char('q')));
+    }
+
+    /// A covered tile without a mine.
+    fn safe_tile(game: &Minesweeper) -> (usize, usize) {
+        game.covered_tiles()
+            .find(|(_, _, tile)| !tile.is_bomb())
+            .map(|(x, y, _)| (x, y))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_shared_board_alternates_turns() {
+        let mut app = App::new(&options(2, false));
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.turn, 0);
+        assert!(!app.handoff);
+
+        app.players[0].cursor = safe_tile(app.board().unwrap());
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.turn, 1);
+        assert_eq!(
+            app.session.as_ref().unwrap().current_player(),
+            Some(player_id(1))
+        );
+
+        // A refused move keeps the turn; any other passes it on.
+        app.players[1].cursor = app.players[0].cursor;
+        press(&mut app, KeyCode::Char('f'));
+        assert_eq!(app.turn, 1);
+        app.players[1].cursor = safe_tile(app.board().unwrap());
+        press(&mut app, KeyCode::Char('f'));
+        assert_eq!(app.turn, 0);
+    }
+
+    #[test]
+    fn test_race_hides_the_board_between_turns() {
+        let mut app = App::new(&options(2, true));
+        assert!(app.handoff);
+        let session = app.session.as_ref().unwrap();
+        assert_eq!(
+            session.game(1).unwrap().mine_positions(),
+            session.game(2).unwrap().mine_positions()
+        );
+
+        // Nothing but "ready" gets past the handoff screen.
+        press(&mut app, KeyCode::Char('f'));
+        assert!(app.handoff);
+        press(&mut app, KeyCode::Enter);
+        assert!(!app.handoff && app.turn_started.is_some());
+
+        let safe = safe_tile(app.board().unwrap());
+        app.players[0].cursor = safe;
+        press(&mut app, KeyCode::Enter);
+
+        assert_eq!(app.turn, 1);
+        assert!(app.handoff && app.turn_started.is_none());
+        let session = app.session.as_ref().unwrap();
+        let exposed = |player| {
+            session
+                .game(player)
+                .unwrap()
+                .get_tile(safe.0, safe.1)
+                .unwrap()
+                .exposed
+        };
+        assert!(exposed(1));
+        assert!(!exposed(2));
+    }
+}
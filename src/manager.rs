@@ -0,0 +1,574 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use crate::{Clock, ClockState, GameState, Minesweeper, Move, TimeControl};
+
+/// Identifies a game owned by a [`GameManager`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GameId(pub u64);
+
+/// Sent to a game's listeners after every move applied through the
+/// manager, and when a game's clock runs out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameUpdate {
+    pub id: GameId,
+    /// The move applied, or `None` if the game ended on time.
+    pub mv: Option<Move>,
+    /// The game's verdict on the move, or why the manager refused it.
+    pub outcome: Result<(), String>,
+    pub state: GameState,
+    /// The game's clock after the update, if it has one.
+    pub clock: Option<ClockState>,
+}
+
+/// A rectangle of a co-op board set aside for one player. While a game
+/// has regions, moves on a tile inside one are only accepted from its
+/// owner; tiles outside every region stay open to all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Region {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+    /// The player working the region, or `None` if anyone may.
+    pub owner: Option<u64>,
+}
+
+impl Region {
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        (self.x..self.x + self.width).contains(&x) && (self.y..self.y + self.height).contains(&y)
+    }
+
+    /// Splits a `width` x `height` board into one column of equal width
+    /// per player, the leftover columns going to the first players.
+    pub fn columns(width: usize, height: usize, players: &[u64]) -> Vec<Region> {
+        let count = players.len().max(1);
+        let mut x = 0;
+        players
+            .iter()
+            .enumerate()
+            .map(|(i, &player)| {
+                let columns = width / count + usize::from(i < width % count);
+                let region = Region {
+                    x,
+                    y: 0,
+                    width: columns,
+                    height,
+                    owner: Some(player),
+                };
+                x += columns;
+                region
+            })
+            .collect()
+    }
+}
+
+type Listener = Box<dyn FnMut(&GameUpdate) + Send>;
+
+/// Kept apart from the game's own lock, so listeners run without holding it.
+type Listeners = Arc<Mutex<Vec<Listener>>>;
+
+/// A managed game, or `None` once it has been removed.
+type Shared = Arc<Mutex<Option<ManagedGame>>>;
+
+struct ManagedGame {
+    game: Minesweeper,
+    elapsed: Duration,
+    clock: Option<Clock>,
+    listeners: Listeners,
+    regions: Vec<Region>,
+}
+
+impl ManagedGame {
+    fn update(&self, id: GameId, mv: Option<Move>, outcome: Result<(), String>) -> GameUpdate {
+        GameUpdate {
+            id,
+            mv,
+            outcome,
+            state: self.game.get_game_state().clone(),
+            clock: self.clock.as_ref().map(Clock::state),
+        }
+    }
+}
+
+/// Calls a game's listeners. Only done after the game's lock is released,
+/// so a listener may look at the game through the manager.
+fn notify(listeners: &Listeners, update: &GameUpdate) {
+    for listener in listeners.lock().unwrap().iter_mut() {
+        listener(update);
+    }
+}
+
+fn unknown_game(id: GameId) -> String {
+    format!("Unknown game {}", id.0)
+}
+
+/// Runs many games side by side in one process, e.g. a bot arena or a
+/// kiosk with several screens.
+///
+/// Every method takes `&self`: the manager can be shared between threads,
+/// and moves on different games proceed in parallel since each game has
+/// its own lock.
+#[derive(Default)]
+pub struct GameManager {
+    next_id: Mutex<u64>,
+    games: RwLock<HashMap<GameId, Shared>>,
+}
+
+impl GameManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes ownership of `game` and returns its new id. A game created
+    /// with a time control starts on that clock.
+    pub fn add(&self, game: Minesweeper) -> GameId {
+        let id = {
+            let mut next_id = self.next_id.lock().unwrap();
+            *next_id += 1;
+            GameId(*next_id)
+        };
+
+        let managed = ManagedGame {
+            clock: game.time_control.map(Clock::new),
+            game,
+            elapsed: Duration::ZERO,
+            listeners: Listeners::default(),
+            regions: Vec::new(),
+        };
+        self.games
+            .write()
+            .unwrap()
+            .insert(id, Arc::new(Mutex::new(Some(managed))));
+        id
+    }
+
+    /// Removes a game and hands it back. Calls on other threads that
+    /// already hold a handle to the game find it gone.
+    pub fn remove(&self, id: GameId) -> Option<Minesweeper> {
+        let shared = self.games.write().unwrap().remove(&id)?;
+        let managed = match Arc::try_unwrap(shared) {
+            Ok(managed) => managed.into_inner().unwrap(),
+            Err(shared) => shared.lock().unwrap().take(),
+        };
+        managed.map(|managed| managed.game)
+    }
+
+    /// Ids of every managed game, in ascending order.
+    pub fn ids(&self) -> Vec<GameId> {
+        let mut ids: Vec<GameId> = self.games.read().unwrap().keys().copied().collect();
+        ids.sort();
+        ids
+    }
+
+    pub fn len(&self) -> usize {
+        self.games.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Runs `f` on a game under its lock.
+    fn with_managed<T>(
+        &self,
+        id: GameId,
+        f: impl FnOnce(&mut ManagedGame) -> T,
+    ) -> Result<T, String> {
+        let shared = self
+            .games
+            .read()
+            .unwrap()
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| unknown_game(id))?;
+        let mut managed = shared.lock().unwrap();
+        // Removed while this call waited for the lock.
+        managed.as_mut().map(f).ok_or_else(|| unknown_game(id))
+    }
+
+    /// Runs `f` with shared access to a game.
+    pub fn with_game<T>(&self, id: GameId, f: impl FnOnce(&Minesweeper) -> T) -> Result<T, String> {
+        self.with_managed(id, |managed| f(&managed.game))
+    }
+
+    /// Applies a move to one game and notifies that game's listeners.
+    ///
+    /// The outer `Err` means the game does not exist; the inner
+    /// result is the game's own verdict on the move.
+    pub fn apply_move(&self, id: GameId, mv: Move) -> Result<Result<(), String>, String> {
+        let (update, listeners) = self.with_managed(id, |managed| Self::play(id, managed, mv))?;
+        notify(&listeners, &update);
+        Ok(update.outcome)
+    }
+
+    /// Applies a move on behalf of `player`, refusing it if it lands in a
+    /// region someone else owns. Refused moves never reach the game, but
+    /// listeners are told of them like any other. Finishing the game in one
+    /// go touches every region, so it's refused while the game has any.
+    pub fn apply_move_as(
+        &self,
+        id: GameId,
+        player: u64,
+        mv: Move,
+    ) -> Result<Result<(), String>, String> {
+        let (update, listeners) =
+            self.with_managed(id, |managed| Self::play_as(id, managed, player, mv))?;
+        notify(&listeners, &update);
+        Ok(update.outcome)
+    }
+
+    fn play_as(
+        id: GameId,
+        managed: &mut ManagedGame,
+        player: u64,
+        mv: Move,
+    ) -> (GameUpdate, Listeners) {
+        let tile = match mv {
+            Move::Reveal(x, y)
+            | Move::ToggleFlag(x, y)
+            | Move::SetMarker(x, y, _)
+            | Move::Chord(x, y)
+            | Move::Assist(x, y) => Some((x, y)),
+            Move::AutoFinish => None,
+        };
+        let refusal = match tile {
+            Some((x, y)) => managed
+                .regions
+                .iter()
+                .find(|region| region.contains(x, y))
+                .and_then(|region| region.owner)
+                .filter(|&owner| owner != player)
+                .map(|owner| format!("That tile belongs to player {}", owner)),
+            None if managed.regions.is_empty() => None,
+            None => Some("Finishing is locked while the board has regions".to_string()),
+        };
+        match refusal {
+            Some(refusal) => (
+                managed.update(id, Some(mv), Err(refusal)),
+                managed.listeners.clone(),
+            ),
+            None => Self::play(id, managed, mv),
+        }
+    }
+
+    /// Locks parts of a game to players, replacing any earlier regions. An
+    /// empty list unlocks the whole board.
+    pub fn set_regions(&self, id: GameId, regions: Vec<Region>) -> Result<(), String> {
+        self.with_managed(id, |managed| managed.regions = regions)
+    }
+
+    pub fn regions(&self, id: GameId) -> Result<Vec<Region>, String> {
+        self.with_managed(id, |managed| managed.regions.clone())
+    }
+
+    /// Hands region number `region` to `player`, e.g. when its owner left
+    /// or got stuck. Returns the previous owner.
+    pub fn take_over(&self, id: GameId, region: usize, player: u64) -> Result<Option<u64>, String> {
+        self.with_managed(id, |managed| {
+            let region = managed
+                .regions
+                .get_mut(region)
+                .ok_or_else(|| format!("Unknown region {}", region))?;
+            Ok(region.owner.replace(player))
+        })?
+    }
+
+    /// Applies `mv` under the game's lock, returning the update for the
+    /// caller to send once the lock is released.
+    fn play(id: GameId, managed: &mut ManagedGame, mv: Move) -> (GameUpdate, Listeners) {
+        let outcome = managed.game.apply_move(mv);
+        if outcome.is_ok() {
+            if let Some(clock) = &mut managed.clock {
+                clock.on_move();
+            }
+        }
+        (
+            managed.update(id, Some(mv), outcome.map_err(String::from)),
+            managed.listeners.clone(),
+        )
+    }
+
+    /// Registers a callback for every move applied to one game. Callbacks
+    /// run after the game's lock is released, so they may call back into
+    /// the manager, e.g. to read the game.
+    pub fn subscribe(
+        &self,
+        id: GameId,
+        listener: impl FnMut(&GameUpdate) + Send + 'static,
+    ) -> Result<(), String> {
+        let listeners = self.with_managed(id, |managed| managed.listeners.clone())?;
+        listeners.lock().unwrap().push(Box::new(listener));
+        Ok(())
+    }
+
+    /// Puts a game on a chess-style clock. Running out of time, overall or
+    /// on a single move, loses the game.
+    pub fn set_time_control(&self, id: GameId, control: TimeControl) -> Result<(), String> {
+        self.with_managed(id, |managed| managed.clock = Some(Clock::new(control)))
+    }
+
+    /// A game's clock, or `None` if it has no time control.
+    pub fn clock(&self, id: GameId) -> Result<Option<ClockState>, String> {
+        self.with_managed(id, |managed| managed.clock.as_ref().map(Clock::state))
+    }
+
+    /// Advances the clock of every game still in progress by `delta`.
+    /// Paused games' clocks stand still.
+    /// Games whose time runs out are forfeited and their listeners told.
+    pub fn tick(&self, delta: Duration) {
+        let games: Vec<_> = self
+            .games
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(id, m)| (*id, m.clone()))
+            .collect();
+        for (id, shared) in games {
+            let timed_out = {
+                let mut guard = shared.lock().unwrap();
+                let Some(managed) = guard.as_mut() else {
+                    continue;
+                };
+                if *managed.game.get_game_state() != GameState::InProgress {
+                    continue;
+                }
+                managed.elapsed += delta;
+
+                let timed_out = managed
+                    .clock
+                    .as_mut()
+                    .is_some_and(|clock| clock.tick(delta));
+                timed_out.then(|| {
+                    let _ = managed.game.forfeit();
+                    let update = managed.update(id, None, Err("Out of time".to_string()));
+                    (update, managed.listeners.clone())
+                })
+            };
+            if let Some((update, listeners)) = timed_out {
+                notify(&listeners, &update);
+            }
+        }
+    }
+
+    /// How long a game has been played, as accumulated by [`GameManager::tick`].
+    pub fn elapsed(&self, id: GameId) -> Result<Duration, String> {
+        self.with_managed(id, |managed| managed.elapsed)
+    }
+
+    /// Removes every finished game, returning their ids.
+    pub fn remove_finished(&self) -> Vec<GameId> {
+        let mut games = self.games.write().unwrap();
+        let finished: Vec<GameId> = games
+            .iter()
+            .filter(|(_, shared)| {
+                shared
+                    .lock()
+                    .unwrap()
+                    .as_ref()
+                    .is_some_and(|managed| managed.game.get_game_state().is_over())
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        for id in &finished {
+            games.remove(id);
+        }
+        finished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_lifecycle_and_ticking() {
+        let manager = GameManager::new();
+        let a = manager.add(Minesweeper::new(2, vec![(0, 0)]));
+        let b = manager.add(Minesweeper::new(3, vec![(1, 1)]));
+        assert_eq!(manager.ids(), vec![a, b]);
+
+        manager.tick(Duration::from_secs(1));
+        manager.apply_move(b, Move::Reveal(1, 1)).unwrap().unwrap();
+        manager.tick(Duration::from_secs(1));
+
+        // The lost game's clock stopped.
+        assert_eq!(manager.elapsed(a), Ok(Duration::from_secs(2)));
+        assert_eq!(manager.elapsed(b), Ok(Duration::from_secs(1)));
+
+        assert_eq!(manager.remove_finished(), vec![b]);
+        assert!(manager.apply_move(b, Move::Reveal(0, 0)).is_err());
+
+        let game = manager.remove(a).unwrap();
+        assert_eq!(game.get_size(), 2);
+        assert!(manager.is_empty());
+    }
+
+    #[test]
+    fn test_updates_are_routed_per_game() {
+        let manager = GameManager::new();
+        let a = manager.add(Minesweeper::new(3, vec![(0, 0)]));
+        let b = manager.add(Minesweeper::new(3, vec![(0, 0)]));
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        manager
+            .subscribe(a, move |update| sink.lock().unwrap().push(update.clone()))
+            .unwrap();
+
+        manager
+            .apply_move(a, Move::ToggleFlag(0, 0))
+            .unwrap()
+            .unwrap();
+        manager
+            .apply_move(b, Move::ToggleFlag(0, 0))
+            .unwrap()
+            .unwrap();
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].id, a);
+        assert_eq!(seen[0].mv, Some(Move::ToggleFlag(0, 0)));
+        assert_eq!(seen[0].clock, None);
+    }
+
+    #[test]
+    fn test_listeners_can_read_the_game() {
+        let manager = Arc::new(GameManager::new());
+        let id = manager.add(Minesweeper::new(3, vec![(0, 0)]));
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let (sink, reader) = (seen.clone(), manager.clone());
+        manager
+            .subscribe(id, move |update| {
+                let flags = reader.with_game(update.id, Minesweeper::flags_placed);
+                sink.lock().unwrap().push(flags);
+            })
+            .unwrap();
+        manager
+            .apply_move(id, Move::ToggleFlag(0, 0))
+            .unwrap()
+            .unwrap();
+        assert_eq!(*seen.lock().unwrap(), vec![Ok(1)]);
+    }
+
+    #[test]
+    fn test_remove_with_a_call_in_flight() {
+        let manager = GameManager::new();
+        let id = manager.add(Minesweeper::new(3, vec![(0, 0)]));
+        let in_flight = manager.games.read().unwrap()[&id].clone();
+
+        let game = manager.remove(id).unwrap();
+        assert_eq!(game.get_size(), 3);
+        assert!(in_flight.lock().unwrap().is_none());
+        assert!(manager.remove(id).is_none());
+    }
+
+    #[test]
+    fn test_games_are_played_from_many_threads() {
+        let manager = Arc::new(GameManager::new());
+        let ids: Vec<GameId> = (0..8)
+            .map(|_| manager.add(Minesweeper::new(4, vec![(0, 0)])))
+            .collect();
+
+        let handles: Vec<_> = ids
+            .iter()
+            .map(|&id| {
+                let manager = manager.clone();
+                thread::spawn(move || manager.apply_move(id, Move::Reveal(3, 3)).unwrap())
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap().unwrap();
+        }
+
+        for id in ids {
+            let state = manager.with_game(id, |game| game.get_game_state().clone());
+            assert_eq!(state, Ok(GameState::Won));
+        }
+    }
+
+    #[test]
+    fn test_running_out_of_time_loses() {
+        let manager = GameManager::new();
+        let id = manager.add(Minesweeper::new(3, vec![(0, 0)]));
+        let control = TimeControl {
+            initial: Duration::from_secs(10),
+            increment: Duration::from_secs(1),
+            per_move: None,
+        };
+        manager.set_time_control(id, control).unwrap();
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        manager
+            .subscribe(id, move |update| sink.lock().unwrap().push(update.clone()))
+            .unwrap();
+
+        manager.tick(Duration::from_secs(6));
+        manager.apply_move(id, Move::Reveal(1, 1)).unwrap().unwrap();
+        let clock = manager.clock(id).unwrap().unwrap();
+        assert_eq!(clock.remaining, Duration::from_secs(5));
+
+        manager.tick(Duration::from_secs(5));
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[0].clock.unwrap().remaining, Duration::from_secs(5));
+        assert_eq!(seen[1].mv, None);
+        assert_eq!(seen[1].state, GameState::Lost);
+        assert!(seen[1].clock.unwrap().timed_out);
+
+        assert_eq!(
+            manager.with_game(id, |game| game.get_game_state().clone()),
+            Ok(GameState::Lost)
+        );
+
+        // A game whose config has a timer starts on its clock.
+        let config = crate::GameConfig::new(3, 1).with_time_control(control);
+        let timed = Minesweeper::new_with_config_and_seed(&config, (2, 2), 1).unwrap();
+        let id = manager.add(timed);
+        let clock = manager.clock(id).unwrap().unwrap();
+        assert_eq!(clock.remaining, Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_regions_lock_moves_to_their_owner() {
+        let manager = GameManager::new();
+        let id = manager.add(Minesweeper::new_rect(5, 2, vec![(4, 1)]));
+        let regions = Region::columns(5, 2, &[1, 2]);
+        assert_eq!(
+            (regions[0].width, regions[1].x, regions[1].width),
+            (3, 3, 2)
+        );
+        manager.set_regions(id, regions).unwrap();
+
+        assert_eq!(
+            manager.apply_move_as(id, 1, Move::ToggleFlag(4, 1)),
+            Ok(Err("That tile belongs to player 2".to_string()))
+        );
+        assert!(manager
+            .apply_move_as(id, 1, Move::AutoFinish)
+            .unwrap()
+            .is_err());
+        manager
+            .apply_move_as(id, 2, Move::ToggleFlag(4, 1))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(manager.take_over(id, 1, 1), Ok(Some(2)));
+        manager
+            .apply_move_as(id, 1, Move::ToggleFlag(4, 1))
+            .unwrap()
+            .unwrap();
+        assert!(manager.take_over(id, 5, 1).is_err());
+
+        manager.set_regions(id, Vec::new()).unwrap();
+        manager
+            .apply_move_as(id, 3, Move::Reveal(0, 0))
+            .unwrap()
+            .unwrap();
+    }
+}
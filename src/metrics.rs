@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use crate::board::Board;
+use crate::flood::FloodScratch;
+use crate::{Adjacency, Topology, ZeroRule};
+
+/// What a single tile contributes to the board's 3BV.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BvCell {
+    /// Bombs and numbers on the edge of an opening: cleared for free.
+    None,
+    /// A number with no zero neighbour, which needs its own click.
+    Isolated,
+    /// A zero belonging to the opening with this index.
+    Opening(usize),
+}
+
+/// Tracks 3BV (the minimum number of clicks needed to clear the board)
+/// and how much of it is still left, updated one reveal at a time so the
+/// live figures never need a full-board pass.
+///
+/// Under [`ZeroRule::Single`] a zero reveals nothing around it, so each
+/// zero is an opening of its own and every number needs its own click.
+#[derive(Debug, Clone)]
+pub(crate) struct BvTracker {
+    /// Fixed by the layout, so shared by every copy of the tracker.
+    cells: Arc<[BvCell]>,
+    zero_rule: ZeroRule,
+    opening_solved: Vec<bool>,
+    height: usize,
+    total: usize,
+    solved: usize,
+    openings_solved: usize,
+}
+
+impl BvTracker {
+    pub(crate) fn new(
+        board: &Board,
+        adjacency: Adjacency,
+        topology: Topology,
+        zero_rule: ZeroRule,
+    ) -> Self {
+        let (width, height) = (board.width(), board.height());
+        let mut cells = vec![BvCell::None; width * height];
+        let mut openings = 0;
+        let mut scratch = FloodScratch::new(width * height, width * height);
+
+        for x in 0..width {
+            for y in 0..height {
+                if board[(x, y)].get_number() != Some(0) || cells[x * height + y] != BvCell::None {
+                    continue;
+                }
+                if zero_rule == ZeroRule::Single {
+                    cells[x * height + y] = BvCell::Opening(openings);
+                    openings += 1;
+                    continue;
+                }
+
+                scratch.fill(
+                    (x, y),
+                    (width, height),
+                    adjacency,
+                    topology,
+                    |x, y| &board[(x, y)],
+                    |tile| !tile.is_bomb(),
+                );
+                for &(zx, zy) in scratch.region() {
+                    if board[(zx, zy)].get_number() == Some(0) {
+                        cells[zx * height + zy] = BvCell::Opening(openings);
+                    }
+                }
+
+                openings += 1;
+            }
+        }
+
+        let mut isolated = 0;
+        for x in 0..width {
+            for y in 0..height {
+                let tile = &board[(x, y)];
+                if tile.is_bomb() || tile.get_number() == Some(0) {
+                    continue;
+                }
+
+                let borders_opening = zero_rule == ZeroRule::Cascade
+                    && adjacency
+                        .neighbors_in(topology, x, y, width, height)
+                        .any(|(nx, ny)| board[(nx, ny)].get_number() == Some(0));
+                if !borders_opening {
+                    cells[x * height + y] = BvCell::Isolated;
+                    isolated += 1;
+                }
+            }
+        }
+
+        BvTracker {
+            cells: cells.into(),
+            zero_rule,
+            opening_solved: vec![false; openings],
+            height,
+            total: openings + isolated,
+            solved: 0,
+            openings_solved: 0,
+        }
+    }
+
+    /// Records that the tile at (x, y) has just been exposed.
+    pub(crate) fn on_reveal(&mut self, x: usize, y: usize) {
+        match self.cells[x * self.height + y] {
+            BvCell::None => {}
+            BvCell::Isolated => self.solved += 1,
+            BvCell::Opening(id) => {
+                if !self.opening_solved[id] {
+                    self.opening_solved[id] = true;
+                    self.solved += 1;
+                    self.openings_solved += 1;
+                }
+            }
+        }
+    }
+
+    /// The rule the counts were made for.
+    pub(crate) fn zero_rule(&self) -> ZeroRule {
+        self.zero_rule
+    }
+
+    pub(crate) fn total(&self) -> usize {
+        self.total
+    }
+
+    pub(crate) fn remaining(&self) -> usize {
+        self.total - self.solved
+    }
+
+    pub(crate) fn openings(&self) -> usize {
+        self.opening_solved.len()
+    }
+
+    pub(crate) fn openings_remaining(&self) -> usize {
+        self.opening_solved.len() - self.openings_solved
+    }
+}
@@ -0,0 +1,166 @@
+use crate::metrics::BvTracker;
+use crate::{GameState, Minesweeper, TileValue, Topology};
+
+impl Minesweeper {
+    /// Copies a `size` area of `source`, starting at `from`, onto this
+    /// board at `at`: mines, uncovered tiles, flags, markers and notes.
+    /// Endless and campaign modes use it to carry part of the last level's
+    /// terrain into the next, shifted or set into a larger board.
+    ///
+    /// Only the numbers within reach of the area's edge are recounted;
+    /// further in they are the source's. Copied tiles keep their state
+    /// without cascading, and a mine the source lost on comes over
+    /// covered. The mine count follows the new layout. Undo history is
+    /// cleared, since an embed is not a move.
+    pub fn embed(
+        &mut self,
+        source: &Minesweeper,
+        from: (usize, usize),
+        size: (usize, usize),
+        at: (usize, usize),
+    ) -> Result<(), String> {
+        if self.game_state != GameState::InProgress {
+            return Err(self.finished_error().into());
+        }
+        if source.adjacency != self.adjacency {
+            return Err("Boards use different adjacency".to_string());
+        }
+        let (width, height) = size;
+        if from.0 + width > source.width || from.1 + height > source.height {
+            return Err("Area is outside the source board".to_string());
+        }
+        if at.0 + width > self.width || at.1 + height > self.height {
+            return Err("Area does not fit on the board".to_string());
+        }
+
+        for dx in 0..width {
+            for dy in 0..height {
+                let (sx, sy) = (from.0 + dx, from.1 + dy);
+                let (x, y) = (at.0 + dx, at.1 + dy);
+                let old = &self.board[(x, y)];
+                if old.is_bomb() {
+                    self.bomb_count -= 1;
+                } else if !old.exposed {
+                    self.covered_safe -= 1;
+                }
+
+                let mut tile = source.board[(sx, sy)].clone();
+                tile.exposed &= !tile.is_bomb();
+                if tile.is_bomb() {
+                    self.bomb_count += 1;
+                } else if !tile.exposed {
+                    self.covered_safe += 1;
+                }
+                *self.board.tile_mut(x, y) = tile;
+                if let Some(provenance) = &mut self.provenance {
+                    provenance.remove(&(x, y));
+                }
+
+                match source.notes.get(&(sx, sy)) {
+                    Some(note) => self.notes.insert((x, y), note.clone()),
+                    None => self.notes.remove(&(x, y)),
+                };
+            }
+        }
+
+        self.renumber_edge(at, size);
+        self.bv = BvTracker::new(&self.board, self.adjacency, self.topology, self.zero_rule);
+        self.forget_history();
+        self.check_win_condition();
+        self.refresh_overlay();
+        Ok(())
+    }
+
+    /// Recounts the numbers that can see across the edge of the area at
+    /// `at`, inside it and out.
+    fn renumber_edge(&mut self, at: (usize, usize), (width, height): (usize, usize)) {
+        let reach = self.adjacency.radius() as i64;
+        let (left, top) = (at.0 as i64, at.1 as i64);
+        let (right, bottom) = (left + width as i64, top + height as i64);
+        let deep_inside = |x: i64, y: i64| {
+            x >= left + reach && x < right - reach && y >= top + reach && y < bottom - reach
+        };
+
+        for x in left - reach..right + reach {
+            for y in top - reach..bottom + reach {
+                if deep_inside(x, y) {
+                    continue;
+                }
+                let Some((x, y)) = self.wrap(x, y) else {
+                    continue;
+                };
+                if self.board[(x, y)].is_bomb() {
+                    continue;
+                }
+                let count = self
+                    .neighbors(x, y)
+                    .filter(|&(nx, ny)| self.board[(nx, ny)].is_bomb())
+                    .count() as u8;
+                self.board.tile_mut(x, y).value = TileValue::Number(count);
+            }
+        }
+    }
+
+    /// (x, y) on the board, wrapped round on a torus.
+    fn wrap(&self, x: i64, y: i64) -> Option<(usize, usize)> {
+        let (width, height) = (self.width as i64, self.height as i64);
+        match self.topology {
+            Topology::Torus => Some((x.rem_euclid(width) as usize, y.rem_euclid(height) as usize)),
+            Topology::Plane => {
+                (x >= 0 && y >= 0 && x < width && y < height).then_some((x as usize, y as usize))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embed_carries_terrain_into_a_larger_board() {
+        // The last level: a 3x2 board with a cleared column.
+        let mut level = Minesweeper::new_rect(3, 2, vec![(0, 1)]);
+        level.click_tile(2, 0).unwrap();
+        level.toggle_flag(0, 1).unwrap();
+
+        let mut next = Minesweeper::new_rect(6, 4, vec![(5, 3)]);
+        next.set_history(true);
+        next.toggle_flag(5, 3).unwrap();
+        next.embed(&level, (0, 0), (3, 2), (1, 1)).unwrap();
+
+        assert_eq!(next.get_bomb_count(), 2);
+        assert!(next.history().is_empty());
+        let tile = next.get_tile(1, 2).unwrap();
+        assert!(tile.is_bomb() && tile.flagged);
+        assert!(next.get_tile(3, 1).unwrap().exposed);
+        // Numbers around the embedded mine were recounted on both sides of
+        // the edge.
+        assert_eq!(next.get_tile(0, 3).unwrap().get_number(), Some(1));
+        assert_eq!(next.get_tile(2, 2).unwrap().get_number(), Some(1));
+
+        // The board plays on normally: every safe tile left can be cleared.
+        let safe: Vec<(usize, usize)> = next
+            .covered_tiles()
+            .filter(|(_, _, tile)| !tile.is_bomb())
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        for (x, y) in safe {
+            let _ = next.click_tile(x, y);
+        }
+        assert_eq!(next.get_game_state(), &GameState::Won);
+    }
+
+    #[test]
+    fn test_embed_errors() {
+        let source = Minesweeper::new_rect(3, 3, vec![(0, 0)]);
+        let mut target = Minesweeper::new_rect(4, 4, vec![(3, 3)]);
+        assert!(target.embed(&source, (1, 1), (3, 3), (0, 0)).is_err());
+        assert!(target.embed(&source, (0, 0), (3, 3), (2, 0)).is_err());
+        let hex = Minesweeper::new_rect_with_adjacency(3, 3, vec![], crate::Adjacency::Hex);
+        assert_eq!(
+            target.embed(&hex, (0, 0), (1, 1), (0, 0)),
+            Err("Boards use different adjacency".to_string())
+        );
+    }
+}
@@ -0,0 +1,544 @@
+use std::time::Duration;
+
+use crate::{
+    Clock, ClockState, GameConfig, GameState, Handicap, Minesweeper, Move, Score, TimeControl,
+};
+
+/// How the players of a [`GameSession`] share the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionMode {
+    /// Every player gets their own copy of the same board and plays at
+    /// their own pace. The first to clear theirs wins.
+    Race,
+    /// One board, with the players taking turns in the order they were
+    /// listed. Every accepted move passes the turn on.
+    Turns,
+}
+
+/// Something that happened in a [`GameSession`], for clients following
+/// along; see [`GameSession::set_event_queue`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionEvent {
+    /// `player`'s move was accepted. `clock` is their clock after it, with
+    /// any increment added.
+    Moved {
+        player: u64,
+        mv: Move,
+        clock: Option<ClockState>,
+    },
+    /// `player`'s clock after [`GameSession::tick`] ran it, for rendering
+    /// countdowns.
+    Clock { player: u64, clock: ClockState },
+    /// `player` ran out of time and has lost.
+    TimedOut { player: u64 },
+}
+
+struct Seat {
+    player: u64,
+    handicap: Handicap,
+    clock: Option<Clock>,
+    /// Whether the player lost on time, which rules them out of winning.
+    timed_out: bool,
+    score: Score,
+    /// Which of the session's boards the player plays on.
+    board: usize,
+    /// When the player cleared their board in a race, with their
+    /// handicap's time penalty added.
+    finished_at: Option<Duration>,
+}
+
+/// A game for several players, tracking each one's score and, when taking
+/// turns, whose move it is.
+///
+/// Boards are generated from a seed, so every board in a race is the same
+/// and a server can hand the seed to clients that want to build the board
+/// themselves.
+pub struct GameSession {
+    mode: SessionMode,
+    first_click: (usize, usize),
+    seed: u64,
+    seats: Vec<Seat>,
+    boards: Vec<Minesweeper>,
+    turn: usize,
+    events: Option<Vec<SessionEvent>>,
+}
+
+impl GameSession {
+    /// A race between `players` on boards made from `config`, `first_click`
+    /// and `seed`.
+    pub fn race(
+        config: &GameConfig,
+        first_click: (usize, usize),
+        seed: u64,
+        players: &[u64],
+    ) -> Result<Self, String> {
+        let players: Vec<(u64, Handicap)> = players
+            .iter()
+            .map(|&player| (player, Handicap::default()))
+            .collect();
+        Self::new(SessionMode::Race, config, first_click, seed, &players)
+    }
+
+    /// A race in which each player's board is built with their own
+    /// [`Handicap`] applied to `config`, so stronger players can face
+    /// more mines or a worse opening. Time penalties count against the
+    /// time a player clears their board in.
+    pub fn handicapped_race(
+        config: &GameConfig,
+        first_click: (usize, usize),
+        seed: u64,
+        players: &[(u64, Handicap)],
+    ) -> Result<Self, String> {
+        Self::new(SessionMode::Race, config, first_click, seed, players)
+    }
+
+    /// `players` taking turns on one board.
+    pub fn turns(
+        config: &GameConfig,
+        first_click: (usize, usize),
+        seed: u64,
+        players: &[u64],
+    ) -> Result<Self, String> {
+        let players: Vec<(u64, Handicap)> = players
+            .iter()
+            .map(|&player| (player, Handicap::default()))
+            .collect();
+        Self::new(SessionMode::Turns, config, first_click, seed, &players)
+    }
+
+    fn new(
+        mode: SessionMode,
+        config: &GameConfig,
+        first_click: (usize, usize),
+        seed: u64,
+        players: &[(u64, Handicap)],
+    ) -> Result<Self, String> {
+        if players.is_empty() {
+            return Err("A session needs at least one player".to_string());
+        }
+        if (1..players.len()).any(|i| players[..i].iter().any(|p| p.0 == players[i].0)) {
+            return Err("Players must be listed once each".to_string());
+        }
+
+        // A shared board is everyone's, so it is built without handicaps.
+        let boards = match mode {
+            SessionMode::Race => players
+                .iter()
+                .map(|(_, handicap)| {
+                    Minesweeper::new_with_config_and_seed(
+                        &handicap.config(config),
+                        first_click,
+                        seed,
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>(),
+            SessionMode::Turns => {
+                Minesweeper::new_with_config_and_seed(config, first_click, seed).map(|b| vec![b])
+            }
+        }
+        .map_err(|e| e.to_string())?;
+        let seats = players
+            .iter()
+            .enumerate()
+            .map(|(i, (player, handicap))| Seat {
+                player: *player,
+                handicap: handicap.clone(),
+                clock: config.time_control.map(Clock::new),
+                timed_out: false,
+                score: Score::default(),
+                board: if mode == SessionMode::Race { i } else { 0 },
+                finished_at: None,
+            })
+            .collect();
+
+        Ok(GameSession {
+            mode,
+            first_click,
+            seed,
+            seats,
+            boards,
+            turn: 0,
+            events: None,
+        })
+    }
+
+    pub fn mode(&self) -> SessionMode {
+        self.mode
+    }
+
+    /// The tile every board was opened at.
+    pub fn first_click(&self) -> (usize, usize) {
+        self.first_click
+    }
+
+    /// The seed every board was made from.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The players, in the order they were listed.
+    pub fn players(&self) -> impl Iterator<Item = u64> + '_ {
+        self.seats.iter().map(|seat| seat.player)
+    }
+
+    /// The player whose move it is when taking turns, or `None` in a race
+    /// or once the game is over.
+    pub fn current_player(&self) -> Option<u64> {
+        match self.mode {
+            SessionMode::Turns if !self.is_finished() => Some(self.seats[self.turn].player),
+            _ => None,
+        }
+    }
+
+    /// The board `player` plays on.
+    pub fn game(&self, player: u64) -> Option<&Minesweeper> {
+        let seat = self.seat(player).ok()?;
+        Some(&self.boards[self.seats[seat].board])
+    }
+
+    pub fn handicap(&self, player: u64) -> Option<&Handicap> {
+        let seat = self.seat(player).ok()?;
+        Some(&self.seats[seat].handicap)
+    }
+
+    pub fn score(&self, player: u64) -> Option<i64> {
+        let seat = self.seat(player).ok()?;
+        Some(self.seats[seat].score.total())
+    }
+
+    /// Every player's score, in the order they were listed.
+    pub fn scores(&self) -> Vec<(u64, i64)> {
+        self.seats
+            .iter()
+            .map(|seat| (seat.player, seat.score.total()))
+            .collect()
+    }
+
+    fn seat(&self, player: u64) -> Result<usize, String> {
+        self.seats
+            .iter()
+            .position(|seat| seat.player == player)
+            .ok_or_else(|| format!("Player {} is not in this session", player))
+    }
+
+    /// Plays `mv` for `player`, `elapsed` into the session, and scores it.
+    /// Moves out of turn are refused without reaching the board.
+    pub fn play(&mut self, player: u64, mv: Move, elapsed: Duration) -> Result<(), String> {
+        let seat = self.seat(player)?;
+        if self.mode == SessionMode::Turns && seat != self.turn && !self.is_finished() {
+            return Err(format!(
+                "It is player {}'s turn",
+                self.seats[self.turn].player
+            ));
+        }
+
+        let Seat {
+            score,
+            board,
+            clock,
+            ..
+        } = &mut self.seats[seat];
+        let board = &mut self.boards[*board];
+        score.apply_move(board, mv, elapsed)?;
+        if let Some(clock) = clock {
+            clock.on_move();
+        }
+        let clock = clock.as_ref().map(Clock::state);
+        self.emit(SessionEvent::Moved { player, mv, clock });
+
+        match self.mode {
+            SessionMode::Race => {
+                let seat = &mut self.seats[seat];
+                if seat.finished_at.is_none()
+                    && *self.boards[seat.board].get_game_state() == GameState::Won
+                {
+                    seat.finished_at = Some(seat.handicap.adjusted_time(elapsed));
+                }
+            }
+            SessionMode::Turns => self.turn = (self.turn + 1) % self.seats.len(),
+        }
+        Ok(())
+    }
+
+    /// Puts every player on a chess-style clock of their own. Running out
+    /// of time, overall or on a single move, loses: the player's board is
+    /// forfeited, which on a shared board ends the game, and they can't
+    /// win.
+    pub fn set_time_control(&mut self, control: TimeControl) {
+        for seat in &mut self.seats {
+            seat.clock = Some(Clock::new(control));
+        }
+    }
+
+    /// `player`'s clock, or `None` without a time control.
+    pub fn clock(&self, player: u64) -> Option<ClockState> {
+        let seat = self.seat(player).ok()?;
+        self.seats[seat].clock.as_ref().map(Clock::state)
+    }
+
+    /// Runs the clocks for `delta`: the current player's when taking
+    /// turns, and everyone's still playing in a race. Clocks on paused
+    /// boards stand still.
+    pub fn tick(&mut self, delta: Duration) {
+        for seat in 0..self.seats.len() {
+            if self.mode == SessionMode::Turns && seat != self.turn {
+                continue;
+            }
+            let Seat {
+                player,
+                clock: Some(clock),
+                board,
+                ..
+            } = &mut self.seats[seat]
+            else {
+                continue;
+            };
+            let (player, board) = (*player, *board);
+            if *self.boards[board].get_game_state() != GameState::InProgress {
+                continue;
+            }
+
+            let timed_out = clock.tick(delta);
+            let clock = clock.state();
+            self.emit(SessionEvent::Clock { player, clock });
+            if timed_out {
+                self.seats[seat].timed_out = true;
+                let _ = self.boards[board].forfeit();
+                self.emit(SessionEvent::TimedOut { player });
+            }
+        }
+    }
+
+    /// Turns the event queue on or off. While it is on, every
+    /// [`SessionEvent`] is kept until [`GameSession::drain_events`]
+    /// collects it.
+    pub fn set_event_queue(&mut self, enabled: bool) {
+        if !enabled {
+            self.events = None;
+        } else if self.events.is_none() {
+            self.events = Some(Vec::new());
+        }
+    }
+
+    /// The events queued since the last call, oldest first.
+    pub fn drain_events(&mut self) -> Vec<SessionEvent> {
+        self.events.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    fn emit(&mut self, event: SessionEvent) {
+        if let Some(events) = &mut self.events {
+            events.push(event);
+        }
+    }
+
+    /// Whether every board is finished.
+    pub fn is_finished(&self) -> bool {
+        self.boards
+            .iter()
+            .all(|board| board.get_game_state().is_over())
+    }
+
+    /// The winner: in a race, the first player to clear their board once
+    /// time penalties are added; otherwise, once the session is finished,
+    /// the highest scorer who didn't run out of time. `None` while
+    /// undecided or on a tie for the highest score.
+    pub fn winner(&self) -> Option<u64> {
+        let first_home = self
+            .seats
+            .iter()
+            .filter_map(|seat| Some((seat.finished_at?, seat.player)))
+            .min();
+        if let Some((_, player)) = first_home {
+            return Some(player);
+        }
+        if !self.is_finished() {
+            return None;
+        }
+        let in_time = || self.seats.iter().filter(|seat| !seat.timed_out);
+        let best = in_time().map(|seat| seat.score.total()).max()?;
+        let mut leaders = in_time().filter(|seat| seat.score.total() == best);
+        match (leaders.next(), leaders.next()) {
+            (Some(leader), None) => Some(leader.player),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> GameConfig {
+        GameConfig::new(6, 5)
+    }
+
+    /// A safe covered tile and a mine on `game`.
+    fn safe_and_mine(game: &Minesweeper) -> ((usize, usize), (usize, usize)) {
+        let covered: Vec<_> = game.covered_tiles().collect();
+        let safe = covered.iter().find(|(_, _, tile)| !tile.is_bomb()).unwrap();
+        let mine = covered.iter().find(|(_, _, tile)| tile.is_bomb()).unwrap();
+        ((safe.0, safe.1), (mine.0, mine.1))
+    }
+
+    #[test]
+    fn test_race_boards_are_identical_and_first_home_wins() {
+        let mut session = GameSession::race(&config(), (0, 0), 11, &[1, 2]).unwrap();
+        assert_eq!(session.current_player(), None);
+        let (one, two) = (session.game(1).unwrap(), session.game(2).unwrap());
+        assert_eq!(one.board_id(), two.board_id());
+
+        // Player 2 clears their board; player 1 is still playing.
+        let safe: Vec<(usize, usize)> = session
+            .game(2)
+            .unwrap()
+            .covered_tiles()
+            .filter(|(_, _, tile)| !tile.is_bomb())
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        for (i, (x, y)) in safe.into_iter().enumerate() {
+            let _ = session.play(2, Move::Reveal(x, y), Duration::from_secs(i as u64));
+        }
+        assert_eq!(*session.game(2).unwrap().get_game_state(), GameState::Won);
+        assert!(session.score(2).unwrap() > 0);
+        assert_eq!(session.score(1), Some(0));
+        assert_eq!(session.winner(), Some(2));
+        assert!(!session.is_finished());
+        assert!(session.play(3, Move::Reveal(0, 0), Duration::ZERO).is_err());
+    }
+
+    #[test]
+    fn test_turns_alternate_on_a_shared_board() {
+        let mut session = GameSession::turns(&config(), (0, 0), 5, &[7, 8]).unwrap();
+        assert_eq!(session.current_player(), Some(7));
+        let (safe, mine) = safe_and_mine(session.game(7).unwrap());
+
+        assert_eq!(
+            session.play(8, Move::Reveal(safe.0, safe.1), Duration::ZERO),
+            Err("It is player 7's turn".to_string())
+        );
+        session
+            .play(7, Move::Reveal(safe.0, safe.1), Duration::ZERO)
+            .unwrap();
+        assert_eq!(session.current_player(), Some(8));
+        // Both see the same board.
+        assert!(
+            session
+                .game(8)
+                .unwrap()
+                .get_tile(safe.0, safe.1)
+                .unwrap()
+                .exposed
+        );
+
+        // A refused move keeps the turn.
+        assert!(session
+            .play(8, Move::Reveal(safe.0, safe.1), Duration::ZERO)
+            .is_err());
+        assert_eq!(session.current_player(), Some(8));
+
+        session
+            .play(8, Move::Reveal(mine.0, mine.1), Duration::ZERO)
+            .unwrap();
+        assert!(session.is_finished());
+        assert_eq!(session.current_player(), None);
+        assert!(session.score(8).unwrap() < 0);
+        assert_eq!(session.winner(), Some(7));
+    }
+
+    #[test]
+    fn test_handicaps_apply_per_seat() {
+        let slow = Handicap {
+            extra_mines: 3,
+            time_penalty: Duration::from_secs(100),
+            ..Default::default()
+        };
+        let players = [(1, Handicap::default()), (2, slow.clone())];
+        let mut session = GameSession::handicapped_race(&config(), (0, 0), 11, &players).unwrap();
+        assert_eq!(session.game(1).unwrap().get_bomb_count(), 5);
+        assert_eq!(session.game(2).unwrap().get_bomb_count(), 8);
+        assert_eq!(session.handicap(2), Some(&slow));
+        assert_eq!((session.first_click(), session.seed()), ((0, 0), 11));
+
+        // Player 2 clears first, but the penalty puts them behind.
+        let clear = |session: &mut GameSession, player: u64, at: u64| {
+            let safe: Vec<(usize, usize)> = session
+                .game(player)
+                .unwrap()
+                .covered_tiles()
+                .filter(|(_, _, tile)| !tile.is_bomb())
+                .map(|(x, y, _)| (x, y))
+                .collect();
+            for (x, y) in safe {
+                let _ = session.play(player, Move::Reveal(x, y), Duration::from_secs(at));
+            }
+        };
+        clear(&mut session, 2, 10);
+        assert_eq!(session.winner(), Some(2));
+        clear(&mut session, 1, 50);
+        assert_eq!(session.winner(), Some(1));
+    }
+
+    #[test]
+    fn test_running_out_of_time_loses() {
+        let mut session = GameSession::turns(&config(), (0, 0), 5, &[7, 8]).unwrap();
+        session.set_time_control(TimeControl {
+            initial: Duration::from_secs(10),
+            increment: Duration::from_secs(2),
+            per_move: None,
+        });
+        session.set_event_queue(true);
+        let (safe, _) = safe_and_mine(session.game(7).unwrap());
+
+        // Only the player to move is on the clock.
+        session.tick(Duration::from_secs(4));
+        session
+            .play(7, Move::Reveal(safe.0, safe.1), Duration::from_secs(4))
+            .unwrap();
+        assert_eq!(session.clock(7).unwrap().remaining, Duration::from_secs(8));
+        assert_eq!(session.clock(8).unwrap().remaining, Duration::from_secs(10));
+
+        session.tick(Duration::from_secs(10));
+        let events = session.drain_events();
+        assert!(matches!(
+            events[1],
+            SessionEvent::Moved {
+                player: 7,
+                clock: Some(_),
+                ..
+            }
+        ));
+        assert_eq!(events.last(), Some(&SessionEvent::TimedOut { player: 8 }));
+        assert!(session.clock(8).unwrap().timed_out);
+        assert!(session.is_finished());
+        assert_eq!(session.winner(), Some(7));
+
+        // In a race everyone's clock runs, and a timeout ends only that
+        // player's game.
+        let mut race = GameSession::race(&config(), (0, 0), 5, &[1, 2]).unwrap();
+        race.set_time_control(TimeControl {
+            initial: Duration::from_secs(5),
+            increment: Duration::ZERO,
+            per_move: Some(Duration::from_secs(3)),
+        });
+        race.tick(Duration::from_secs(2));
+        race.play(1, Move::Reveal(safe.0, safe.1), Duration::from_secs(2))
+            .unwrap();
+        race.tick(Duration::from_secs(1));
+        assert_eq!(*race.game(2).unwrap().get_game_state(), GameState::Lost);
+        assert_eq!(
+            *race.game(1).unwrap().get_game_state(),
+            GameState::InProgress
+        );
+        assert_eq!(
+            race.clock(1).unwrap().move_remaining,
+            Some(Duration::from_secs(2))
+        );
+    }
+
+    #[test]
+    fn test_session_errors() {
+        assert!(GameSession::race(&config(), (0, 0), 1, &[]).is_err());
+        assert!(GameSession::turns(&config(), (0, 0), 1, &[1, 2, 1]).is_err());
+        assert!(GameSession::race(&GameConfig::new(2, 9), (0, 0), 1, &[1]).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crate::{GameId, Move};
+
+/// Decides whether a session may play a move, so a server open to the
+/// public can't be flooded. Install one with
+/// [`Broker::with_policy`](crate::Broker::with_policy), or with
+/// `GameServer::with_policy` under the `server` feature; [`RateLimit`]
+/// covers the usual limits.
+pub trait MovePolicy: Send {
+    /// Called before `session` plays `mv` on `game`. An error refuses the
+    /// move and is sent back to the session.
+    fn check(&mut self, session: u64, game: GameId, mv: Move, now: Instant) -> Result<(), String>;
+
+    /// Called before `session` starts a new game. A game costs far more
+    /// to create than a move does to play, so a public server should limit
+    /// these too; allowed unless overridden.
+    fn check_new_game(&mut self, _session: u64, _now: Instant) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Called when a session ends, to forget what was kept about it.
+    fn end_session(&mut self, _session: u64) {}
+}
+
+/// Limits each session to a steady rate of moves, with short bursts up to
+/// `burst` moves, and optionally to `quota` moves in all. Starting a new
+/// game counts as a move.
+///
+/// Bursts faster than a person could click are refused outright, which
+/// keeps scripted clients from clearing a board in one go.
+#[derive(Debug, Clone)]
+pub struct RateLimit {
+    moves_per_second: f64,
+    burst: u32,
+    quota: Option<u64>,
+    sessions: HashMap<u64, Allowance>,
+}
+
+/// What one session has left.
+#[derive(Debug, Clone)]
+struct Allowance {
+    /// Moves that may be played right away, topped up over time.
+    tokens: f64,
+    refilled_at: Instant,
+    played: u64,
+}
+
+impl RateLimit {
+    pub fn new(moves_per_second: f64, burst: u32) -> Self {
+        RateLimit {
+            moves_per_second,
+            burst: burst.max(1),
+            quota: None,
+            sessions: HashMap::new(),
+        }
+    }
+
+    /// Caps the moves one session may play, across all its games.
+    pub fn with_quota(mut self, moves: u64) -> Self {
+        self.quota = Some(moves);
+        self
+    }
+}
+
+impl RateLimit {
+    /// Takes one move from `session`'s allowance, if it has one left.
+    fn spend(&mut self, session: u64, now: Instant) -> Result<(), String> {
+        let burst = self.burst as f64;
+        let allowance = self.sessions.entry(session).or_insert(Allowance {
+            tokens: burst,
+            refilled_at: now,
+            played: 0,
+        });
+        let elapsed = now.saturating_duration_since(allowance.refilled_at);
+        allowance.tokens =
+            (allowance.tokens + elapsed.as_secs_f64() * self.moves_per_second).min(burst);
+        allowance.refilled_at = now;
+
+        if self.quota.is_some_and(|quota| allowance.played >= quota) {
+            return Err("Move quota used up".to_string());
+        }
+        if allowance.tokens < 1.0 {
+            return Err("Too many moves, slow down".to_string());
+        }
+        allowance.tokens -= 1.0;
+        allowance.played += 1;
+        Ok(())
+    }
+}
+
+impl MovePolicy for RateLimit {
+    fn check(
+        &mut self,
+        session: u64,
+        _game: GameId,
+        _mv: Move,
+        now: Instant,
+    ) -> Result<(), String> {
+        self.spend(session, now)
+    }
+
+    fn check_new_game(&mut self, session: u64, now: Instant) -> Result<(), String> {
+        self.spend(session, now)
+    }
+
+    fn end_session(&mut self, session: u64) {
+        self.sessions.remove(&session);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const GAME: GameId = GameId(1);
+    const MOVE: Move = Move::Reveal(0, 0);
+
+    #[test]
+    fn test_rate_limit_allows_bursts_then_refills() {
+        let mut limit = RateLimit::new(2.0, 3);
+        let start = Instant::now();
+        for _ in 0..3 {
+            assert_eq!(limit.check(1, GAME, MOVE, start), Ok(()));
+        }
+        assert!(limit.check(1, GAME, MOVE, start).is_err());
+        // Other sessions have their own allowance.
+        assert_eq!(limit.check(2, GAME, MOVE, start), Ok(()));
+
+        let later = start + Duration::from_millis(500);
+        assert_eq!(limit.check(1, GAME, MOVE, later), Ok(()));
+        assert!(limit.check(1, GAME, MOVE, later).is_err());
+
+        // The bucket never holds more than a burst.
+        let much_later = start + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert_eq!(limit.check(1, GAME, MOVE, much_later), Ok(()));
+        }
+        assert!(limit.check(1, GAME, MOVE, much_later).is_err());
+    }
+
+    #[test]
+    fn test_quota() {
+        let mut limit = RateLimit::new(100.0, 10).with_quota(2);
+        let now = Instant::now();
+        assert_eq!(limit.check(1, GAME, MOVE, now), Ok(()));
+        assert_eq!(limit.check(1, GAME, MOVE, now), Ok(()));
+        assert_eq!(
+            limit.check(1, GAME, MOVE, now),
+            Err("Move quota used up".to_string())
+        );
+
+        limit.end_session(1);
+        assert_eq!(limit.check(1, GAME, MOVE, now), Ok(()));
+        assert_eq!(limit.check_new_game(1, now), Ok(()));
+        assert!(limit.check_new_game(1, now).is_err());
+    }
+}
@@ -0,0 +1,21 @@
+//! The stable core of the crate, for frontends:
+//! `use minesweeper::prelude::*;`.
+//!
+//! Everything here follows semver: it is only removed or changed in a
+//! breaking release, and `tests/public_api.rs` fails to compile if that
+//! happens by accident. [`GameConfig`], [`GameState`] and [`Move`] are
+//! `#[non_exhaustive]`, so new fields and variants can still arrive in
+//! minor releases; match them with a wildcard arm and build configs with
+//! the `with_*` methods.
+//!
+//! The rest of the crate root is public too, but newer subsystems such as
+//! the solvers, servers and simulation tools may still change shape in
+//! minor releases. `minesweeper::internal`, hidden from the docs, holds
+//! plumbing shared with the crate's own binaries and has no guarantees
+//! at all.
+
+pub use crate::{
+    board_rng, Adjacency, BoardRenderer, BoardRng, FlagRule, GameConfig, GameEvent, GameState,
+    GeneratorOptions, Mark, Marker, MineCount, Minesweeper, MinesweeperError, Move, Outcome,
+    TextRenderer, Tile, TileValue, Topology, View, VisibleTile, WinCondition, ZeroRule,
+};
@@ -0,0 +1,605 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use crate::solver::Meter;
+use crate::{Minesweeper, SolverBudget, TileValue};
+
+/// Probabilities this close to 0 or 1 are certainties that rounding in the
+/// engine's weights has blurred.
+pub(crate) const CERTAIN: f64 = 1e-9;
+
+/// A revealed number's requirement: (mines required, tiles it covers).
+type Constraint = (u8, Vec<usize>);
+
+/// Part of the frontier whose tiles share constraints only with each other.
+///
+/// Tiles are board indices in ascending order; constraints refer to tiles
+/// by their position in `tiles` and are sorted, so two components with the
+/// same tiles and constraints compare equal however they were built.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Component {
+    tiles: Vec<usize>,
+    constraints: Vec<Constraint>,
+}
+
+/// Every mine arrangement of a component that satisfies its constraints,
+/// grouped by how many mines it uses.
+#[derive(Debug)]
+struct Solutions {
+    /// Indexed by mine count: (number of arrangements, how many of those
+    /// arrangements put a mine on each tile). Both are scaled by a common
+    /// factor to keep them in range; only ratios matter.
+    by_mines: Vec<(f64, Vec<f64>)>,
+}
+
+/// Computes the probability that each covered tile is a mine, given what
+/// the player can see and what they are told of the total mine count.
+/// When only a range is known, every layout with a count in the range is
+/// taken to be equally likely.
+///
+/// The frontier (covered tiles next to revealed numbers) is split into
+/// independent components whose arrangements are enumerated separately and
+/// combined with the global mine count. Enumeration results are cached per
+/// component, so after a move only the components whose constraints changed
+/// are enumerated again.
+#[derive(Debug, Default)]
+pub(crate) struct ProbabilityEngine {
+    cache: HashMap<Component, Arc<Solutions>>,
+}
+
+impl ProbabilityEngine {
+    /// One probability per tile, indexed by `x * height + y`. Revealed tiles
+    /// are certain: 0.0 for numbers and 1.0 for exploded mines.
+    pub(crate) fn compute(&mut self, game: &Minesweeper) -> Vec<f64> {
+        self.compute_metered(game, &mut Meter::new(SolverBudget::default()))
+            .expect("an unlimited budget never runs out")
+    }
+
+    /// [`ProbabilityEngine::compute`] within a budget: `None` if it ran out
+    /// before every component was enumerated, as probabilities from part of
+    /// the arrangements would be wrong. Components that were finished stay
+    /// cached for the next call.
+    pub(crate) fn compute_metered(
+        &mut self,
+        game: &Minesweeper,
+        meter: &mut Meter,
+    ) -> Option<Vec<f64>> {
+        let (width, height) = (game.get_width(), game.get_height());
+        let mut probabilities = vec![0.0; width * height];
+
+        let mut known_mines = 0;
+        for x in 0..width {
+            for y in 0..height {
+                let tile = game.get_tile(x, y).unwrap();
+                if tile.exposed && tile.is_bomb() {
+                    probabilities[x * height + y] = 1.0;
+                    known_mines += 1;
+                }
+            }
+        }
+
+        let components = frontier_components(game);
+        let mut solutions: Vec<Arc<Solutions>> = Vec::with_capacity(components.len());
+        for component in &components {
+            let solution = match self.cache.get(component) {
+                Some(solution) => solution.clone(),
+                None => {
+                    let solution = Arc::new(enumerate(component, meter)?);
+                    self.cache.insert(component.clone(), solution.clone());
+                    solution
+                }
+            };
+            solutions.push(solution);
+        }
+
+        // Forget components that no longer exist; they cannot come back
+        // unchanged once the board has moved on.
+        let live: HashSet<&Component> = components.iter().collect();
+        self.cache.retain(|component, _| live.contains(component));
+
+        let mut in_frontier = vec![false; width * height];
+        for component in &components {
+            for &tile in &component.tiles {
+                in_frontier[tile] = true;
+            }
+        }
+        let interior: Vec<usize> = (0..width * height)
+            .filter(|&index| {
+                let tile = game.get_tile(index / height, index % height).unwrap();
+                !tile.exposed && !in_frontier[index]
+            })
+            .collect();
+
+        // What the player knows of the mines not yet found.
+        let (fewest, most) = game.mine_count_bounds();
+        let (fewest, most) = (
+            fewest.saturating_sub(known_mines),
+            most.saturating_sub(known_mines),
+        );
+        let counts: Vec<Vec<f64>> = solutions
+            .iter()
+            .map(|s| s.by_mines.iter().map(|(count, _)| *count).collect())
+            .collect();
+        let max_frontier_mines: usize = counts.iter().map(|c| c.len() - 1).sum();
+        let (weights, interior_mines) =
+            interior_weights(interior.len(), fewest, most, max_frontier_mines);
+        let weight = |k: usize| weights.get(k).copied().unwrap_or(0.0);
+
+        // Arrangements of all components together, by total mine count, and
+        // the same with each component left out in turn.
+        let total = counts.iter().fold(vec![1.0], |acc, c| convolve(&acc, c));
+        let mut prefix = vec![vec![1.0]];
+        for c in &counts {
+            prefix.push(convolve(prefix.last().unwrap(), c));
+        }
+        let mut suffix = vec![vec![1.0]; counts.len() + 1];
+        for i in (0..counts.len()).rev() {
+            suffix[i] = convolve(&suffix[i + 1], &counts[i]);
+        }
+
+        let normaliser: f64 = total.iter().enumerate().map(|(k, n)| n * weight(k)).sum();
+
+        for (i, (component, solution)) in components.iter().zip(&solutions).enumerate() {
+            let others = convolve(&prefix[i], &suffix[i + 1]);
+            let mut mine_weight = vec![0.0; component.tiles.len()];
+
+            for (k, (_, per_tile)) in solution.by_mines.iter().enumerate() {
+                let w: f64 = others
+                    .iter()
+                    .enumerate()
+                    .map(|(j, n)| n * weight(k + j))
+                    .sum();
+                for (acc, count) in mine_weight.iter_mut().zip(per_tile) {
+                    *acc += count * w;
+                }
+            }
+
+            for (&tile, w) in component.tiles.iter().zip(mine_weight) {
+                probabilities[tile] = if normaliser > 0.0 {
+                    w / normaliser
+                } else {
+                    0.0
+                };
+            }
+        }
+
+        if !interior.is_empty() {
+            let expected: f64 = total
+                .iter()
+                .enumerate()
+                .map(|(k, n)| n * interior_mines.get(k).copied().unwrap_or(0.0))
+                .sum();
+            let p = if normaliser > 0.0 {
+                expected / normaliser / interior.len() as f64
+            } else {
+                fewest as f64 / interior.len() as f64
+            };
+            for &tile in &interior {
+                probabilities[tile] = p.clamp(0.0, 1.0);
+            }
+        }
+
+        Some(probabilities)
+    }
+}
+
+/// Mine probabilities kept up to date as the game is played.
+#[derive(Debug, Default)]
+pub(crate) struct ProbabilityOverlay {
+    engine: ProbabilityEngine,
+    probabilities: Arc<Vec<f64>>,
+}
+
+impl ProbabilityOverlay {
+    pub(crate) fn refresh(&mut self, game: &Minesweeper) {
+        self.probabilities = Arc::new(self.engine.compute(game));
+    }
+
+    /// The latest probabilities, shared so snapshots can hold on to them.
+    pub(crate) fn probabilities(&self) -> &Arc<Vec<f64>> {
+        &self.probabilities
+    }
+}
+
+/// Builds the constraints implied by revealed numbers and splits their
+/// covered tiles into independent components.
+fn frontier_components(game: &Minesweeper) -> Vec<Component> {
+    let (width, height) = (game.get_width(), game.get_height());
+    let (adjacency, topology) = (game.get_adjacency(), game.get_topology());
+    let mut constraints: Vec<Constraint> = Vec::new();
+
+    for x in 0..width {
+        for y in 0..height {
+            let tile = game.get_tile(x, y).unwrap();
+            let number = match tile.value {
+                TileValue::Number(n) if tile.exposed => n,
+                _ => continue,
+            };
+
+            let mut covered = Vec::new();
+            let mut mines = 0;
+            for (nx, ny) in adjacency.neighbors_in(topology, x, y, width, height) {
+                let neighbor = game.get_tile(nx, ny).unwrap();
+                if !neighbor.exposed {
+                    covered.push(nx * height + ny);
+                } else if neighbor.is_bomb() {
+                    mines += 1;
+                }
+            }
+
+            if !covered.is_empty() {
+                constraints.push((number.saturating_sub(mines), covered));
+            }
+        }
+    }
+
+    // Union covered tiles that appear in the same constraint.
+    let mut parent: HashMap<usize, usize> = HashMap::new();
+    fn find(parent: &mut HashMap<usize, usize>, tile: usize) -> usize {
+        let p = *parent.entry(tile).or_insert(tile);
+        if p == tile {
+            return tile;
+        }
+        let root = find(parent, p);
+        parent.insert(tile, root);
+        root
+    }
+    for (_, tiles) in &constraints {
+        let first = find(&mut parent, tiles[0]);
+        for &tile in &tiles[1..] {
+            let root = find(&mut parent, tile);
+            parent.insert(root, first);
+        }
+    }
+
+    let mut groups: HashMap<usize, (Vec<usize>, Vec<Constraint>)> = HashMap::new();
+    let tiles: Vec<usize> = parent.keys().copied().collect();
+    for tile in tiles {
+        let root = find(&mut parent, tile);
+        groups.entry(root).or_default().0.push(tile);
+    }
+    for constraint in constraints {
+        let root = find(&mut parent, constraint.1[0]);
+        groups.entry(root).or_default().1.push(constraint);
+    }
+
+    let mut components: Vec<Component> = groups
+        .into_values()
+        .map(|(mut tiles, constraints)| {
+            tiles.sort_unstable();
+            let local = |tile: &usize| tiles.binary_search(tile).unwrap();
+            let mut constraints: Vec<Constraint> = constraints
+                .iter()
+                .map(|(target, members)| {
+                    let mut members: Vec<usize> = members.iter().map(local).collect();
+                    members.sort_unstable();
+                    (*target, members)
+                })
+                .collect();
+            constraints.sort();
+            constraints.dedup();
+            Component { tiles, constraints }
+        })
+        .collect();
+    components.sort_by_key(|component| component.tiles[0]);
+    components
+}
+
+/// Enumerates every arrangement of mines on a component's tiles that
+/// satisfies all of its constraints, or `None` if `meter` runs out first.
+fn enumerate(component: &Component, meter: &mut Meter) -> Option<Solutions> {
+    let n = component.tiles.len();
+    let mut tile_constraints = vec![Vec::new(); n];
+    for (c, (_, members)) in component.constraints.iter().enumerate() {
+        for &tile in members {
+            tile_constraints[tile].push(c);
+        }
+    }
+
+    // Assign tiles in breadth-first order through shared constraints so
+    // each constraint fills up quickly and bad branches are cut early.
+    let mut order = Vec::with_capacity(n);
+    let mut seen = vec![false; n];
+    let mut queue = VecDeque::from([0]);
+    seen[0] = true;
+    while let Some(tile) = queue.pop_front() {
+        order.push(tile);
+        for &c in &tile_constraints[tile] {
+            for &other in &component.constraints[c].1 {
+                if !seen[other] {
+                    seen[other] = true;
+                    queue.push_back(other);
+                }
+            }
+        }
+    }
+
+    let mut search = Search {
+        constraints: &component.constraints,
+        tile_constraints: &tile_constraints,
+        order: &order,
+        mines: component.constraints.iter().map(|_| 0).collect(),
+        unassigned: component.constraints.iter().map(|(_, m)| m.len()).collect(),
+        assignment: vec![false; n],
+        by_mines: vec![(0.0, vec![0.0; n]); n + 1],
+        meter,
+        exhausted: false,
+    };
+    search.run(0, 0);
+    if search.exhausted {
+        return None;
+    }
+
+    let mut by_mines = search.by_mines;
+    while by_mines.len() > 1 && by_mines.last().unwrap().0 == 0.0 {
+        by_mines.pop();
+    }
+    let scale = by_mines.iter().map(|(count, _)| *count).fold(0.0, f64::max);
+    if scale > 0.0 {
+        for (count, per_tile) in &mut by_mines {
+            *count /= scale;
+            per_tile.iter_mut().for_each(|c| *c /= scale);
+        }
+    }
+
+    Some(Solutions { by_mines })
+}
+
+struct Search<'a> {
+    constraints: &'a [Constraint],
+    tile_constraints: &'a [Vec<usize>],
+    order: &'a [usize],
+    /// Mines placed so far in each constraint.
+    mines: Vec<usize>,
+    /// Tiles of each constraint not yet assigned.
+    unassigned: Vec<usize>,
+    assignment: Vec<bool>,
+    by_mines: Vec<(f64, Vec<f64>)>,
+    meter: &'a mut Meter,
+    /// Whether the meter ran out, abandoning the search.
+    exhausted: bool,
+}
+
+impl Search<'_> {
+    fn run(&mut self, depth: usize, placed: usize) {
+        if self.exhausted || !self.meter.spend() {
+            self.exhausted = true;
+            return;
+        }
+        if depth == self.order.len() {
+            let (count, per_tile) = &mut self.by_mines[placed];
+            *count += 1.0;
+            for (tile, &mine) in self.assignment.iter().enumerate() {
+                if mine {
+                    per_tile[tile] += 1.0;
+                }
+            }
+            return;
+        }
+
+        let tile = self.order[depth];
+        for mine in [false, true] {
+            if self.assign(tile, mine) {
+                self.run(depth + 1, placed + mine as usize);
+            }
+            self.unassign(tile, mine);
+        }
+    }
+
+    /// Assigns a tile and reports whether every constraint can still be met.
+    fn assign(&mut self, tile: usize, mine: bool) -> bool {
+        self.assignment[tile] = mine;
+        let mut feasible = true;
+        for &c in &self.tile_constraints[tile] {
+            self.unassigned[c] -= 1;
+            self.mines[c] += mine as usize;
+            let target = self.constraints[c].0 as usize;
+            if self.mines[c] > target || self.mines[c] + self.unassigned[c] < target {
+                feasible = false;
+            }
+        }
+        feasible
+    }
+
+    fn unassign(&mut self, tile: usize, mine: bool) {
+        self.assignment[tile] = false;
+        for &c in &self.tile_constraints[tile] {
+            self.unassigned[c] += 1;
+            self.mines[c] -= mine as usize;
+        }
+    }
+}
+
+fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut out = vec![0.0; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            out[i + j] += x * y;
+        }
+    }
+    out
+}
+
+/// For each number of mines `k` the frontier could hold, the relative
+/// number of ways to place the rest among the `interior` tiles so that the
+/// total is between `fewest` and `most`, and the same sum with each way
+/// weighted by the mines it puts in the interior.
+fn interior_weights(
+    interior: usize,
+    fewest: usize,
+    most: usize,
+    max_frontier: usize,
+) -> (Vec<f64>, Vec<f64>) {
+    let mut weights = vec![0.0; max_frontier + 1];
+    let mut expected = vec![0.0; max_frontier + 1];
+    let low = fewest.saturating_sub(max_frontier);
+    let high = most.min(interior);
+    if low > high {
+        return (weights, expected);
+    }
+
+    // ln C(interior, m), via the ratio C(n, m) / C(n, m - 1) =
+    // (n - m + 1) / m, then rescaled so the largest weight that can be
+    // used is 1.
+    let mut ln_choose = Vec::with_capacity(high + 1);
+    let mut ln = 0.0;
+    ln_choose.push(ln);
+    for m in 1..=high {
+        ln += ((interior - m + 1) as f64).ln() - (m as f64).ln();
+        ln_choose.push(ln);
+    }
+    let max = ln_choose[low..=high]
+        .iter()
+        .copied()
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    for k in 0..=max_frontier.min(most) {
+        let from = fewest.saturating_sub(k).max(low);
+        let to = (most - k).min(high);
+        for (m, ln) in ln_choose.iter().enumerate().take(to + 1).skip(from) {
+            let ways = (ln - max).exp();
+            weights[k] += ways;
+            expected[k] += ways * m as f64;
+        }
+    }
+    (weights, expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MineCount;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_single_constraint_with_global_count() {
+        // Revealing (0, 0) shows a 1 with three covered neighbours; with one
+        // mine in total it must be among them.
+        let mut game = Minesweeper::new(3, vec![(1, 1)]);
+        game.click_tile(0, 0).unwrap();
+
+        let p = ProbabilityEngine::default().compute(&game);
+        assert!(approx(p[0], 0.0));
+        for tile in [1, 3, 4] {
+            assert!(approx(p[tile], 1.0 / 3.0), "tile {} is {}", tile, p[tile]);
+        }
+        for tile in [2, 5, 6, 7, 8] {
+            assert!(approx(p[tile], 0.0));
+        }
+    }
+
+    /// Exact probabilities by trying every placement of as many mines as
+    /// the player knows the game could hold.
+    fn brute_force(game: &Minesweeper) -> Vec<f64> {
+        let (width, height) = (game.get_width(), game.get_height());
+        let tiles = width * height;
+        let mut hits = vec![0.0; tiles];
+        let mut consistent = 0.0;
+
+        let (fewest, most) = game.mine_count_bounds();
+        for layout in 0u32..(1 << tiles) {
+            if !(fewest..=most).contains(&(layout.count_ones() as usize)) {
+                continue;
+            }
+            let mine = |x: usize, y: usize| layout & (1 << (x * height + y)) != 0;
+            let fits = (0..tiles).all(|i| {
+                let (x, y) = (i / height, i % height);
+                let tile = game.get_tile(x, y).unwrap();
+                if !tile.exposed {
+                    return true;
+                }
+                let around = game
+                    .get_adjacency()
+                    .neighbors_in(game.get_topology(), x, y, width, height)
+                    .filter(|&(nx, ny)| mine(nx, ny))
+                    .count();
+                !mine(x, y) && tile.get_number() == Some(around as u8)
+            });
+            if fits {
+                consistent += 1.0;
+                for (i, hit) in hits.iter_mut().enumerate() {
+                    if layout & (1 << i) != 0 {
+                        *hit += 1.0;
+                    }
+                }
+            }
+        }
+
+        hits.iter().map(|h| h / consistent).collect()
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let mut game = Minesweeper::new(4, vec![(1, 1), (2, 3), (3, 0)]);
+        let mut engine = ProbabilityEngine::default();
+
+        for click in [(0, 0), (2, 1), (0, 3), (3, 2)] {
+            game.click_tile(click.0, click.1).unwrap();
+            let expected = brute_force(&game);
+            let actual = engine.compute(&game);
+            for (i, (a, e)) in actual.iter().zip(&expected).enumerate() {
+                assert!(
+                    approx(*a, *e),
+                    "after {:?}, tile {}: {} != {}",
+                    click,
+                    i,
+                    a,
+                    e
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_uncertain_mine_count_matches_brute_force() {
+        for mine_count in [MineCount::Range { min: 2, max: 5 }, MineCount::Hidden] {
+            let mut game = Minesweeper::new(4, vec![(1, 1), (2, 3), (3, 0)]);
+            game.set_mine_count(mine_count).unwrap();
+            let mut engine = ProbabilityEngine::default();
+
+            for click in [(0, 0), (2, 1), (0, 3)] {
+                game.click_tile(click.0, click.1).unwrap();
+                let expected = brute_force(&game);
+                let actual = engine.compute(&game);
+                for (i, (a, e)) in actual.iter().zip(&expected).enumerate() {
+                    assert!(
+                        approx(*a, *e),
+                        "{:?} after {:?}, tile {}: {} != {}",
+                        mine_count,
+                        click,
+                        i,
+                        a,
+                        e
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cache_reuses_unchanged_components() {
+        // Two separate numbers, each with its own ring of covered tiles.
+        let mut game = Minesweeper::new(7, vec![(1, 1), (5, 5)]);
+        game.click_tile(1, 0).unwrap();
+        game.click_tile(5, 6).unwrap();
+
+        let mut engine = ProbabilityEngine::default();
+        engine.compute(&game);
+        assert_eq!(engine.cache.len(), 2);
+        let far = engine
+            .cache
+            .iter()
+            .find(|(component, _)| component.tiles.contains(&(5 * 7 + 5)))
+            .map(|(_, solutions)| solutions.clone())
+            .unwrap();
+
+        // Only the component around (1, 1) changes.
+        game.click_tile(0, 0).unwrap();
+        engine.compute(&game);
+        assert_eq!(engine.cache.len(), 2);
+        assert!(engine.cache.values().any(|s| Arc::ptr_eq(s, &far)));
+    }
+}
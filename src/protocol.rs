@@ -0,0 +1,916 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, BufRead, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BoardDelta, BoardRenderer, GameConfig, GameId, GameManager, GameState, GameUpdate, Minesweeper,
+    Move, MovePolicy, Region, TextRenderer, View, VisibleTile,
+};
+
+/// One line sent by a client to a [`Broker`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Request {
+    /// Starts a game from a config in the
+    /// [`GameConfig::to_config_string`] format and joins it. Two clients
+    /// that create games with the same config, first click and seed get
+    /// the same board, which is how races are set up.
+    NewGame {
+        config: String,
+        first_click: (usize, usize),
+        seed: Option<u64>,
+    },
+    /// Starts receiving updates for a game, e.g. to play it together or to
+    /// watch an opponent's board during a race.
+    Join { game: u64 },
+    /// Plays a move, in the text form of [`Move`], such as `"reveal 3 4"`.
+    /// Its outcome arrives as an update to every session that joined. Moves
+    /// on a game the session hasn't joined, or in a region another session
+    /// owns, are refused.
+    Move {
+        game: u64,
+        #[serde(rename = "move")]
+        mv: String,
+    },
+    /// Asks for a joined game's board as the player sees it.
+    Board { game: u64 },
+    /// Splits a co-op board into one column per listed session, so each
+    /// works its own area. An empty list unlocks the board. Only the
+    /// session that created the game may split it.
+    Split { game: u64, sessions: Vec<u64> },
+    /// Takes over region number `region` from a partner who left. Regions
+    /// whose owner is still connected can't be claimed.
+    Claim { game: u64, region: usize },
+}
+
+/// One line sent by a [`Broker`] to a client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Response {
+    /// Sent first, with the number moves from this session are played as.
+    Welcome { session: u64 },
+    /// Answers [`Request::NewGame`].
+    Created { game: u64 },
+    /// Answers [`Request::Join`].
+    Joined { game: u64 },
+    /// A move was played on a joined game, by any session.
+    Update {
+        game: u64,
+        /// The move, or `None` if the game ended on time.
+        #[serde(rename = "move")]
+        mv: Option<String>,
+        /// Why the move was refused, if it was.
+        error: Option<String>,
+        state: GameState,
+    },
+    /// Answers [`Request::Board`]: one string per row, in ASCII.
+    Board {
+        game: u64,
+        rows: Vec<String>,
+        state: GameState,
+    },
+    /// Answers [`Request::Split`] and [`Request::Claim`] with the game's
+    /// regions.
+    Regions { game: u64, regions: Vec<Region> },
+    /// A request could not be parsed or carried out.
+    Error { message: String },
+}
+
+/// A message from a game client to a server, for frontends that talk to
+/// a networked server over TCP or WebSockets rather than to a [`Broker`].
+/// Serialized as JSON objects tagged with `"type"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Click {
+        x: usize,
+        y: usize,
+    },
+    /// Toggles the flag on (x, y).
+    Flag {
+        x: usize,
+        y: usize,
+    },
+    Chord {
+        x: usize,
+        y: usize,
+    },
+    /// Starts a game from a config in the
+    /// [`GameConfig::to_config_string`] format. Games with the same config,
+    /// first click and seed have the same board.
+    NewGame {
+        config: String,
+        first_click: (usize, usize),
+        seed: Option<u64>,
+    },
+}
+
+impl ClientMessage {
+    /// The move the message asks for, or `None` for one that isn't a move.
+    pub fn to_move(&self) -> Option<Move> {
+        match *self {
+            ClientMessage::Click { x, y } => Some(Move::Reveal(x, y)),
+            ClientMessage::Flag { x, y } => Some(Move::ToggleFlag(x, y)),
+            ClientMessage::Chord { x, y } => Some(Move::Chord(x, y)),
+            ClientMessage::NewGame { .. } => None,
+        }
+    }
+}
+
+/// A tile that changed, as the player may see it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VisibleChange {
+    pub x: usize,
+    pub y: usize,
+    pub tile: VisibleTile,
+}
+
+/// A message from a game server to its clients; see [`ClientMessage`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// The tiles a move changed. `seq` counts up by one per diff, so a
+    /// client that sees a gap should ask for the whole board again.
+    BoardDiff {
+        seq: u64,
+        changes: Vec<VisibleChange>,
+        state: GameState,
+    },
+    /// The game ended, with the mine that was hit if it was lost that way.
+    GameOver {
+        state: GameState,
+        exploded: Option<(usize, usize)>,
+    },
+    PlayerJoined {
+        player: u64,
+    },
+    /// A message was refused, e.g. a move the game doesn't allow.
+    Error {
+        message: String,
+    },
+}
+
+impl ServerMessage {
+    /// A [`ServerMessage::BoardDiff`] for `delta`, polled from `game`,
+    /// showing only what the player can see.
+    pub fn board_diff(game: &Minesweeper, delta: &BoardDelta) -> Self {
+        ServerMessage::BoardDiff {
+            seq: delta.seq,
+            changes: delta
+                .changes
+                .iter()
+                .map(|change| VisibleChange {
+                    x: change.x,
+                    y: change.y,
+                    tile: game.visible_tile(change.x, change.y),
+                })
+                .collect(),
+            state: delta.game_state.clone(),
+        }
+    }
+
+    /// A [`ServerMessage::GameOver`] for `game`, or `None` while it is in
+    /// progress or paused.
+    pub fn game_over(game: &Minesweeper) -> Option<Self> {
+        match game.get_game_state() {
+            GameState::InProgress | GameState::Paused => None,
+            state => Some(ServerMessage::GameOver {
+                state: state.clone(),
+                exploded: game.losing_tile(),
+            }),
+        }
+    }
+}
+
+impl From<&GameUpdate> for Response {
+    fn from(update: &GameUpdate) -> Self {
+        Response::Update {
+            game: update.id.0,
+            mv: update.mv.map(|mv| mv.to_string()),
+            error: update.outcome.clone().err(),
+            state: update.state.clone(),
+        }
+    }
+}
+
+/// Where a session's responses go. Updates for joined games are written
+/// from whichever thread played the move, so the writer is shared.
+struct Outbox {
+    writer: Mutex<Box<dyn Write + Send>>,
+    /// Set once the session ends, so listeners it left behind go quiet.
+    closed: AtomicBool,
+}
+
+impl Outbox {
+    fn send(&self, response: &Response) -> io::Result<()> {
+        if self.closed.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        let line = serde_json::to_string(response)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let mut writer = self.writer.lock().unwrap();
+        writeln!(writer, "{}", line)?;
+        writer.flush()
+    }
+}
+
+/// One client's session with a [`Broker`], from [`Broker::connect`].
+pub struct Connection {
+    session: u64,
+    outbox: Arc<Outbox>,
+    joined: HashSet<GameId>,
+}
+
+impl Connection {
+    /// The number moves from this session are played as.
+    pub fn session(&self) -> u64 {
+        self.session
+    }
+}
+
+/// Serves the line-based JSON protocol to several clients at once over one
+/// [`GameManager`], so local frontends can race or play co-op without a
+/// network server.
+///
+/// Each client is a session: a stream of [`Request`]s, one JSON object per
+/// line, answered with [`Response`]s on its own output. Run every session
+/// on its own thread with [`Broker::serve`]; a frontend started as a
+/// subprocess would pass its stdin and stdout. Servers with their own event
+/// loop feed lines in through [`Broker::connect`] instead.
+#[derive(Default)]
+pub struct Broker {
+    manager: GameManager,
+    next_session: AtomicU64,
+    policy: Option<Mutex<Box<dyn MovePolicy>>>,
+    /// The session that created each game, which alone may split it.
+    creators: Mutex<HashMap<GameId, u64>>,
+    /// Sessions still connected, whose regions can't be claimed.
+    live: Mutex<HashSet<u64>>,
+}
+
+impl Broker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A broker that asks `policy` before playing any session's move, e.g.
+    /// a [`RateLimit`](crate::RateLimit) for a public server.
+    pub fn with_policy(policy: impl MovePolicy + 'static) -> Self {
+        Broker {
+            policy: Some(Mutex::new(Box::new(policy))),
+            ..Self::default()
+        }
+    }
+
+    /// The games being played, e.g. to set a time control on one.
+    pub fn manager(&self) -> &GameManager {
+        &self.manager
+    }
+
+    /// Runs one session until `input` ends, writing its responses and the
+    /// updates of the games it joined to `output`. Returns the number the
+    /// session was given.
+    pub fn serve(
+        &self,
+        input: impl BufRead,
+        output: impl Write + Send + 'static,
+    ) -> io::Result<u64> {
+        let mut connection = self.connect(output)?;
+        let result = (|| {
+            for line in input.lines() {
+                self.receive(&mut connection, &line?)?;
+            }
+            Ok(())
+        })();
+        let session = connection.session;
+        self.disconnect(connection);
+        result.map(|()| session)
+    }
+
+    /// Starts a session whose requests are handed over one line at a time
+    /// with [`Broker::receive`], for servers that don't give each client a
+    /// blocking thread. The welcome is written to `output` right away.
+    pub fn connect(&self, output: impl Write + Send + 'static) -> io::Result<Connection> {
+        let session = self.next_session.fetch_add(1, Ordering::SeqCst) + 1;
+        let connection = Connection {
+            session,
+            outbox: Arc::new(Outbox {
+                writer: Mutex::new(Box::new(output)),
+                closed: AtomicBool::new(false),
+            }),
+            joined: HashSet::new(),
+        };
+        connection.outbox.send(&Response::Welcome { session })?;
+        self.live.lock().unwrap().insert(session);
+        Ok(connection)
+    }
+
+    /// Carries out one line of a session's input.
+    pub fn receive(&self, connection: &mut Connection, line: &str) -> io::Result<()> {
+        self.receive_at(connection, line, Instant::now())
+    }
+
+    /// [`Broker::receive`] with the move policy told it is `now`.
+    pub(crate) fn receive_at(
+        &self,
+        connection: &mut Connection,
+        line: &str,
+        now: Instant,
+    ) -> io::Result<()> {
+        if line.trim().is_empty() {
+            return Ok(());
+        }
+        let response = match serde_json::from_str::<Request>(line) {
+            Ok(request) => self.handle(connection, request, now),
+            Err(e) => Some(Response::Error {
+                message: format!("Invalid request: {}", e),
+            }),
+        };
+        match response {
+            Some(response) => connection.outbox.send(&response),
+            None => Ok(()),
+        }
+    }
+
+    /// Ends a session: updates stop reaching it and the move policy
+    /// forgets it.
+    pub fn disconnect(&self, connection: Connection) {
+        connection.outbox.closed.store(true, Ordering::SeqCst);
+        self.live.lock().unwrap().remove(&connection.session);
+        if let Some(policy) = &self.policy {
+            policy.lock().unwrap().end_session(connection.session);
+        }
+    }
+
+    /// Carries out one request, returning the direct reply, if any.
+    fn handle(
+        &self,
+        connection: &mut Connection,
+        request: Request,
+        now: Instant,
+    ) -> Option<Response> {
+        let session = connection.session;
+        let (outbox, joined) = (&connection.outbox, &mut connection.joined);
+        let result = match request {
+            Request::NewGame {
+                config,
+                first_click,
+                seed,
+            } => self.new_game(&config, first_click, seed).and_then(|id| {
+                self.creators.lock().unwrap().insert(id, session);
+                self.join(id, outbox, joined)?;
+                Ok(Response::Created { game: id.0 })
+            }),
+            Request::Join { game } => self
+                .join(GameId(game), outbox, joined)
+                .map(|()| Response::Joined { game }),
+            Request::Move { game, .. }
+            | Request::Board { game }
+            | Request::Split { game, .. }
+            | Request::Claim { game, .. }
+                if !joined.contains(&GameId(game)) =>
+            {
+                Err(not_joined(game))
+            }
+            Request::Move { game, mv } => match mv.parse::<Move>() {
+                // The outcome reaches joined sessions as an update.
+                Ok(mv) => match self
+                    .check_policy(session, GameId(game), mv, now)
+                    .and_then(|()| self.manager.apply_move_as(GameId(game), session, mv))
+                {
+                    Ok(_) => return None,
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(e),
+            },
+            Request::Board { game } => self.manager.with_game(GameId(game), |g| {
+                let text = TextRenderer::ascii().render(g, View::Player);
+                Response::Board {
+                    game,
+                    rows: text.lines().map(str::to_string).collect(),
+                    state: g.get_game_state().clone(),
+                }
+            }),
+            Request::Split { game, .. }
+                if self.creators.lock().unwrap().get(&GameId(game)) != Some(&session) =>
+            {
+                Err(format!(
+                    "Only the session that created game {} can split it",
+                    game
+                ))
+            }
+            Request::Split { game, sessions } => {
+                let id = GameId(game);
+                self.manager
+                    .with_game(id, |g| (g.get_width(), g.get_height()))
+                    .and_then(|(width, height)| {
+                        let regions = if sessions.is_empty() {
+                            Vec::new()
+                        } else {
+                            Region::columns(width, height, &sessions)
+                        };
+                        self.manager.set_regions(id, regions.clone())?;
+                        Ok(Response::Regions { game, regions })
+                    })
+            }
+            Request::Claim { game, region } => {
+                let id = GameId(game);
+                // Held throughout, so two sessions can't both claim a
+                // region whose owner just left.
+                let live = self.live.lock().unwrap();
+                self.manager
+                    .regions(id)
+                    .and_then(|regions| match regions.get(region).and_then(|r| r.owner) {
+                        Some(owner) if owner != session && live.contains(&owner) => Err(format!(
+                            "Region {} belongs to session {}, who is still here",
+                            region, owner
+                        )),
+                        _ => self.manager.take_over(id, region, session),
+                    })
+                    .and_then(|_| self.manager.regions(id))
+                    .map(|regions| Response::Regions { game, regions })
+            }
+        };
+        Some(result.unwrap_or_else(|message| Response::Error { message }))
+    }
+
+    fn check_policy(
+        &self,
+        session: u64,
+        game: GameId,
+        mv: Move,
+        now: Instant,
+    ) -> Result<(), String> {
+        match &self.policy {
+            Some(policy) => policy.lock().unwrap().check(session, game, mv, now),
+            None => Ok(()),
+        }
+    }
+
+    fn new_game(
+        &self,
+        config: &str,
+        first_click: (usize, usize),
+        seed: Option<u64>,
+    ) -> Result<GameId, String> {
+        let config = client_config(config)?;
+        let game = match seed {
+            Some(seed) => Minesweeper::new_with_config_and_seed(&config, first_click, seed),
+            None => Minesweeper::new_with_config(&config, first_click),
+        }
+        .map_err(|e| e.to_string())?;
+        Ok(self.manager.add(game))
+    }
+
+    fn join(
+        &self,
+        id: GameId,
+        outbox: &Arc<Outbox>,
+        joined: &mut HashSet<GameId>,
+    ) -> Result<(), String> {
+        if joined.contains(&id) {
+            return Ok(());
+        }
+        let outbox = outbox.clone();
+        self.manager.subscribe(id, move |update| {
+            // A session whose client went away can't be told.
+            let _ = outbox.send(&Response::from(update));
+        })?;
+        joined.insert(id);
+        Ok(())
+    }
+}
+
+/// The most tiles a game created from a client's message may have, so one
+/// message can't make a server allocate without bound.
+pub(crate) const MAX_CLIENT_TILES: usize = 1 << 20;
+
+/// Parses a config sent by a client, refusing anything
+/// [`GameConfig::validate`] rejects and boards over [`MAX_CLIENT_TILES`].
+pub(crate) fn client_config(text: &str) -> Result<GameConfig, String> {
+    let config = GameConfig::from_config_string(text)?;
+    config.validate()?;
+    // Validated, so the product fits.
+    if config.width * config.height > MAX_CLIENT_TILES {
+        return Err(format!(
+            "Board is too large; games may have at most {} tiles",
+            MAX_CLIENT_TILES
+        ));
+    }
+    Ok(config)
+}
+
+fn not_joined(game: u64) -> String {
+    format!("Join game {} before playing or viewing it", game)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Read};
+    use std::sync::mpsc::{self, Receiver, Sender};
+    use std::thread;
+
+    /// Output that can be read back while the session is still running.
+    #[derive(Clone, Default)]
+    struct SharedOutput(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedOutput {
+        fn all(&self) -> Vec<Response> {
+            let bytes = self.0.lock().unwrap().clone();
+            String::from_utf8(bytes)
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        }
+
+        /// The session's number, from its welcome.
+        fn session(&self) -> u64 {
+            match self.all()[0] {
+                Response::Welcome { session } => session,
+                ref other => panic!("unexpected {:?}", other),
+            }
+        }
+
+        /// Everything after the welcome.
+        fn responses(&self) -> Vec<Response> {
+            self.all().into_iter().skip(1).collect()
+        }
+    }
+
+    /// Input fed a line at a time from the test.
+    struct ChannelInput {
+        lines: Receiver<String>,
+        pending: Cursor<Vec<u8>>,
+    }
+
+    impl Read for ChannelInput {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.pending.position() as usize == self.pending.get_ref().len() {
+                match self.lines.recv() {
+                    Ok(line) => self.pending = Cursor::new(format!("{}\n", line).into_bytes()),
+                    Err(_) => return Ok(0),
+                }
+            }
+            self.pending.read(buf)
+        }
+    }
+
+    fn channel() -> (Sender<String>, io::BufReader<ChannelInput>) {
+        let (sender, lines) = mpsc::channel();
+        let input = ChannelInput {
+            lines,
+            pending: Cursor::new(Vec::new()),
+        };
+        (sender, io::BufReader::new(input))
+    }
+
+    fn request(request: &Request) -> String {
+        serde_json::to_string(request).unwrap()
+    }
+
+    const CONFIG: &str = "width=4\nheight=4\nmines=3";
+
+    #[test]
+    fn test_message_wire_format() {
+        let click: ClientMessage = serde_json::from_str(r#"{"type":"click","x":2,"y":3}"#).unwrap();
+        assert_eq!(click.to_move(), Some(Move::Reveal(2, 3)));
+        let new_game = ClientMessage::NewGame {
+            config: CONFIG.to_string(),
+            first_click: (0, 0),
+            seed: None,
+        };
+        assert_eq!(new_game.to_move(), None);
+        let text = serde_json::to_string(&new_game).unwrap();
+        assert!(text.starts_with(r#"{"type":"new_game","#));
+        assert_eq!(
+            serde_json::from_str::<ClientMessage>(&text).unwrap(),
+            new_game
+        );
+
+        let joined = serde_json::to_string(&ServerMessage::PlayerJoined { player: 4 }).unwrap();
+        assert_eq!(joined, r#"{"type":"player_joined","player":4}"#);
+    }
+
+    #[test]
+    fn test_server_messages_hide_the_mines() {
+        let mut game = Minesweeper::new_rect(3, 1, vec![(2, 0)]);
+        let mut stream = crate::DeltaStream::new(&game);
+        game.click_tile(0, 0).unwrap();
+        let delta = stream.poll(&game).unwrap();
+        assert_eq!(
+            ServerMessage::board_diff(&game, &delta),
+            ServerMessage::BoardDiff {
+                seq: 1,
+                changes: vec![
+                    VisibleChange {
+                        x: 0,
+                        y: 0,
+                        tile: VisibleTile::Number(0)
+                    },
+                    VisibleChange {
+                        x: 1,
+                        y: 0,
+                        tile: VisibleTile::Number(1)
+                    },
+                ],
+                state: GameState::Won,
+            }
+        );
+        assert_eq!(
+            ServerMessage::game_over(&game),
+            Some(ServerMessage::GameOver {
+                state: GameState::Won,
+                exploded: None
+            })
+        );
+        assert_eq!(
+            ServerMessage::game_over(&Minesweeper::new_rect(3, 1, vec![(2, 0)])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_coop_sessions_share_updates() {
+        let broker = Arc::new(Broker::new());
+        let output = SharedOutput::default();
+        let (host, input) = channel();
+        let host_thread = {
+            let (broker, output) = (broker.clone(), output.clone());
+            thread::spawn(move || broker.serve(input, output).unwrap())
+        };
+
+        // The first session creates the game and waits for its partner.
+        host.send(request(&Request::NewGame {
+            config: CONFIG.to_string(),
+            first_click: (0, 0),
+            seed: Some(7),
+        }))
+        .unwrap();
+        while output.responses().is_empty() {
+            thread::yield_now();
+        }
+        let game = match output.responses()[0] {
+            Response::Created { game } => game,
+            ref other => panic!("unexpected {:?}", other),
+        };
+
+        let guest_input = [
+            request(&Request::Join { game }),
+            request(&Request::Move {
+                game,
+                mv: "flag 0 0".to_string(),
+            }),
+            r#"{"type":"move","game":99,"move":"flag 0 0"}"#.to_string(),
+            "not json".to_string(),
+        ]
+        .join("\n");
+        let guest_output = SharedOutput::default();
+        broker
+            .serve(Cursor::new(guest_input), guest_output.clone())
+            .unwrap();
+
+        let guest = guest_output.responses();
+        assert_eq!(guest[0], Response::Joined { game });
+        assert!(matches!(
+            &guest[1],
+            Response::Update { mv: Some(mv), error: Some(_), .. } if mv == "flag 0 0"
+        ));
+        // Sessions only play on games they joined.
+        assert_eq!(
+            guest[2],
+            Response::Error {
+                message: "Join game 99 before playing or viewing it".to_string()
+            }
+        );
+        assert!(matches!(&guest[3], Response::Error { .. }));
+
+        // The host saw the guest's move as well.
+        assert_eq!(output.responses()[1], guest[1]);
+        drop(host);
+        host_thread.join().unwrap();
+    }
+
+    #[test]
+    fn test_bad_configs_are_refused() {
+        let broker = Broker::new();
+        let input = [
+            "width=9\nheight=9\nmines=4\nmine-count=5-3",
+            "width=18446744073709551615\nheight=2\nmines=1",
+            "width=2000\nheight=2000\nmines=1",
+        ]
+        .map(|config| {
+            request(&Request::NewGame {
+                config: config.to_string(),
+                first_click: (0, 0),
+                seed: Some(1),
+            })
+        })
+        .join("\n");
+        let output = SharedOutput::default();
+        broker.serve(Cursor::new(input), output.clone()).unwrap();
+        let responses = output.responses();
+        assert_eq!(responses.len(), 3);
+        assert!(responses
+            .iter()
+            .all(|response| matches!(response, Response::Error { .. })));
+    }
+
+    #[test]
+    fn test_racers_get_the_same_board() {
+        let broker = Broker::new();
+        let new_game = request(&Request::NewGame {
+            config: CONFIG.to_string(),
+            first_click: (1, 1),
+            seed: Some(42),
+        });
+        let mut boards = Vec::new();
+        for _ in 0..2 {
+            let output = SharedOutput::default();
+            broker
+                .serve(Cursor::new(new_game.clone()), output.clone())
+                .unwrap();
+            let Response::Created { game } = output.responses()[0] else {
+                panic!("no game created");
+            };
+            // A rival can't play on this board without joining it.
+            let rival = [
+                request(&Request::Move {
+                    game,
+                    mv: "reveal 0 0".to_string(),
+                }),
+                request(&Request::Join { game }),
+                request(&Request::Board { game }),
+            ]
+            .join("\n");
+            let output = SharedOutput::default();
+            broker.serve(Cursor::new(rival), output.clone()).unwrap();
+            let mut responses = output.responses();
+            assert!(
+                matches!(&responses[0], Response::Error { message } if message.starts_with("Join game"))
+            );
+            boards.push(responses.remove(2));
+        }
+        match (&boards[0], &boards[1]) {
+            (
+                Response::Board {
+                    game: a, rows: x, ..
+                },
+                Response::Board {
+                    game: b, rows: y, ..
+                },
+            ) => {
+                assert_ne!(a, b);
+                assert_eq!(x, y);
+                assert_eq!(x.len(), 4);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_regions_keep_sessions_apart() {
+        let broker = Broker::new();
+        let output = SharedOutput::default();
+        let session_input = [
+            request(&Request::NewGame {
+                config: CONFIG.to_string(),
+                first_click: (0, 0),
+                seed: Some(7),
+            }),
+            // Split between this session and one that never showed up.
+            request(&Request::Split {
+                game: 1,
+                sessions: vec![1, 2],
+            }),
+            request(&Request::Move {
+                game: 1,
+                mv: "flag 3 3".to_string(),
+            }),
+            request(&Request::Claim { game: 1, region: 1 }),
+            request(&Request::Move {
+                game: 1,
+                mv: "flag 3 3".to_string(),
+            }),
+        ]
+        .join("\n");
+        broker
+            .serve(Cursor::new(session_input), output.clone())
+            .unwrap();
+        assert_eq!(output.session(), 1);
+
+        let responses = output.responses();
+        assert!(matches!(
+            &responses[1],
+            Response::Regions { regions, .. } if regions[1].owner == Some(2)
+        ));
+        assert!(matches!(
+            &responses[2],
+            Response::Update { error: Some(e), .. } if e == "That tile belongs to player 2"
+        ));
+        assert!(matches!(
+            &responses[3],
+            Response::Regions { regions, .. } if regions[1].owner == Some(1)
+        ));
+        assert!(matches!(
+            &responses[4],
+            Response::Update { error: None, .. }
+        ));
+    }
+
+    #[test]
+    fn test_regions_of_connected_sessions_cant_be_taken() {
+        let broker = Broker::new();
+        let (host_output, guest_output) = (SharedOutput::default(), SharedOutput::default());
+        let mut host = broker.connect(host_output.clone()).unwrap();
+        let mut guest = broker.connect(guest_output.clone()).unwrap();
+        let new_game = request(&Request::NewGame {
+            config: CONFIG.to_string(),
+            first_click: (0, 0),
+            seed: Some(7),
+        });
+        broker.receive(&mut host, &new_game).unwrap();
+        let split = request(&Request::Split {
+            game: 1,
+            sessions: vec![host.session(), guest.session()],
+        });
+        broker.receive(&mut host, &split).unwrap();
+
+        let claim = request(&Request::Claim { game: 1, region: 0 });
+        for line in [request(&Request::Join { game: 1 }), split, claim.clone()] {
+            broker.receive(&mut guest, &line).unwrap();
+        }
+        broker.disconnect(host);
+        broker.receive(&mut guest, &claim).unwrap();
+
+        let responses = guest_output.responses();
+        assert!(matches!(
+            &responses[1],
+            Response::Error { message } if message == "Only the session that created game 1 can split it"
+        ));
+        assert!(matches!(
+            &responses[2],
+            Response::Error { message } if message == "Region 0 belongs to session 1, who is still here"
+        ));
+        // Once the host has gone, their region is free to take.
+        assert!(matches!(
+            &responses[3],
+            Response::Regions { regions, .. } if regions[0].owner == Some(2)
+        ));
+    }
+
+    #[test]
+    fn test_policy_refuses_floods() {
+        let broker = Broker::with_policy(crate::RateLimit::new(0.001, 2));
+        let flag = request(&Request::Move {
+            game: 1,
+            mv: "flag 3 3".to_string(),
+        });
+        let session_input = [
+            request(&Request::NewGame {
+                config: CONFIG.to_string(),
+                first_click: (0, 0),
+                seed: Some(7),
+            }),
+            flag.clone(),
+            flag.clone(),
+            flag,
+        ]
+        .join("\n");
+        let output = SharedOutput::default();
+        broker
+            .serve(Cursor::new(session_input), output.clone())
+            .unwrap();
+
+        let responses = output.responses();
+        assert!(matches!(
+            &responses[1],
+            Response::Update { error: None, .. }
+        ));
+        assert!(matches!(
+            &responses[2],
+            Response::Update { error: None, .. }
+        ));
+        assert_eq!(
+            responses[3],
+            Response::Error {
+                message: "Too many moves, slow down".to_string()
+            }
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use crate::Minesweeper;
+
+/// What uncovered a tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RevealSource {
+    /// Clicked directly, including the first click.
+    Click,
+    /// Opened by the cascade from the zero at `origin`.
+    Flood { origin: (usize, usize) },
+    /// Revealed by chording the number at `origin`.
+    Chord { origin: (usize, usize) },
+    /// Revealed by [`Minesweeper::auto_finish`].
+    AutoFinish,
+    /// Picked by [`Minesweeper::reveal_random_safe`].
+    Assist,
+}
+
+/// How and when a tile was uncovered, from
+/// [`Minesweeper::reveal_provenance`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Provenance {
+    pub source: RevealSource,
+    /// Which revealing move it was, counting from 1. Tiles with the same
+    /// number were uncovered by the same move.
+    pub move_number: usize,
+}
+
+impl Minesweeper {
+    /// Turns reveal tracking on or off. While it is on, every tile a move
+    /// uncovers is recorded with what uncovered it, for efficiency
+    /// analysis, replays and showing what a click revealed. Off by default
+    /// so moves stay free of allocation; turning it off clears it.
+    pub fn set_reveal_tracking(&mut self, enabled: bool) {
+        if !enabled {
+            self.provenance = None;
+        } else if self.provenance.is_none() {
+            self.provenance = Some(HashMap::new());
+        }
+    }
+
+    pub fn reveal_tracking_enabled(&self) -> bool {
+        self.provenance.is_some()
+    }
+
+    /// How the exposed tile at (x, y) was uncovered. `None` for covered
+    /// tiles, for tiles uncovered while tracking was off, and for tiles
+    /// that started exposed, e.g. from an [`Editor`](crate::Editor) layout
+    /// or a save.
+    pub fn reveal_provenance(&self, x: usize, y: usize) -> Option<Provenance> {
+        self.provenance.as_ref()?.get(&(x, y)).copied()
+    }
+
+    /// Every tile uncovered by the same move as (x, y), sorted, for
+    /// showing what a click revealed. Empty if (x, y) has no provenance.
+    pub fn revealed_with(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
+        let Some(provenance) = self.reveal_provenance(x, y) else {
+            return Vec::new();
+        };
+        let mut tiles: Vec<(usize, usize)> = self
+            .provenance
+            .iter()
+            .flatten()
+            .filter(|(_, other)| other.move_number == provenance.move_number)
+            .map(|(&tile, _)| tile)
+            .collect();
+        tiles.sort_unstable();
+        tiles
+    }
+
+    /// Starts a new revealing move.
+    pub(crate) fn begin_reveal_move(&mut self) {
+        self.reveal_moves += 1;
+    }
+
+    /// Exposes (x, y) as part of the current revealing move.
+    pub(crate) fn expose_from(&mut self, x: usize, y: usize, source: RevealSource) {
+        if self.board[(x, y)].exposed {
+            return;
+        }
+        self.expose(x, y);
+        if let Some(provenance) = &mut self.provenance {
+            provenance.insert(
+                (x, y),
+                Provenance {
+                    source,
+                    move_number: self.reveal_moves,
+                },
+            );
+        }
+    }
+
+    /// Drops the provenance of tiles that are covered again after an undo.
+    pub(crate) fn forget_covered_provenance(&mut self) {
+        let board = &self.board;
+        if let Some(provenance) = &mut self.provenance {
+            provenance.retain(|&(x, y), _| board[(x, y)].exposed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AssistFairness;
+
+    #[test]
+    fn test_provenance_of_clicks_floods_and_chords() {
+        // Mines along the bottom row; the rest opens from one click.
+        let mut game = Minesweeper::new_rect(4, 4, vec![(0, 3), (2, 3)]);
+        game.set_reveal_tracking(true);
+        game.click_tile(3, 0).unwrap();
+        let first = game.reveal_provenance(3, 0).unwrap();
+        assert_eq!(first.source, RevealSource::Click);
+        assert_eq!(
+            game.reveal_provenance(0, 0).unwrap().source,
+            RevealSource::Flood { origin: (3, 0) }
+        );
+        assert_eq!(game.reveal_provenance(0, 3), None);
+        assert_eq!(game.revealed_with(0, 0).len(), 12);
+
+        game.toggle_flag(0, 3).unwrap();
+        game.toggle_flag(2, 3).unwrap();
+        game.chord(2, 2).unwrap();
+        let chorded = game.reveal_provenance(1, 3).unwrap();
+        assert_eq!(chorded.source, RevealSource::Chord { origin: (2, 2) });
+        assert_eq!(chorded.move_number, first.move_number + 1);
+        assert_eq!(game.revealed_with(1, 3), vec![(1, 3), (3, 3)]);
+    }
+
+    #[test]
+    fn test_assists_and_undo() {
+        let mut game = Minesweeper::new_rect(3, 1, vec![(0, 0)]);
+        game.set_history(true);
+        game.set_reveal_tracking(true);
+        game.click_tile(1, 0).unwrap();
+        game.reveal_random_safe(AssistFairness::AnySafe, &mut rand::thread_rng())
+            .unwrap();
+        assert_eq!(
+            game.reveal_provenance(2, 0).unwrap().source,
+            RevealSource::Assist
+        );
+
+        game.undo().unwrap();
+        assert_eq!(game.reveal_provenance(2, 0), None);
+        assert!(game.reveal_provenance(1, 0).is_some());
+
+        game.set_reveal_tracking(false);
+        assert_eq!(game.reveal_provenance(1, 0), None);
+        assert!(game.revealed_with(1, 0).is_empty());
+    }
+}
@@ -0,0 +1,528 @@
+use std::borrow::Cow;
+
+use crate::{Adjacency, CoordinateStyle, Glyph, Minesweeper, Theme, ThemeColor, Tile};
+
+/// How much of the board a renderer shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum View {
+    /// What the player can see.
+    #[default]
+    Player,
+    /// What every tile holds, as if it were revealed, for debugging.
+    Full,
+}
+
+impl View {
+    /// `tile` as this view shows it.
+    pub fn show(self, tile: &Tile) -> Cow<'_, Tile> {
+        match self {
+            View::Player => Cow::Borrowed(tile),
+            View::Full => {
+                let mut tile = tile.clone();
+                tile.exposed = true;
+                Cow::Owned(tile)
+            }
+        }
+    }
+}
+
+/// The part of a board on screen and how far it is zoomed out, for boards
+/// too big to show whole.
+///
+/// At zoom 1 every cell is a tile; at zoom k each cell sums up a k x k
+/// block of tiles as a [`BlockSummary`]. The corner is kept on a block
+/// boundary so blocks don't shift as the view scrolls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    /// The tile in the top-left corner.
+    pub x: usize,
+    pub y: usize,
+    /// How many cells fit on screen across and down.
+    pub columns: usize,
+    pub rows: usize,
+    /// Tiles per cell side.
+    pub zoom: usize,
+}
+
+impl Viewport {
+    /// A view of `columns` x `rows` cells at full detail, from the top left.
+    pub fn new(columns: usize, rows: usize) -> Self {
+        Viewport {
+            x: 0,
+            y: 0,
+            columns,
+            rows,
+            zoom: 1,
+        }
+    }
+
+    /// The smallest zoom that fits a `width` x `height` board on screen.
+    pub fn fit_zoom(&self, width: usize, height: usize) -> usize {
+        let across = width.div_ceil(self.columns.max(1));
+        let down = height.div_ceil(self.rows.max(1));
+        across.max(down).max(1)
+    }
+
+    /// Zooms to `zoom` (at least 1, at most [`Viewport::fit_zoom`]),
+    /// keeping the tile at `focus` on screen.
+    pub fn set_zoom(&mut self, zoom: usize, focus: (usize, usize), width: usize, height: usize) {
+        self.zoom = zoom.clamp(1, self.fit_zoom(width, height));
+        self.follow(focus, width, height);
+    }
+
+    /// Scrolls as little as possible to bring the tile at `focus` on screen,
+    /// without showing past the board's far edges when it can be helped.
+    pub fn follow(&mut self, (x, y): (usize, usize), width: usize, height: usize) {
+        let zoom = self.zoom.max(1);
+        let scroll = |start: usize, at: usize, cells: usize, tiles: usize| {
+            let cells = cells.max(1);
+            let (block, mut first) = (at / zoom, start / zoom);
+            if block < first {
+                first = block;
+            } else if block >= first + cells {
+                first = block + 1 - cells;
+            }
+            first.min(tiles.div_ceil(zoom).saturating_sub(cells)) * zoom
+        };
+        self.x = scroll(self.x, x, self.columns, width);
+        self.y = scroll(self.y, y, self.rows, height);
+    }
+
+    /// The cell showing the tile at (x, y), if it is on screen.
+    pub fn cell(&self, (x, y): (usize, usize)) -> Option<(usize, usize)> {
+        let zoom = self.zoom.max(1);
+        if x < self.x || y < self.y {
+            return None;
+        }
+        let cell = ((x - self.x) / zoom, (y - self.y) / zoom);
+        (cell.0 < self.columns && cell.1 < self.rows).then_some(cell)
+    }
+}
+
+/// What a block of tiles holds, for drawing it as one cell when zoomed
+/// out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BlockSummary {
+    /// Tiles in the block; fewer than k x k at the board's far edges.
+    pub tiles: usize,
+    pub covered: usize,
+    pub flagged: usize,
+    /// Mines on show, e.g. the one that ended the game.
+    pub mines: usize,
+}
+
+impl BlockSummary {
+    /// The `size` x `size` block with its top-left tile at (x, y), as
+    /// `view` shows it.
+    pub fn of(game: &Minesweeper, view: View, (x, y): (usize, usize), size: usize) -> Self {
+        let mut summary = BlockSummary::default();
+        for bx in x..(x + size).min(game.get_width()) {
+            for by in y..(y + size).min(game.get_height()) {
+                let tile = view.show(&game.board[(bx, by)]);
+                summary.tiles += 1;
+                if tile.exposed {
+                    summary.mines += usize::from(tile.is_bomb());
+                } else {
+                    summary.covered += 1;
+                    summary.flagged += usize::from(tile.flagged);
+                }
+            }
+        }
+        summary
+    }
+
+    /// One character for the block: `*` if a mine is showing, `F` if every
+    /// covered tile is flagged, and otherwise a shade for how much is still
+    /// covered, from `█` (all of it) to `·` (none).
+    pub fn glyph(&self) -> char {
+        if self.mines > 0 {
+            return '*';
+        }
+        if self.flagged > 0 && self.flagged == self.covered {
+            return 'F';
+        }
+        match (self.covered * 3).checked_div(self.tiles) {
+            _ if self.covered == 0 => '·',
+            _ if self.covered == self.tiles => '█',
+            Some(2..) => '▓',
+            Some(1) => '▒',
+            _ => '░',
+        }
+    }
+}
+
+/// Turns a board into text.
+pub trait BoardRenderer {
+    /// `tile` as text, taking up the same number of columns as every other
+    /// tile.
+    fn render_tile(&self, tile: &Tile) -> String;
+
+    /// How many columns [`BoardRenderer::render_tile`] takes up.
+    fn cell_width(&self) -> usize {
+        1
+    }
+
+    /// The spaces to start row `y` with. Hex boards shift each row half a
+    /// tile right of the one above, so the board is drawn as a rhombus.
+    fn row_indent(&self, adjacency: Adjacency, y: usize) -> String {
+        match adjacency {
+            Adjacency::Hex => " ".repeat(y * (self.cell_width() + 1) / 2),
+            _ => String::new(),
+        }
+    }
+
+    /// A number with more flags around it than it shows, while the flag
+    /// check assist is on. Drawn like any other tile unless overridden.
+    fn render_conflict(&self, tile: &Tile) -> String {
+        self.render_tile(tile)
+    }
+
+    /// A zoomed-out cell for `block`, as wide as a tile.
+    fn render_block(&self, block: &BlockSummary) -> String {
+        format!("{:>width$}", block.glyph(), width = self.cell_width())
+    }
+
+    /// The part of the board in `viewport`, one line per row of cells
+    /// separated by spaces: tiles at zoom 1 and block summaries beyond.
+    fn render_viewport(&self, game: &Minesweeper, view: View, viewport: &Viewport) -> String {
+        let zoom = viewport.zoom.max(1);
+        let (width, height) = (game.get_width(), game.get_height());
+        let mut text = String::new();
+        for row in 0..viewport.rows {
+            let y = viewport.y + row * zoom;
+            if y >= height {
+                break;
+            }
+            if zoom == 1 {
+                text.push_str(&self.row_indent(game.get_adjacency(), y));
+            }
+            let cells: Vec<String> = (0..viewport.columns)
+                .map(|column| viewport.x + column * zoom)
+                .take_while(|&x| x < width)
+                .map(|x| match zoom {
+                    1 => render_cell(self, game, view, x, y),
+                    _ => self.render_block(&BlockSummary::of(game, view, (x, y), zoom)),
+                })
+                .collect();
+            text.push_str(&cells.join(" "));
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Like [`BoardRenderer::render`], with the columns labelled above the
+    /// board and the rows down its left side, in `style`.
+    fn render_labeled(&self, game: &Minesweeper, view: View, style: CoordinateStyle) -> String {
+        let (width, height) = (game.get_width(), game.get_height());
+        let margin = row_label_width(style, height);
+        let mut text = column_header(style, 0..width, self.cell_width(), margin);
+        for y in 0..height {
+            text.push_str(&format!("{:>margin$}", style.row_label(y)));
+            text.push_str(&self.row_indent(game.get_adjacency(), y));
+            for x in 0..width {
+                text.push(' ');
+                text.push_str(&render_cell(self, game, view, x, y));
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// The board, one line per row with the tiles separated by spaces.
+    fn render(&self, game: &Minesweeper, view: View) -> String {
+        let (width, height) = (game.get_width(), game.get_height());
+        let mut text = String::new();
+        for y in 0..height {
+            text.push_str(&self.row_indent(game.get_adjacency(), y));
+            let row: Vec<String> = (0..width)
+                .map(|x| render_cell(self, game, view, x, y))
+                .collect();
+            text.push_str(&row.join(" "));
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// How many columns the widest of a board's first `rows` row labels takes.
+pub fn row_label_width(style: CoordinateStyle, rows: usize) -> usize {
+    (0..rows)
+        .map(|y| style.row_label(y).len())
+        .max()
+        .unwrap_or(0)
+}
+
+/// Labels for `columns` of cells `cell_width` wide, each drawn after a
+/// space, with `margin` columns left free for the row labels. Labels wider
+/// than a cell are written downwards, one character per line, so they
+/// stay lined up with their column.
+pub fn column_header(
+    style: CoordinateStyle,
+    columns: impl Iterator<Item = usize>,
+    cell_width: usize,
+    margin: usize,
+) -> String {
+    let labels: Vec<String> = columns.map(|x| style.column_label(x)).collect();
+    let depth = labels.iter().map(String::len).max().unwrap_or(0);
+    let mut text = String::new();
+    for line in 0..depth {
+        text.push_str(&" ".repeat(margin));
+        for label in &labels {
+            // Bottom-aligned, so the last character sits by the board.
+            let skip = depth - label.len();
+            let c = match line.checked_sub(skip) {
+                Some(at) => label.as_bytes()[at] as char,
+                None => ' ',
+            };
+            text.push_str(&format!(" {:>cell_width$}", c));
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// The tile at (x, y), highlighted if the flag check flags it.
+fn render_cell<R: BoardRenderer + ?Sized>(
+    renderer: &R,
+    game: &Minesweeper,
+    view: View,
+    x: usize,
+    y: usize,
+) -> String {
+    let tile = view.show(&game.board[(x, y)]);
+    if view == View::Player && game.flag_check_enabled() && game.is_over_flagged(x, y) {
+        renderer.render_conflict(&tile)
+    } else {
+        renderer.render_tile(&tile)
+    }
+}
+
+/// Plain text in a [`Theme`]'s glyphs, ignoring its colours.
+#[derive(Debug, Clone, Default)]
+pub struct TextRenderer {
+    theme: Theme,
+}
+
+impl TextRenderer {
+    pub fn new(theme: Theme) -> Self {
+        TextRenderer { theme }
+    }
+
+    /// ASCII only: `#` covered, `F` flagged, `?` question mark, `*` mine,
+    /// `.` zero.
+    pub fn ascii() -> Self {
+        Self::new(Theme::builtin("monochrome").expect("monochrome is built in"))
+    }
+
+    /// Emoji tiles, two columns each.
+    pub fn emoji() -> Self {
+        Self::new(Theme::builtin("emoji").expect("emoji is built in"))
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+}
+
+impl BoardRenderer for TextRenderer {
+    fn render_tile(&self, tile: &Tile) -> String {
+        self.theme.pad(self.theme.glyph(tile))
+    }
+
+    fn cell_width(&self) -> usize {
+        self.theme.cell_width()
+    }
+}
+
+/// Text coloured with ANSI escape codes, for terminals.
+#[derive(Debug, Clone, Default)]
+pub struct AnsiRenderer {
+    theme: Theme,
+}
+
+impl AnsiRenderer {
+    pub fn new(theme: Theme) -> Self {
+        AnsiRenderer { theme }
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+}
+
+impl BoardRenderer for AnsiRenderer {
+    /// The padded glyph, wrapped in escape codes when it is coloured or
+    /// bold. The codes take up no columns.
+    fn render_tile(&self, tile: &Tile) -> String {
+        let glyph = self.theme.glyph(tile);
+        let text = self.theme.pad(glyph);
+        match sgr_codes(glyph) {
+            Some(codes) => format!("\x1b[{}m{}\x1b[0m", codes, text),
+            None => text,
+        }
+    }
+
+    fn cell_width(&self) -> usize {
+        self.theme.cell_width()
+    }
+
+    /// The padded glyph in white on red.
+    fn render_conflict(&self, tile: &Tile) -> String {
+        let glyph = self.theme.glyph(tile);
+        format!("\x1b[1;97;41m{}\x1b[0m", self.theme.pad(glyph))
+    }
+}
+
+/// The Select Graphic Rendition parameters for `glyph`'s style, if it has
+/// one.
+fn sgr_codes(glyph: &Glyph) -> Option<String> {
+    let mut codes = Vec::new();
+    if glyph.bold {
+        codes.push("1");
+    }
+    if let Some(color) = glyph.color {
+        codes.push(match color {
+            ThemeColor::Black => "30",
+            ThemeColor::DarkRed => "31",
+            ThemeColor::DarkGreen => "32",
+            ThemeColor::DarkYellow => "33",
+            ThemeColor::DarkBlue => "34",
+            ThemeColor::DarkMagenta => "35",
+            ThemeColor::DarkCyan => "36",
+            ThemeColor::Gray => "37",
+            ThemeColor::DarkGray => "90",
+            ThemeColor::Red => "91",
+            ThemeColor::Green => "92",
+            ThemeColor::Yellow => "93",
+            ThemeColor::Blue => "94",
+            ThemeColor::Magenta => "95",
+            ThemeColor::Cyan => "96",
+            ThemeColor::White => "97",
+        });
+    }
+    (!codes.is_empty()).then(|| codes.join(";"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game() -> Minesweeper {
+        let mut game = Minesweeper::new_rect(4, 2, vec![(0, 0)]);
+        game.click_tile(1, 0).unwrap();
+        game.toggle_flag(0, 0).unwrap();
+        game
+    }
+
+    #[test]
+    fn test_ascii_views() {
+        let renderer = TextRenderer::ascii();
+        assert_eq!(renderer.render(&game(), View::Player), "F 1 # #\n# # # #\n");
+        assert_eq!(renderer.render(&game(), View::Full), "* 1 . .\n1 1 . .\n");
+    }
+
+    #[test]
+    fn test_labeled_axes() {
+        let renderer = TextRenderer::ascii();
+        assert_eq!(
+            renderer.render_labeled(&game(), View::Player, CoordinateStyle::Chess),
+            "  A B C D\n1 F 1 # #\n2 # # # #\n"
+        );
+
+        let game = Minesweeper::new_rect(12, 1, vec![]);
+        let numeric = renderer.render_labeled(&game, View::Player, CoordinateStyle::Numeric);
+        let lines: Vec<&str> = numeric.lines().collect();
+        assert_eq!(lines[0], "                      1 1");
+        assert_eq!(lines[1], "  0 1 2 3 4 5 6 7 8 9 0 1");
+        assert_eq!(lines[2].len(), lines[1].len());
+    }
+
+    #[test]
+    fn test_hex_rows_are_shifted() {
+        let game = Minesweeper::new_rect_with_adjacency(3, 3, vec![(2, 2)], Adjacency::Hex);
+        assert_eq!(
+            TextRenderer::ascii().render(&game, View::Full),
+            ". . .\n . . 1\n  . 1 *\n"
+        );
+        let emoji = TextRenderer::emoji().render(&game, View::Player);
+        let indents: Vec<usize> = emoji
+            .lines()
+            .map(|line| line.len() - line.trim_start().len())
+            .collect();
+        assert_eq!(indents, vec![0, 1, 3]);
+    }
+
+    #[test]
+    fn test_viewport_zooms_into_blocks() {
+        // A wall of mines down x = 7 keeps the opening on the left.
+        let wall: Vec<(usize, usize)> = (0..6).map(|y| (7, y)).collect();
+        let mut game = Minesweeper::new_rect(10, 6, wall.clone());
+        game.click_tile(0, 0).unwrap();
+        for y in 3..6 {
+            game.toggle_flag(9, y).unwrap();
+        }
+
+        let mut viewport = Viewport::new(4, 2);
+        assert_eq!(viewport.fit_zoom(10, 6), 3);
+        let renderer = TextRenderer::ascii();
+        assert_eq!(
+            renderer.render_viewport(&game, View::Player, &viewport),
+            ". . . .\n. . . .\n"
+        );
+
+        viewport.follow((9, 5), 10, 6);
+        assert_eq!((viewport.x, viewport.y), (6, 4));
+        assert_eq!(viewport.cell((9, 5)), Some((3, 1)));
+        assert_eq!(viewport.cell((0, 0)), None);
+
+        viewport.set_zoom(8, (9, 5), 10, 6);
+        assert_eq!(viewport.zoom, 3);
+        assert_eq!((viewport.x, viewport.y), (0, 0));
+        assert_eq!(
+            renderer.render_viewport(&game, View::Player, &viewport),
+            "· · ▓ █\n· · ▓ F\n"
+        );
+
+        let covered = Minesweeper::new_rect(10, 6, wall);
+        assert_eq!(
+            renderer.render_viewport(&covered, View::Player, &viewport),
+            "█ █ █ █\n█ █ █ █\n"
+        );
+        assert_eq!(
+            renderer.render_viewport(&covered, View::Full, &viewport),
+            "· · * ·\n· · * ·\n"
+        );
+    }
+
+    #[test]
+    fn test_ansi_colours() {
+        let renderer = AnsiRenderer::default();
+        let text = renderer.render(&game(), View::Player);
+        assert!(text.starts_with("\x1b[91mF\x1b[0m \x1b[94m1\x1b[0m # #\n"));
+
+        let high_contrast = AnsiRenderer::new(Theme::builtin("high-contrast").unwrap());
+        assert_eq!(
+            high_contrast.render_tile(&Tile::new()),
+            "\x1b[1;97m#\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_flag_check_highlights_over_flagged_numbers() {
+        let mut game = game();
+        game.toggle_flag(0, 1).unwrap();
+        let renderer = AnsiRenderer::default();
+        assert!(renderer
+            .render(&game, View::Player)
+            .starts_with("\x1b[91mF\x1b[0m \x1b[94m1\x1b[0m"));
+
+        game.set_flag_check(true);
+        assert!(renderer
+            .render(&game, View::Player)
+            .starts_with("\x1b[91mF\x1b[0m \x1b[1;97;41m1\x1b[0m"));
+        // Plain text has nothing to highlight with.
+        assert!(TextRenderer::ascii()
+            .render(&game, View::Player)
+            .starts_with("F 1 # #\n"));
+    }
+}
@@ -0,0 +1,266 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::flood::FloodScratch;
+use crate::{GameState, Minesweeper};
+
+/// Structural statistics of a board layout, from
+/// [`Minesweeper::board_report`]. Useful for tuning generators and picking
+/// puzzles; nothing in it depends on how far the game has been played.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardReport {
+    pub width: usize,
+    pub height: usize,
+    pub mines: usize,
+    pub three_bv: usize,
+    /// How many safe tiles show each number, indexed by the number.
+    pub number_histogram: Vec<usize>,
+    /// The tiles each opening reveals, zeros and their bordering numbers
+    /// together, largest first.
+    pub opening_sizes: Vec<usize>,
+    /// The most mines connected to each other through neighbouring tiles.
+    pub largest_mine_cluster: usize,
+    /// How many times a solver starting in the largest opening has to
+    /// guess to clear the board, counting the first click when there is
+    /// no opening. Zero means the board is solvable without guessing.
+    pub forced_guesses: usize,
+}
+
+/// The headline difficulty numbers of a board, from
+/// [`Minesweeper::board_metrics`]. Unlike [`BoardReport`] these are kept up
+/// to date by the game and cost nothing to read, so a generator can check
+/// thousands of candidate boards against a target band.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoardMetrics {
+    /// The fewest clicks that clear the board.
+    pub three_bv: usize,
+    /// Connected regions of zeros, each cleared by one click.
+    pub openings: usize,
+    /// Mines per tile, from 0 to 1.
+    pub mine_density: f64,
+}
+
+impl BoardMetrics {
+    /// The speed of a win that took `time`, the usual speedrunning score.
+    /// `None` for a zero time.
+    pub fn three_bv_per_second(&self, time: Duration) -> Option<f64> {
+        let secs = time.as_secs_f64();
+        (secs > 0.0).then(|| self.three_bv as f64 / secs)
+    }
+}
+
+impl fmt::Display for BoardReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}x{} board, {} mines, 3BV {}",
+            self.width, self.height, self.mines, self.three_bv
+        )?;
+        let numbers: Vec<String> = self
+            .number_histogram
+            .iter()
+            .enumerate()
+            .map(|(n, count)| format!("{}:{}", n, count))
+            .collect();
+        writeln!(f, "numbers: {}", numbers.join(" "))?;
+        let sizes: Vec<String> = self.opening_sizes.iter().map(usize::to_string).collect();
+        writeln!(
+            f,
+            "openings: {} ({})",
+            self.opening_sizes.len(),
+            sizes.join(", ")
+        )?;
+        writeln!(f, "largest mine cluster: {}", self.largest_mine_cluster)?;
+        write!(f, "forced guesses: {}", self.forced_guesses)
+    }
+}
+
+impl Minesweeper {
+    /// 3BV, openings and mine density: see [`BoardMetrics`].
+    pub fn board_metrics(&self) -> BoardMetrics {
+        BoardMetrics {
+            three_bv: self.three_bv(),
+            openings: self.openings(),
+            mine_density: self.bomb_count as f64 / (self.width * self.height) as f64,
+        }
+    }
+
+    /// Measures the layout: see [`BoardReport`].
+    pub fn board_report(&self) -> BoardReport {
+        let (width, height) = (self.width, self.height);
+        let tile = |x: usize, y: usize| &self.board[(x, y)];
+
+        let mut number_histogram = Vec::new();
+        for x in 0..width {
+            for y in 0..height {
+                if let Some(n) = tile(x, y).get_number() {
+                    let n = n as usize;
+                    if number_histogram.len() <= n {
+                        number_histogram.resize(n + 1, 0);
+                    }
+                    number_histogram[n] += 1;
+                }
+            }
+        }
+
+        let mut scratch = FloodScratch::new(width * height, width * height);
+        let mut in_opening = vec![false; width * height];
+        let mut openings: Vec<(usize, (usize, usize))> = Vec::new();
+        for x in 0..width {
+            for y in 0..height {
+                if tile(x, y).get_number() != Some(0) || in_opening[x * height + y] {
+                    continue;
+                }
+                scratch.fill(
+                    (x, y),
+                    (width, height),
+                    self.adjacency,
+                    self.topology,
+                    tile,
+                    |tile| !tile.is_bomb(),
+                );
+                for &(rx, ry) in scratch.region() {
+                    if tile(rx, ry).get_number() == Some(0) {
+                        in_opening[rx * height + ry] = true;
+                    }
+                }
+                openings.push((scratch.region().len(), (x, y)));
+            }
+        }
+        // Largest first; the first of equal openings found wins.
+        openings.sort_by_key(|&(size, _)| std::cmp::Reverse(size));
+
+        BoardReport {
+            width,
+            height,
+            mines: self.bomb_count,
+            three_bv: self.three_bv(),
+            number_histogram,
+            opening_sizes: openings.iter().map(|&(size, _)| size).collect(),
+            largest_mine_cluster: self.largest_mine_cluster(),
+            forced_guesses: self.forced_guesses(openings.first().map(|&(_, start)| start)),
+        }
+    }
+
+    fn largest_mine_cluster(&self) -> usize {
+        let (width, height) = (self.width, self.height);
+        let mut seen = vec![false; width * height];
+        let mut stack = Vec::new();
+        let mut largest = 0;
+
+        for (x, y) in self.mine_positions() {
+            if seen[x * height + y] {
+                continue;
+            }
+            seen[x * height + y] = true;
+            stack.push((x, y));
+            let mut size = 0;
+            while let Some((cx, cy)) = stack.pop() {
+                size += 1;
+                for (nx, ny) in self
+                    .adjacency
+                    .neighbors_in(self.topology, cx, cy, width, height)
+                {
+                    if self.board[(nx, ny)].is_bomb() && !seen[nx * height + ny] {
+                        seen[nx * height + ny] = true;
+                        stack.push((nx, ny));
+                    }
+                }
+            }
+            largest = largest.max(size);
+        }
+        largest
+    }
+
+    /// Plays a fresh copy of the board with [`Minesweeper::analyze`],
+    /// counting the times it runs out of deductions and has to be handed a
+    /// safe tile.
+    fn forced_guesses(&self, start: Option<(usize, usize)>) -> usize {
+        let mut game = Minesweeper::new_rect_with_topology(
+            self.width,
+            self.height,
+            self.mine_positions(),
+            self.adjacency,
+            self.topology,
+        );
+        if let Some((x, y)) = start {
+            let _ = game.click_tile(x, y);
+        }
+
+        let mut guesses = 0;
+        while game.game_state == GameState::InProgress {
+            let safe = game.analyze().safe;
+            if safe.is_empty() {
+                guesses += 1;
+                let guess = (0..self.width)
+                    .flat_map(|x| (0..self.height).map(move |y| (x, y)))
+                    .find(|&(x, y)| {
+                        let tile = &game.board[(x, y)];
+                        !tile.exposed && !tile.is_bomb()
+                    });
+                match guess {
+                    Some((x, y)) => {
+                        let _ = game.click_tile(x, y);
+                    }
+                    None => break,
+                }
+            }
+            for (x, y) in safe {
+                // Earlier reveals in the batch may have opened it already.
+                let _ = game.click_tile(x, y);
+            }
+        }
+        guesses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_of_a_solvable_board() {
+        let game = Minesweeper::new(4, vec![(0, 0), (0, 1)]);
+        let report = game.board_report();
+
+        assert_eq!(report.mines, 2);
+        assert_eq!(report.number_histogram, vec![10, 2, 2]);
+        assert_eq!(report.opening_sizes, vec![14]);
+        assert_eq!(report.largest_mine_cluster, 2);
+        assert_eq!(report.forced_guesses, 0);
+        assert_eq!(
+            report.to_string(),
+            "4x4 board, 2 mines, 3BV 1\n\
+             numbers: 0:10 1:2 2:2\n\
+             openings: 1 (14)\n\
+             largest mine cluster: 2\n\
+             forced guesses: 0"
+        );
+    }
+
+    #[test]
+    fn test_board_metrics() {
+        // Two openings, plus three numbers neither of them reaches.
+        let game = Minesweeper::new(4, vec![(0, 0), (2, 2)]);
+        let metrics = game.board_metrics();
+        assert_eq!(metrics.mine_density, 0.125);
+        assert_eq!(metrics.openings, 2);
+        assert_eq!(metrics.three_bv, 5);
+        assert_eq!(
+            metrics.three_bv_per_second(Duration::from_millis(500)),
+            Some(metrics.three_bv as f64 * 2.0)
+        );
+        assert_eq!(metrics.three_bv_per_second(Duration::ZERO), None);
+    }
+
+    #[test]
+    fn test_fifty_fifty_forces_a_guess() {
+        // Two mines on a 2x2 board: no number can tell them apart.
+        let game = Minesweeper::new(2, vec![(0, 0), (1, 1)]);
+        let report = game.board_report();
+        assert!(report.opening_sizes.is_empty());
+        assert_eq!(report.largest_mine_cluster, 2);
+        // The first click, then the coin flip between the two remaining tiles.
+        assert_eq!(report.forced_guesses, 2);
+    }
+}
@@ -0,0 +1,446 @@
+use std::path::Path;
+use std::time::Duration;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::board::Board;
+use crate::{
+    Adjacency, FlagRule, GameConfig, GameState, Guess, MineCount, Minesweeper, Move, Provenance,
+    Tile, Topology, WinCondition, ZeroRule,
+};
+
+/// Starts every bincode save, so [`Minesweeper::load_from_file`] can tell
+/// one from JSON whatever the board's size.
+const BINCODE_MAGIC: &[u8] = b"MSWB";
+
+/// The serialized form of a [`Minesweeper`]: the player-visible state.
+/// Caches such as the 3BV tracker are rebuilt on load.
+#[derive(Serialize, Deserialize)]
+struct SavedGame {
+    width: usize,
+    height: usize,
+    adjacency: Adjacency,
+    #[serde(default)]
+    topology: Topology,
+    flag_rule: FlagRule,
+    #[serde(default)]
+    zero_rule: ZeroRule,
+    #[serde(default)]
+    win_condition: WinCondition,
+    #[serde(default)]
+    mine_count: MineCount,
+    #[serde(default)]
+    question_marks: bool,
+    #[serde(default)]
+    auto_flag: bool,
+    /// The generator options and time control as a config string, when
+    /// they can be written as one.
+    config: Option<String>,
+    game_state: GameState,
+    #[serde(default)]
+    exploded: Option<(usize, usize)>,
+    #[serde(default)]
+    lives: usize,
+    #[serde(default)]
+    contained: Vec<(usize, usize)>,
+    #[serde(default)]
+    flag_check: bool,
+    /// Tiles indexed `board[x][y]`.
+    board: Vec<Vec<Tile>>,
+    flags_placed: usize,
+    notes: Vec<((usize, usize), String)>,
+    probability_overlay: bool,
+    #[serde(default)]
+    history: Option<SavedHistory>,
+    /// How each exposed tile was uncovered, sorted by tile, when reveal
+    /// tracking is on.
+    #[serde(default)]
+    provenance: Option<Vec<((usize, usize), Provenance)>>,
+    #[serde(default)]
+    reveal_moves: usize,
+    /// The guess journal, when it is on.
+    #[serde(default)]
+    journal: Option<Vec<Guess>>,
+}
+
+/// Undo/redo history, saved as the board before the oldest move and the
+/// moves since, which are replayed on load.
+#[derive(Serialize, Deserialize)]
+struct SavedHistory {
+    /// Tiles before the oldest move, indexed like [`SavedGame::board`].
+    start: Vec<Vec<Tile>>,
+    flags_placed: usize,
+    lives: usize,
+    /// Each move with when it was made.
+    moves: Vec<(Duration, Move)>,
+    /// Moves that can be redone, most recently undone last.
+    undone: Vec<Move>,
+}
+
+/// Tiles indexed `[x][y]`.
+fn columns(board: &Board) -> Vec<Vec<Tile>> {
+    (0..board.width())
+        .map(|x| (0..board.height()).map(|y| board[(x, y)].clone()).collect())
+        .collect()
+}
+
+impl SavedGame {
+    fn new(game: &Minesweeper) -> Self {
+        let mut notes: Vec<_> = game
+            .notes()
+            .map(|(pos, note)| (pos, note.to_string()))
+            .collect();
+        notes.sort();
+        let provenance = game.provenance.as_ref().map(|provenance| {
+            let mut tiles: Vec<_> = provenance.iter().map(|(&pos, &p)| (pos, p)).collect();
+            tiles.sort_by_key(|&(pos, _)| pos);
+            tiles
+        });
+
+        SavedGame {
+            width: game.width,
+            height: game.height,
+            adjacency: game.adjacency,
+            topology: game.topology,
+            flag_rule: game.flag_rule,
+            zero_rule: game.zero_rule,
+            win_condition: game.win_condition,
+            mine_count: game.mine_count,
+            question_marks: game.question_marks,
+            auto_flag: game.auto_flag,
+            config: game.config().to_config_string().ok(),
+            game_state: game.game_state.clone(),
+            exploded: game.exploded,
+            lives: game.lives,
+            contained: game.contained.clone(),
+            flag_check: game.flag_check,
+            board: columns(&game.board),
+            flags_placed: game.flags_placed,
+            notes,
+            probability_overlay: game.overlay.is_some(),
+            history: game
+                .history_start()
+                .map(|(start, flags_placed, lives)| SavedHistory {
+                    start: columns(start),
+                    flags_placed,
+                    lives,
+                    moves: game.timed_history(),
+                    undone: game.redoable().to_vec(),
+                }),
+            provenance,
+            reveal_moves: game.reveal_moves,
+            journal: game.journal.clone(),
+        }
+    }
+
+    fn restore(self) -> Result<Minesweeper, String> {
+        let fits = |board: &Vec<Vec<Tile>>| {
+            board.len() == self.width && board.iter().all(|row| row.len() == self.height)
+        };
+        if !fits(&self.board) || self.history.as_ref().is_some_and(|h| !fits(&h.start)) {
+            return Err("Saved board does not match its dimensions".to_string());
+        }
+        let on_board = |&(x, y): &(usize, usize)| x < self.width && y < self.height;
+        let positions = self
+            .exploded
+            .iter()
+            .chain(&self.contained)
+            .chain(self.notes.iter().map(|(pos, _)| pos))
+            .chain(self.provenance.iter().flatten().map(|(pos, _)| pos));
+        if let Some((x, y)) = positions.copied().find(|pos| !on_board(pos)) {
+            return Err(format!("Saved tile ({}, {}) is off the board", x, y));
+        }
+
+        let mut mines = Vec::new();
+        for (x, row) in self.board.iter().enumerate() {
+            for (y, tile) in row.iter().enumerate() {
+                if tile.is_bomb() {
+                    mines.push((x, y));
+                }
+            }
+        }
+
+        let mut game = Minesweeper::new_rect_with_topology(
+            self.width,
+            self.height,
+            mines,
+            self.adjacency,
+            self.topology,
+        );
+        if let Some(config) = self.config {
+            let config = GameConfig::from_config_string(&config)?;
+            game.generator = config.generator;
+            game.time_control = config.time_control;
+        }
+        game.flag_rule = self.flag_rule;
+        game.set_zero_rule(self.zero_rule);
+        game.win_condition = self.win_condition;
+        game.mine_count = self.mine_count;
+        game.question_marks = self.question_marks;
+        game.auto_flag = self.auto_flag;
+        game.flag_check = self.flag_check;
+
+        // With history, the board is rebuilt by replaying the moves, so
+        // they can be undone again.
+        if let Some(history) = self.history {
+            lay_tiles(&mut game, &history.start)?;
+            game.flags_placed = history.flags_placed;
+            game.lives = history.lives;
+            game.replay_history(&history.moves, history.undone)?;
+            let replayed = self.board.iter().enumerate().all(|(x, row)| {
+                row.iter()
+                    .enumerate()
+                    .all(|(y, saved)| saved.exposed == game.board[(x, y)].exposed)
+            });
+            if !replayed {
+                return Err("Saved history does not lead to the saved board".to_string());
+            }
+        }
+        lay_tiles(&mut game, &self.board)?;
+
+        game.game_state = self.game_state;
+        game.exploded = self.exploded;
+        game.lives = self.lives;
+        game.contained = self.contained;
+        game.flags_placed = self.flags_placed;
+        game.notes = self.notes.into_iter().collect();
+        game.set_probability_overlay(self.probability_overlay);
+        game.provenance = self.provenance.map(|tiles| tiles.into_iter().collect());
+        game.reveal_moves = self.reveal_moves;
+        game.journal = self.journal;
+        Ok(game)
+    }
+}
+
+/// Exposes, flags and marks `game`'s tiles as in `board`, checking the
+/// numbers match the mines.
+fn lay_tiles(game: &mut Minesweeper, board: &[Vec<Tile>]) -> Result<(), String> {
+    for (x, row) in board.iter().enumerate() {
+        for (y, saved) in row.iter().enumerate() {
+            if saved.value != game.board[(x, y)].value {
+                return Err("Saved numbers do not match the mines".to_string());
+            }
+            if saved.exposed {
+                game.expose(x, y);
+            }
+            let tile = game.board.tile_mut(x, y);
+            tile.flagged = saved.flagged;
+            tile.marker = saved.marker;
+        }
+    }
+    Ok(())
+}
+
+impl Serialize for Minesweeper {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SavedGame::new(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Minesweeper {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        SavedGame::deserialize(deserializer)?
+            .restore()
+            .map_err(D::Error::custom)
+    }
+}
+
+impl Minesweeper {
+    /// The whole game as JSON, for saving or sending over a network.
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string(self).map_err(|e| e.to_string())
+    }
+
+    /// Restores a game written by [`Minesweeper::to_json`].
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+
+    /// The whole game in bincode's binary form, several times smaller than
+    /// the JSON, after a short header marking it as a binary save.
+    #[cfg(feature = "bincode")]
+    pub fn to_bincode(&self) -> Result<Vec<u8>, String> {
+        let mut bytes = BINCODE_MAGIC.to_vec();
+        bincode::serialize_into(&mut bytes, self).map_err(|e| e.to_string())?;
+        Ok(bytes)
+    }
+
+    /// Restores a game written by [`Minesweeper::to_bincode`].
+    #[cfg(feature = "bincode")]
+    pub fn from_bincode(bytes: &[u8]) -> Result<Self, String> {
+        let bytes = bytes
+            .strip_prefix(BINCODE_MAGIC)
+            .ok_or_else(|| "Not a binary save".to_string())?;
+        bincode::deserialize(bytes).map_err(|e| e.to_string())
+    }
+
+    /// Writes the game to `path` as JSON, so it can be picked up later with
+    /// [`Minesweeper::load_from_file`]. With history on, the moves that can
+    /// be undone or redone and when each was made are kept too, as are
+    /// reveal provenance and the guess journal when they are on.
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        std::fs::write(path, self.to_json()?).map_err(|e| e.to_string())
+    }
+
+    /// [`Minesweeper::save_to_file`] in the binary form of
+    /// [`Minesweeper::to_bincode`].
+    #[cfg(feature = "bincode")]
+    pub fn save_to_binary_file(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        std::fs::write(path, self.to_bincode()?).map_err(|e| e.to_string())
+    }
+
+    /// Loads a game saved with [`Minesweeper::save_to_file`], or with
+    /// [`Minesweeper::save_to_binary_file`] when the `bincode` feature is
+    /// on. Saved history comes back on, and the move clock carries on from
+    /// the last move.
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+        if bytes.starts_with(BINCODE_MAGIC) {
+            #[cfg(feature = "bincode")]
+            return Self::from_bincode(&bytes);
+            #[cfg(not(feature = "bincode"))]
+            return Err("Binary saves need the bincode feature".to_string());
+        }
+        let json = String::from_utf8(bytes).map_err(|e| e.to_string())?;
+        Self::from_json(&json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Marker, Move};
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut game = Minesweeper::new_rect(5, 3, vec![(0, 0), (2, 1), (4, 2)]);
+        game.click_tile(0, 2).unwrap();
+        game.toggle_flag(0, 0).unwrap();
+        game.apply_move(Move::SetMarker(4, 2, Some(Marker::Symbol('?'))))
+            .unwrap();
+        game.set_note(4, 2, "maybe").unwrap();
+
+        let restored = Minesweeper::from_json(&game.to_json().unwrap()).unwrap();
+        for x in 0..5 {
+            for y in 0..3 {
+                assert_eq!(restored.get_tile(x, y), game.get_tile(x, y));
+            }
+        }
+        assert_eq!(restored.board_id(), game.board_id());
+        assert_eq!(restored.safe_tiles_remaining(), game.safe_tiles_remaining());
+        assert_eq!(restored.flags_placed(), 1);
+        assert_eq!(restored.get_note(4, 2), Some("maybe"));
+        assert_eq!(restored.to_json(), game.to_json());
+    }
+
+    #[test]
+    fn test_tampered_numbers_are_rejected() {
+        let game = Minesweeper::new(3, vec![(0, 0)]);
+        let json = game
+            .to_json()
+            .unwrap()
+            .replacen("{\"Number\":1}", "{\"Number\":2}", 1);
+        assert!(Minesweeper::from_json(&json).is_err());
+    }
+
+    #[test]
+    fn test_round_trip_keeps_time_control() {
+        let control = crate::TimeControl::sudden_death(Duration::from_secs(90));
+        let config = GameConfig::new(4, 2).with_time_control(control);
+        let game = Minesweeper::new_with_config_and_seed(&config, (0, 0), 3).unwrap();
+        let restored = Minesweeper::from_json(&game.to_json().unwrap()).unwrap();
+        assert_eq!(restored.config().time_control, Some(control));
+    }
+
+    #[test]
+    fn test_tiles_off_the_board_are_rejected() {
+        let mut game = Minesweeper::new(3, vec![(0, 0)]);
+        game.set_note(1, 2, "here").unwrap();
+        let json = game.to_json().unwrap();
+        for (from, to) in [
+            ("[[1,2],\"here\"]", "[[1,3],\"here\"]"),
+            ("\"exploded\":null", "\"exploded\":[7,0]"),
+            ("\"contained\":[]", "\"contained\":[[0,9]]"),
+        ] {
+            let tampered = json.replacen(from, to, 1);
+            assert_ne!(tampered, json);
+            assert!(Minesweeper::from_json(&tampered).is_err());
+        }
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("minesweeper-{}-{}.json", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_file_round_trip_keeps_history() {
+        let mut game = Minesweeper::new_rect(5, 3, vec![(0, 0), (2, 1), (4, 2)]);
+        let ticks = std::sync::atomic::AtomicU64::new(0);
+        game.set_move_clock(std::sync::Arc::new(move || {
+            Duration::from_secs(ticks.fetch_add(1, std::sync::atomic::Ordering::SeqCst))
+        }));
+        game.click_tile(0, 2).unwrap();
+        game.toggle_flag(0, 0).unwrap();
+        game.click_tile(4, 0).unwrap();
+        game.click_tile(3, 2).unwrap();
+        game.undo().unwrap();
+
+        let path = temp_path("history");
+        game.save_to_file(&path).unwrap();
+        let mut restored = Minesweeper::load_from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(restored.timed_history(), game.timed_history());
+        assert_eq!(restored.to_json(), game.to_json());
+        assert_eq!(restored.redo(), Ok(Move::Reveal(3, 2)));
+        assert_eq!(restored.undo(), Ok(Move::Reveal(3, 2)));
+        assert_eq!(restored.undo(), Ok(Move::Reveal(4, 0)));
+        assert_eq!(restored.undo(), game.undo().and_then(|_| game.undo()));
+        assert!(!restored.get_tile(0, 0).unwrap().flagged);
+        assert!(Minesweeper::load_from_file(&path).is_err());
+    }
+
+    #[test]
+    fn test_round_trip_keeps_provenance_and_journal() {
+        let mut game = Minesweeper::new_rect(5, 3, vec![(0, 0), (2, 1), (4, 2)]);
+        game.set_reveal_tracking(true);
+        game.set_guess_journal(true);
+        game.click_tile(0, 2).unwrap();
+        game.click_tile(4, 0).unwrap();
+
+        let restored = Minesweeper::from_json(&game.to_json().unwrap()).unwrap();
+        assert!(restored.reveal_tracking_enabled());
+        assert_eq!(
+            restored.reveal_provenance(4, 0),
+            game.reveal_provenance(4, 0)
+        );
+        assert_eq!(restored.revealed_with(0, 2), game.revealed_with(0, 2));
+        assert_eq!(restored.guesses(), game.guesses());
+        assert_eq!(restored.to_json(), game.to_json());
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn test_binary_file_round_trip() {
+        let mut game = Minesweeper::new_rect(5, 3, vec![(0, 0), (2, 1), (4, 2)]);
+        game.set_history(true);
+        game.click_tile(0, 2).unwrap();
+        game.toggle_flag(0, 0).unwrap();
+
+        let path = temp_path("binary");
+        game.save_to_binary_file(&path).unwrap();
+        let restored = Minesweeper::load_from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(restored.to_json(), game.to_json());
+        assert!(game.to_bincode().unwrap().len() < game.to_json().unwrap().len());
+
+        // A width of 123 is b'{', which once passed for JSON.
+        let mut game = Minesweeper::new_rect(123, 1, vec![(0, 0)]);
+        game.click_tile(122, 0).unwrap();
+        game.save_to_binary_file(&path).unwrap();
+        let restored = Minesweeper::load_from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(restored.to_json(), game.to_json());
+    }
+}
@@ -0,0 +1,189 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{GameState, Minesweeper, Move, Outcome};
+
+/// Something that happened in a game that a [`Scoring`] rule may reward or
+/// penalise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScoreEvent {
+    /// A click that revealed this many safe tiles.
+    Reveal { tiles: usize },
+    /// A chord that revealed this many safe tiles.
+    Chord { tiles: usize },
+    /// A reveal made without a safe deduction available.
+    Guess,
+    /// A mine was revealed.
+    MineHit,
+}
+
+/// Turns game events into points, so every mode shares one definition of
+/// what a move is worth.
+pub trait Scoring {
+    /// Points for `event`, which happened `elapsed` into the game. May be
+    /// negative for penalties.
+    fn points(&self, event: ScoreEvent, elapsed: Duration) -> i64;
+}
+
+/// Lets a shared rule, such as a [`Variant`](crate::Variant)'s, drive a
+/// [`Score`].
+impl<S: Scoring + ?Sized> Scoring for Arc<S> {
+    fn points(&self, event: ScoreEvent, elapsed: Duration) -> i64 {
+        (**self).points(event, elapsed)
+    }
+}
+
+/// The default scoring rule: points per revealed tile with a bonus for
+/// chords, fixed penalties for guesses and mines, and reveal points that
+/// halve every `half_life` to reward speed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassicScoring {
+    pub points_per_tile: i64,
+    /// Extra points per tile revealed by a chord.
+    pub chord_bonus: i64,
+    pub guess_penalty: i64,
+    pub mine_penalty: i64,
+    /// `None` disables time decay.
+    pub half_life: Option<Duration>,
+}
+
+impl Default for ClassicScoring {
+    fn default() -> Self {
+        ClassicScoring {
+            points_per_tile: 10,
+            chord_bonus: 5,
+            guess_penalty: 25,
+            mine_penalty: 100,
+            half_life: Some(Duration::from_secs(300)),
+        }
+    }
+}
+
+impl ClassicScoring {
+    fn decay(&self, points: i64, elapsed: Duration) -> i64 {
+        match self.half_life {
+            Some(half_life) if !half_life.is_zero() => {
+                let halvings = elapsed.as_secs_f64() / half_life.as_secs_f64();
+                (points as f64 * 0.5f64.powf(halvings)).round() as i64
+            }
+            _ => points,
+        }
+    }
+}
+
+impl Scoring for ClassicScoring {
+    fn points(&self, event: ScoreEvent, elapsed: Duration) -> i64 {
+        match event {
+            ScoreEvent::Reveal { tiles } => {
+                self.decay(self.points_per_tile * tiles as i64, elapsed)
+            }
+            ScoreEvent::Chord { tiles } => self.decay(
+                (self.points_per_tile + self.chord_bonus) * tiles as i64,
+                elapsed,
+            ),
+            ScoreEvent::Guess => -self.guess_penalty,
+            ScoreEvent::MineHit => -self.mine_penalty,
+        }
+    }
+}
+
+/// A running score under some [`Scoring`] rule.
+#[derive(Debug, Clone, Default)]
+pub struct Score<S: Scoring = ClassicScoring> {
+    scoring: S,
+    total: i64,
+}
+
+impl<S: Scoring> Score<S> {
+    pub fn new(scoring: S) -> Self {
+        Score { scoring, total: 0 }
+    }
+
+    pub fn total(&self) -> i64 {
+        self.total
+    }
+
+    pub fn scoring(&self) -> &S {
+        &self.scoring
+    }
+
+    /// Adds the points for `event` and returns them.
+    pub fn record(&mut self, event: ScoreEvent, elapsed: Duration) -> i64 {
+        let points = self.scoring.points(event, elapsed);
+        self.total += points;
+        points
+    }
+
+    /// Applies `mv` to `game` and scores whatever it revealed.
+    pub fn apply_move(&mut self, game: &mut Minesweeper, mv: Move, elapsed: Duration) -> Outcome {
+        let covered_before = game.safe_tiles_remaining();
+        game.apply_move(mv)?;
+
+        if let Move::Reveal(..) | Move::Chord(..) | Move::Assist(..) | Move::AutoFinish = mv {
+            if *game.get_game_state() == GameState::Lost {
+                self.record(ScoreEvent::MineHit, elapsed);
+            } else {
+                let tiles = covered_before - game.safe_tiles_remaining();
+                let event = match mv {
+                    Move::Chord(..) => ScoreEvent::Chord { tiles },
+                    _ => ScoreEvent::Reveal { tiles },
+                };
+                self.record(event, elapsed);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classic_points() {
+        let scoring = ClassicScoring::default();
+
+        assert_eq!(
+            scoring.points(ScoreEvent::Reveal { tiles: 3 }, Duration::ZERO),
+            30
+        );
+        assert_eq!(
+            scoring.points(ScoreEvent::Chord { tiles: 2 }, Duration::ZERO),
+            30
+        );
+        assert_eq!(scoring.points(ScoreEvent::MineHit, Duration::ZERO), -100);
+        // One half-life later reveals are worth half as much.
+        assert_eq!(
+            scoring.points(ScoreEvent::Reveal { tiles: 3 }, Duration::from_secs(300)),
+            15
+        );
+    }
+
+    #[test]
+    fn test_score_applies_and_scores_moves() {
+        let mut game = Minesweeper::new(3, vec![(0, 0), (2, 2)]);
+        let mut score = Score::new(ClassicScoring {
+            half_life: None,
+            ..Default::default()
+        });
+
+        score
+            .apply_move(&mut game, Move::Reveal(1, 1), Duration::ZERO)
+            .unwrap();
+        score
+            .apply_move(&mut game, Move::ToggleFlag(0, 0), Duration::ZERO)
+            .unwrap();
+        assert_eq!(score.total(), 10);
+
+        // Rejected moves score nothing.
+        assert!(score
+            .apply_move(&mut game, Move::Reveal(1, 1), Duration::ZERO)
+            .is_err());
+        assert_eq!(score.total(), 10);
+
+        score
+            .apply_move(&mut game, Move::Reveal(2, 2), Duration::ZERO)
+            .unwrap();
+        assert_eq!(score.total(), -90);
+    }
+}
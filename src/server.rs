@@ -0,0 +1,474 @@
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Instant;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio_tungstenite::tungstenite::{self, Message};
+
+use crate::protocol::client_config;
+use crate::{
+    ClientMessage, DeltaStream, GameId, Minesweeper, Move, MovePolicy, ServerMessage, VisibleChange,
+};
+
+/// Boxed, as `tungstenite::Error` is large.
+type SocketResult = Result<(), Box<tungstenite::Error>>;
+
+/// Hosts one shared board for everyone connected, over WebSockets: every
+/// client's clicks and flags land on the same game and every client gets
+/// the resulting [`ServerMessage::BoardDiff`]s. Clients speak
+/// [`ClientMessage`] JSON; `web/index.html` is a ready-made one.
+///
+/// Players join with nothing to play until someone sends
+/// [`ClientMessage::NewGame`], which replaces the current game for all.
+#[derive(Default)]
+pub struct GameServer {
+    room: Mutex<Room>,
+}
+
+#[derive(Default)]
+struct Room {
+    game: Option<(Minesweeper, DeltaStream)>,
+    /// Games started so far, numbering the current one for the policy.
+    games: u64,
+    players: Vec<(u64, UnboundedSender<ServerMessage>)>,
+    next_player: u64,
+    policy: Option<Box<dyn MovePolicy>>,
+}
+
+impl Room {
+    fn broadcast(&mut self, message: &ServerMessage) {
+        // Players whose connection went away are dropped.
+        self.players
+            .retain(|(_, outbox)| outbox.send(message.clone()).is_ok());
+    }
+
+    fn send(&mut self, player: u64, message: ServerMessage) {
+        if let Some((_, outbox)) = self.players.iter().find(|(id, _)| *id == player) {
+            let _ = outbox.send(message);
+        }
+    }
+}
+
+/// A diff listing every tile, which brings a client up to date.
+fn whole_board(game: &Minesweeper, seq: u64) -> ServerMessage {
+    let changes = (0..game.get_width())
+        .flat_map(|x| (0..game.get_height()).map(move |y| (x, y)))
+        .map(|(x, y)| VisibleChange {
+            x,
+            y,
+            tile: game.visible_tile(x, y),
+        })
+        .collect();
+    ServerMessage::BoardDiff {
+        seq,
+        changes,
+        state: game.get_game_state().clone(),
+    }
+}
+
+impl GameServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A server that asks `policy` before playing any player's click, flag
+    /// or chord, or starting a new game, e.g. a
+    /// [`RateLimit`](crate::RateLimit) when it is open to the public. Each
+    /// new game has its own [`GameId`].
+    pub fn with_policy(policy: impl MovePolicy + 'static) -> Self {
+        let server = Self::default();
+        server.room().policy = Some(Box::new(policy));
+        server
+    }
+
+    /// The room, locked. Nothing that can panic runs under the lock, but
+    /// should something, the room carries on rather than refusing every
+    /// player from then on.
+    fn room(&self) -> MutexGuard<'_, Room> {
+        self.room.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Adds a player, returning their number and where their messages
+    /// arrive. Everyone is told they joined, and they get the board.
+    pub fn join(&self) -> (u64, UnboundedReceiver<ServerMessage>) {
+        let mut room = self.room();
+        room.next_player += 1;
+        let player = room.next_player;
+        let (outbox, inbox) = mpsc::unbounded_channel();
+        room.players.push((player, outbox));
+        room.broadcast(&ServerMessage::PlayerJoined { player });
+        if let Some((game, stream)) = &room.game {
+            let board = whole_board(game, stream.seq());
+            room.send(player, board);
+        }
+        (player, inbox)
+    }
+
+    /// Removes a player, and has the move policy forget them.
+    pub fn leave(&self, player: u64) {
+        let mut room = self.room();
+        room.players.retain(|(id, _)| *id != player);
+        if let Some(policy) = &mut room.policy {
+            policy.end_session(player);
+        }
+    }
+
+    /// Carries out a message from `player`. Refused moves are answered
+    /// with an error to that player alone.
+    pub fn handle(&self, player: u64, message: ClientMessage) {
+        self.handle_at(player, message, Instant::now());
+    }
+
+    /// [`GameServer::handle`] with the move policy told it is `now`.
+    pub(crate) fn handle_at(&self, player: u64, message: ClientMessage, now: Instant) {
+        let result = match &message {
+            ClientMessage::NewGame {
+                config,
+                first_click,
+                seed,
+            } => self.new_game(player, config, *first_click, *seed, now),
+            _ => {
+                let mv = message.to_move().expect("every other message is a move");
+                Self::play(&mut self.room(), player, mv, now)
+            }
+        };
+        if let Err(message) = result {
+            self.room().send(player, ServerMessage::Error { message });
+        }
+    }
+
+    /// Replaces the game for everyone. The board is generated without the
+    /// room locked, as a big one takes a while.
+    fn new_game(
+        &self,
+        player: u64,
+        config: &str,
+        first_click: (usize, usize),
+        seed: Option<u64>,
+        now: Instant,
+    ) -> Result<(), String> {
+        if let Some(policy) = &mut self.room().policy {
+            policy.check_new_game(player, now)?;
+        }
+        let config = client_config(config)?;
+        let game = match seed {
+            Some(seed) => Minesweeper::new_with_config_and_seed(&config, first_click, seed),
+            None => Minesweeper::new_with_config(&config, first_click),
+        }
+        .map_err(|e| e.to_string())?;
+
+        let stream = DeltaStream::new(&game);
+        let board = whole_board(&game, stream.seq());
+        let mut room = self.room();
+        room.game = Some((game, stream));
+        room.games += 1;
+        Self::announce(&mut room, board);
+        Ok(())
+    }
+
+    fn play(room: &mut Room, player: u64, mv: Move, now: Instant) -> Result<(), String> {
+        let Room {
+            game,
+            games,
+            policy,
+            ..
+        } = room;
+        let (game, stream) = game
+            .as_mut()
+            .ok_or_else(|| "No game yet; start one with new_game".to_string())?;
+        if let Some(policy) = policy {
+            policy.check(player, GameId(*games), mv, now)?;
+        }
+        game.apply_move(mv)?;
+        if let Some(delta) = stream.poll(game) {
+            let update = ServerMessage::board_diff(game, &delta);
+            Self::announce(room, update);
+        }
+        Ok(())
+    }
+
+    /// Sends `update` to everyone, followed by the result if the game just
+    /// ended.
+    fn announce(room: &mut Room, update: ServerMessage) {
+        room.broadcast(&update);
+        let over = room.game.as_ref().and_then(|(game, _)| {
+            let finished = game.get_game_state().is_over();
+            finished.then(|| ServerMessage::game_over(game)).flatten()
+        });
+        if let Some(over) = over {
+            room.broadcast(&over);
+        }
+    }
+
+    /// Accepts WebSocket connections on `listener` until it fails, serving
+    /// each as its own task on the tokio runtime this is run on.
+    pub async fn serve(self: Arc<Self>, listener: TcpListener) -> io::Result<()> {
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let server = self.clone();
+            tokio::spawn(async move {
+                // A client that goes away mid-game only ends its own
+                // connection.
+                let _ = server.serve_connection(stream).await;
+            });
+        }
+    }
+
+    async fn serve_connection(&self, stream: TcpStream) -> SocketResult {
+        let socket = tokio_tungstenite::accept_async(stream).await?;
+        let (player, inbox) = self.join();
+        let result = self.pump(socket, player, inbox).await;
+        self.leave(player);
+        result
+    }
+
+    /// Passes messages both ways until the client closes the connection,
+    /// waking only when the client or the room has something to say.
+    async fn pump(
+        &self,
+        socket: tokio_tungstenite::WebSocketStream<TcpStream>,
+        player: u64,
+        mut inbox: UnboundedReceiver<ServerMessage>,
+    ) -> SocketResult {
+        let (mut sink, mut source) = socket.split();
+        loop {
+            tokio::select! {
+                message = inbox.recv() => match message {
+                    Some(message) => sink.send(to_message(&message)).await?,
+                    None => return Ok(()),
+                },
+                frame = source.next() => match frame {
+                    Some(Ok(Message::Text(text))) => {
+                        match serde_json::from_str::<ClientMessage>(&text) {
+                            Ok(message) => self.handle(player, message),
+                            Err(e) => {
+                                let error = ServerMessage::Error {
+                                    message: format!("Invalid message: {}", e),
+                                };
+                                sink.send(to_message(&error)).await?
+                            }
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None => return Ok(()),
+                    Some(Ok(_)) => {}
+                    Some(Err(tungstenite::Error::ConnectionClosed)) => return Ok(()),
+                    Some(Err(e)) => return Err(e.into()),
+                },
+            }
+        }
+    }
+}
+
+fn to_message(message: &ServerMessage) -> Message {
+    Message::Text(serde_json::to_string(message).expect("server messages always serialize"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameState, VisibleTile};
+
+    fn new_game() -> ClientMessage {
+        ClientMessage::NewGame {
+            config: "width=4\nheight=4\nmines=3".to_string(),
+            first_click: (0, 0),
+            seed: Some(7),
+        }
+    }
+
+    #[test]
+    fn test_players_share_one_board() {
+        let server = GameServer::new();
+        let (one, mut inbox_one) = server.join();
+        server.handle(one, ClientMessage::Click { x: 0, y: 0 });
+        assert_eq!(
+            inbox_one.try_recv().unwrap(),
+            ServerMessage::PlayerJoined { player: one }
+        );
+        assert!(matches!(
+            inbox_one.try_recv().unwrap(),
+            ServerMessage::Error { message } if message.starts_with("No game yet")
+        ));
+
+        server.handle(one, new_game());
+        let (two, mut inbox_two) = server.join();
+        let board = inbox_one.try_recv().unwrap();
+        assert!(matches!(&board, ServerMessage::BoardDiff { changes, .. } if changes.len() == 16));
+        assert_eq!(
+            inbox_one.try_recv().unwrap(),
+            ServerMessage::PlayerJoined { player: two }
+        );
+        // The newcomer is told about themself, then gets the whole board.
+        assert_eq!(
+            inbox_two.try_recv().unwrap(),
+            ServerMessage::PlayerJoined { player: two }
+        );
+        assert_eq!(inbox_two.try_recv().unwrap(), board);
+
+        // A flag from one player reaches both.
+        server.handle(two, ClientMessage::Flag { x: 3, y: 3 });
+        for inbox in [&mut inbox_one, &mut inbox_two] {
+            assert!(matches!(
+                inbox.try_recv().unwrap(),
+                ServerMessage::BoardDiff { seq: 1, changes, .. }
+                    if changes == vec![VisibleChange { x: 3, y: 3, tile: VisibleTile::Flagged }]
+            ));
+        }
+    }
+
+    #[test]
+    fn test_game_over_is_broadcast() {
+        let server = GameServer::new();
+        let (player, mut inbox) = server.join();
+        server.handle(
+            player,
+            ClientMessage::NewGame {
+                config: "width=4\nheight=1\nmines=1".to_string(),
+                first_click: (0, 0),
+                seed: Some(3),
+            },
+        );
+        drain(&mut inbox);
+        let covered: Vec<usize> = (0..4)
+            .filter(|&x| {
+                let room = server.room.lock().unwrap();
+                let (game, _) = room.game.as_ref().unwrap();
+                let tile = game.get_tile(x, 0).unwrap();
+                !tile.exposed && !tile.is_bomb()
+            })
+            .collect();
+        for x in covered {
+            server.handle(player, ClientMessage::Click { x, y: 0 });
+        }
+        let messages = drain(&mut inbox);
+        assert!(matches!(
+            messages.last(),
+            Some(ServerMessage::GameOver {
+                state: GameState::Won,
+                exploded: None
+            })
+        ));
+    }
+
+    /// Every message waiting in `inbox`.
+    fn drain(inbox: &mut UnboundedReceiver<ServerMessage>) -> Vec<ServerMessage> {
+        std::iter::from_fn(|| inbox.try_recv().ok()).collect()
+    }
+
+    #[test]
+    fn test_policy_refuses_floods() {
+        let server = GameServer::with_policy(crate::RateLimit::new(0.001, 2));
+        let (player, mut inbox) = server.join();
+        server.handle(player, new_game());
+        drain(&mut inbox);
+
+        let now = Instant::now();
+        for (x, y) in [(3, 3), (3, 2), (3, 1)] {
+            server.handle_at(player, ClientMessage::Flag { x, y }, now);
+        }
+        let messages = drain(&mut inbox);
+        assert_eq!(messages.len(), 3);
+        assert!(matches!(messages.last(), Some(ServerMessage::Error { .. })));
+
+        // Leaving resets the allowance, so a rejoin isn't punished for it.
+        server.leave(player);
+        let (again, mut inbox) = server.join();
+        assert_ne!(again, player);
+        drain(&mut inbox);
+        server.handle_at(again, ClientMessage::Flag { x: 3, y: 1 }, now);
+        assert!(matches!(
+            drain(&mut inbox).as_slice(),
+            [ServerMessage::BoardDiff { .. }]
+        ));
+    }
+
+    #[test]
+    fn test_bad_configs_leave_the_server_running() {
+        let server = GameServer::new();
+        let (player, mut inbox) = server.join();
+        drain(&mut inbox);
+        for config in [
+            "width=9\nheight=9\nmines=4\nmine-count=5-3",
+            "width=18446744073709551615\nheight=2\nmines=1",
+            "width=5000\nheight=5000\nmines=1",
+        ] {
+            server.handle(
+                player,
+                ClientMessage::NewGame {
+                    config: config.to_string(),
+                    first_click: (0, 0),
+                    seed: Some(1),
+                },
+            );
+        }
+        let messages = drain(&mut inbox);
+        assert_eq!(messages.len(), 3);
+        assert!(messages
+            .iter()
+            .all(|message| matches!(message, ServerMessage::Error { .. })));
+
+        // Everyone can still join and play.
+        let (other, _) = server.join();
+        server.handle(other, new_game());
+        assert!(server.room().game.is_some());
+    }
+
+    #[test]
+    fn test_policy_limits_new_games() {
+        let server = GameServer::with_policy(crate::RateLimit::new(0.001, 1));
+        let (player, mut inbox) = server.join();
+        drain(&mut inbox);
+        let now = Instant::now();
+        server.handle_at(player, new_game(), now);
+        server.handle_at(player, new_game(), now);
+        let messages = drain(&mut inbox);
+        assert!(matches!(messages[0], ServerMessage::BoardDiff { .. }));
+        assert!(matches!(messages[1], ServerMessage::Error { .. }));
+    }
+
+    type ClientSocket = tokio_tungstenite::WebSocketStream<TcpStream>;
+
+    async fn receive(socket: &mut ClientSocket) -> ServerMessage {
+        match socket.next().await.unwrap().unwrap() {
+            Message::Text(text) => serde_json::from_str(&text).unwrap(),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_websocket_round_trip() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(Arc::new(GameServer::new()).serve(listener));
+
+        let stream = TcpStream::connect(address).await.unwrap();
+        let (mut socket, _) = tokio_tungstenite::client_async(format!("ws://{}/", address), stream)
+            .await
+            .unwrap();
+        assert!(matches!(
+            receive(&mut socket).await,
+            ServerMessage::PlayerJoined { .. }
+        ));
+
+        socket.send(to_message_text(&new_game())).await.unwrap();
+        assert!(matches!(
+            receive(&mut socket).await,
+            ServerMessage::BoardDiff { seq: 0, .. }
+        ));
+        socket
+            .send(Message::Text("nonsense".to_string()))
+            .await
+            .unwrap();
+        assert!(matches!(
+            receive(&mut socket).await,
+            ServerMessage::Error { .. }
+        ));
+        socket.close(None).await.unwrap();
+    }
+
+    fn to_message_text(message: &ClientMessage) -> Message {
+        Message::Text(serde_json::to_string(message).unwrap())
+    }
+}
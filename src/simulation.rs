@@ -0,0 +1,359 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::{Broker, Connection, Request, Response};
+
+/// What one simulated client sends and how its connection misbehaves.
+#[derive(Debug, Clone, Default)]
+pub struct ClientScript {
+    requests: Vec<(Duration, Request)>,
+    lag: Duration,
+    duplicate: bool,
+    disconnect_at: Option<Duration>,
+}
+
+impl ClientScript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sends `request` at virtual time `at`.
+    pub fn send(mut self, at: Duration, request: Request) -> Self {
+        self.requests.push((at, request));
+        self
+    }
+
+    /// Delays every request by `lag` on its way to the broker.
+    pub fn with_lag(mut self, lag: Duration) -> Self {
+        self.lag = lag;
+        self
+    }
+
+    /// Delivers every request twice, as a client retrying after a timeout
+    /// would.
+    pub fn duplicating(mut self) -> Self {
+        self.duplicate = true;
+        self
+    }
+
+    /// Drops the connection at virtual time `at`. Requests that haven't
+    /// arrived by then are lost.
+    pub fn disconnect_at(mut self, at: Duration) -> Self {
+        self.disconnect_at = Some(at);
+        self
+    }
+}
+
+/// Runs scripted clients against a [`Broker`] on virtual time, so
+/// multiplayer behaviour can be tested without sockets, threads or sleeps.
+///
+/// Everything happens in order of arrival time, ties going to whatever was
+/// scheduled first, so a run always plays out the same way. Game clocks
+/// and the broker's move policy see the virtual time too.
+pub struct Simulation {
+    broker: Broker,
+    start: Instant,
+    now: Duration,
+    clients: Vec<SimulatedClient>,
+    queue: BinaryHeap<Arrival>,
+    scheduled: u64,
+}
+
+struct SimulatedClient {
+    connection: Option<Connection>,
+    output: Transcript,
+}
+
+/// Everything written to one client.
+#[derive(Clone, Default)]
+struct Transcript(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Transcript {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+enum Delivery {
+    Line(String),
+    Disconnect,
+}
+
+/// Something reaching the broker from a client at a virtual time.
+struct Arrival {
+    at: Duration,
+    order: u64,
+    client: usize,
+    delivery: Delivery,
+}
+
+impl PartialEq for Arrival {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Arrival {}
+
+impl PartialOrd for Arrival {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Arrival {
+    // Reversed, so the heap yields the earliest arrival first.
+    fn cmp(&self, other: &Self) -> Ordering {
+        (other.at, other.order).cmp(&(self.at, self.order))
+    }
+}
+
+impl Simulation {
+    pub fn new(broker: Broker) -> Self {
+        Simulation {
+            broker,
+            start: Instant::now(),
+            now: Duration::ZERO,
+            clients: Vec::new(),
+            queue: BinaryHeap::new(),
+            scheduled: 0,
+        }
+    }
+
+    pub fn broker(&self) -> &Broker {
+        &self.broker
+    }
+
+    /// The virtual time reached so far.
+    pub fn now(&self) -> Duration {
+        self.now
+    }
+
+    /// Connects a client that will follow `script`, returning its index.
+    pub fn add_client(&mut self, script: ClientScript) -> usize {
+        let client = self.clients.len();
+        let output = Transcript::default();
+        let connection = self
+            .broker
+            .connect(output.clone())
+            .expect("writing to memory cannot fail");
+        self.clients.push(SimulatedClient {
+            connection: Some(connection),
+            output,
+        });
+
+        for (at, request) in script.requests {
+            let line = serde_json::to_string(&request).expect("requests always serialize");
+            let copies = if script.duplicate { 2 } else { 1 };
+            for _ in 0..copies {
+                self.schedule(at + script.lag, client, Delivery::Line(line.clone()));
+            }
+        }
+        if let Some(at) = script.disconnect_at {
+            self.schedule(at, client, Delivery::Disconnect);
+        }
+        client
+    }
+
+    fn schedule(&mut self, at: Duration, client: usize, delivery: Delivery) {
+        self.scheduled += 1;
+        self.queue.push(Arrival {
+            at: at.max(self.now),
+            order: self.scheduled,
+            client,
+            delivery,
+        });
+    }
+
+    /// Delivers everything due by virtual time `until`, ticking game
+    /// clocks along the way.
+    pub fn run_until(&mut self, until: Duration) {
+        while self.queue.peek().is_some_and(|next| next.at <= until) {
+            let arrival = self.queue.pop().expect("peeked");
+            self.advance_to(arrival.at);
+            let client = &mut self.clients[arrival.client];
+            match arrival.delivery {
+                Delivery::Line(line) => {
+                    if let Some(connection) = &mut client.connection {
+                        let now = self.start + self.now;
+                        self.broker
+                            .receive_at(connection, &line, now)
+                            .expect("writing to memory cannot fail");
+                    }
+                }
+                Delivery::Disconnect => {
+                    if let Some(connection) = client.connection.take() {
+                        self.broker.disconnect(connection);
+                    }
+                }
+            }
+        }
+        self.advance_to(until);
+    }
+
+    /// Delivers everything still scheduled.
+    pub fn run(&mut self) {
+        let last = self.queue.iter().map(|arrival| arrival.at).max();
+        if let Some(last) = last {
+            self.run_until(last);
+        }
+    }
+
+    fn advance_to(&mut self, at: Duration) {
+        if at > self.now {
+            self.broker.manager().tick(at - self.now);
+            self.now = at;
+        }
+    }
+
+    /// The session number a client was given.
+    pub fn session(&self, client: usize) -> u64 {
+        match self.all_responses(client).first() {
+            Some(Response::Welcome { session }) => *session,
+            _ => unreachable!("every client is welcomed on connecting"),
+        }
+    }
+
+    /// Everything a client received after its welcome, in order.
+    pub fn responses(&self, client: usize) -> Vec<Response> {
+        self.all_responses(client).into_iter().skip(1).collect()
+    }
+
+    fn all_responses(&self, client: usize) -> Vec<Response> {
+        let bytes = self.clients[client].output.0.lock().unwrap().clone();
+        String::from_utf8(bytes)
+            .expect("responses are JSON")
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("responses are JSON"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameId, GameState, RateLimit, TimeControl};
+
+    const CONFIG: &str = "width=4\nheight=4\nmines=3";
+
+    fn secs(secs: f64) -> Duration {
+        Duration::from_secs_f64(secs)
+    }
+
+    fn new_game() -> Request {
+        Request::NewGame {
+            config: CONFIG.to_string(),
+            first_click: (0, 0),
+            seed: Some(7),
+        }
+    }
+
+    fn flag(game: u64, x: usize, y: usize) -> Request {
+        Request::Move {
+            game,
+            mv: format!("flag {} {}", x, y),
+        }
+    }
+
+    #[test]
+    fn test_lag_decides_who_moves_first() {
+        let mut sim = Simulation::new(Broker::new());
+        let host = sim.add_client(ClientScript::new().send(secs(0.0), new_game()));
+        // Both flag the same tile; the guest sends first but on a slow link.
+        let guest = sim.add_client(
+            ClientScript::new()
+                .send(secs(0.5), Request::Join { game: 1 })
+                .send(secs(1.0), flag(1, 3, 3))
+                .with_lag(secs(0.5)),
+        );
+        sim.add_client(
+            ClientScript::new()
+                .send(secs(1.2), flag(1, 3, 3))
+                .send(secs(1.1), Request::Join { game: 1 }),
+        );
+        sim.run();
+
+        let moves: Vec<(u64, Option<String>)> = sim
+            .responses(host)
+            .into_iter()
+            .filter_map(|response| match response {
+                Response::Update { game, error, .. } => Some((game, error)),
+                _ => None,
+            })
+            .collect();
+        // The third client's flag landed first, so the guest's took it off.
+        assert_eq!(moves, vec![(1, None), (1, None)]);
+        assert_eq!(sim.responses(guest)[0], Response::Joined { game: 1 });
+        let flagged = sim
+            .broker()
+            .manager()
+            .with_game(GameId(1), |g| g.get_tile(3, 3).unwrap().flagged)
+            .unwrap();
+        assert!(!flagged);
+        assert_eq!(sim.now(), secs(1.5));
+    }
+
+    #[test]
+    fn test_duplicates_and_disconnects() {
+        let broker = Broker::with_policy(RateLimit::new(1.0, 1));
+        let mut sim = Simulation::new(broker);
+        let client = sim.add_client(
+            ClientScript::new()
+                .send(secs(0.0), new_game())
+                .send(secs(1.0), flag(1, 3, 3))
+                .send(secs(5.0), flag(1, 2, 3))
+                .duplicating()
+                .disconnect_at(secs(3.0)),
+        );
+        sim.run();
+
+        let responses = sim.responses(client);
+        // The second game comes from the duplicated request; the duplicated
+        // flag is refused by the rate limit on virtual time.
+        assert_eq!(responses[0], Response::Created { game: 1 });
+        assert_eq!(responses[1], Response::Created { game: 2 });
+        assert!(matches!(
+            &responses[2],
+            Response::Update { error: None, .. }
+        ));
+        assert_eq!(
+            responses[3],
+            Response::Error {
+                message: "Too many moves, slow down".to_string()
+            }
+        );
+        // Nothing after the disconnect arrived.
+        assert_eq!(responses.len(), 4);
+        assert_eq!(sim.session(client), 1);
+    }
+
+    #[test]
+    fn test_clocks_run_on_virtual_time() {
+        let mut sim = Simulation::new(Broker::new());
+        let client = sim.add_client(ClientScript::new().send(secs(0.0), new_game()));
+        sim.run();
+        sim.broker()
+            .manager()
+            .set_time_control(GameId(1), TimeControl::sudden_death(secs(10.0)))
+            .unwrap();
+
+        sim.run_until(secs(9.0));
+        assert_eq!(sim.responses(client).len(), 1);
+        sim.run_until(secs(11.0));
+        assert!(matches!(
+            sim.responses(client).last(),
+            Some(Response::Update {
+                mv: None,
+                state: GameState::Lost,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,241 @@
+use std::sync::Arc;
+
+use crate::board::{Board, CHUNK};
+use crate::{GameState, Minesweeper, Tile};
+
+/// An immutable, cheaply cloned view of a board at one point in time.
+///
+/// Taken with [`Minesweeper::snapshot`](crate::Minesweeper::snapshot).
+/// Tiles are stored in reference-counted chunks shared with the live game
+/// until it modifies them, so spectators, undo stacks and solver forks can keep many snapshots
+/// of a large board around without a full copy for each.
+#[derive(Debug, Clone)]
+pub struct BoardSnapshot {
+    board: Board,
+    game_state: GameState,
+    probabilities: Option<Arc<Vec<f64>>>,
+}
+
+impl BoardSnapshot {
+    pub(crate) fn new(
+        board: Board,
+        game_state: GameState,
+        probabilities: Option<Arc<Vec<f64>>>,
+    ) -> Self {
+        BoardSnapshot {
+            board,
+            game_state,
+            probabilities,
+        }
+    }
+
+    pub fn get_tile(&self, x: usize, y: usize) -> Option<&Tile> {
+        if x < self.get_width() && y < self.get_height() {
+            Some(&self.board[(x, y)])
+        } else {
+            None
+        }
+    }
+
+    /// The side length of a square board, or the width of a rectangular
+    /// one.
+    pub fn get_size(&self) -> usize {
+        self.board.width()
+    }
+
+    pub fn get_width(&self) -> usize {
+        self.board.width()
+    }
+
+    pub fn get_height(&self) -> usize {
+        self.board.height()
+    }
+
+    pub fn get_game_state(&self) -> &GameState {
+        &self.game_state
+    }
+
+    /// The chance that the tile at (x, y) is a mine, if the game had its
+    /// probability overlay enabled when the snapshot was taken.
+    pub fn mine_probability(&self, x: usize, y: usize) -> Option<f64> {
+        let probabilities = self.probabilities.as_ref()?;
+        if x < self.get_width() && y < self.get_height() {
+            Some(probabilities[x * self.get_height() + y])
+        } else {
+            None
+        }
+    }
+
+    /// The number of storage chunks shared with `other`. Chunks hold a
+    /// fixed number of tiles, so this measures how much memory the two
+    /// snapshots have in common.
+    pub fn shared_chunks(&self, other: &BoardSnapshot) -> usize {
+        self.board
+            .chunks()
+            .iter()
+            .zip(other.board.chunks())
+            .filter(|(a, b)| Arc::ptr_eq(a, b))
+            .count()
+    }
+}
+
+/// A tile whose state differs between two snapshots, with its new state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileChange {
+    pub x: usize,
+    pub y: usize,
+    pub tile: Tile,
+}
+
+/// The tiles that changed from `before` to `after`, in row-major order.
+///
+/// Chunks of tiles still shared between the two snapshots are skipped
+/// without being compared, so diffing consecutive snapshots costs roughly the size of the
+/// change. Snapshots of differently sized boards share nothing, so every
+/// tile of `after` is reported.
+pub fn diff(before: &BoardSnapshot, after: &BoardSnapshot) -> Vec<TileChange> {
+    let mut changes = Vec::new();
+    let same_shape =
+        (before.get_width(), before.get_height()) == (after.get_width(), after.get_height());
+    let height = after.get_height();
+    for (c, chunk) in after.board.chunks().iter().enumerate() {
+        let old_chunk = before.board.chunks().get(c).filter(|_| same_shape);
+        if old_chunk.is_some_and(|old_chunk| Arc::ptr_eq(old_chunk, chunk)) {
+            continue;
+        }
+        for (i, tile) in chunk.iter().enumerate() {
+            if old_chunk.is_none_or(|old_chunk| old_chunk[i] != *tile) {
+                let index = c * CHUNK + i;
+                changes.push(TileChange {
+                    x: index / height,
+                    y: index % height,
+                    tile: tile.clone(),
+                });
+            }
+        }
+    }
+    changes
+}
+
+/// One step of a [`DeltaStream`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardDelta {
+    /// Starts at 1 and increases by one per delta, so a client that sees a
+    /// gap knows it missed an update and should resync from a snapshot.
+    pub seq: u64,
+    pub changes: Vec<TileChange>,
+    pub game_state: GameState,
+}
+
+/// Turns a game's successive states into numbered [`BoardDelta`]s, for
+/// remote clients and reactive UIs that apply minimal updates instead of
+/// redrawing or resending the whole board.
+#[derive(Debug, Clone)]
+pub struct DeltaStream {
+    last: BoardSnapshot,
+    seq: u64,
+}
+
+impl DeltaStream {
+    /// Starts a stream from the game's current state, which clients are
+    /// expected to get as a full snapshot.
+    pub fn new(game: &Minesweeper) -> Self {
+        DeltaStream {
+            last: game.snapshot(),
+            seq: 0,
+        }
+    }
+
+    /// The changes since the previous call, or `None` if nothing changed.
+    pub fn poll(&mut self, game: &Minesweeper) -> Option<BoardDelta> {
+        let current = game.snapshot();
+        let changes = diff(&self.last, &current);
+        if changes.is_empty() && current.game_state == self.last.game_state {
+            return None;
+        }
+
+        self.seq += 1;
+        let delta = BoardDelta {
+            seq: self.seq,
+            changes,
+            game_state: current.game_state.clone(),
+        };
+        self.last = current;
+        Some(delta)
+    }
+
+    /// The sequence number of the last delta produced, 0 if none yet.
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameState, Minesweeper};
+
+    #[test]
+    fn test_snapshot_is_unaffected_by_later_moves() {
+        let mut game = Minesweeper::new(3, vec![(1, 1)]);
+        let before = game.snapshot();
+
+        game.click_tile(0, 0).unwrap();
+
+        assert!(!before.get_tile(0, 0).unwrap().exposed);
+        assert!(game.get_tile(0, 0).unwrap().exposed);
+        assert_eq!(*before.get_game_state(), GameState::InProgress);
+    }
+
+    #[test]
+    fn test_snapshots_share_unchanged_chunks() {
+        // 3 chunks: tiles 0..1024, 1024..2048 and 2048..2500.
+        let mut game = Minesweeper::new(50, vec![(49, 49)]);
+        let first = game.snapshot();
+
+        // A numbered tile at index 48 * 50 + 48, so only the last chunk
+        // changes.
+        game.click_tile(48, 48).unwrap();
+        let second = game.snapshot();
+
+        assert_eq!(first.shared_chunks(&second), 2);
+        assert_eq!(second.shared_chunks(&game.snapshot()), 3);
+    }
+
+    #[test]
+    fn test_diff_reports_changed_tiles() {
+        let mut game = Minesweeper::new(3, vec![(2, 2)]);
+        let before = game.snapshot();
+        game.toggle_flag(2, 2).unwrap();
+        game.click_tile(1, 2).unwrap();
+
+        let changes = diff(&before, &game.snapshot());
+        let positions: Vec<_> = changes.iter().map(|c| (c.x, c.y)).collect();
+        assert_eq!(positions, vec![(1, 2), (2, 2)]);
+        assert!(changes[0].tile.exposed);
+        assert!(changes[1].tile.flagged);
+
+        assert!(diff(&before, &before).is_empty());
+        let other = Minesweeper::new(2, vec![(0, 0)]).snapshot();
+        assert_eq!(diff(&before, &other).len(), 4);
+    }
+
+    #[test]
+    fn test_delta_stream() {
+        let mut game = Minesweeper::new(3, vec![(1, 1)]);
+        let mut stream = DeltaStream::new(&game);
+        assert_eq!(stream.poll(&game), None);
+
+        game.click_tile(0, 0).unwrap();
+        let delta = stream.poll(&game).unwrap();
+        assert_eq!(delta.seq, 1);
+        assert_eq!(delta.changes.len(), 1);
+        assert_eq!(stream.poll(&game), None);
+
+        game.click_tile(1, 1).unwrap();
+        let delta = stream.poll(&game).unwrap();
+        assert_eq!(delta.seq, 2);
+        assert_eq!(delta.game_state, GameState::Lost);
+        assert_eq!(stream.seq(), 2);
+    }
+}
@@ -0,0 +1,353 @@
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+
+use crate::{Minesweeper, TileValue};
+
+/// What the revealed numbers prove about the covered tiles, from
+/// [`Minesweeper::analyze`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Analysis {
+    /// Covered tiles that cannot be mines.
+    pub safe: BTreeSet<(usize, usize)>,
+    /// Covered tiles that must be mines.
+    pub mines: BTreeSet<(usize, usize)>,
+}
+
+impl Analysis {
+    /// Whether nothing could be deduced.
+    pub fn is_empty(&self) -> bool {
+        self.safe.is_empty() && self.mines.is_empty()
+    }
+}
+
+/// Limits on how much work [`Minesweeper::analyze_within`],
+/// [`Minesweeper::hint_within`] and
+/// [`Minesweeper::mine_probabilities_within`] may do. The default is
+/// unlimited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SolverBudget {
+    /// The most steps to take; a step compares a number with the covered
+    /// tiles around it or with another number, or tries a mine arrangement
+    /// while weighing probabilities.
+    pub max_steps: Option<usize>,
+    /// The most wall-clock time to spend. Leave unset on targets without
+    /// a clock, such as `wasm32-unknown-unknown`, and limit steps instead.
+    pub max_time: Option<Duration>,
+}
+
+/// What [`Minesweeper::analyze_within`] proved before it finished or ran
+/// out of budget.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BudgetedAnalysis {
+    /// Every deduction made; all of them are sound even when cut short.
+    pub analysis: Analysis,
+    /// Whether the solver ran to completion, so `analysis` matches
+    /// [`Minesweeper::analyze`].
+    pub complete: bool,
+}
+
+/// Counts down a [`SolverBudget`], shared by everything one budgeted call
+/// runs.
+pub(crate) struct Meter {
+    steps_left: Option<usize>,
+    deadline: Option<Instant>,
+    steps: usize,
+}
+
+impl Meter {
+    pub(crate) fn new(budget: SolverBudget) -> Self {
+        Meter {
+            steps_left: budget.max_steps,
+            deadline: budget.max_time.map(|time| Instant::now() + time),
+            steps: 0,
+        }
+    }
+
+    /// Pays for one step, returning false once the budget is spent.
+    pub(crate) fn spend(&mut self) -> bool {
+        if let Some(left) = &mut self.steps_left {
+            if *left == 0 {
+                return false;
+            }
+            *left -= 1;
+        }
+        self.steps += 1;
+        // Reading the clock costs more than a step, so only look now and
+        // then.
+        match self.deadline {
+            Some(deadline) if self.steps.is_multiple_of(64) => Instant::now() < deadline,
+            _ => true,
+        }
+    }
+}
+
+/// A revealed number's requirement on the covered tiles around it: this
+/// many mines among these tiles (board indices, ascending).
+type Constraint = (usize, Vec<usize>);
+
+impl Minesweeper {
+    /// Finds the covered tiles whose contents follow from the revealed
+    /// numbers and the known [`MineCount`](crate::MineCount), by
+    /// constraint propagation over the
+    /// frontier: a number with all its mines accounted for clears its
+    /// other neighbours, one with as many covered neighbours as mines
+    /// left fills them, and a number whose covered neighbours contain
+    /// another's settles the difference. Deductions feed back into the
+    /// constraints until nothing more follows.
+    ///
+    /// Flags are the player's opinion and are ignored. Propagation misses
+    /// some deductions that need whole-frontier reasoning, so an empty
+    /// result does not prove a guess is needed; tiles it does report are
+    /// always right.
+    pub fn analyze(&self) -> Analysis {
+        self.analyze_within(SolverBudget::default()).analysis
+    }
+
+    /// [`Minesweeper::analyze`] with a cap on the work done, for hints on
+    /// slow hardware where a big frontier mustn't stall the game. When the
+    /// budget runs out the deductions made so far are returned, marked
+    /// incomplete.
+    pub fn analyze_within(&self, budget: SolverBudget) -> BudgetedAnalysis {
+        self.analyze_metered(&mut Meter::new(budget))
+    }
+
+    /// [`Minesweeper::analyze_within`], spending from a meter the caller
+    /// may go on to use.
+    pub(crate) fn analyze_metered(&self, meter: &mut Meter) -> BudgetedAnalysis {
+        let mut complete = true;
+        let (width, height) = (self.width, self.height);
+        // Some(true) for a proven mine, Some(false) for a proven safe tile.
+        let mut known: Vec<Option<bool>> = vec![None; width * height];
+        let mut constraints: Vec<Constraint> = Vec::new();
+        // What the player knows of the mine count, less exploded mines.
+        let (mut fewest_left, mut most_left) = self.mine_count_bounds();
+
+        for x in 0..width {
+            for y in 0..height {
+                let tile = &self.board[(x, y)];
+                if !tile.exposed {
+                    continue;
+                }
+                let number = match tile.value {
+                    TileValue::Number(n) => n as usize,
+                    TileValue::Bomb => {
+                        fewest_left = fewest_left.saturating_sub(1);
+                        most_left = most_left.saturating_sub(1);
+                        continue;
+                    }
+                };
+
+                let mut covered = Vec::new();
+                let mut mines = 0;
+                for (nx, ny) in self
+                    .adjacency
+                    .neighbors_in(self.topology, x, y, width, height)
+                {
+                    let neighbor = &self.board[(nx, ny)];
+                    if !neighbor.exposed {
+                        covered.push(nx * height + ny);
+                    } else if neighbor.is_bomb() {
+                        mines += 1;
+                    }
+                }
+                if !covered.is_empty() {
+                    covered.sort_unstable();
+                    constraints.push((number.saturating_sub(mines), covered));
+                }
+            }
+        }
+
+        let mut unknown: Vec<usize> = (0..width * height)
+            .filter(|&index| !self.board[(index / height, index % height)].exposed)
+            .collect();
+
+        'solve: loop {
+            // Fold what is known into the constraints.
+            for (target, tiles) in &mut constraints {
+                tiles.retain(|&tile| match known[tile] {
+                    Some(true) => {
+                        *target = target.saturating_sub(1);
+                        false
+                    }
+                    Some(false) => false,
+                    None => true,
+                });
+            }
+            constraints.retain(|(_, tiles)| !tiles.is_empty());
+            constraints.sort();
+            constraints.dedup();
+            unknown.retain(|&tile| known[tile].is_none());
+
+            let mut changed = false;
+            for (target, tiles) in &constraints {
+                if !meter.spend() {
+                    complete = false;
+                    break 'solve;
+                }
+                if *target == 0 {
+                    changed |= settle(&mut known, tiles, false);
+                } else if *target == tiles.len() {
+                    changed |= settle(&mut known, tiles, true);
+                }
+            }
+
+            if !changed {
+                for (i, (small_target, small)) in constraints.iter().enumerate() {
+                    for (j, (big_target, big)) in constraints.iter().enumerate() {
+                        if !meter.spend() {
+                            complete = false;
+                            break 'solve;
+                        }
+                        if i == j
+                            || small.len() >= big.len()
+                            || small_target > big_target
+                            || !small.iter().all(|tile| big.binary_search(tile).is_ok())
+                        {
+                            continue;
+                        }
+                        let rest: Vec<usize> = big
+                            .iter()
+                            .copied()
+                            .filter(|tile| small.binary_search(tile).is_err())
+                            .collect();
+                        let target = big_target - small_target;
+                        if target == 0 {
+                            changed |= settle(&mut known, &rest, false);
+                        } else if target == rest.len() {
+                            changed |= settle(&mut known, &rest, true);
+                        }
+                    }
+                }
+            }
+
+            if !changed {
+                // The mine count settles the rest once it is all or nothing.
+                let proven = known.iter().filter(|&&k| k == Some(true)).count();
+                let open: Vec<usize> = unknown
+                    .iter()
+                    .copied()
+                    .filter(|&tile| known[tile].is_none())
+                    .collect();
+                if !open.is_empty() && most_left.saturating_sub(proven) == 0 {
+                    changed = settle(&mut known, &open, false);
+                } else if !open.is_empty() && fewest_left.saturating_sub(proven) >= open.len() {
+                    changed = settle(&mut known, &open, true);
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        let mut analysis = Analysis::default();
+        for (index, &state) in known.iter().enumerate() {
+            let pos = (index / height, index % height);
+            match state {
+                Some(true) => analysis.mines.insert(pos),
+                Some(false) => analysis.safe.insert(pos),
+                None => false,
+            };
+        }
+        BudgetedAnalysis { analysis, complete }
+    }
+}
+
+/// Marks every tile in `tiles` as proven, returning whether any of them
+/// was not already known.
+fn settle(known: &mut [Option<bool>], tiles: &[usize], mine: bool) -> bool {
+    let mut changed = false;
+    for &tile in tiles {
+        if known[tile].is_none() {
+            known[tile] = Some(mine);
+            changed = true;
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MineCount;
+
+    #[test]
+    fn test_one_one_pattern() {
+        let mut game = Minesweeper::new_rect(4, 2, vec![(0, 0), (3, 0)]);
+        for x in 0..4 {
+            game.click_tile(x, 1).unwrap();
+        }
+
+        let analysis = game.analyze();
+        assert_eq!(analysis.safe, BTreeSet::from([(1, 0), (2, 0)]));
+        assert_eq!(analysis.mines, BTreeSet::from([(0, 0), (3, 0)]));
+    }
+
+    #[test]
+    fn test_nothing_to_deduce() {
+        let mut game = Minesweeper::new(2, vec![(0, 0)]);
+        game.click_tile(1, 1).unwrap();
+        assert!(game.analyze().is_empty());
+    }
+
+    #[test]
+    fn test_mine_count_settles_the_rest() {
+        // The 1 in the corner pins the only mine next to it, which leaves
+        // the tiles out of its sight safe.
+        let mut game = Minesweeper::new_rect(4, 1, vec![(1, 0)]);
+        game.click_tile(0, 0).unwrap();
+        let analysis = game.analyze();
+        assert_eq!(analysis.mines, BTreeSet::from([(1, 0)]));
+        assert_eq!(analysis.safe, BTreeSet::from([(2, 0), (3, 0)]));
+    }
+
+    #[test]
+    fn test_uncertain_mine_count() {
+        // Exactly as in the test above, but the player only knows a range,
+        // which no longer rules out mines beyond the 1's reach.
+        let mut game = Minesweeper::new_rect(4, 1, vec![(1, 0)]);
+        game.set_mine_count(MineCount::Range { min: 1, max: 2 })
+            .unwrap();
+        game.click_tile(0, 0).unwrap();
+        let analysis = game.analyze();
+        assert_eq!(analysis.mines, BTreeSet::from([(1, 0)]));
+        assert!(analysis.safe.is_empty());
+
+        // A range whose low end covers every covered tile still settles them.
+        let mut game = Minesweeper::new_rect(3, 1, vec![(1, 0), (2, 0)]);
+        game.set_mine_count(MineCount::Range { min: 2, max: 2 })
+            .unwrap();
+        game.click_tile(0, 0).unwrap();
+        assert_eq!(game.analyze().mines, BTreeSet::from([(1, 0), (2, 0)]));
+    }
+
+    #[test]
+    fn test_budget_cuts_the_solver_short() {
+        let mut game = Minesweeper::new_rect(4, 2, vec![(0, 0), (3, 0)]);
+        for x in 0..4 {
+            game.click_tile(x, 1).unwrap();
+        }
+
+        let full = game.analyze_within(SolverBudget::default());
+        assert!(full.complete);
+        assert_eq!(full.analysis, game.analyze());
+
+        let budget = SolverBudget {
+            max_steps: Some(0),
+            ..Default::default()
+        };
+        let none = game.analyze_within(budget);
+        assert!(!none.complete);
+        assert!(none.analysis.is_empty());
+
+        // Partial results are a subset of the full ones.
+        for steps in 1..20 {
+            let partial = game.analyze_within(SolverBudget {
+                max_steps: Some(steps),
+                max_time: Some(Duration::from_secs(10)),
+            });
+            assert!(partial.analysis.safe.is_subset(&full.analysis.safe));
+            assert!(partial.analysis.mines.is_subset(&full.analysis.mines));
+        }
+    }
+}
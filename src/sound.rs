@@ -0,0 +1,197 @@
+use crate::{GameState, Minesweeper, Move, Outcome};
+
+/// Openings at least this large get [`SoundEvent::BigOpening`] instead of a
+/// plain reveal.
+pub const BIG_OPENING_TILES: usize = 10;
+
+/// Something worth making a noise about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundEvent {
+    Reveal,
+    BigOpening {
+        tiles: usize,
+    },
+    Flag,
+    Unflag,
+    Explosion,
+    /// A mine was hit, but a life was spent instead of the game.
+    LifeLost {
+        lives_left: usize,
+    },
+    Win,
+}
+
+/// Receives [`SoundEvent`]s as a game is played. Every method has a silent
+/// default, so frontends only override what they can play.
+pub trait SoundSink {
+    fn play(&mut self, _event: SoundEvent) {}
+}
+
+/// A sink that plays nothing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoSound;
+
+impl SoundSink for NoSound {}
+
+/// Applies `mv` to `game` and sends the sounds it caused to `sink`.
+pub fn apply_move_with_sound<S: SoundSink + ?Sized>(
+    game: &mut Minesweeper,
+    mv: Move,
+    sink: &mut S,
+) -> Outcome {
+    let covered_before = game.safe_tiles_remaining();
+    let lives_before = game.lives_remaining();
+    game.apply_move(mv)?;
+
+    match mv {
+        Move::Reveal(..) | Move::Chord(..) | Move::Assist(..) | Move::AutoFinish => {
+            if *game.get_game_state() == GameState::Lost {
+                sink.play(SoundEvent::Explosion);
+                return Ok(());
+            }
+            let lives_left = game.lives_remaining();
+            if lives_left < lives_before {
+                sink.play(SoundEvent::LifeLost { lives_left });
+            }
+            let tiles = covered_before - game.safe_tiles_remaining();
+            if tiles >= BIG_OPENING_TILES {
+                sink.play(SoundEvent::BigOpening { tiles });
+            } else if tiles > 0 {
+                sink.play(SoundEvent::Reveal);
+            }
+            if *game.get_game_state() == GameState::Won {
+                sink.play(SoundEvent::Win);
+            }
+        }
+        Move::ToggleFlag(x, y) => {
+            if game.get_tile(x, y).is_some_and(|tile| tile.flagged) {
+                sink.play(SoundEvent::Flag);
+            } else {
+                sink.play(SoundEvent::Unflag);
+            }
+        }
+        Move::SetMarker(..) => {}
+    }
+    Ok(())
+}
+
+/// Plays each event as a short synthesized chime on the default output
+/// device.
+#[cfg(feature = "sound")]
+pub struct RodioSink {
+    // Dropping the stream stops playback, so it lives as long as the sink.
+    _stream: rodio::OutputStream,
+    handle: rodio::OutputStreamHandle,
+}
+
+#[cfg(feature = "sound")]
+impl RodioSink {
+    pub fn new() -> Result<Self, String> {
+        let (stream, handle) = rodio::OutputStream::try_default()
+            .map_err(|e| format!("No audio output available: {}", e))?;
+        Ok(RodioSink {
+            _stream: stream,
+            handle,
+        })
+    }
+
+    /// The (frequency in Hz, duration in ms) notes played for `event`.
+    fn notes(event: SoundEvent) -> &'static [(f32, u64)] {
+        match event {
+            SoundEvent::Reveal => &[(880.0, 30)],
+            SoundEvent::BigOpening { .. } => &[(523.0, 60), (659.0, 60), (784.0, 90)],
+            SoundEvent::Flag => &[(660.0, 40)],
+            SoundEvent::Unflag => &[(440.0, 40)],
+            SoundEvent::Explosion => &[(110.0, 150), (82.0, 350)],
+            SoundEvent::LifeLost { .. } => &[(220.0, 100), (165.0, 200)],
+            SoundEvent::Win => &[(523.0, 120), (659.0, 120), (784.0, 120), (1047.0, 300)],
+        }
+    }
+}
+
+#[cfg(feature = "sound")]
+impl SoundSink for RodioSink {
+    fn play(&mut self, event: SoundEvent) {
+        use rodio::Source;
+        use std::time::Duration;
+
+        // Sound is best-effort: a missing device shouldn't stop the game.
+        let Ok(sink) = rodio::Sink::try_new(&self.handle) else {
+            return;
+        };
+        for &(frequency, millis) in Self::notes(event) {
+            sink.append(
+                rodio::source::SineWave::new(frequency)
+                    .take_duration(Duration::from_millis(millis))
+                    .amplify(0.2),
+            );
+        }
+        sink.detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Recorder(Vec<SoundEvent>);
+
+    impl SoundSink for Recorder {
+        fn play(&mut self, event: SoundEvent) {
+            self.0.push(event);
+        }
+    }
+
+    #[test]
+    fn test_sounds_follow_moves() {
+        let mut game = Minesweeper::new(2, vec![(0, 0)]);
+        let mut sink = Recorder::default();
+
+        apply_move_with_sound(&mut game, Move::ToggleFlag(0, 0), &mut sink).unwrap();
+        apply_move_with_sound(&mut game, Move::ToggleFlag(0, 0), &mut sink).unwrap();
+        apply_move_with_sound(&mut game, Move::Reveal(1, 1), &mut sink).unwrap();
+        apply_move_with_sound(&mut game, Move::Reveal(0, 1), &mut sink).unwrap();
+        apply_move_with_sound(&mut game, Move::Reveal(1, 0), &mut sink).unwrap();
+
+        assert_eq!(
+            sink.0,
+            vec![
+                SoundEvent::Flag,
+                SoundEvent::Unflag,
+                SoundEvent::Reveal,
+                SoundEvent::Reveal,
+                SoundEvent::Reveal,
+                SoundEvent::Win,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_big_opening_and_explosion() {
+        let mut game = Minesweeper::new(5, vec![(4, 4)]);
+        let mut sink = Recorder::default();
+        apply_move_with_sound(&mut game, Move::Reveal(0, 0), &mut sink).unwrap();
+        assert_eq!(
+            sink.0,
+            vec![SoundEvent::BigOpening { tiles: 24 }, SoundEvent::Win]
+        );
+
+        let mut recorder = Recorder::default();
+        let mut game = Minesweeper::new(3, vec![(1, 1)]);
+        apply_move_with_sound(&mut game, Move::Reveal(1, 1), &mut recorder).unwrap();
+        assert_eq!(recorder.0, vec![SoundEvent::Explosion]);
+
+        let mut game = Minesweeper::new(3, vec![(1, 1)]);
+        assert!(apply_move_with_sound(&mut game, Move::Reveal(0, 0), &mut NoSound).is_ok());
+    }
+
+    #[test]
+    fn test_life_lost() {
+        let mut game = Minesweeper::new(3, vec![(1, 1)]);
+        game.set_lives(2);
+        let mut sink = Recorder::default();
+        apply_move_with_sound(&mut game, Move::Reveal(1, 1), &mut sink).unwrap();
+        assert_eq!(sink.0, vec![SoundEvent::LifeLost { lives_left: 1 }]);
+    }
+}
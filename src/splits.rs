@@ -0,0 +1,172 @@
+use std::time::Duration;
+
+use crate::Minesweeper;
+
+/// A point in a game at which a split time is taken.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Milestone {
+    /// This fraction of the board's 3BV has been cleared.
+    Progress(f64),
+    /// Every safe tile in the rectangle between the two corners (inclusive)
+    /// has been revealed.
+    RegionCleared {
+        from: (usize, usize),
+        to: (usize, usize),
+    },
+}
+
+impl Milestone {
+    fn reached(&self, game: &Minesweeper) -> bool {
+        match *self {
+            Milestone::Progress(fraction) => game.progress() >= fraction,
+            Milestone::RegionCleared { from, to } => {
+                let (x0, x1) = (from.0.min(to.0), from.0.max(to.0));
+                let (y0, y1) = (from.1.min(to.1), from.1.max(to.1));
+                (x0..=x1).all(|x| {
+                    (y0..=y1).all(|y| match game.get_tile(x, y) {
+                        Some(tile) => tile.exposed || tile.is_bomb(),
+                        None => true,
+                    })
+                })
+            }
+        }
+    }
+}
+
+/// How a split compares with the personal best for the same milestone.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SplitDelta {
+    Ahead(Duration),
+    Behind(Duration),
+}
+
+/// Records the time at which each milestone is first reached, like a
+/// speedrun timer, and compares the splits against a personal best.
+///
+/// Call [`SplitTracker::update`] after every move with the game's elapsed
+/// time.
+#[derive(Debug, Clone)]
+pub struct SplitTracker {
+    milestones: Vec<Milestone>,
+    splits: Vec<Option<Duration>>,
+    personal_best: Vec<Option<Duration>>,
+}
+
+impl SplitTracker {
+    pub fn new(milestones: Vec<Milestone>) -> Self {
+        let count = milestones.len();
+        SplitTracker {
+            milestones,
+            splits: vec![None; count],
+            personal_best: vec![None; count],
+        }
+    }
+
+    /// Splits at every `step` of 3BV progress, e.g. 0.25 for splits at 25%,
+    /// 50%, 75% and 100%.
+    pub fn every_progress(step: f64) -> Self {
+        let count = (1.0 / step).round().max(1.0) as usize;
+        let milestones = (1..=count)
+            .map(|i| Milestone::Progress(i as f64 / count as f64))
+            .collect();
+        Self::new(milestones)
+    }
+
+    /// Compares against the splits of an earlier run with the same
+    /// milestones, typically [`SplitTracker::splits`] from a personal best.
+    pub fn with_personal_best(mut self, personal_best: Vec<Option<Duration>>) -> Self {
+        self.personal_best = personal_best;
+        self.personal_best.resize(self.milestones.len(), None);
+        self
+    }
+
+    /// Takes splits for any milestones `game` has newly reached, returning
+    /// their indices.
+    pub fn update(&mut self, game: &Minesweeper, elapsed: Duration) -> Vec<usize> {
+        let mut reached = Vec::new();
+        for (i, milestone) in self.milestones.iter().enumerate() {
+            if self.splits[i].is_none() && milestone.reached(game) {
+                self.splits[i] = Some(elapsed);
+                reached.push(i);
+            }
+        }
+        reached
+    }
+
+    pub fn milestones(&self) -> &[Milestone] {
+        &self.milestones
+    }
+
+    /// The time each milestone was reached, if it has been.
+    pub fn splits(&self) -> &[Option<Duration>] {
+        &self.splits
+    }
+
+    /// How the split for milestone `index` compares with the personal best,
+    /// if both exist.
+    pub fn delta(&self, index: usize) -> Option<SplitDelta> {
+        let split = (*self.splits.get(index)?)?;
+        let best = (*self.personal_best.get(index)?)?;
+        Some(if split <= best {
+            SplitDelta::Ahead(best - split)
+        } else {
+            SplitDelta::Behind(split - best)
+        })
+    }
+
+    /// Whether this run beat `personal_best` at the final milestone.
+    pub fn is_personal_best(&self) -> bool {
+        match (self.splits.last(), self.personal_best.last()) {
+            (Some(Some(split)), Some(Some(best))) => split < best,
+            (Some(Some(_)), _) => true,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_splits() {
+        // Eight isolated numbers around a central mine: 3BV of 8.
+        let mut game = Minesweeper::new(3, vec![(1, 1)]);
+        let mut tracker = SplitTracker::every_progress(0.25);
+        assert_eq!(tracker.milestones().len(), 4);
+
+        game.click_tile(0, 0).unwrap();
+        assert!(tracker.update(&game, Duration::from_secs(1)).is_empty());
+        game.click_tile(0, 1).unwrap();
+        assert_eq!(tracker.update(&game, Duration::from_secs(2)), vec![0]);
+
+        for (x, y) in [(0, 2), (1, 0), (1, 2), (2, 0)] {
+            game.click_tile(x, y).unwrap();
+        }
+        assert_eq!(tracker.update(&game, Duration::from_secs(6)), vec![1, 2]);
+        assert_eq!(tracker.splits()[2], Some(Duration::from_secs(6)));
+        assert_eq!(tracker.splits()[3], None);
+    }
+
+    #[test]
+    fn test_region_split_and_personal_best() {
+        let mut game = Minesweeper::new(3, vec![(1, 1)]);
+        let region = Milestone::RegionCleared {
+            from: (0, 0),
+            to: (0, 2),
+        };
+        let mut tracker =
+            SplitTracker::new(vec![region]).with_personal_best(vec![Some(Duration::from_secs(5))]);
+
+        for y in 0..3 {
+            game.click_tile(0, y).unwrap();
+        }
+        tracker.update(&game, Duration::from_secs(3));
+
+        assert_eq!(
+            tracker.delta(0),
+            Some(SplitDelta::Ahead(Duration::from_secs(2)))
+        );
+        assert!(tracker.is_personal_best());
+    }
+}
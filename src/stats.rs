@@ -0,0 +1,293 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::{GameState, GameSummary};
+
+/// A board size and mine count, e.g. 9 x 9 with 10 mines, that best times
+/// are kept for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Difficulty {
+    pub width: usize,
+    pub height: usize,
+    pub bomb_count: usize,
+}
+
+impl Difficulty {
+    /// 9 x 9 with 10 mines.
+    pub const BEGINNER: Difficulty = Difficulty::new(9, 9, 10);
+    /// 16 x 16 with 40 mines.
+    pub const INTERMEDIATE: Difficulty = Difficulty::new(16, 16, 40);
+    /// 30 x 16 with 99 mines.
+    pub const EXPERT: Difficulty = Difficulty::new(30, 16, 99);
+
+    pub const fn new(width: usize, height: usize, bomb_count: usize) -> Self {
+        Difficulty {
+            width,
+            height,
+            bomb_count,
+        }
+    }
+
+    fn of(summary: &GameSummary) -> Self {
+        Difficulty {
+            width: summary.width,
+            height: summary.height,
+            bomb_count: summary.bomb_count,
+        }
+    }
+}
+
+/// One player's record across every game they finished: results, streaks,
+/// best times and speed.
+///
+/// Kept as plain text (see [`Statistics::to_stats_string`]) so a frontend
+/// can store it in a stats file next to its other settings.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Statistics {
+    played: usize,
+    won: usize,
+    /// Games played with the flag check assist on.
+    assisted: usize,
+    current_streak: usize,
+    best_streak: usize,
+    best_times: BTreeMap<Difficulty, Duration>,
+    /// 3BV cleared and time taken across won games, for the average speed.
+    won_three_bv: usize,
+    won_time: Duration,
+    best_three_bv_per_second: Option<f64>,
+}
+
+impl Statistics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a finished game. Games still in progress are ignored.
+    pub fn record(&mut self, summary: &GameSummary) {
+        if summary.outcome.is_over() && summary.flag_check {
+            self.assisted += 1;
+        }
+        match summary.outcome {
+            GameState::InProgress | GameState::Paused => return,
+            GameState::Lost => {
+                self.played += 1;
+                self.current_streak = 0;
+                return;
+            }
+            GameState::Won => {}
+        }
+
+        self.played += 1;
+        self.won += 1;
+        self.current_streak += 1;
+        self.best_streak = self.best_streak.max(self.current_streak);
+
+        let best = self
+            .best_times
+            .entry(Difficulty::of(summary))
+            .or_insert(summary.time);
+        *best = (*best).min(summary.time);
+
+        self.won_three_bv += summary.three_bv;
+        self.won_time += summary.time;
+        if let Some(speed) = three_bv_per_second(summary.three_bv, summary.time) {
+            if self
+                .best_three_bv_per_second
+                .is_none_or(|best| speed > best)
+            {
+                self.best_three_bv_per_second = Some(speed);
+            }
+        }
+    }
+
+    pub fn played(&self) -> usize {
+        self.played
+    }
+
+    pub fn won(&self) -> usize {
+        self.won
+    }
+
+    pub fn lost(&self) -> usize {
+        self.played - self.won
+    }
+
+    /// Games played with the flag check assist on (see
+    /// [`Minesweeper::set_flag_check`](crate::Minesweeper::set_flag_check)).
+    pub fn assisted(&self) -> usize {
+        self.assisted
+    }
+
+    /// The share of games won, from 0 to 1, or `None` before any game.
+    pub fn win_rate(&self) -> Option<f64> {
+        (self.played > 0).then(|| self.won as f64 / self.played as f64)
+    }
+
+    /// Wins in a row up to the latest game.
+    pub fn current_streak(&self) -> usize {
+        self.current_streak
+    }
+
+    pub fn best_streak(&self) -> usize {
+        self.best_streak
+    }
+
+    pub fn best_time(&self, difficulty: Difficulty) -> Option<Duration> {
+        self.best_times.get(&difficulty).copied()
+    }
+
+    /// Every difficulty won at least once, with its best time.
+    pub fn best_times(&self) -> impl Iterator<Item = (Difficulty, Duration)> + '_ {
+        self.best_times.iter().map(|(&d, &time)| (d, time))
+    }
+
+    /// 3BV per second over all won games together.
+    pub fn three_bv_per_second(&self) -> Option<f64> {
+        three_bv_per_second(self.won_three_bv, self.won_time)
+    }
+
+    /// The fastest single win, in 3BV per second.
+    pub fn best_three_bv_per_second(&self) -> Option<f64> {
+        self.best_three_bv_per_second
+    }
+
+    /// The statistics as `key=value` lines, with one
+    /// `best=WIDTHxHEIGHT/MINES:MILLISECONDS` line per difficulty.
+    pub fn to_stats_string(&self) -> String {
+        let mut text = String::new();
+        text.push_str(&format!("played={}\n", self.played));
+        text.push_str(&format!("won={}\n", self.won));
+        text.push_str(&format!("assisted={}\n", self.assisted));
+        text.push_str(&format!("streak={}\n", self.current_streak));
+        text.push_str(&format!("best-streak={}\n", self.best_streak));
+        text.push_str(&format!("won-3bv={}\n", self.won_three_bv));
+        text.push_str(&format!("won-ms={}\n", self.won_time.as_millis()));
+        if let Some(speed) = self.best_three_bv_per_second {
+            text.push_str(&format!("best-3bv-per-second={}\n", speed));
+        }
+        for (difficulty, time) in &self.best_times {
+            text.push_str(&format!(
+                "best={}x{}/{}:{}\n",
+                difficulty.width,
+                difficulty.height,
+                difficulty.bomb_count,
+                time.as_millis()
+            ));
+        }
+        text
+    }
+
+    /// Parses text produced by [`Statistics::to_stats_string`].
+    pub fn from_stats_string(text: &str) -> Result<Self, String> {
+        let mut stats = Statistics::default();
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let invalid = || format!("Invalid stats line: {}", line);
+            let (key, value) = line.split_once('=').ok_or_else(invalid)?;
+            let number = || value.parse::<usize>().map_err(|_| invalid());
+            match key {
+                "played" => stats.played = number()?,
+                "won" => stats.won = number()?,
+                "assisted" => stats.assisted = number()?,
+                "streak" => stats.current_streak = number()?,
+                "best-streak" => stats.best_streak = number()?,
+                "won-3bv" => stats.won_three_bv = number()?,
+                "won-ms" => stats.won_time = Duration::from_millis(number()? as u64),
+                "best-3bv-per-second" => {
+                    stats.best_three_bv_per_second = Some(value.parse().map_err(|_| invalid())?)
+                }
+                "best" => {
+                    let (difficulty, time) = parse_best(value).ok_or_else(invalid)?;
+                    stats.best_times.insert(difficulty, time);
+                }
+                _ => return Err(format!("Unknown stat: {}", key)),
+            }
+        }
+        if stats.won > stats.played || stats.assisted > stats.played {
+            return Err("More games won than played".to_string());
+        }
+        Ok(stats)
+    }
+}
+
+fn three_bv_per_second(three_bv: usize, time: Duration) -> Option<f64> {
+    let secs = time.as_secs_f64();
+    (secs > 0.0).then(|| three_bv as f64 / secs)
+}
+
+/// Parses `WIDTHxHEIGHT/MINES:MILLISECONDS`.
+fn parse_best(value: &str) -> Option<(Difficulty, Duration)> {
+    let (size, rest) = value.split_once('/')?;
+    let (width, height) = size.split_once('x')?;
+    let (bomb_count, millis) = rest.split_once(':')?;
+    let difficulty = Difficulty {
+        width: width.parse().ok()?,
+        height: height.parse().ok()?,
+        bomb_count: bomb_count.parse().ok()?,
+    };
+    Some((difficulty, Duration::from_millis(millis.parse().ok()?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(outcome: GameState, size: usize, secs: u64) -> GameSummary {
+        GameSummary {
+            outcome,
+            width: size,
+            height: size,
+            bomb_count: 10,
+            time: Duration::from_secs(secs),
+            flags_placed: 0,
+            three_bv: 30,
+            flag_check: false,
+            guesses: None,
+        }
+    }
+
+    #[test]
+    fn test_statistics_aggregate_games() {
+        let mut stats = Statistics::new();
+        assert_eq!(stats.win_rate(), None);
+        stats.record(&game(GameState::Won, 9, 20));
+        stats.record(&game(GameState::Won, 9, 15));
+        stats.record(&game(GameState::Lost, 9, 5));
+        stats.record(&GameSummary {
+            flag_check: true,
+            ..game(GameState::Won, 16, 60)
+        });
+        stats.record(&game(GameState::InProgress, 9, 1));
+
+        assert_eq!((stats.played(), stats.won(), stats.lost()), (4, 3, 1));
+        assert_eq!(stats.assisted(), 1);
+        assert_eq!(stats.win_rate(), Some(0.75));
+        assert_eq!((stats.current_streak(), stats.best_streak()), (1, 2));
+        let beginner = Difficulty {
+            width: 9,
+            height: 9,
+            bomb_count: 10,
+        };
+        assert_eq!(stats.best_time(beginner), Some(Duration::from_secs(15)));
+        assert_eq!(stats.best_times().count(), 2);
+        assert_eq!(stats.three_bv_per_second(), Some(90.0 / 95.0));
+        assert_eq!(stats.best_three_bv_per_second(), Some(2.0));
+    }
+
+    #[test]
+    fn test_stats_string_round_trip() {
+        let mut stats = Statistics::new();
+        stats.record(&game(GameState::Won, 9, 20));
+        stats.record(&game(GameState::Lost, 16, 20));
+        let text = stats.to_stats_string();
+        assert!(text.contains("best=9x9/10:20000\n"));
+        assert_eq!(Statistics::from_stats_string(&text).unwrap(), stats);
+
+        assert_eq!(
+            Statistics::from_stats_string("").unwrap(),
+            Statistics::new()
+        );
+        assert!(Statistics::from_stats_string("best=9x9:10").is_err());
+        assert!(Statistics::from_stats_string("wins=3").is_err());
+        assert!(Statistics::from_stats_string("played=1\nwon=2").is_err());
+    }
+}
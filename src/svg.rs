@@ -0,0 +1,178 @@
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::num::NonZeroUsize;
+use std::thread;
+
+use crate::{Minesweeper, VisibleTile};
+
+/// Columns of tiles each thread renders at a time. Only one band per
+/// thread is held in memory before it is written out.
+const BAND_COLUMNS: usize = 64;
+
+const STYLE: &str = "text{font:bold 70% sans-serif;text-anchor:middle;dominant-baseline:central}\
+.o{fill:#e0e0e0;stroke:#9e9e9e}.x{fill:#e53935;stroke:#9e9e9e}.m{fill:#212121}\
+.f{fill:#d32f2f}.w{fill:#212121}\
+.n1{fill:#1976d2}.n2{fill:#388e3c}.n3{fill:#d32f2f}.n4{fill:#303f9f}\
+.n5{fill:#8d6e63}.n6{fill:#00838f}.n7{fill:#212121}.n8{fill:#757575}";
+
+/// Writes the board as the player sees it (see
+/// [`Minesweeper::player_view`]) as an SVG image with `cell` pixels per
+/// tile.
+///
+/// Bands of columns are rendered on all available cores and streamed to
+/// `out` in order, so exporting a 1000x1000 board neither takes long nor
+/// holds the whole document in memory. Covered tiles come from a
+/// background pattern and cost nothing.
+pub fn export_svg(game: &Minesweeper, cell: usize, mut out: impl Write) -> io::Result<()> {
+    let (width, height) = (game.get_width(), game.get_height());
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+        w = width * cell,
+        h = height * cell
+    )?;
+    writeln!(out, "<style>{}</style>", STYLE)?;
+    writeln!(
+        out,
+        "<defs><pattern id=\"covered\" width=\"{c}\" height=\"{c}\" patternUnits=\"userSpaceOnUse\">\
+<rect width=\"{c}\" height=\"{c}\" fill=\"#bdbdbd\" stroke=\"#757575\"/></pattern></defs>",
+        c = cell
+    )?;
+    writeln!(
+        out,
+        "<rect width=\"100%\" height=\"100%\" fill=\"url(#covered)\"/>"
+    )?;
+
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let bands: Vec<usize> = (0..width).step_by(BAND_COLUMNS).collect();
+    for batch in bands.chunks(threads) {
+        let rendered: Vec<String> = thread::scope(|scope| {
+            let handles: Vec<_> = batch
+                .iter()
+                .map(|&start| {
+                    let end = (start + BAND_COLUMNS).min(width);
+                    scope.spawn(move || render_band(game, start..end, cell))
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("SVG band rendering panicked"))
+                .collect()
+        });
+        for band in rendered {
+            out.write_all(band.as_bytes())?;
+        }
+    }
+    writeln!(out, "</svg>")
+}
+
+/// The SVG elements for the tiles in `columns`, skipping covered ones.
+fn render_band(game: &Minesweeper, columns: std::ops::Range<usize>, cell: usize) -> String {
+    let mut svg = String::new();
+    let half = cell as f64 / 2.0;
+    for x in columns {
+        for y in 0..game.get_height() {
+            let (left, top) = (x * cell, y * cell);
+            let (cx, cy) = (left as f64 + half, top as f64 + half);
+            let square = |svg: &mut String, class: &str| {
+                let _ = write!(
+                    svg,
+                    "<rect class=\"{}\" x=\"{}\" y=\"{}\" width=\"{c}\" height=\"{c}\"/>",
+                    class,
+                    left,
+                    top,
+                    c = cell
+                );
+            };
+            let label = |svg: &mut String, class: &str, text: &str| {
+                let _ = write!(
+                    svg,
+                    "<text class=\"{}\" x=\"{}\" y=\"{}\">{}</text>",
+                    class, cx, cy, text
+                );
+            };
+            let mine = |svg: &mut String| {
+                let _ = write!(
+                    svg,
+                    "<circle class=\"m\" cx=\"{}\" cy=\"{}\" r=\"{}\"/>",
+                    cx,
+                    cy,
+                    half * 0.6
+                );
+            };
+            match game.visible_tile(x, y) {
+                VisibleTile::Covered => continue,
+                VisibleTile::Flagged => label(&mut svg, "f", "F"),
+                VisibleTile::Number(n) => {
+                    square(&mut svg, "o");
+                    if n > 0 {
+                        label(&mut svg, &format!("n{}", n.min(8)), &n.to_string());
+                    }
+                }
+                VisibleTile::Mine => {
+                    square(&mut svg, "o");
+                    mine(&mut svg);
+                }
+                VisibleTile::Exploded => {
+                    square(&mut svg, "x");
+                    mine(&mut svg);
+                }
+                VisibleTile::WrongFlag => {
+                    square(&mut svg, "o");
+                    label(&mut svg, "w", "×");
+                }
+            }
+        }
+        svg.push('\n');
+    }
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn svg(game: &Minesweeper) -> String {
+        let mut out = Vec::new();
+        export_svg(game, 10, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_svg_shows_the_player_view() {
+        let mut game = Minesweeper::new_rect(3, 2, vec![(0, 0), (2, 1)]);
+        game.click_tile(1, 0).unwrap();
+        game.toggle_flag(0, 1).unwrap();
+        let text = svg(&game);
+        assert!(text
+            .starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"30\" height=\"20\""));
+        assert!(text.contains("<text class=\"n2\" x=\"15\" y=\"5\">2</text>"));
+        assert!(text.contains("<text class=\"f\" x=\"5\" y=\"15\">F</text>"));
+        assert!(!text.contains("circle"));
+        assert!(text.trim_end().ends_with("</svg>"));
+
+        game.click_tile(0, 0).unwrap();
+        let text = svg(&game);
+        assert!(text.contains("<rect class=\"x\" x=\"0\" y=\"0\""));
+        assert!(text.contains(">×</text>"));
+        assert_eq!(text.matches("<circle").count(), 2);
+    }
+
+    #[test]
+    fn test_bands_are_written_in_order() {
+        let width = BAND_COLUMNS * 3 + 5;
+        let mut game = Minesweeper::new_rect(width, 1, vec![(width - 1, 0)]);
+        game.click_tile(0, 0).unwrap();
+        let text = svg(&game);
+        let xs: Vec<usize> = text
+            .lines()
+            .filter(|line| line.starts_with("<rect class"))
+            .map(|line| {
+                let x = line.split("x=\"").nth(1).unwrap();
+                x[..x.find('"').unwrap()].parse().unwrap()
+            })
+            .collect();
+        let expected: Vec<usize> = (0..width - 1).map(|x| x * 10).collect();
+        assert_eq!(xs, expected);
+    }
+}
@@ -0,0 +1,422 @@
+use crate::{Mark, Tile, TileValue};
+
+/// One of the sixteen standard terminal colours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeColor {
+    Black,
+    DarkRed,
+    DarkGreen,
+    DarkYellow,
+    DarkBlue,
+    DarkMagenta,
+    DarkCyan,
+    Gray,
+    DarkGray,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+/// How one kind of tile is drawn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Glyph {
+    pub text: String,
+    pub color: Option<ThemeColor>,
+    pub bold: bool,
+}
+
+impl Glyph {
+    pub fn new(text: impl Into<String>) -> Self {
+        Glyph {
+            text: text.into(),
+            color: None,
+            bold: false,
+        }
+    }
+
+    pub fn with_color(mut self, color: ThemeColor) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn with_bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    /// The terminal columns the glyph takes up; see [`display_width`].
+    pub fn width(&self) -> usize {
+        display_width(&self.text)
+    }
+}
+
+/// The glyphs and colours a frontend draws a board with.
+///
+/// Glyphs may differ in width (an emoji takes two columns, a digit one),
+/// so frontends should draw every tile [`Theme::pad`]ded to
+/// [`Theme::cell_width`] to keep the columns of the board lined up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    /// The name the theme is selected by, e.g. `emoji`.
+    pub name: String,
+    pub covered: Glyph,
+    pub flag: Glyph,
+    pub question: Glyph,
+    pub mine: Glyph,
+    /// An exposed tile with no mines around it.
+    pub zero: Glyph,
+    /// The exposed numbers 1 to 8.
+    pub numbers: [Glyph; 8],
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::builtin("default").expect("the default theme is built in")
+    }
+}
+
+impl Theme {
+    /// The names of the themes shipped with the crate, for
+    /// [`Theme::builtin`].
+    pub const BUILTIN: [&'static str; 6] = [
+        "default",
+        "classic",
+        "monochrome",
+        "high-contrast",
+        "emoji",
+        "nerd-font",
+    ];
+
+    /// The built-in theme called `name`:
+    ///
+    /// - `default`: ASCII glyphs with coloured numbers.
+    /// - `classic`: the colours of the classic Windows game.
+    /// - `monochrome`: ASCII glyphs and no colour at all.
+    /// - `high-contrast`: bold, bright colours that stay apart for
+    ///   colour-blind players.
+    /// - `emoji`: emoji tiles, two columns wide.
+    /// - `nerd-font`: icons from a patched [Nerd Font](https://www.nerdfonts.com/).
+    pub fn builtin(name: &str) -> Result<Theme, String> {
+        use ThemeColor::*;
+        let numbers = |colors: [Option<ThemeColor>; 8]| {
+            let mut n = 0;
+            colors.map(|color| {
+                n += 1;
+                let glyph = Glyph::new(n.to_string());
+                match color {
+                    Some(color) => glyph.with_color(color),
+                    None => glyph,
+                }
+            })
+        };
+
+        let theme = match name {
+            "default" => Theme {
+                name: name.to_string(),
+                covered: Glyph::new("#"),
+                flag: Glyph::new("F").with_color(Red),
+                question: Glyph::new("?"),
+                mine: Glyph::new("*").with_color(Red),
+                zero: Glyph::new("."),
+                numbers: numbers(
+                    [Blue, Green, Red, DarkBlue, DarkRed, Cyan, Magenta, Magenta].map(Some),
+                ),
+            },
+            "classic" => Theme {
+                name: name.to_string(),
+                covered: Glyph::new("#").with_color(Gray),
+                flag: Glyph::new("F").with_color(Red).with_bold(),
+                question: Glyph::new("?").with_color(Black),
+                mine: Glyph::new("*").with_color(Black).with_bold(),
+                zero: Glyph::new(" "),
+                numbers: numbers(
+                    [
+                        Blue, DarkGreen, Red, DarkBlue, DarkRed, DarkCyan, Black, DarkGray,
+                    ]
+                    .map(Some),
+                ),
+            },
+            "monochrome" => Theme {
+                name: name.to_string(),
+                covered: Glyph::new("#"),
+                flag: Glyph::new("F"),
+                question: Glyph::new("?"),
+                mine: Glyph::new("*"),
+                zero: Glyph::new("."),
+                numbers: numbers([None; 8]),
+            },
+            "high-contrast" => {
+                let theme = Theme {
+                    name: name.to_string(),
+                    covered: Glyph::new("#").with_color(White),
+                    flag: Glyph::new("F").with_color(Yellow),
+                    question: Glyph::new("?").with_color(Yellow),
+                    mine: Glyph::new("*").with_color(Red),
+                    zero: Glyph::new(".").with_color(White),
+                    numbers: numbers(
+                        [Cyan, Green, Yellow, Magenta, Red, White, Blue, White].map(Some),
+                    ),
+                };
+                theme.bold()
+            }
+            "emoji" => Theme {
+                name: name.to_string(),
+                covered: Glyph::new("\u{1F7E6}"),
+                flag: Glyph::new("\u{1F6A9}"),
+                question: Glyph::new("\u{2753}"),
+                mine: Glyph::new("\u{1F4A3}"),
+                zero: Glyph::new("\u{2B1C}"),
+                // Keycap emoji are drawn at different widths by different
+                // terminals, so numbers stay digits.
+                numbers: numbers(
+                    [Blue, Green, Red, DarkBlue, DarkRed, Cyan, Magenta, Magenta].map(Some),
+                ),
+            },
+            "nerd-font" => Theme {
+                name: name.to_string(),
+                covered: Glyph::new("\u{F0C8}").with_color(Gray),
+                flag: Glyph::new("\u{F024}").with_color(Red),
+                question: Glyph::new("\u{F128}").with_color(Yellow),
+                mine: Glyph::new("\u{F1E2}").with_color(Red),
+                zero: Glyph::new("\u{B7}").with_color(DarkGray),
+                numbers: numbers(
+                    [Blue, Green, Red, DarkBlue, DarkRed, Cyan, Magenta, Magenta].map(Some),
+                ),
+            },
+            _ => {
+                return Err(format!(
+                    "Unknown theme: {} (expected one of {})",
+                    name,
+                    Theme::BUILTIN.join(", ")
+                ))
+            }
+        };
+        Ok(theme)
+    }
+
+    /// Every built-in theme.
+    pub fn builtins() -> Vec<Theme> {
+        Theme::BUILTIN
+            .iter()
+            .map(|name| Theme::builtin(name).expect("listed themes are built in"))
+            .collect()
+    }
+
+    /// How `tile` is drawn. Exposed tiles show what they hide; covered ones
+    /// show the player's [`Mark`].
+    pub fn glyph(&self, tile: &Tile) -> &Glyph {
+        if tile.exposed {
+            return match tile.value {
+                TileValue::Bomb => &self.mine,
+                TileValue::Number(0) => &self.zero,
+                TileValue::Number(n) => &self.numbers[(n as usize - 1).min(7)],
+            };
+        }
+        match tile.mark() {
+            Mark::Flag => &self.flag,
+            Mark::Question => &self.question,
+            Mark::None => &self.covered,
+        }
+    }
+
+    /// The columns every tile is drawn in: the width of the widest glyph.
+    pub fn cell_width(&self) -> usize {
+        self.glyphs().map(Glyph::width).max().unwrap_or(1)
+    }
+
+    /// `glyph`'s text padded on the left to [`Theme::cell_width`].
+    pub fn pad(&self, glyph: &Glyph) -> String {
+        let padding = self.cell_width().saturating_sub(glyph.width());
+        format!("{}{}", " ".repeat(padding), glyph.text)
+    }
+
+    fn glyphs(&self) -> impl Iterator<Item = &Glyph> {
+        [
+            &self.covered,
+            &self.flag,
+            &self.question,
+            &self.mine,
+            &self.zero,
+        ]
+        .into_iter()
+        .chain(&self.numbers)
+    }
+
+    /// The theme with every glyph in bold.
+    fn bold(mut self) -> Self {
+        for glyph in [
+            &mut self.covered,
+            &mut self.flag,
+            &mut self.question,
+            &mut self.mine,
+            &mut self.zero,
+        ]
+        .into_iter()
+        .chain(&mut self.numbers)
+        {
+            glyph.bold = true;
+        }
+        self
+    }
+}
+
+/// The terminal columns `text` takes up: two for each wide character (East
+/// Asian scripts and emoji, including a character turned into an emoji by
+/// a variation selector), none for combining marks and other zero-width
+/// characters, and one for anything else. Private-use characters, such as
+/// Nerd Font icons, count as one.
+pub fn display_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let mut columns = char_width(c);
+        if chars.peek() == Some(&'\u{FE0F}') {
+            chars.next();
+            columns = 2;
+        }
+        width += columns;
+    }
+    width
+}
+
+fn char_width(c: char) -> usize {
+    const ZERO: &[(u32, u32)] = &[
+        (0x0300, 0x036F),
+        (0x200B, 0x200F),
+        (0x20D0, 0x20FF),
+        (0xFE00, 0xFE0F),
+    ];
+    const WIDE: &[(u32, u32)] = &[
+        (0x1100, 0x115F),
+        (0x231A, 0x231B),
+        (0x23E9, 0x23EC),
+        (0x23F0, 0x23F0),
+        (0x23F3, 0x23F3),
+        (0x25FD, 0x25FE),
+        (0x2614, 0x2615),
+        (0x2648, 0x2653),
+        (0x267F, 0x267F),
+        (0x2693, 0x2693),
+        (0x26A1, 0x26A1),
+        (0x26AA, 0x26AB),
+        (0x26BD, 0x26BE),
+        (0x26C4, 0x26C5),
+        (0x26CE, 0x26CE),
+        (0x26D4, 0x26D4),
+        (0x26EA, 0x26EA),
+        (0x26F2, 0x26F3),
+        (0x26F5, 0x26F5),
+        (0x26FA, 0x26FA),
+        (0x26FD, 0x26FD),
+        (0x2705, 0x2705),
+        (0x270A, 0x270B),
+        (0x2728, 0x2728),
+        (0x274C, 0x274C),
+        (0x274E, 0x274E),
+        (0x2753, 0x2755),
+        (0x2757, 0x2757),
+        (0x2795, 0x2797),
+        (0x27B0, 0x27B0),
+        (0x27BF, 0x27BF),
+        (0x2B1B, 0x2B1C),
+        (0x2B50, 0x2B50),
+        (0x2B55, 0x2B55),
+        (0x2E80, 0x303E),
+        (0x3041, 0x33FF),
+        (0x3400, 0x4DBF),
+        (0x4E00, 0x9FFF),
+        (0xA000, 0xA4CF),
+        (0xAC00, 0xD7A3),
+        (0xF900, 0xFAFF),
+        (0xFE30, 0xFE4F),
+        (0xFF00, 0xFF60),
+        (0xFFE0, 0xFFE6),
+        (0x1F004, 0x1F004),
+        (0x1F0CF, 0x1F0CF),
+        (0x1F18E, 0x1F18E),
+        (0x1F191, 0x1F19A),
+        (0x1F300, 0x1F64F),
+        (0x1F680, 0x1F6FF),
+        (0x1F7E0, 0x1F7EB),
+        (0x1F900, 0x1F9FF),
+        (0x1FA70, 0x1FAFF),
+        (0x20000, 0x3FFFD),
+    ];
+    let code = c as u32;
+    let within = |ranges: &[(u32, u32)]| {
+        ranges
+            .iter()
+            .any(|&(start, end)| (start..=end).contains(&code))
+    };
+    if within(ZERO) {
+        0
+    } else if within(WIDE) {
+        2
+    } else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BoardRenderer, Minesweeper, TextRenderer, View};
+
+    #[test]
+    fn test_display_width() {
+        assert_eq!(display_width("12"), 2);
+        assert_eq!(display_width("\u{1F4A3}"), 2);
+        assert_eq!(display_width("\u{2691}\u{FE0F}"), 2);
+        assert_eq!(display_width("e\u{301}"), 1);
+        assert_eq!(display_width("\u{F1E2}"), 1);
+        assert_eq!(display_width("地雷"), 4);
+    }
+
+    #[test]
+    fn test_builtin_themes_render_aligned() {
+        let mut game = Minesweeper::new_rect(5, 3, vec![(0, 0), (4, 2)]);
+        game.click_tile(1, 0).unwrap();
+        game.toggle_flag(0, 0).unwrap();
+        game.set_question_marks(true);
+        game.cycle_mark(4, 2).unwrap();
+        game.cycle_mark(4, 2).unwrap();
+
+        for theme in Theme::builtins() {
+            let cell = theme.cell_width();
+            let rendered = TextRenderer::new(theme.clone()).render(&game, View::Player);
+            let lines: Vec<&str> = rendered.lines().collect();
+            assert_eq!(lines.len(), 3, "{}", theme.name);
+            for line in lines {
+                assert_eq!(display_width(line), 5 * cell + 4, "{}", theme.name);
+            }
+        }
+
+        let emoji = Theme::builtin("emoji").unwrap();
+        assert_eq!(emoji.cell_width(), 2);
+        assert_eq!(
+            TextRenderer::emoji()
+                .render(&game, View::Player)
+                .lines()
+                .next(),
+            Some("\u{1F6A9}  1 \u{1F7E6} \u{1F7E6} \u{1F7E6}")
+        );
+        assert_eq!(emoji.pad(&emoji.numbers[0]), " 1");
+
+        let monochrome = Theme::builtin("monochrome").unwrap();
+        assert_eq!(
+            TextRenderer::new(monochrome).render(&game, View::Player),
+            "F 1 # # #\n# # # # #\n# # # # ?\n"
+        );
+    }
+
+    #[test]
+    fn test_unknown_theme() {
+        assert!(Theme::builtin("sepia").is_err());
+        assert_eq!(Theme::default().name, "default");
+        assert_eq!(Theme::builtins().len(), Theme::BUILTIN.len());
+    }
+}
@@ -0,0 +1,249 @@
+use std::fmt;
+#[cfg(feature = "serde")]
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "serde")]
+use crate::GameState;
+use crate::{Minesweeper, Move, MoveClock, Outcome};
+
+/// A [`Minesweeper`] with a game timer: it starts on the first reveal,
+/// stops when the game is won or lost, and stands still while the game is
+/// [paused](Minesweeper::pause).
+///
+/// The game itself never reads the time. On targets without
+/// [`Instant`], such as `wasm32-unknown-unknown`, build the wrapper with
+/// [`TimedMinesweeper::with_clock`] and a time source of your own.
+pub struct TimedMinesweeper {
+    game: Minesweeper,
+    clock: MoveClock,
+    /// Time counted before the timer last stopped or was paused.
+    banked: Duration,
+    /// The clock reading the timer was last started at, while it runs.
+    running_since: Option<Duration>,
+    started: bool,
+}
+
+impl fmt::Debug for TimedMinesweeper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TimedMinesweeper")
+            .field("game", &self.game)
+            .field("elapsed", &self.elapsed())
+            .field("paused", &self.is_paused())
+            .finish_non_exhaustive()
+    }
+}
+
+impl TimedMinesweeper {
+    /// Times `game` with the system clock. A game that already has tiles
+    /// revealed, such as one opened around the first click, starts its
+    /// timer straight away.
+    pub fn new(game: Minesweeper) -> Self {
+        let start = Instant::now();
+        Self::with_clock(game, Arc::new(move || start.elapsed()))
+    }
+
+    /// Times `game` with `clock`, which reports how much time has passed
+    /// since any fixed point and never goes backwards. A paused game's
+    /// timer waits for [`TimedMinesweeper::resume`].
+    pub fn with_clock(game: Minesweeper, clock: MoveClock) -> Self {
+        let mut timed = TimedMinesweeper {
+            game,
+            clock,
+            banked: Duration::ZERO,
+            running_since: None,
+            started: false,
+        };
+        if timed.game.count_exposed_tiles() > 0 {
+            timed.start();
+            if timed.is_paused() {
+                timed.stop();
+            }
+        }
+        timed
+    }
+
+    pub fn game(&self) -> &Minesweeper {
+        &self.game
+    }
+
+    pub fn into_inner(self) -> Minesweeper {
+        self.game
+    }
+
+    /// Plays `mv` on the game, starting the timer on the first reveal and
+    /// stopping it once the game is over. Moves are refused while paused.
+    pub fn apply_move(&mut self, mv: Move) -> Outcome {
+        self.game.apply_move(mv)?;
+
+        let reveals = !matches!(mv, Move::ToggleFlag(..) | Move::SetMarker(..));
+        if reveals && !self.started {
+            self.start();
+        }
+        if self.game.get_game_state().is_over() {
+            self.stop();
+        }
+        Ok(())
+    }
+
+    /// Time on the clock: from the first reveal until the game ended or
+    /// until now, less any time spent paused.
+    pub fn elapsed(&self) -> Duration {
+        let running = self
+            .running_since
+            .map_or(Duration::ZERO, |since| (self.clock)().saturating_sub(since));
+        self.banked + running
+    }
+
+    /// Whether the timer is counting.
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.game.is_paused()
+    }
+
+    /// Pauses the game, stopping the timer and refusing moves until
+    /// [`TimedMinesweeper::resume`]. Does nothing unless the game is in
+    /// progress.
+    pub fn pause(&mut self) {
+        if self.game.pause().is_ok() {
+            self.stop();
+        }
+    }
+
+    /// Lifts a pause, restarting the timer if the game had started.
+    pub fn resume(&mut self) {
+        if self.game.resume().is_ok() && self.started {
+            self.running_since = Some((self.clock)());
+        }
+    }
+
+    /// Writes the game and the time on the clock to `path` as JSON; see
+    /// [`Minesweeper::save_to_file`].
+    #[cfg(feature = "serde")]
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let saved = SavedTimer {
+            game: &self.game,
+            elapsed: self.elapsed(),
+            started: self.started,
+        };
+        let json = serde_json::to_string(&saved).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| e.to_string())
+    }
+
+    /// Loads a game saved with [`TimedMinesweeper::save_to_file`], timed
+    /// with the system clock. The timer carries on from the saved time,
+    /// unless the game was paused or over.
+    #[cfg(feature = "serde")]
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let saved: SavedTimer<Minesweeper> =
+            serde_json::from_str(&json).map_err(|e| e.to_string())?;
+        let start = Instant::now();
+        let mut timed = TimedMinesweeper {
+            game: saved.game,
+            clock: Arc::new(move || start.elapsed()),
+            banked: saved.elapsed,
+            running_since: None,
+            started: saved.started,
+        };
+        if timed.started && *timed.game.get_game_state() == GameState::InProgress {
+            timed.running_since = Some((timed.clock)());
+        }
+        Ok(timed)
+    }
+
+    fn start(&mut self) {
+        self.started = true;
+        self.running_since = Some((self.clock)());
+    }
+
+    fn stop(&mut self) {
+        self.banked = self.elapsed();
+        self.running_since = None;
+    }
+}
+
+/// A [`TimedMinesweeper`] on disk; `G` is the game or a reference to it.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct SavedTimer<G> {
+    game: G,
+    elapsed: Duration,
+    started: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameState, MinesweeperError};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[test]
+    fn test_timer_runs_from_first_reveal_to_the_end() {
+        let now = Arc::new(AtomicU64::new(0));
+        let clock_now = now.clone();
+        let clock: MoveClock =
+            Arc::new(move || Duration::from_secs(clock_now.load(Ordering::SeqCst)));
+        let set = |secs| now.store(secs, Ordering::SeqCst);
+
+        let game = Minesweeper::new(3, vec![(0, 0), (2, 2)]);
+        let mut timed = TimedMinesweeper::with_clock(game, clock);
+        set(5);
+        timed.apply_move(Move::ToggleFlag(0, 0)).unwrap();
+        assert_eq!(timed.elapsed(), Duration::ZERO);
+        assert!(!timed.is_running());
+
+        timed.apply_move(Move::Reveal(0, 2)).unwrap();
+        set(8);
+        assert_eq!(timed.elapsed(), Duration::from_secs(3));
+
+        timed.pause();
+        assert_eq!(*timed.game().get_game_state(), GameState::Paused);
+        assert_eq!(
+            timed.apply_move(Move::Reveal(2, 0)),
+            Err(MinesweeperError::Paused)
+        );
+        set(20);
+        assert_eq!(timed.elapsed(), Duration::from_secs(3));
+        timed.resume();
+        set(22);
+        assert_eq!(timed.elapsed(), Duration::from_secs(5));
+
+        timed.apply_move(Move::Reveal(2, 0)).unwrap();
+        assert_eq!(*timed.game().get_game_state(), GameState::Won);
+        set(30);
+        assert_eq!(timed.elapsed(), Duration::from_secs(5));
+        assert!(!timed.is_running());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_timer_survives_a_save() {
+        let game = Minesweeper::new(3, vec![(0, 0), (2, 2)]);
+        let clock: MoveClock = Arc::new(|| Duration::from_secs(40));
+        let mut timed = TimedMinesweeper::with_clock(game, clock);
+        timed.apply_move(Move::Reveal(0, 2)).unwrap();
+        timed.banked = Duration::from_secs(12);
+        timed.pause();
+
+        let path =
+            std::env::temp_dir().join(format!("minesweeper-timer-{}.json", std::process::id()));
+        timed.save_to_file(&path).unwrap();
+        let mut restored = TimedMinesweeper::load_from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(restored.is_paused());
+        assert!(!restored.is_running());
+        assert_eq!(restored.elapsed(), Duration::from_secs(12));
+        restored.resume();
+        assert!(restored.is_running());
+        assert!(restored.elapsed() >= Duration::from_secs(12));
+    }
+}
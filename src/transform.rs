@@ -0,0 +1,226 @@
+use crate::{Adjacency, BoardId, Minesweeper, Topology};
+
+/// A rearrangement of a board's layout that keeps every number right: one
+/// of the eight symmetries of a rectangle, or a shift around a wrap-around
+/// board.
+///
+/// Quarter turns and transposes swap the board's width and height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Transform {
+    Identity,
+    /// A quarter turn clockwise.
+    Rotate90,
+    Rotate180,
+    /// A quarter turn anticlockwise.
+    Rotate270,
+    /// Mirrored across the vertical centre line: `x -> width - 1 - x`.
+    MirrorX,
+    /// Mirrored across the horizontal centre line: `y -> height - 1 - y`.
+    MirrorY,
+    /// Mirrored across the main diagonal: `(x, y) -> (y, x)`.
+    Transpose,
+    /// Mirrored across the other diagonal.
+    AntiTranspose,
+    /// Every tile moved `dx` right and `dy` down, wrapping around the
+    /// edges. Only keeps the numbers right on a [`Topology::Torus`].
+    Translate {
+        dx: usize,
+        dy: usize,
+    },
+}
+
+impl Transform {
+    /// The symmetries of a rectangle, starting with the identity.
+    pub const SYMMETRIES: [Transform; 8] = [
+        Transform::Identity,
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::MirrorX,
+        Transform::MirrorY,
+        Transform::Transpose,
+        Transform::AntiTranspose,
+    ];
+
+    /// The dimensions of a `width` x `height` board after the transform.
+    pub fn dimensions(self, width: usize, height: usize) -> (usize, usize) {
+        match self {
+            Transform::Rotate90
+            | Transform::Rotate270
+            | Transform::Transpose
+            | Transform::AntiTranspose => (height, width),
+            _ => (width, height),
+        }
+    }
+
+    /// Where the tile at (x, y) of a `width` x `height` board ends up.
+    pub fn apply(self, (x, y): (usize, usize), width: usize, height: usize) -> (usize, usize) {
+        let (right, bottom) = (width - 1, height - 1);
+        match self {
+            Transform::Identity => (x, y),
+            Transform::Rotate90 => (bottom - y, x),
+            Transform::Rotate180 => (right - x, bottom - y),
+            Transform::Rotate270 => (y, right - x),
+            Transform::MirrorX => (right - x, y),
+            Transform::MirrorY => (x, bottom - y),
+            Transform::Transpose => (y, x),
+            Transform::AntiTranspose => (bottom - y, right - x),
+            Transform::Translate { dx, dy } => ((x + dx) % width, (y + dy) % height),
+        }
+    }
+
+    /// Whether the transform keeps every tile's neighbours its neighbours
+    /// under `adjacency` and `topology`, so a transformed board has the
+    /// same numbers in the moved places. Hex boards only allow the
+    /// symmetries that map the hexagon onto itself, and translations need
+    /// a torus.
+    pub fn preserves(self, adjacency: Adjacency, topology: Topology) -> bool {
+        if let Transform::Translate { .. } = self {
+            return topology == Topology::Torus;
+        }
+        // Each neighbour's offset must map onto another neighbour's.
+        let r = adjacency.radius();
+        let side = 2 * r + 1;
+        let offsets: Vec<(usize, usize)> = adjacency.neighbors(r, r, side, side).collect();
+        offsets
+            .iter()
+            .all(|&offset| offsets.contains(&self.apply(offset, side, side)))
+    }
+}
+
+impl Minesweeper {
+    /// A fresh copy of this game's layout under `transform`, with the same
+    /// rules and nothing revealed. Fails if the transform would change the
+    /// numbers; see [`Transform::preserves`].
+    pub fn transformed(&self, transform: Transform) -> Result<Minesweeper, String> {
+        if !transform.preserves(self.adjacency, self.topology) {
+            return Err(format!(
+                "{:?} does not preserve this board's adjacency",
+                transform
+            ));
+        }
+        let (width, height) = transform.dimensions(self.width, self.height);
+        let mines = self
+            .mine_positions()
+            .into_iter()
+            .map(|pos| transform.apply(pos, self.width, self.height))
+            .collect();
+        let mut game = Minesweeper::new_rect_with_topology(
+            width,
+            height,
+            mines,
+            self.adjacency,
+            self.topology,
+        );
+        game.flag_rule = self.flag_rule;
+        game.set_zero_rule(self.zero_rule);
+        game.win_condition = self.win_condition;
+        game.mine_count = self.mine_count;
+        game.question_marks = self.question_marks;
+        game.auto_flag = self.auto_flag;
+        game.lives = self.config().lives;
+        game.time_control = self.time_control;
+        game.generator = self.generator.clone();
+        Ok(game)
+    }
+
+    /// Like [`Minesweeper::board_id`], but the same for every layout that
+    /// transforms into this one: the smallest id among the board's valid
+    /// symmetries and, on a torus, their translations. Tells apart boards
+    /// that are really different from ones that are just turned or
+    /// mirrored.
+    pub fn canonical_board_id(&self) -> BoardId {
+        let mines = self.mine_positions();
+        let shifts: Vec<(usize, usize)> = match self.topology {
+            Topology::Torus => (0..self.width)
+                .flat_map(|dx| (0..self.height).map(move |dy| (dx, dy)))
+                .collect(),
+            Topology::Plane => vec![(0, 0)],
+        };
+
+        let mut best: Option<BoardId> = None;
+        for symmetry in Transform::SYMMETRIES {
+            if !symmetry.preserves(self.adjacency, self.topology) {
+                continue;
+            }
+            let dimensions = symmetry.dimensions(self.width, self.height);
+            for &(dx, dy) in &shifts {
+                let shift = Transform::Translate { dx, dy };
+                let mut moved: Vec<(usize, usize)> = mines
+                    .iter()
+                    .map(|&pos| {
+                        let pos = shift.apply(pos, self.width, self.height);
+                        symmetry.apply(pos, self.width, self.height)
+                    })
+                    .collect();
+                moved.sort_unstable();
+                let id = self.layout_id(dimensions, &moved);
+                best = Some(best.map_or(id, |best| best.min(id)));
+            }
+        }
+        best.expect("the identity always applies")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(game: &Minesweeper) -> Vec<Option<u8>> {
+        let mut numbers: Vec<Option<u8>> = (0..game.get_width())
+            .flat_map(|x| (0..game.get_height()).map(move |y| (x, y)))
+            .map(|(x, y)| game.get_tile(x, y).unwrap().get_number())
+            .collect();
+        numbers.sort();
+        numbers
+    }
+
+    #[test]
+    fn test_symmetries_keep_numbers() {
+        let game = Minesweeper::new_rect(4, 3, vec![(0, 0), (3, 1), (2, 2)]);
+        for transform in Transform::SYMMETRIES {
+            let moved = game.transformed(transform).unwrap();
+            assert_eq!(
+                (moved.get_width(), moved.get_height()),
+                transform.dimensions(4, 3)
+            );
+            assert_eq!(numbers(&moved), numbers(&game));
+            for (x, y) in game.mine_positions() {
+                let (mx, my) = transform.apply((x, y), 4, 3);
+                assert!(moved.get_tile(mx, my).unwrap().is_bomb());
+            }
+            assert_eq!(moved.canonical_board_id(), game.canonical_board_id());
+        }
+
+        let rotated = game.transformed(Transform::Rotate90).unwrap();
+        assert_eq!(rotated.mine_positions(), vec![(0, 2), (1, 3), (2, 0)]);
+        assert_ne!(rotated.board_id(), game.board_id());
+        let other = Minesweeper::new_rect(4, 3, vec![(0, 0), (1, 1), (2, 2)]);
+        assert_ne!(other.canonical_board_id(), game.canonical_board_id());
+    }
+
+    #[test]
+    fn test_transforms_that_break_adjacency_are_refused() {
+        let hex = Minesweeper::new_rect_with_adjacency(4, 4, vec![(0, 0)], Adjacency::Hex);
+        assert!(hex.transformed(Transform::Rotate180).is_ok());
+        assert!(hex.transformed(Transform::Transpose).is_ok());
+        assert!(hex.transformed(Transform::MirrorX).is_err());
+
+        let shift = Transform::Translate { dx: 1, dy: 2 };
+        assert!(Minesweeper::new(4, vec![(0, 0)])
+            .transformed(shift)
+            .is_err());
+
+        let torus = Minesweeper::new_rect_with_topology(
+            4,
+            3,
+            vec![(0, 0)],
+            Adjacency::Standard,
+            Topology::Torus,
+        );
+        let shifted = torus.transformed(shift).unwrap();
+        assert_eq!(shifted.mine_positions(), vec![(1, 2)]);
+        assert_eq!(numbers(&shifted), numbers(&torus));
+        assert_eq!(shifted.canonical_board_id(), torus.canonical_board_id());
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{GameEvent, Minesweeper, Move};
+
+/// A whole game as a plain value, for frontends that keep state immutable:
+/// Elm-style update functions, Redux-like stores, actors that snapshot
+/// their state.
+///
+/// [`GameSnapshot::step`] never changes the snapshot it is called on; it
+/// returns the next one alongside the events the move caused. Cloning is
+/// cheap since tile storage is shared until a move changes it. History,
+/// the probability overlay and event listeners are left behind.
+#[derive(Debug)]
+pub struct GameSnapshot {
+    game: Minesweeper,
+}
+
+impl GameSnapshot {
+    /// Copies the current state of `game`.
+    pub fn new(game: &Minesweeper) -> Self {
+        let mut copy = game.position();
+        copy.notes = game.notes.clone();
+        copy.provenance = game.provenance.clone();
+        GameSnapshot { game: copy }
+    }
+
+    /// The game this snapshot holds, for reading.
+    pub fn game(&self) -> &Minesweeper {
+        &self.game
+    }
+
+    /// A live game to carry on playing from this snapshot.
+    pub fn into_game(self) -> Minesweeper {
+        self.game
+    }
+
+    /// The state after `mv`, and what happened on the way. A move the game
+    /// refuses is an error, and this snapshot is still the current state.
+    pub fn step(&self, mv: Move) -> Result<(GameSnapshot, Vec<GameEvent>), String> {
+        let mut next = self.clone();
+        next.game.set_event_queue(true);
+        next.game.apply_move(mv)?;
+        let events = next.game.drain_events();
+        next.game.set_event_queue(false);
+        Ok((next, events))
+    }
+}
+
+impl Clone for GameSnapshot {
+    fn clone(&self) -> Self {
+        GameSnapshot::new(&self.game)
+    }
+}
+
+impl From<Minesweeper> for GameSnapshot {
+    fn from(game: Minesweeper) -> Self {
+        GameSnapshot::new(&game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameState;
+
+    #[test]
+    fn test_step_leaves_the_old_state_alone() {
+        let start = GameSnapshot::from(Minesweeper::new_rect(3, 2, vec![(0, 1)]));
+
+        let (opened, events) = start.step(Move::Reveal(2, 0)).unwrap();
+        assert_eq!(start.game().count_exposed_tiles(), 0);
+        assert_eq!(opened.game().count_exposed_tiles(), 4);
+        assert!(matches!(
+            events.last(),
+            Some(GameEvent::FloodFillCompleted { tiles: 4, .. })
+        ));
+
+        let (lost, events) = opened.step(Move::Reveal(0, 1)).unwrap();
+        assert_eq!(lost.game().get_game_state(), &GameState::Lost);
+        assert_eq!(
+            events,
+            vec![GameEvent::BombExploded { x: 0, y: 1 }, GameEvent::GameLost]
+        );
+        assert_eq!(opened.game().get_game_state(), &GameState::InProgress);
+
+        assert!(lost.step(Move::Reveal(0, 0)).is_err());
+        let (won, _) = opened.step(Move::Reveal(0, 0)).unwrap();
+        assert_eq!(won.into_game().get_game_state(), &GameState::Won);
+    }
+}
@@ -0,0 +1,256 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{Adjacency, ClassicScoring, FlagRule, GameConfig, MineCount, Scoring, Topology};
+
+/// Adjusts a config to a variant's rules.
+pub type RulesFn = Arc<dyn Fn(&mut GameConfig) + Send + Sync>;
+
+/// A named rule set: how its games are configured and how they are
+/// scored. Frontends, save files and network peers refer to variants by
+/// [`Variant::id`].
+#[derive(Clone)]
+pub struct Variant {
+    id: String,
+    name: String,
+    description: String,
+    rules: Option<RulesFn>,
+    scoring: Arc<dyn Scoring + Send + Sync>,
+}
+
+impl fmt::Debug for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Variant")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Variant {
+    /// A variant with classic rules and [`ClassicScoring`], to be adjusted
+    /// with [`Variant::with_rules`] and [`Variant::with_scoring`].
+    pub fn new(
+        id: impl Into<String>,
+        name: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        Variant {
+            id: id.into(),
+            name: name.into(),
+            description: description.into(),
+            rules: None,
+            scoring: Arc::new(ClassicScoring::default()),
+        }
+    }
+
+    pub fn with_rules(mut self, rules: impl Fn(&mut GameConfig) + Send + Sync + 'static) -> Self {
+        self.rules = Some(Arc::new(rules));
+        self
+    }
+
+    pub fn with_scoring(mut self, scoring: impl Scoring + Send + Sync + 'static) -> Self {
+        self.scoring = Arc::new(scoring);
+        self
+    }
+
+    /// The stable identifier, e.g. `knight`.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// The variant's scoring rule, ready for [`Score::new`](crate::Score::new).
+    pub fn scoring(&self) -> Arc<dyn Scoring + Send + Sync> {
+        self.scoring.clone()
+    }
+
+    /// A config for a `width` x `height` board with `bomb_count` mines
+    /// under this variant's rules.
+    pub fn config(&self, width: usize, height: usize, bomb_count: usize) -> GameConfig {
+        let mut config = GameConfig::new_rect(width, height, bomb_count);
+        if let Some(rules) = &self.rules {
+            rules(&mut config);
+        }
+        config
+    }
+}
+
+/// Variants by identifier.
+#[derive(Debug, Clone, Default)]
+pub struct VariantRegistry {
+    variants: Vec<Variant>,
+}
+
+impl VariantRegistry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry holding the built-in variants: `classic`, `extended`,
+    /// `knight`, `hex`, `torus`, `no-flag`, `uncertain`, `hidden-count` and
+    /// `lives`.
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        let builtins = [
+            Variant::new("classic", "Classic", "The standard rules."),
+            Variant::new(
+                "extended",
+                "Extended",
+                "Numbers count mines in the surrounding 5x5 area.",
+            )
+            .with_rules(|config| config.generator.adjacency = Adjacency::Extended),
+            Variant::new(
+                "knight",
+                "Knight",
+                "Numbers count mines a knight's move away.",
+            )
+            .with_rules(|config| config.generator.adjacency = Adjacency::Knight),
+            Variant::new("hex", "Hex", "Hexagonal tiles, each touching six others.")
+                .with_rules(|config| config.generator.adjacency = Adjacency::Hex),
+            Variant::new(
+                "torus",
+                "Torus",
+                "The edges wrap around, so every tile has eight neighbours.",
+            )
+            .with_rules(|config| config.generator.topology = Topology::Torus),
+            Variant::new("no-flag", "No flags", "Flags cannot be placed.")
+                .with_rules(|config| config.flag_rule = FlagRule::Forbidden),
+            Variant::new(
+                "uncertain",
+                "Uncertain",
+                "The mine count is only known to within a quarter either way.",
+            )
+            .with_rules(|config| {
+                let spread = config.bomb_count.div_ceil(4);
+                config.mine_count = MineCount::Range {
+                    min: config.bomb_count.saturating_sub(spread).max(1),
+                    max: (config.bomb_count + spread)
+                        .min((config.width * config.height).saturating_sub(1)),
+                };
+            }),
+            Variant::new(
+                "hidden-count",
+                "Hidden count",
+                "The mine count is not shown.",
+            )
+            .with_rules(|config| config.mine_count = MineCount::Hidden),
+            Variant::new(
+                "lives",
+                "Three lives",
+                "The first three mines hit cost a life instead of the game.",
+            )
+            .with_rules(|config| config.lives = 3),
+        ];
+        for variant in builtins {
+            registry.register(variant).unwrap();
+        }
+        registry
+    }
+
+    /// Adds a variant. Identifiers must be unique.
+    pub fn register(&mut self, variant: Variant) -> Result<(), String> {
+        if self.variants.iter().any(|v| v.id == variant.id) {
+            return Err(format!("Variant {} is already registered", variant.id));
+        }
+        self.variants.push(variant);
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Result<&Variant, String> {
+        self.variants
+            .iter()
+            .find(|variant| variant.id == id)
+            .ok_or_else(|| format!("Unknown variant: {}", id))
+    }
+
+    /// Every registered identifier, in registration order.
+    pub fn ids(&self) -> Vec<&str> {
+        self.variants
+            .iter()
+            .map(|variant| variant.id.as_str())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Minesweeper, Move, Score};
+    use std::time::Duration;
+
+    #[test]
+    fn test_builtin_variants() {
+        let registry = VariantRegistry::builtin();
+        assert_eq!(
+            registry.ids(),
+            vec![
+                "classic",
+                "extended",
+                "knight",
+                "hex",
+                "torus",
+                "no-flag",
+                "uncertain",
+                "hidden-count",
+                "lives"
+            ]
+        );
+        assert_eq!(
+            registry.get("fog").unwrap_err(),
+            "Unknown variant: fog".to_string()
+        );
+
+        let knight = registry.get("knight").unwrap();
+        let config = knight.config(8, 8, 10);
+        assert_eq!(config.get_adjacency(), Adjacency::Knight);
+        let game = Minesweeper::new_with_config_and_seed(&config, (0, 0), 4).unwrap();
+        assert_eq!(game.get_adjacency(), Adjacency::Knight);
+
+        let no_flag = registry.get("no-flag").unwrap().config(8, 8, 10);
+        assert_eq!(no_flag.flag_rule, FlagRule::Forbidden);
+
+        let lives = registry.get("lives").unwrap().config(8, 8, 10);
+        let game = Minesweeper::new_with_config_and_seed(&lives, (0, 0), 4).unwrap();
+        assert_eq!(game.lives_remaining(), 3);
+
+        let uncertain = registry.get("uncertain").unwrap().config(9, 9, 10);
+        assert_eq!(uncertain.mine_count, MineCount::Range { min: 7, max: 13 });
+        for seed in 0..20 {
+            let game = Minesweeper::new_with_config_and_seed(&uncertain, (4, 4), seed).unwrap();
+            assert!((7..=13).contains(&game.get_bomb_count()));
+            assert_eq!(game.mine_count_bounds(), (7, 13));
+        }
+    }
+
+    #[test]
+    fn test_custom_variants() {
+        let mut registry = VariantRegistry::builtin();
+        let scoring = ClassicScoring {
+            half_life: None,
+            ..Default::default()
+        };
+        registry
+            .register(Variant::new("relaxed", "Relaxed", "No time pressure.").with_scoring(scoring))
+            .unwrap();
+        assert!(registry
+            .register(Variant::new("classic", "Again", ""))
+            .is_err());
+
+        let relaxed = registry.get("relaxed").unwrap();
+        let mut score = Score::new(relaxed.scoring());
+        let mut game = Minesweeper::new(3, vec![(0, 0)]);
+        score
+            .apply_move(&mut game, Move::Reveal(1, 0), Duration::from_secs(600))
+            .unwrap();
+        assert_eq!(score.total(), 10);
+    }
+}
@@ -0,0 +1,86 @@
+//! Guards the move hot path against heap allocation. Bots simulate millions
+//! of moves, so `click_tile`, `flood_fill`, `toggle_flag` and `chord` must
+//! reuse the buffers set up when the game was created.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use minesweeper::Minesweeper;
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations_during(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(|count| count.get());
+    f();
+    ALLOCATIONS.with(|count| count.get()) - before
+}
+
+#[test]
+fn test_flood_fills_do_not_allocate() {
+    // Flags split the board so each click triggers a separate fill.
+    let mut game = Minesweeper::new(10, vec![(9, 9)]);
+    for y in 0..10 {
+        game.toggle_flag(4, y).unwrap();
+    }
+
+    let allocations = allocations_during(|| {
+        game.click_tile(0, 0).unwrap();
+        game.click_tile(8, 0).unwrap();
+        game.toggle_flag(4, 0).unwrap();
+        game.toggle_flag(4, 0).unwrap();
+    });
+
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn test_number_reveals_do_not_allocate() {
+    let mut game = Minesweeper::new(3, vec![(1, 1)]);
+
+    let allocations = allocations_during(|| {
+        game.click_tile(0, 0).unwrap();
+        game.click_tile(2, 2).unwrap();
+        game.toggle_flag(1, 1).unwrap();
+    });
+
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn test_chords_do_not_allocate() {
+    let mut game = Minesweeper::new(5, vec![(1, 1)]);
+    game.click_tile(2, 2).unwrap();
+    game.click_tile(0, 0).unwrap();
+
+    let allocations = allocations_during(|| {
+        game.toggle_flag(1, 1).unwrap();
+        // Reveals numbers next to the mine and sets off a fill from (3, 3).
+        game.chord(2, 2).unwrap();
+        game.chord(0, 0).unwrap();
+    });
+
+    assert_eq!(allocations, 0);
+}
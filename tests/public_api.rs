@@ -0,0 +1,120 @@
+//! Compile-time guard for the stable API in `minesweeper::prelude`. Every
+//! item and signature below is promised to downstream frontends; if this
+//! file stops compiling, the change is a breaking one and needs a major
+//! release (or a deliberate update here).
+
+use minesweeper::prelude::*;
+
+type SeededConstructor =
+    fn(&GameConfig, (usize, usize), u64) -> Result<Minesweeper, MinesweeperError>;
+
+#[test]
+fn test_prelude_signatures() {
+    let _: fn(usize, Vec<(usize, usize)>) -> Minesweeper = Minesweeper::new;
+    let _: fn(usize, usize, Vec<(usize, usize)>) -> Minesweeper = Minesweeper::new_rect;
+    let _: fn(&GameConfig, (usize, usize)) -> Result<Minesweeper, MinesweeperError> =
+        Minesweeper::new_with_config;
+    let _: SeededConstructor = Minesweeper::new_with_config_and_seed;
+    let _: fn(&mut Minesweeper, usize, usize) -> Result<(), MinesweeperError> =
+        Minesweeper::click_tile;
+    let _: fn(&mut Minesweeper, usize, usize) -> Result<(), MinesweeperError> =
+        Minesweeper::toggle_flag;
+    let _: fn(&mut Minesweeper, usize, usize) -> Result<(), MinesweeperError> = Minesweeper::chord;
+    let _: fn(&mut Minesweeper, Move) -> Outcome = Minesweeper::apply_move;
+    let _: for<'a> fn(&'a Minesweeper, usize, usize) -> Option<&'a Tile> = Minesweeper::get_tile;
+    let _: fn(&Minesweeper) -> &GameState = Minesweeper::get_game_state;
+    let _: fn(&Minesweeper) -> Vec<Vec<VisibleTile>> = Minesweeper::player_view;
+    let _: fn(&Minesweeper) -> usize = Minesweeper::get_width;
+    let _: fn(&Minesweeper) -> usize = Minesweeper::get_height;
+    let _: fn(&Minesweeper) -> usize = Minesweeper::get_bomb_count;
+    let _: fn(&Minesweeper) -> GameConfig = Minesweeper::config;
+    let _: fn(usize, usize) -> GameConfig = GameConfig::new;
+    let _: fn(u64) -> BoardRng = board_rng;
+    let _: fn(&TextRenderer, &Minesweeper, View) -> String = TextRenderer::render;
+
+    let config = GameConfig::new_rect(9, 9, 10)
+        .with_flag_rule(FlagRule::Allowed)
+        .with_zero_rule(ZeroRule::Cascade)
+        .with_win_condition(WinCondition::Classic)
+        .with_mine_count(MineCount::Exact)
+        .with_question_marks(false)
+        .with_auto_flag(false)
+        .with_lives(0)
+        .with_generator(GeneratorOptions::default())
+        .with_adjacency(Adjacency::Standard)
+        .with_topology(Topology::Plane);
+    let _: (usize, usize, usize, usize) =
+        (config.width, config.height, config.bomb_count, config.lives);
+    let _: (FlagRule, ZeroRule, WinCondition, MineCount) = (
+        config.flag_rule,
+        config.zero_rule,
+        config.win_condition,
+        config.mine_count,
+    );
+    let _: (bool, bool, &GeneratorOptions) =
+        (config.question_marks, config.auto_flag, &config.generator);
+    let _ = Tile {
+        value: TileValue::Number(0),
+        exposed: false,
+        flagged: false,
+        marker: Some(Marker::Red),
+    };
+}
+
+/// Exhaustive matches, so adding a variant to a stable enum is caught too:
+/// it breaks downstream matches just the same. `GameState` and `Move` are
+/// `#[non_exhaustive]` and take a wildcard arm; naming every variant still
+/// catches one being removed or renamed.
+#[test]
+fn test_prelude_enums() {
+    let state = |state: GameState| match state {
+        GameState::InProgress | GameState::Paused | GameState::Won | GameState::Lost => (),
+        _ => (),
+    };
+    state(GameState::Won);
+
+    let mv = |mv: Move| match mv {
+        Move::Reveal(..)
+        | Move::ToggleFlag(..)
+        | Move::SetMarker(..)
+        | Move::Chord(..)
+        | Move::Assist(..)
+        | Move::AutoFinish => (),
+        _ => (),
+    };
+    mv(Move::AutoFinish);
+
+    let tile = |tile: VisibleTile| match tile {
+        VisibleTile::Covered
+        | VisibleTile::Flagged
+        | VisibleTile::Number(_)
+        | VisibleTile::Mine
+        | VisibleTile::Exploded
+        | VisibleTile::WrongFlag => (),
+    };
+    tile(VisibleTile::Covered);
+
+    let mark = |mark: Mark| match mark {
+        Mark::None | Mark::Flag | Mark::Question => (),
+    };
+    mark(Mark::Flag);
+
+    let value = |value: TileValue| match value {
+        TileValue::Bomb | TileValue::Number(_) => (),
+    };
+    value(TileValue::Bomb);
+
+    let win = |win: WinCondition| match win {
+        WinCondition::Classic | WinCondition::FlagAllMines | WinCondition::Score(_) => (),
+    };
+    win(WinCondition::Classic);
+}
+
+#[test]
+fn test_prelude_plays_a_game() {
+    let mut game = Minesweeper::new_rect(3, 1, vec![(2, 0)]);
+    game.apply_move(Move::Reveal(0, 0)).unwrap();
+    assert_eq!(game.get_game_state(), &GameState::Won);
+    let text = TextRenderer::ascii().render(&game, View::Player);
+    assert_eq!(text.lines().count(), 1);
+}